@@ -51,6 +51,11 @@ impl SchemaCache {
         config.cache_dir.join(&config.cache_file_name)
     }
 
+    /// Read a cache file without contacting the database.
+    pub fn load_from_file(path: &Path) -> CheckResult<Self> {
+        read_cache_file(path)
+    }
+
     pub async fn load_or_refresh<C: CheckClient>(
         client: &C,
         config: &SchemaCacheConfig,
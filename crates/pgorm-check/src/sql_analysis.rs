@@ -19,6 +19,13 @@ pub struct ColumnRefFull {
     pub location: Option<i32>,
 }
 
+/// A positional parameter reference (`$n`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamRef {
+    pub number: i32,
+    pub location: Option<i32>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TargetColumn {
     pub name: String,
@@ -55,6 +62,8 @@ pub struct SqlAnalysis {
     pub range_vars: Vec<RangeVarRef>,
     pub table_names: Vec<String>,
     pub column_refs: Vec<ColumnRefFull>,
+    /// `$n` references in source order (a number may appear more than once).
+    pub param_refs: Vec<ParamRef>,
 
     pub select_has_limit: Option<bool>,
     pub select_has_star: Option<bool>,
@@ -133,6 +142,7 @@ pub fn analyze_sql(sql: &str) -> SqlAnalysis {
             let mut range_vars = Vec::new();
             let mut table_names: Vec<String> = Vec::new();
             let mut column_refs = Vec::new();
+            let mut param_refs = Vec::new();
             let mut has_star = false;
 
             for (node, _depth, _context, _has_filter_columns) in parsed.protobuf.nodes() {
@@ -169,6 +179,13 @@ pub fn analyze_sql(sql: &str) -> SqlAnalysis {
                     }
                 }
 
+                if let pg_query::NodeRef::ParamRef(p) = node {
+                    param_refs.push(ParamRef {
+                        number: p.number,
+                        location: location_opt(p.location),
+                    });
+                }
+
                 if let pg_query::NodeRef::ColumnRef(c) = node {
                     let mut parts: Vec<String> = Vec::new();
                     let mut star = false;
@@ -214,6 +231,7 @@ pub fn analyze_sql(sql: &str) -> SqlAnalysis {
                 range_vars,
                 table_names,
                 column_refs,
+                param_refs,
                 select_has_limit,
                 select_has_star,
                 delete_has_where,
@@ -236,6 +254,7 @@ pub fn analyze_sql(sql: &str) -> SqlAnalysis {
                 range_vars: Vec::new(),
                 table_names: Vec::new(),
                 column_refs: Vec::new(),
+                param_refs: Vec::new(),
                 select_has_limit: None,
                 select_has_star: None,
                 delete_has_where: None,
@@ -557,6 +576,17 @@ mod tests {
         assert!(!Arc::ptr_eq(&a1, &a3));
     }
 
+    #[test]
+    fn test_analyze_param_refs() {
+        let sql = "SELECT * FROM users WHERE id = $1 AND (org_id = $2 OR owner_id = $1)";
+        let analysis = analyze_sql(sql);
+        let numbers: Vec<i32> = analysis.param_refs.iter().map(|p| p.number).collect();
+        assert_eq!(numbers.len(), 3);
+        assert_eq!(numbers.iter().max(), Some(&2));
+        let first = analysis.param_refs.iter().find(|p| p.number == 2).unwrap();
+        assert_eq!(first.location, Some(sql.find("$2").unwrap() as i32));
+    }
+
     #[test]
     fn test_analyze_insert_update_on_conflict_columns() {
         let insert_sql = "INSERT INTO users (id, name) VALUES (1, 'a') ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name";
@@ -0,0 +1,307 @@
+//! `checked_sql!`: SQL validated at compile time against the local schema cache.
+
+use pgorm_check::{DbSchema, SchemaCache, SqlAnalysis, SqlCheckLevel};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, LitStr, Token};
+
+/// Environment variable overriding the schema cache location.
+const SCHEMA_CACHE_ENV: &str = "PGORM_SCHEMA_CACHE";
+
+struct CheckedSqlInput {
+    schema: Option<LitStr>,
+    sql: LitStr,
+    args: Vec<Expr>,
+}
+
+impl Parse for CheckedSqlInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut schema = None;
+        if input.peek(syn::Ident) && input.peek2(Token![=]) {
+            let key: syn::Ident = input.parse()?;
+            if key != "schema" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `schema = \"path\"` or a SQL string literal",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            schema = Some(input.parse()?);
+            input.parse::<Token![,]>()?;
+        }
+
+        let sql = input.parse()?;
+        let mut args = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            args.push(input.parse()?);
+        }
+        Ok(Self { schema, sql, args })
+    }
+}
+
+/// A problem found in the SQL, with the byte offset it refers to (if known).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Problem {
+    location: Option<usize>,
+    message: String,
+}
+
+impl Problem {
+    fn new(location: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            location,
+            message: message.into(),
+        }
+    }
+}
+
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: CheckedSqlInput = syn::parse2(input)?;
+    let sql = input.sql.value();
+
+    let analysis = pgorm_check::analyze_sql(&sql);
+    if !analysis.parse_result.valid {
+        let message = analysis.parse_result.error.clone().unwrap_or_default();
+        return Err(problem_error(
+            &input.sql,
+            &sql,
+            &Problem::new(
+                analysis.parse_result.error_location,
+                format!("invalid SQL: {message}"),
+            ),
+        ));
+    }
+
+    if let Some(problem) = check_params(&analysis, input.args.len()) {
+        let max = max_param(&analysis);
+        return Err(match input.args.get(max) {
+            // Too many arguments: point at the first surplus one.
+            Some(extra) => syn::Error::new_spanned(extra, problem.message),
+            None => problem_error(&input.sql, &sql, &problem),
+        });
+    }
+
+    let cache_path = resolve_cache_path(input.schema.as_ref())?;
+    let cache = load_cache(&cache_path).map_err(|e| syn::Error::new(input.sql.span(), e))?;
+
+    let problems = check_schema(&cache.schema, &analysis);
+    if let Some(error) = problems
+        .iter()
+        .map(|p| problem_error(&input.sql, &sql, p))
+        .reduce(|mut all, e| {
+            all.combine(e);
+            all
+        })
+    {
+        return Err(error);
+    }
+
+    let sql_lit = &input.sql;
+    let args = &input.args;
+    let cache_file = cache_path.to_string_lossy();
+    Ok(quote! {{
+        // Recompile when the schema cache changes.
+        const _: &[u8] = ::core::include_bytes!(#cache_file);
+        ::pgorm::query(#sql_lit) #( .bind(#args) )*
+    }})
+}
+
+fn max_param(analysis: &SqlAnalysis) -> usize {
+    analysis
+        .param_refs
+        .iter()
+        .map(|p| p.number.max(0) as usize)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Check that `$1..$n` are all referenced and `n` matches the argument count.
+fn check_params(analysis: &SqlAnalysis, arg_count: usize) -> Option<Problem> {
+    let max = max_param(analysis);
+    if let Some(p) = analysis
+        .param_refs
+        .iter()
+        .find(|p| p.number as usize > arg_count)
+    {
+        return Some(Problem::new(
+            p.location.map(|l| l as usize),
+            format!(
+                "SQL uses ${} but only {arg_count} argument(s) were supplied",
+                p.number
+            ),
+        ));
+    }
+    if arg_count > max {
+        return Some(Problem::new(
+            None,
+            format!("SQL uses {max} parameter(s) but {arg_count} argument(s) were supplied"),
+        ));
+    }
+    (1..=max)
+        .find(|n| !analysis.param_refs.iter().any(|p| p.number as usize == *n))
+        .map(|n| Problem::new(None, format!("parameter ${n} is never used in the SQL")))
+}
+
+fn check_schema(schema: &DbSchema, analysis: &SqlAnalysis) -> Vec<Problem> {
+    match pgorm_check::check_sql_analysis(schema, analysis) {
+        Ok(issues) => issues
+            .into_iter()
+            .filter(|i| i.level == SqlCheckLevel::Error)
+            .map(|i| Problem::new(i.location.map(|l| l as usize), i.message))
+            .collect(),
+        Err(e) => vec![Problem::new(None, e.to_string())],
+    }
+}
+
+/// Build an error pointing at `location` inside the SQL literal.
+///
+/// Sub-spans of a literal are only available on nightly compilers, so the message
+/// also quotes the offending SQL text.
+fn problem_error(lit: &LitStr, sql: &str, problem: &Problem) -> syn::Error {
+    let Some(location) = problem.location.filter(|l| *l < sql.len()) else {
+        return syn::Error::new(lit.span(), &problem.message);
+    };
+    let len = sql[location..]
+        .char_indices()
+        .find(|(i, c)| *i > 0 && !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+        .map_or(sql.len() - location, |(i, _)| i);
+    let snippet = &sql[location..location + len];
+    let span = literal_subspan(lit, location, len).unwrap_or_else(|| lit.span());
+    syn::Error::new(span, format!("{} (at `{snippet}`)", problem.message))
+}
+
+fn literal_subspan(lit: &LitStr, offset: usize, len: usize) -> Option<Span> {
+    let token = lit.token();
+    let source = token.to_string();
+    let prefix = match source.strip_prefix('r') {
+        Some(rest) => 2 + rest.len() - rest.trim_start_matches('#').len(),
+        // Escapes make source offsets differ from SQL offsets.
+        None if source.contains('\\') => return None,
+        None => 1,
+    };
+    token.subspan(prefix + offset..prefix + offset + len)
+}
+
+fn resolve_cache_path(explicit: Option<&LitStr>) -> syn::Result<PathBuf> {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let (requested, span) = match explicit {
+        Some(lit) => (Some(lit.value()), lit.span()),
+        None => (std::env::var(SCHEMA_CACHE_ENV).ok(), Span::call_site()),
+    };
+
+    if let Some(path) = requested {
+        let path = manifest_dir.join(path);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(syn::Error::new(
+                span,
+                format!("schema cache not found: {}", path.display()),
+            ))
+        };
+    }
+
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join(".pgorm").join("schema.json"))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            syn::Error::new(
+                span,
+                format!(
+                    "no .pgorm/schema.json found in {} or its parents; run `pgorm schema` \
+                     or set {SCHEMA_CACHE_ENV}",
+                    manifest_dir.display()
+                ),
+            )
+        })
+}
+
+type CachedSchema = (PathBuf, Option<SystemTime>, Arc<SchemaCache>);
+
+/// Load the schema cache once per compiler process (reloaded if the file changes).
+fn load_cache(path: &Path) -> Result<Arc<SchemaCache>, String> {
+    static LOADED: Mutex<Vec<CachedSchema>> = Mutex::new(Vec::new());
+
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, _, cache)) = loaded.iter().find(|(p, m, _)| p == path && *m == modified) {
+        return Ok(cache.clone());
+    }
+
+    let cache = Arc::new(
+        SchemaCache::load_from_file(path)
+            .map_err(|e| format!("failed to load schema cache {}: {e}", path.display()))?,
+    );
+    loaded.retain(|(p, _, _)| p != path);
+    loaded.push((path.to_path_buf(), modified, cache.clone()));
+    Ok(cache)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pgorm_check::{ColumnInfo, RelationKind, TableInfo, analyze_sql};
+
+    fn schema() -> DbSchema {
+        let column = |name: &str, ordinal| ColumnInfo {
+            name: name.to_string(),
+            data_type: "text".to_string(),
+            not_null: true,
+            default_expr: None,
+            ordinal,
+        };
+        DbSchema {
+            schemas: vec!["public".to_string()],
+            tables: vec![TableInfo {
+                schema: "public".to_string(),
+                name: "users".to_string(),
+                kind: RelationKind::Table,
+                columns: vec![column("id", 1), column("email", 2)],
+            }],
+        }
+    }
+
+    #[test]
+    fn param_count_must_match() {
+        let analysis = analyze_sql("SELECT id FROM users WHERE id = $1 OR email = $2");
+        assert_eq!(check_params(&analysis, 2), None);
+
+        let problem = check_params(&analysis, 1).unwrap();
+        assert!(problem.message.contains("$2"));
+        assert_eq!(problem.location, Some(46));
+
+        assert!(check_params(&analysis, 3).is_some());
+
+        let analysis = analyze_sql("SELECT id FROM users WHERE id = $2");
+        assert!(
+            check_params(&analysis, 2)
+                .unwrap()
+                .message
+                .contains("$1 is never used")
+        );
+    }
+
+    #[test]
+    fn unknown_columns_and_tables_are_reported() {
+        let schema = schema();
+        let ok = analyze_sql("SELECT u.id, email FROM users u WHERE id = $1");
+        assert!(check_schema(&schema, &ok).is_empty());
+
+        let sql = "SELECT id, emali FROM users";
+        let problems = check_schema(&schema, &analyze_sql(sql));
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].location, sql.find("emali"));
+
+        let problems = check_schema(&schema, &analyze_sql("SELECT id FROM userz"));
+        assert!(problems.iter().any(|p| p.message.contains("userz")));
+    }
+}
@@ -5,6 +5,8 @@
 use proc_macro::TokenStream;
 use syn::{DeriveInput, parse_macro_input};
 
+#[cfg(feature = "check")]
+mod checked_sql;
 mod common;
 mod from_row;
 mod insert_model;
@@ -181,6 +183,9 @@ pub fn derive_update_model(input: TokenStream) -> TokenStream {
 ///
 /// Struct-level:
 /// - `#[orm(model = "TypePath")]` - The model type that provides `Model::query()`
+/// - `#[orm(query_string)]` / `#[orm(query_string(sort(COL, ...)))]` - Also generate
+///   `allow_list()`, a `QueryAllowList` of the field filters (and sortable columns)
+///   for parsing HTTP query strings
 ///
 /// Field-level:
 /// - `#[orm(eq(COL))]` - Equality filter (auto uses `eq_opt_str` for `&str`/`String`)
@@ -290,3 +295,35 @@ pub fn derive_pg_composite(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|e| e.to_compile_error())
         .into()
 }
+
+/// Build a [`Query`](../pgorm/struct.Query.html) whose SQL is checked at compile time.
+///
+/// The SQL is parsed with `pgorm-check` and its tables and columns are validated
+/// against the schema cache written by `pgorm schema` (`.pgorm/schema.json`, searched
+/// from the crate directory upwards). The number of `$n` parameters must match the
+/// supplied arguments. No database connection is needed at build time.
+///
+/// # Example
+///
+/// ```ignore
+/// let user: User = pgorm::checked_sql!(
+///     "SELECT id, email FROM users WHERE id = $1 AND status = $2",
+///     user_id,
+///     "active",
+/// )
+/// .fetch_one_as(&client)
+/// .await?;
+/// ```
+///
+/// # Schema cache location
+///
+/// - `checked_sql!(schema = "path/to/schema.json", "SELECT ...")` - explicit path (relative to the crate)
+/// - `PGORM_SCHEMA_CACHE` environment variable - same, for the whole build
+/// - otherwise the nearest `.pgorm/schema.json`
+#[cfg(feature = "check")]
+#[proc_macro]
+pub fn checked_sql(input: TokenStream) -> TokenStream {
+    checked_sql::expand(input.into())
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
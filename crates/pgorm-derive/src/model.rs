@@ -3,24 +3,33 @@
 //! ## Module Structure
 //!
 //! - `attrs`: Struct and field attribute parsing (`get_table_name`, `get_field_info`, `is_id_field`)
-//! - `join`: JOIN clause parsing (`get_join_clauses`)
-//! - `query`: Query struct generation
-//! - `relations`: relation parsing (`get_has_many_relations`, `get_has_one_relations`, `get_many_to_many_relations`, `get_belongs_to_relations`)
+//! - `cursor`: cursor pagination keys (`get_cursor_config`, `generate_cursor_key_impl`)
+//! - `join`: JOIN clause parsing (`get_join_clauses`) and through-relation join chains (`through_hops`)
+//! - `query`: Query struct generation (including relation filters)
+//! - `relations`: relation parsing (`get_has_many_relations`, `get_has_one_relations`, `get_many_to_many_relations`, `get_belongs_to_relations`, `get_through_relations`)
+//! - `search`: full-text search (`get_search_config`, `generate_search_methods`)
+//! - `tree`: adjacency-list trees (`get_tree_config`, `generate_tree_methods`)
 
 mod attrs;
+mod cursor;
 mod join;
 mod query;
 mod relations;
+mod search;
+mod tree;
 
 use crate::common::syn_types::option_inner;
 use attrs::{get_field_info, get_table_name, is_id_field};
-use join::{JoinClause, get_join_clauses};
-use query::{QueryFieldInfo, generate_query_struct};
+use cursor::{generate_cursor_key_impl, get_cursor_config};
+use join::{JoinClause, get_join_clauses, through_hops};
+use query::{QueryFieldInfo, generate_query_struct, generate_relation_filters};
 use relations::{
-    BelongsToRelation, HasManyRelation, HasOneRelation, ManyToManyRelation,
+    BelongsToRelation, HasManyRelation, HasOneRelation, ManyToManyRelation, ThroughRelation,
     get_belongs_to_relations, get_has_many_relations, get_has_one_relations,
-    get_many_to_many_relations,
+    get_many_to_many_relations, get_through_relations,
 };
+use search::{generate_search_methods, get_search_config};
+use tree::{generate_tree_methods, get_tree_config};
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
@@ -35,7 +44,11 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
     let has_one_relations = get_has_one_relations(&input)?;
     let many_to_many_relations = get_many_to_many_relations(&input)?;
     let belongs_to_relations = get_belongs_to_relations(&input)?;
+    let (has_many_through_relations, has_one_through_relations) = get_through_relations(&input)?;
     let join_clauses = get_join_clauses(&input)?;
+    let search_config = get_search_config(&input)?;
+    let cursor_config = get_cursor_config(&input)?;
+    let tree_config = get_tree_config(&input)?;
 
     let fields = match &input.data {
         Data::Struct(data) => match &data.fields {
@@ -58,10 +71,14 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
     let mut column_names = Vec::with_capacity(fields.len());
     let mut columns_for_alias = Vec::with_capacity(fields.len());
     let mut qualified_columns = Vec::with_capacity(fields.len()); // table.column AS field_name format
+    let mut json_pairs = Vec::with_capacity(fields.len()); // 'field_name', table.column
     let mut id_fields: Vec<(String, &syn::Type, syn::Ident)> = Vec::new();
     let mut fk_field_types: HashMap<String, &syn::Type> = HashMap::with_capacity(fields.len() * 2);
     let mut fk_field_idents: HashMap<String, syn::Ident> = HashMap::with_capacity(fields.len() * 2);
+    let mut main_columns: HashMap<String, String> = HashMap::with_capacity(fields.len() * 2);
     let mut query_fields: Vec<QueryFieldInfo> = Vec::with_capacity(fields.len());
+    let mut prefixed_extracts = Vec::with_capacity(fields.len()); // field: row.get(prefix + column)
+    let mut has_joined_fields = false;
 
     for field in fields.iter() {
         let field_ident = field.ident.clone().unwrap();
@@ -77,7 +94,12 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
         column_names.push(column_name.clone());
         if is_main_table {
             columns_for_alias.push(column_name.clone());
+        } else {
+            has_joined_fields = true;
         }
+        prefixed_extracts.push(quote! {
+            #field_ident: row.try_get_column(&::std::format!("{}{}", prefix, #column_name))?
+        });
 
         // Build qualified column name with alias (table.column AS field_name)
         let qualified = if let Some(ref tbl) = field_info.table {
@@ -96,6 +118,11 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
             }
         };
         qualified_columns.push(qualified);
+        json_pairs.push(format!(
+            "'{}', {}.{column_name}",
+            field_name.trim_start_matches("r#"),
+            field_info.table.as_deref().unwrap_or(&table_name)
+        ));
 
         if is_id {
             if !is_main_table {
@@ -107,7 +134,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
             id_fields.push((column_name.clone(), &field.ty, field_ident.clone()));
         }
 
-        // Track fields for belongs_to foreign key lookups.
+        // Track main-table fields for belongs_to foreign key lookups; `main_columns` also
+        // resolves search, cursor and tree columns.
         //
         // Only include columns that originate from the model's main table; joined-table fields
         // are not valid foreign keys for this model.
@@ -116,6 +144,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
             fk_field_types.insert(column_name.clone(), &field.ty);
             fk_field_idents.insert(field_name.clone(), field_ident.clone());
             fk_field_idents.insert(column_name.clone(), field_ident.clone());
+            main_columns.insert(field_name.clone(), column_name.clone());
+            main_columns.insert(column_name.clone(), column_name.clone());
         }
 
         // Collect query field info
@@ -140,6 +170,13 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
         column_names.join(", ")
     };
 
+    // jsonb_build_object takes at most 100 arguments, so large models are concatenated.
+    let json_object = json_pairs
+        .chunks(50)
+        .map(|pairs| format!("jsonb_build_object({})", pairs.join(", ")))
+        .collect::<Vec<_>>()
+        .join(" || ");
+
     // Build JOIN clause string
     let join_sql = build_join_sql(&join_clauses);
 
@@ -185,9 +222,19 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
         id_field_ident.as_ref(),
     );
 
+    // Generate has_many_through / has_one_through methods (requires ID field)
+    let has_many_through_methods =
+        generate_through_methods(&has_many_through_relations, false, id_field_type);
+    let has_one_through_methods =
+        generate_through_methods(&has_one_through_relations, true, id_field_type);
+
     // Generate belongs_to methods
-    let belongs_to_methods =
-        generate_belongs_to_methods(&belongs_to_relations, &fk_field_types, &fk_field_idents);
+    let belongs_to_methods = generate_belongs_to_methods(
+        &belongs_to_relations,
+        &fk_field_types,
+        &fk_field_idents,
+        &main_columns,
+    );
 
     // Generate JOIN_CLAUSE constant and modified select_all if joins exist
     let join_const = if has_joins {
@@ -202,7 +249,88 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
     let generated_sql_method = generate_generated_sql_method(&table_name, &id_columns);
 
     // Generate Query struct for dynamic queries
-    let query_struct = generate_query_struct(name, &table_name, &query_fields, has_joins);
+    let query_struct = generate_query_struct(
+        name,
+        &table_name,
+        id_column.as_deref(),
+        &query_fields,
+        has_joins,
+    );
+
+    // Generate where_has_* / where_doesnt_have_* filters on the Query struct
+    let relation_filters = generate_relation_filters(
+        name,
+        id_column.is_some(),
+        &has_many_relations,
+        &has_one_relations,
+        &many_to_many_relations,
+        &has_many_through_relations,
+        &has_one_through_relations,
+        &belongs_to_relations,
+        &main_columns,
+    );
+
+    // Generate search_vector() / search() / search_ranked() from #[orm(search(...))]
+    let search_methods = match &search_config {
+        Some(search) => generate_search_methods(name, &table_name, has_joins, search, &main_columns)?,
+        None => quote! {},
+    };
+
+    // Generate CursorKey from #[orm(cursor = "...")]
+    let cursor_key_impl = match &cursor_config {
+        Some(cursor) => generate_cursor_key_impl(
+            name,
+            &table_name,
+            has_joins,
+            cursor,
+            &main_columns,
+            &fk_field_idents,
+            &fk_field_types,
+        )?,
+        None => quote! {},
+    };
+
+    // Generate TreeModel and tree helpers from #[orm(tree(parent = "..."))]
+    let tree_methods = match &tree_config {
+        Some(tree) => generate_tree_methods(
+            name,
+            has_joins,
+            tree,
+            id_column.as_deref().zip(id_field_type),
+            &main_columns,
+        )?,
+        None => quote! {},
+    };
+
+    // Prefixed select/decode helpers (pivot rows) need every field on the main table.
+    let prefixed_methods = if has_joined_fields {
+        quote! {}
+    } else {
+        quote! {
+            /// `alias.col AS <prefix>col, ...` for all columns, so the row can share a
+            /// result set with another model; read back with [`Self::from_row_prefixed`].
+            ///
+            /// Not generated for models with fields from joined tables.
+            pub fn select_list_prefixed(alias: &str, prefix: &str) -> String {
+                [#(#columns_for_alias),*]
+                    .iter()
+                    .map(|col| format!("{}.{} AS {}{}", alias, col, prefix, col))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+
+            /// Decode a row whose columns were selected with [`Self::select_list_prefixed`].
+            pub fn from_row_prefixed(
+                row: &::pgorm::tokio_postgres::Row,
+                prefix: &str,
+            ) -> pgorm::OrmResult<Self> {
+                use pgorm::RowExt;
+                ::std::result::Result::Ok(Self {
+                    #(#prefixed_extracts),*
+                })
+            }
+        }
+    };
 
     // Generate ModelPk implementation only if there's an ID field
     let model_pk_impl =
@@ -226,6 +354,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
             #ids_const
             #id_const
             pub const SELECT_LIST: &'static str = #select_list;
+            /// `jsonb_build_object(...)` of one row keyed by field name, used by `Strategy::JsonAgg`.
+            pub const JSON_OBJECT: &'static str = #json_object;
             #join_const
 
             pub fn select_list_as(alias: &str) -> String {
@@ -236,6 +366,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
                     .join(", ")
             }
 
+            #prefixed_methods
+
             #select_all_method
 
             #select_by_pk_method
@@ -252,6 +384,10 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
 
             #(#many_to_many_methods)*
 
+            #(#has_many_through_methods)*
+
+            #(#has_one_through_methods)*
+
             #(#belongs_to_methods)*
 
             #generated_sql_method
@@ -277,6 +413,14 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
 
         #query_struct
 
+        #relation_filters
+
+        #search_methods
+
+        #cursor_key_impl
+
+        #tree_methods
+
         #model_pk_impl
 
         // Auto-register this model with CheckedClient via inventory
@@ -333,7 +477,7 @@ fn pk_params_and_where(
     (params, defs, where_parts.join(" AND "))
 }
 
-/// Generate select_by_pk method when one or more ID fields exist.
+/// Generate select_by_pk / select_by_pk_for_update methods when one or more ID fields exist.
 fn generate_select_by_pk_method(
     table_name: &str,
     id_fields: &[(String, &syn::Type, syn::Ident)],
@@ -346,50 +490,56 @@ fn generate_select_by_pk_method(
     let (param_idents, param_defs, where_clause) = pk_params_and_where(table_name, id_fields, true);
     let params = quote! { &[#(&#param_idents),*] };
 
-    if has_joins {
-        quote! {
-            /// Fetch a single record by primary key columns.
-            ///
-            /// Returns `OrmError::NotFound` if no record is found.
-            pub async fn select_by_pk(
-                conn: &impl pgorm::GenericClient,
-                #(#param_defs),*
-            ) -> pgorm::OrmResult<Self>
-            where
-                Self: pgorm::FromRow,
-            {
-                let sql = ::std::format!(
-                    "SELECT {} FROM {} {} WHERE {}",
-                    Self::SELECT_LIST,
-                    Self::TABLE,
-                    Self::JOIN_CLAUSE,
-                    #where_clause,
-                );
-                let row = conn.query_one(&sql, #params).await?;
-                pgorm::FromRow::from_row(&row)
-            }
-        }
+    let (from_clause, from_args) = if has_joins {
+        (
+            "SELECT {} FROM {} {} WHERE {}",
+            quote! { Self::SELECT_LIST, Self::TABLE, Self::JOIN_CLAUSE, },
+        )
     } else {
-        quote! {
-            /// Fetch a single record by primary key columns.
-            ///
-            /// Returns `OrmError::NotFound` if no record is found.
-            pub async fn select_by_pk(
-                conn: &impl pgorm::GenericClient,
-                #(#param_defs),*
-            ) -> pgorm::OrmResult<Self>
-            where
-                Self: pgorm::FromRow,
-            {
-                let sql = ::std::format!(
-                    "SELECT {} FROM {} WHERE {}",
-                    Self::SELECT_LIST,
-                    Self::TABLE,
-                    #where_clause,
-                );
-                let row = conn.query_one(&sql, #params).await?;
-                pgorm::FromRow::from_row(&row)
-            }
+        (
+            "SELECT {} FROM {} WHERE {}",
+            quote! { Self::SELECT_LIST, Self::TABLE, },
+        )
+    };
+    // With joins, only lock the model's own rows. `OF` takes the unqualified name.
+    let lock_clause = if has_joins {
+        let locked = table_name.rsplit('.').next().unwrap_or(table_name);
+        format!(" FOR UPDATE OF {locked}")
+    } else {
+        " FOR UPDATE".to_string()
+    };
+    let locked_format = format!("{from_clause}{lock_clause}");
+
+    quote! {
+        /// Fetch a single record by primary key columns.
+        ///
+        /// Returns `OrmError::NotFound` if no record is found.
+        pub async fn select_by_pk(
+            conn: &impl pgorm::GenericClient,
+            #(#param_defs),*
+        ) -> pgorm::OrmResult<Self>
+        where
+            Self: pgorm::FromRow,
+        {
+            let sql = ::std::format!(#from_clause, #from_args #where_clause);
+            let row = conn.query_one(&sql, #params).await?;
+            pgorm::FromRow::from_row(&row)
+        }
+
+        /// Fetch a single record by primary key columns and lock it (`FOR UPDATE`).
+        ///
+        /// Only accepts transaction clients: the lock is held until the
+        /// transaction ends. Returns `OrmError::NotFound` if no record is found.
+        pub async fn select_by_pk_for_update(
+            tx: &impl pgorm::TransactionClient,
+            #(#param_defs),*
+        ) -> pgorm::OrmResult<Self>
+        where
+            Self: pgorm::FromRow,
+        {
+            let sql = ::std::format!(#locked_format, #from_args #where_clause);
+            let row = tx.query_one(&sql, #params).await?;
+            pgorm::FromRow::from_row(&row)
         }
     }
 }
@@ -604,8 +754,13 @@ fn generate_has_many_methods(
             .iter()
             .map(|rel| {
                 let method_name = format_ident!("select_{}", rel.method_name);
+                let rel_name = format_ident!("{}", rel.method_name);
                 let load_map_name = format_ident!("load_{}_map", rel.method_name);
                 let load_map_with_name = format_ident!("load_{}_map_with", rel.method_name);
+                let load_map_limited_name = format_ident!("load_{}_map_limited", rel.method_name);
+                let load_count_map_name = format_ident!("load_{}_count_map", rel.method_name);
+                let load_sum_map_name = format_ident!("load_{}_sum_map", rel.method_name);
+                let load_exists_map_name = format_ident!("load_{}_exists_map", rel.method_name);
                 let load_attach_name = format_ident!("load_{}", rel.method_name);
                 let load_attach_with_name = format_ident!("load_{}_with", rel.method_name);
                 let related_model = &rel.model;
@@ -631,6 +786,17 @@ fn generate_has_many_methods(
                         rows.iter().map(pgorm::FromRow::from_row).collect()
                     }
 
+                    /// Preload descriptor for this relation, see [`pgorm::preload`].
+                    pub fn #rel_name() -> pgorm::PreloadMany<Self, #related_model> {
+                        pgorm::PreloadMany::has_many(
+                            #related_model::SELECT_LIST,
+                            #related_model::TABLE,
+                            #related_model::JOIN_CLAUSE,
+                            #fk,
+                            #related_model::JSON_OBJECT,
+                        )
+                    }
+
                     /// Batch load related records into a map keyed by parent id.
                     ///
                     /// Exactly one extra query is executed.
@@ -697,6 +863,138 @@ fn generate_has_many_methods(
                         .await
                     }
 
+                    /// Batch load at most `n` related records per parent, in `order_by` order
+                    /// (ranked with `ROW_NUMBER()`).
+                    ///
+                    /// Exactly one extra query is executed.
+                    pub async fn #load_map_limited_name(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                        n: i64,
+                        order_by: pgorm::OrderBy,
+                    ) -> pgorm::OrmResult<pgorm::HasManyMap<#id_ty, #related_model>>
+                    where
+                        #related_model: pgorm::FromRow,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_has_many_map_limited(
+                            conn,
+                            parent_ids,
+                            #related_model::SELECT_LIST,
+                            #related_model::TABLE,
+                            #related_model::JOIN_CLAUSE,
+                            #fk,
+                            n,
+                            order_by,
+                        )
+                        .await
+                    }
+
+                    /// Count related records per parent id; parents without any map to `0`.
+                    ///
+                    /// Exactly one extra query is executed (`GROUP BY` the parent key).
+                    pub async fn #load_count_map_name(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                    ) -> pgorm::OrmResult<::std::collections::HashMap<#id_ty, i64>>
+                    where
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_has_many_count_map(
+                            conn,
+                            parent_ids,
+                            #related_model::TABLE,
+                            #fk,
+                        )
+                        .await
+                    }
+
+                    /// Sum `column` of the related records per parent id; parents without any
+                    /// map to `T::default()`.
+                    ///
+                    /// Exactly one extra query is executed (`GROUP BY` the parent key).
+                    pub async fn #load_sum_map_name<T>(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                        column: &str,
+                    ) -> pgorm::OrmResult<::std::collections::HashMap<#id_ty, T>>
+                    where
+                        T: ::pgorm::tokio_postgres::types::FromSqlOwned + ::std::default::Default,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_has_many_sum_map(
+                            conn,
+                            parent_ids,
+                            #related_model::TABLE,
+                            #fk,
+                            column,
+                        )
+                        .await
+                    }
+
+                    /// Whether each parent id has at least one related record.
+                    ///
+                    /// Exactly one extra query is executed.
+                    pub async fn #load_exists_map_name(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                    ) -> pgorm::OrmResult<::std::collections::HashMap<#id_ty, bool>>
+                    where
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_has_many_exists_map(
+                            conn,
+                            parent_ids,
+                            #related_model::TABLE,
+                            #fk,
+                        )
+                        .await
+                    }
+
                     /// Batch load related records and attach them to each base row.
                     ///
                     /// Output order matches the input `base` order.
@@ -770,6 +1068,7 @@ fn generate_has_one_methods(
             .iter()
             .map(|rel| {
                 let method_name = format_ident!("select_{}", rel.method_name);
+                let rel_name = format_ident!("{}", rel.method_name);
                 let load_map_name = format_ident!("load_{}_map", rel.method_name);
                 let load_map_with_name = format_ident!("load_{}_map_with", rel.method_name);
                 let load_map_strict_name = format_ident!("load_{}_map_strict", rel.method_name);
@@ -807,6 +1106,17 @@ fn generate_has_one_methods(
                         }
                     }
 
+                    /// Preload descriptor for this relation, see [`pgorm::preload`].
+                    pub fn #rel_name() -> pgorm::PreloadOne<Self, #related_model> {
+                        pgorm::PreloadOne::has_one(
+                            #related_model::SELECT_LIST,
+                            #related_model::TABLE,
+                            #related_model::JOIN_CLAUSE,
+                            #fk,
+                            #related_model::JSON_OBJECT,
+                        )
+                    }
+
                     /// Batch load related records into a map keyed by parent id.
                     ///
                     /// Exactly one extra query is executed.
@@ -1011,14 +1321,26 @@ fn generate_many_to_many_methods(
             .iter()
             .map(|rel| {
                 let method_name = format_ident!("select_{}", rel.method_name);
+                let rel_name = format_ident!("{}", rel.method_name);
                 let load_map_name = format_ident!("load_{}_map", rel.method_name);
                 let load_map_with_name = format_ident!("load_{}_map_with", rel.method_name);
+                let load_map_limited_name = format_ident!("load_{}_map_limited", rel.method_name);
+                let load_count_map_name = format_ident!("load_{}_count_map", rel.method_name);
+                let load_sum_map_name = format_ident!("load_{}_sum_map", rel.method_name);
+                let load_exists_map_name = format_ident!("load_{}_exists_map", rel.method_name);
                 let load_attach_name = format_ident!("load_{}", rel.method_name);
                 let load_attach_with_name = format_ident!("load_{}_with", rel.method_name);
+                let attach_name = format_ident!("attach_{}", rel.method_name);
+                let detach_name = format_ident!("detach_{}", rel.method_name);
+                let sync_name = format_ident!("sync_{}", rel.method_name);
                 let related_model = &rel.model;
                 let through = &rel.through;
                 let self_key = &rel.self_key;
                 let other_key = &rel.other_key;
+                let pivot_methods = rel
+                    .pivot
+                    .as_ref()
+                    .map(|pivot| generate_many_to_many_pivot_methods(rel, pivot, id_ty));
 
                 quote! {
                     /// Fetch related records (many_to_many relationship) through a join table.
@@ -1050,6 +1372,25 @@ fn generate_many_to_many_methods(
                         rows.iter().map(pgorm::FromRow::from_row).collect()
                     }
 
+                    /// Preload descriptor for this relation, see [`pgorm::preload`].
+                    pub fn #rel_name() -> pgorm::PreloadMany<Self, #related_model> {
+                        let select_list = if #related_model::JOIN_CLAUSE.trim().is_empty() {
+                            #related_model::select_list_as(#related_model::TABLE)
+                        } else {
+                            #related_model::SELECT_LIST.to_string()
+                        };
+                        pgorm::PreloadMany::many_to_many(
+                            select_list,
+                            #related_model::TABLE,
+                            #related_model::JOIN_CLAUSE,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            #related_model::ID,
+                            #related_model::JSON_OBJECT,
+                        )
+                    }
+
                     /// Batch load related records into a map keyed by parent id.
                     ///
                     /// Exactly one extra query is executed.
@@ -1132,6 +1473,155 @@ fn generate_many_to_many_methods(
                         .await
                     }
 
+                    /// Batch load at most `n` related records per parent, in `order_by` order
+                    /// (ranked with `ROW_NUMBER()`).
+                    ///
+                    /// Exactly one extra query is executed.
+                    pub async fn #load_map_limited_name(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                        n: i64,
+                        order_by: pgorm::OrderBy,
+                    ) -> pgorm::OrmResult<pgorm::HasManyMap<#id_ty, #related_model>>
+                    where
+                        #related_model: pgorm::FromRow,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        let select_list = if #related_model::JOIN_CLAUSE.trim().is_empty() {
+                            #related_model::select_list_as(#related_model::TABLE)
+                        } else {
+                            #related_model::SELECT_LIST.to_string()
+                        };
+                        pgorm::eager::load_many_to_many_map_limited(
+                            conn,
+                            parent_ids,
+                            select_list.as_str(),
+                            #related_model::TABLE,
+                            #related_model::JOIN_CLAUSE,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            #related_model::ID,
+                            n,
+                            order_by,
+                        )
+                        .await
+                    }
+
+                    /// Count related records per parent id; parents without any map to `0`.
+                    ///
+                    /// Exactly one extra query is executed (`GROUP BY` the parent key).
+                    pub async fn #load_count_map_name(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                    ) -> pgorm::OrmResult<::std::collections::HashMap<#id_ty, i64>>
+                    where
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_many_to_many_count_map(
+                            conn,
+                            parent_ids,
+                            #related_model::TABLE,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            #related_model::ID,
+                        )
+                        .await
+                    }
+
+                    /// Sum `column` of the related records per parent id; parents without any
+                    /// map to `T::default()`.
+                    ///
+                    /// Exactly one extra query is executed (`GROUP BY` the parent key).
+                    pub async fn #load_sum_map_name<T>(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                        column: &str,
+                    ) -> pgorm::OrmResult<::std::collections::HashMap<#id_ty, T>>
+                    where
+                        T: ::pgorm::tokio_postgres::types::FromSqlOwned + ::std::default::Default,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_many_to_many_sum_map(
+                            conn,
+                            parent_ids,
+                            #related_model::TABLE,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            #related_model::ID,
+                            column,
+                        )
+                        .await
+                    }
+
+                    /// Whether each parent id has at least one related record.
+                    ///
+                    /// Exactly one extra query is executed.
+                    pub async fn #load_exists_map_name(
+                        conn: &impl pgorm::GenericClient,
+                        base: &[Self],
+                    ) -> pgorm::OrmResult<::std::collections::HashMap<#id_ty, bool>>
+                    where
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                            + ::pgorm::tokio_postgres::types::FromSqlOwned
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static,
+                    {
+                        let parent_ids: ::std::vec::Vec<#id_ty> = base
+                            .iter()
+                            .map(|m| pgorm::ModelPk::pk(m).clone())
+                            .collect();
+
+                        pgorm::eager::load_many_to_many_exists_map(
+                            conn,
+                            parent_ids,
+                            #related_model::TABLE,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            #related_model::ID,
+                        )
+                        .await
+                    }
+
                     /// Batch load related records and attach them to each base row.
                     ///
                     /// Output order matches the input `base` order.
@@ -1186,6 +1676,88 @@ fn generate_many_to_many_methods(
                             .collect();
                         ::std::result::Result::Ok(out)
                     }
+
+                    #pivot_methods
+
+                    /// Link a related record through the join table; `pivot` sets extra
+                    /// join table columns (`SetExpr::set`). An existing link is updated.
+                    ///
+                    /// Returns the number of affected rows.
+                    pub async fn #attach_name(
+                        &self,
+                        conn: &impl pgorm::GenericClient,
+                        id: <#related_model as pgorm::ModelPk>::Id,
+                        pivot: impl ::std::iter::IntoIterator<Item = pgorm::SetExpr>,
+                    ) -> pgorm::OrmResult<u64>
+                    where
+                        #related_model: pgorm::ModelPk,
+                        <#related_model as pgorm::ModelPk>::Id: ::pgorm::tokio_postgres::types::ToSql,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql,
+                    {
+                        pgorm::pivot::attach(
+                            conn,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            self.#id_field.clone(),
+                            id,
+                            pivot.into_iter().collect(),
+                        )
+                        .await
+                    }
+
+                    /// Unlink related records from the join table.
+                    ///
+                    /// Returns the number of deleted rows.
+                    pub async fn #detach_name(
+                        &self,
+                        conn: &impl pgorm::GenericClient,
+                        ids: ::std::vec::Vec<<#related_model as pgorm::ModelPk>::Id>,
+                    ) -> pgorm::OrmResult<u64>
+                    where
+                        #related_model: pgorm::ModelPk,
+                        <#related_model as pgorm::ModelPk>::Id: ::pgorm::tokio_postgres::types::ToSql,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql,
+                    {
+                        pgorm::pivot::detach(
+                            conn,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            self.#id_field.clone(),
+                            ids,
+                        )
+                        .await
+                    }
+
+                    /// Make `ids_with_pivot` the complete set of links in one statement:
+                    /// insert missing links, update pivot columns of existing ones and
+                    /// delete all others.
+                    pub async fn #sync_name(
+                        &self,
+                        conn: &impl pgorm::GenericClient,
+                        ids_with_pivot: ::std::vec::Vec<(
+                            <#related_model as pgorm::ModelPk>::Id,
+                            ::std::vec::Vec<pgorm::SetExpr>,
+                        )>,
+                    ) -> pgorm::OrmResult<pgorm::pivot::SyncReport>
+                    where
+                        #related_model: pgorm::ModelPk,
+                        <#related_model as pgorm::ModelPk>::Id: ::pgorm::tokio_postgres::types::ToSql
+                            + ::std::cmp::Eq
+                            + ::std::hash::Hash,
+                        #id_ty: ::pgorm::tokio_postgres::types::ToSql,
+                    {
+                        pgorm::pivot::sync(
+                            conn,
+                            #through,
+                            #self_key,
+                            #other_key,
+                            self.#id_field.clone(),
+                            ids_with_pivot,
+                        )
+                        .await
+                    }
                 }
             })
             .collect()
@@ -1194,11 +1766,272 @@ fn generate_many_to_many_methods(
     }
 }
 
+/// Generate the `(Child, Pivot)` loaders of a many_to_many relation with `pivot = "..."`.
+fn generate_many_to_many_pivot_methods(
+    rel: &ManyToManyRelation,
+    pivot: &syn::Path,
+    id_ty: &syn::Type,
+) -> TokenStream {
+    let load_map_name = format_ident!("load_{}_pivot_map", rel.method_name);
+    let load_map_with_name = format_ident!("load_{}_pivot_map_with", rel.method_name);
+    let load_attach_name = format_ident!("load_{}_pivot", rel.method_name);
+    let related_model = &rel.model;
+    let through = &rel.through;
+    let self_key = &rel.self_key;
+    let other_key = &rel.other_key;
+
+    quote! {
+        /// Batch load related records with their join table row into a map keyed by parent id.
+        ///
+        /// Exactly one extra query is executed.
+        pub async fn #load_map_name(
+            conn: &impl pgorm::GenericClient,
+            base: &[Self],
+        ) -> pgorm::OrmResult<pgorm::HasManyMap<#id_ty, (#related_model, #pivot)>>
+        where
+            #related_model: pgorm::FromRow,
+            #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                + ::pgorm::tokio_postgres::types::FromSqlOwned
+                + ::std::cmp::Eq
+                + ::std::hash::Hash
+                + ::core::marker::Send
+                + ::core::marker::Sync
+                + 'static,
+        {
+            Self::#load_map_with_name(conn, base, |_| {}).await
+        }
+
+        /// Like [`Self::#load_map_name`], but allows appending filters / ordering.
+        pub async fn #load_map_with_name(
+            conn: &impl pgorm::GenericClient,
+            base: &[Self],
+            with: impl FnOnce(&mut pgorm::Sql),
+        ) -> pgorm::OrmResult<pgorm::HasManyMap<#id_ty, (#related_model, #pivot)>>
+        where
+            #related_model: pgorm::FromRow,
+            #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                + ::pgorm::tokio_postgres::types::FromSqlOwned
+                + ::std::cmp::Eq
+                + ::std::hash::Hash
+                + ::core::marker::Send
+                + ::core::marker::Sync
+                + 'static,
+        {
+            let parent_ids: ::std::vec::Vec<#id_ty> = base
+                .iter()
+                .map(|m| pgorm::ModelPk::pk(m).clone())
+                .collect();
+
+            let select_list = if #related_model::JOIN_CLAUSE.trim().is_empty() {
+                #related_model::select_list_as(#related_model::TABLE)
+            } else {
+                #related_model::SELECT_LIST.to_string()
+            };
+            pgorm::eager::load_many_to_many_pivot_map_with(
+                conn,
+                parent_ids,
+                select_list.as_str(),
+                #pivot::select_list_prefixed("jt", pgorm::eager::PIVOT_COLUMN_PREFIX).as_str(),
+                |row| #pivot::from_row_prefixed(row, pgorm::eager::PIVOT_COLUMN_PREFIX),
+                #related_model::TABLE,
+                #related_model::JOIN_CLAUSE,
+                #through,
+                #self_key,
+                #other_key,
+                #related_model::ID,
+                with,
+            )
+            .await
+        }
+
+        /// Batch load related records with their join table row and attach them to each base row.
+        ///
+        /// Output order matches the input `base` order.
+        pub async fn #load_attach_name(
+            conn: &impl pgorm::GenericClient,
+            base: ::std::vec::Vec<Self>,
+        ) -> pgorm::OrmResult<
+            ::std::vec::Vec<pgorm::Loaded<Self, ::std::vec::Vec<(#related_model, #pivot)>>>,
+        >
+        where
+            #related_model: pgorm::FromRow,
+            #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                + ::pgorm::tokio_postgres::types::FromSqlOwned
+                + ::std::cmp::Eq
+                + ::std::hash::Hash
+                + ::core::marker::Send
+                + ::core::marker::Sync
+                + 'static,
+        {
+            let mut rel_map = Self::#load_map_name(conn, &base).await?;
+            let out = base
+                .into_iter()
+                .map(|m| {
+                    let rel = rel_map.remove(pgorm::ModelPk::pk(&m)).unwrap_or_default();
+                    pgorm::Loaded { base: m, rel }
+                })
+                .collect();
+            ::std::result::Result::Ok(out)
+        }
+    }
+}
+
+/// Generate has_many_through / has_one_through relationship methods.
+fn generate_through_methods(
+    relations: &[ThroughRelation],
+    has_one: bool,
+    id_field_type: Option<&syn::Type>,
+) -> Vec<TokenStream> {
+    let Some(id_ty) = id_field_type else {
+        return vec![];
+    };
+    relations
+        .iter()
+        .map(|rel| {
+            let method_name = format_ident!("select_{}", rel.method_name);
+            let load_map_name = format_ident!("load_{}_map", rel.method_name);
+            let load_map_with_name = format_ident!("load_{}_map_with", rel.method_name);
+            let load_attach_name = format_ident!("load_{}", rel.method_name);
+            let load_attach_with_name = format_ident!("load_{}_with", rel.method_name);
+            let related_model = &rel.model;
+            let (hops, target_key) = through_hops(rel);
+
+            let (rel_ty, map_ty, loader, load_doc, unwrap_rel) = if has_one {
+                (
+                    quote! { ::std::option::Option<#related_model> },
+                    quote! { pgorm::HasOneMap<#id_ty, #related_model> },
+                    quote! { pgorm::eager::load_has_one_through_map_with },
+                    "Fetch the related record through the intermediate models (has_one_through relationship).",
+                    quote! {},
+                )
+            } else {
+                (
+                    quote! { ::std::vec::Vec<#related_model> },
+                    quote! { pgorm::HasManyMap<#id_ty, #related_model> },
+                    quote! { pgorm::eager::load_has_many_through_map_with },
+                    "Fetch related records through the intermediate models (has_many_through relationship).",
+                    quote! { .unwrap_or_default() },
+                )
+            };
+            let id_bounds = quote! {
+                #id_ty: ::pgorm::tokio_postgres::types::ToSql
+                    + ::pgorm::tokio_postgres::types::FromSqlOwned
+                    + ::std::cmp::Eq
+                    + ::std::hash::Hash
+                    + ::core::marker::Send
+                    + ::core::marker::Sync
+                    + 'static,
+            };
+
+            quote! {
+                #[doc = #load_doc]
+                pub async fn #method_name(
+                    &self,
+                    conn: &impl pgorm::GenericClient,
+                ) -> pgorm::OrmResult<#rel_ty>
+                where
+                    #related_model: pgorm::FromRow,
+                    #id_bounds
+                {
+                    let mut rel_map = Self::#load_map_name(conn, ::std::slice::from_ref(self)).await?;
+                    ::std::result::Result::Ok(rel_map.remove(pgorm::ModelPk::pk(self))#unwrap_rel)
+                }
+
+                /// Batch load related records into a map keyed by parent id.
+                ///
+                /// Exactly one extra query is executed, joining the intermediate models.
+                pub async fn #load_map_name(
+                    conn: &impl pgorm::GenericClient,
+                    base: &[Self],
+                ) -> pgorm::OrmResult<#map_ty>
+                where
+                    #related_model: pgorm::FromRow,
+                    #id_bounds
+                {
+                    Self::#load_map_with_name(conn, base, |_| {}).await
+                }
+
+                /// Like [`Self::#load_map_name`], but allows appending global filters / ordering.
+                ///
+                /// Intermediate tables are aliased `__pgorm_through_0`, `__pgorm_through_1`, ...
+                /// starting next to `Self`.
+                pub async fn #load_map_with_name(
+                    conn: &impl pgorm::GenericClient,
+                    base: &[Self],
+                    with: impl FnOnce(&mut pgorm::Sql),
+                ) -> pgorm::OrmResult<#map_ty>
+                where
+                    #related_model: pgorm::FromRow,
+                    #id_bounds
+                {
+                    let parent_ids: ::std::vec::Vec<#id_ty> = base
+                        .iter()
+                        .map(|m| pgorm::ModelPk::pk(m).clone())
+                        .collect();
+
+                    let select_list = if #related_model::JOIN_CLAUSE.trim().is_empty() {
+                        #related_model::select_list_as(#related_model::TABLE)
+                    } else {
+                        #related_model::SELECT_LIST.to_string()
+                    };
+                    #loader(
+                        conn,
+                        parent_ids,
+                        select_list.as_str(),
+                        #related_model::TABLE,
+                        #related_model::JOIN_CLAUSE,
+                        &#hops,
+                        #target_key,
+                        with,
+                    )
+                    .await
+                }
+
+                /// Batch load related records and attach them to each base row.
+                ///
+                /// Output order matches the input `base` order.
+                pub async fn #load_attach_name(
+                    conn: &impl pgorm::GenericClient,
+                    base: ::std::vec::Vec<Self>,
+                ) -> pgorm::OrmResult<::std::vec::Vec<pgorm::Loaded<Self, #rel_ty>>>
+                where
+                    #related_model: pgorm::FromRow,
+                    #id_bounds
+                {
+                    Self::#load_attach_with_name(conn, base, |_| {}).await
+                }
+
+                /// Like [`Self::#load_attach_name`], but allows appending global filters / ordering.
+                pub async fn #load_attach_with_name(
+                    conn: &impl pgorm::GenericClient,
+                    base: ::std::vec::Vec<Self>,
+                    with: impl FnOnce(&mut pgorm::Sql),
+                ) -> pgorm::OrmResult<::std::vec::Vec<pgorm::Loaded<Self, #rel_ty>>>
+                where
+                    #related_model: pgorm::FromRow,
+                    #id_bounds
+                {
+                    let mut rel_map = Self::#load_map_with_name(conn, &base, with).await?;
+                    let out = base
+                        .into_iter()
+                        .map(|m| {
+                            let rel = rel_map.remove(pgorm::ModelPk::pk(&m))#unwrap_rel;
+                            pgorm::Loaded { base: m, rel }
+                        })
+                        .collect();
+                    ::std::result::Result::Ok(out)
+                }
+            }
+        })
+        .collect()
+}
+
 /// Generate belongs_to relationship methods.
 fn generate_belongs_to_methods(
     belongs_to_relations: &[BelongsToRelation],
     fk_field_types: &HashMap<String, &syn::Type>,
     fk_field_idents: &HashMap<String, syn::Ident>,
+    main_columns: &HashMap<String, String>,
 ) -> Vec<TokenStream> {
     belongs_to_relations
         .iter()
@@ -1206,8 +2039,10 @@ fn generate_belongs_to_methods(
             // Find the field type for the foreign key
             let fk_type = fk_field_types.get(&rel.foreign_key)?;
             let fk_field = fk_field_idents.get(&rel.foreign_key)?;
+            let fk_column = main_columns.get(&rel.foreign_key)?;
             let method_name = format_ident!("select_{}", rel.method_name);
             let fk_accessor_name = format_ident!("{}_id", rel.method_name);
+            let rel_name = format_ident!("{}", rel.method_name);
             let load_map_name = format_ident!("load_{}_map", rel.method_name);
             let load_map_with_name = format_ident!("load_{}_map_with", rel.method_name);
             let load_attach_name = format_ident!("load_{}", rel.method_name);
@@ -1228,6 +2063,11 @@ fn generate_belongs_to_methods(
             } else {
                 quote!(&self.#fk_field)
             };
+            let fk_ref_expr = if fk_is_option {
+                quote!(m.#fk_field.as_ref())
+            } else {
+                quote!(::std::option::Option::Some(&m.#fk_field))
+            };
             let rel_label = &rel.method_name;
             let collect_ids_stmt = if fk_is_option {
                 quote! {
@@ -1308,6 +2148,22 @@ fn generate_belongs_to_methods(
                     #fk_accessor_expr
                 }
 
+                /// Preload descriptor for this relation, see [`pgorm::preload`].
+                pub fn #rel_name() -> pgorm::PreloadBelongsTo<Self, #related_model>
+                where
+                    #related_model: pgorm::ModelPk<Id = #fk_inner_ty>,
+                {
+                    pgorm::PreloadBelongsTo::belongs_to(
+                        #related_model::SELECT_LIST,
+                        #related_model::TABLE,
+                        #related_model::JOIN_CLAUSE,
+                        #related_model::ID,
+                        #related_model::JSON_OBJECT,
+                        #fk_column,
+                        |m: &Self| #fk_ref_expr,
+                    )
+                }
+
                 /// Batch load related parents into a map keyed by foreign key id.
                 ///
                 /// Exactly one extra query is executed.
@@ -0,0 +1,193 @@
+//! Cursor pagination support for Model derive macro.
+//!
+//! Parses `#[orm(cursor = "created_at DESC, id DESC")]` and implements `pgorm::CursorKey`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashMap;
+use syn::{DeriveInput, Result};
+
+/// One sort column of `#[orm(cursor = "...")]`.
+pub(super) struct CursorColumnSpec {
+    /// Field or column name as written in the attribute.
+    pub field: String,
+    pub desc: bool,
+    /// `Some(true)` for `NULLS FIRST`, `Some(false)` for `NULLS LAST`.
+    pub nulls_first: Option<bool>,
+}
+
+/// Parsed `#[orm(cursor = "...")]` attribute.
+pub(super) struct CursorConfig {
+    pub columns: Vec<CursorColumnSpec>,
+    pub span: proc_macro2::Span,
+}
+
+fn parse_column(spec: &str, lit: &syn::LitStr) -> Result<CursorColumnSpec> {
+    let words: Vec<String> = spec.split_whitespace().map(str::to_uppercase).collect();
+    let field = spec
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    let (desc, rest) = match words.get(1).map(String::as_str) {
+        Some("ASC") => (false, &words[2..]),
+        Some("DESC") => (true, &words[2..]),
+        _ => (false, &words[1..]),
+    };
+    let nulls_first = match rest
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => None,
+        ["NULLS", "FIRST"] => Some(true),
+        ["NULLS", "LAST"] => Some(false),
+        _ => {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!(
+                    "invalid cursor column '{spec}', expected `column [ASC|DESC] [NULLS FIRST|NULLS LAST]`"
+                ),
+            ));
+        }
+    };
+
+    Ok(CursorColumnSpec {
+        field,
+        desc,
+        nulls_first,
+    })
+}
+
+/// Parse the `#[orm(cursor = "...")]` attribute, if present.
+pub(super) fn get_cursor_config(input: &DeriveInput) -> Result<Option<CursorConfig>> {
+    let mut found = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("orm") {
+            continue;
+        }
+        let Ok(nested) = attr.parse_args::<syn::MetaNameValue>() else {
+            continue;
+        };
+        if !nested.path.is_ident("cursor") {
+            continue;
+        }
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) = &nested.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &nested.value,
+                "cursor expects a string, e.g. cursor = \"created_at DESC, id DESC\"",
+            ));
+        };
+        if found.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "only one #[orm(cursor = \"...\")] attribute is allowed",
+            ));
+        }
+
+        let columns = lit
+            .value()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| parse_column(s, lit))
+            .collect::<Result<Vec<_>>>()?;
+        if columns.len() < 2 {
+            return Err(syn::Error::new(
+                lit.span(),
+                "cursor requires at least 2 columns (end with a unique tie-breaker such as id)",
+            ));
+        }
+        found = Some(CursorConfig {
+            columns,
+            span: lit.span(),
+        });
+    }
+
+    Ok(found)
+}
+
+/// Generate `impl pgorm::CursorKey`.
+pub(super) fn generate_cursor_key_impl(
+    model_name: &syn::Ident,
+    table_name: &str,
+    has_joins: bool,
+    cursor: &CursorConfig,
+    columns: &HashMap<String, String>,
+    field_idents: &HashMap<String, syn::Ident>,
+    field_types: &HashMap<String, &syn::Type>,
+) -> Result<TokenStream> {
+    let mut column_defs = Vec::with_capacity(cursor.columns.len());
+    let mut idents = Vec::with_capacity(cursor.columns.len());
+    let mut types = Vec::with_capacity(cursor.columns.len());
+
+    for spec in &cursor.columns {
+        let (Some(column), Some(ident), Some(ty)) = (
+            columns.get(&spec.field),
+            field_idents.get(&spec.field),
+            field_types.get(&spec.field),
+        ) else {
+            return Err(syn::Error::new(
+                cursor.span,
+                format!(
+                    "cursor column '{}' is not a field of the model's main table",
+                    spec.field
+                ),
+            ));
+        };
+        let column = if has_joins && !column.contains('.') {
+            format!("{table_name}.{column}")
+        } else {
+            column.clone()
+        };
+        let dir = if spec.desc {
+            quote! { pgorm::SortDir::Desc }
+        } else {
+            quote! { pgorm::SortDir::Asc }
+        };
+        let nulls = match spec.nulls_first {
+            None => quote! { ::core::option::Option::None },
+            Some(true) => quote! { ::core::option::Option::Some(pgorm::NullsOrder::First) },
+            Some(false) => quote! { ::core::option::Option::Some(pgorm::NullsOrder::Last) },
+        };
+        column_defs.push(quote! {
+            pgorm::CursorColumn { name: #column, dir: #dir, nulls: #nulls }
+        });
+        idents.push(ident.clone());
+        types.push(*ty);
+    }
+
+    Ok(quote! {
+        impl pgorm::CursorKey for #model_name {
+            const CURSOR_COLUMNS: &'static [pgorm::CursorColumn] = &[#(#column_defs),*];
+
+            fn cursor_values(
+                &self,
+            ) -> pgorm::OrmResult<::std::vec::Vec<pgorm::page::CursorValue>> {
+                ::std::result::Result::Ok(::std::vec![
+                    #(pgorm::page::__cursor_value(&self.#idents)?),*
+                ])
+            }
+
+            fn cursor_params(
+                values: ::std::vec::Vec<pgorm::page::CursorValue>,
+            ) -> pgorm::OrmResult<
+                ::std::vec::Vec<
+                    ::std::sync::Arc<dyn pgorm::tokio_postgres::types::ToSql + Send + Sync>,
+                >,
+            > {
+                let mut values = values.into_iter();
+                ::std::result::Result::Ok(::std::vec![
+                    #(pgorm::page::__cursor_param::<#types>(values.next())?),*
+                ])
+            }
+        }
+    })
+}
@@ -1,8 +1,11 @@
 //! Join clause handling for Model derive macro.
 //!
-//! Parses `#[orm(join(table = "...", on = "...", type = "..."))]` attributes.
+//! Parses `#[orm(join(table = "...", on = "...", type = "..."))]` attributes and builds
+//! the join chains of through relations.
 
-use proc_macro2::Span;
+use super::relations::ThroughRelation;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
 use syn::ext::IdentExt;
 use syn::{DeriveInput, Result};
 
@@ -99,3 +102,30 @@ pub(super) fn get_join_clauses(input: &DeriveInput) -> Result<Vec<JoinClause>> {
 
     Ok(joins)
 }
+
+/// Build the `[pgorm::relation::ThroughHop]` chain and target key of a through relation.
+///
+/// Hop tables come from the intermediate models' constants, so the joins themselves are
+/// rendered at runtime by `pgorm::relation::through_joins`.
+pub(super) fn through_hops(rel: &ThroughRelation) -> (TokenStream, &str) {
+    let hops = rel.through.iter().enumerate().map(|(i, model)| {
+        let foreign_key = if i == 0 {
+            &rel.foreign_key
+        } else {
+            &rel.through_keys[i - 1]
+        };
+        quote! {
+            pgorm::relation::ThroughHop {
+                table: #model::TABLE,
+                id: #model::ID,
+                foreign_key: #foreign_key,
+            }
+        }
+    });
+    let target_key = rel
+        .through_keys
+        .last()
+        .map(String::as_str)
+        .unwrap_or_default();
+    (quote! { [#(#hops),*] }, target_key)
+}
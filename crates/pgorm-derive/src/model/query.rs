@@ -5,10 +5,18 @@
 //! - Filtering methods (eq, ne, gt, gte, lt, lte, like, ilike, etc.)
 //! - Ordering methods (order_by_asc, order_by_desc)
 //! - Pagination methods (limit, offset, page)
+//! - Window methods (window, define_window, top_n_per_group)
+//! - Row locking (for_update, for_share, skip_locked, nowait, of)
 //! - Execution methods (find, find_one, find_one_opt, count)
+//! - Relation filters (where_has_*, where_doesnt_have_*)
 
+use super::join::through_hops;
+use super::relations::{
+    BelongsToRelation, HasManyRelation, HasOneRelation, ManyToManyRelation, ThroughRelation,
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
+use std::collections::HashMap;
 use syn::ext::IdentExt;
 
 /// Query field info for generating the Query struct
@@ -25,10 +33,15 @@ pub(super) struct QueryFieldInfo {
 pub(super) fn generate_query_struct(
     model_name: &syn::Ident,
     table_name: &str,
+    id_column: Option<&str>,
     fields: &[QueryFieldInfo],
     has_joins: bool,
 ) -> TokenStream {
     let query_name = format_ident!("{}Query", model_name);
+    let id_const = match id_column {
+        Some(id) => quote! { ::std::option::Option::Some(#id) },
+        None => quote! { ::std::option::Option::None },
+    };
 
     // Filter out joined table fields for the query struct
     let query_fields: Vec<_> = fields.iter().filter(|f| !f.is_joined).collect();
@@ -36,24 +49,14 @@ pub(super) fn generate_query_struct(
     // Generate column constants
     let column_consts = gen_column_consts(&query_fields, table_name, has_joins);
 
-    // Generate the base SQL depending on whether we have JOINs
-    let base_sql = if has_joins {
+    // Append the JOIN clause to the FROM item only when we have JOINs
+    let push_join_clause = if has_joins {
         quote! {
-            ::std::format!(
-                "SELECT {} FROM {} {}",
-                #model_name::SELECT_LIST,
-                #model_name::TABLE,
-                #model_name::JOIN_CLAUSE
-            )
+            q.push(" ");
+            q.push(#model_name::JOIN_CLAUSE);
         }
     } else {
-        quote! {
-            ::std::format!(
-                "SELECT {} FROM {}",
-                #model_name::SELECT_LIST,
-                #model_name::TABLE
-            )
-        }
+        quote! {}
     };
 
     // Generate filtering methods
@@ -65,6 +68,27 @@ pub(super) fn generate_query_struct(
     // Generate pagination methods
     let pagination_methods = gen_pagination_methods();
 
+    // Generate window methods
+    let window_methods = gen_window_methods(model_name);
+    let lock_methods = gen_lock_methods();
+
+    // With joins, lock only the model's own rows unless `of` was given.
+    // `OF` takes the unqualified table name.
+    let append_lock = if has_joins {
+        let locked = table_name.rsplit('.').next().unwrap_or(table_name);
+        quote! {
+            if lock.has_of_tables() {
+                lock.append_to_sql(&mut q);
+            } else {
+                lock.clone().of([#locked])?.append_to_sql(&mut q);
+            }
+        }
+    } else {
+        quote! {
+            lock.append_to_sql(&mut q);
+        }
+    };
+
     // Generate execution methods
     let execution_methods = gen_execution_methods(model_name, has_joins);
 
@@ -108,6 +132,10 @@ pub(super) fn generate_query_struct(
             where_expr: pgorm::WhereExpr,
             order_by: pgorm::OrderBy,
             pagination: pgorm::Pagination,
+            windows: ::std::vec::Vec<pgorm::WindowExpr>,
+            named_windows: pgorm::NamedWindows,
+            top_n: ::std::option::Option<pgorm::TopNPerGroup>,
+            lock: ::std::option::Option<pgorm::RowLock>,
         }
 
         impl #query_name {
@@ -122,6 +150,10 @@ pub(super) fn generate_query_struct(
                     where_expr: pgorm::WhereExpr::And(::std::vec::Vec::new()),
                     order_by: pgorm::OrderBy::new(),
                     pagination: pgorm::Pagination::new(),
+                    windows: ::std::vec::Vec::new(),
+                    named_windows: pgorm::NamedWindows::new(),
+                    top_n: ::std::option::Option::None,
+                    lock: ::std::option::Option::None,
                 }
             }
         }
@@ -132,6 +164,14 @@ pub(super) fn generate_query_struct(
                 Self::default()
             }
 
+            /// Consume the query and return its WHERE expression.
+            ///
+            /// Ordering and pagination are discarded. This is what relation filters
+            /// (`where_has_*`) use to embed a nested filter into an `EXISTS` subquery.
+            pub fn into_where_expr(self) -> pgorm::WhereExpr {
+                self.where_expr
+            }
+
             // ==================== Filtering ====================
             #filtering_methods
 
@@ -141,33 +181,102 @@ pub(super) fn generate_query_struct(
             // ==================== Pagination ====================
             #pagination_methods
 
+            // ==================== Window functions ====================
+            #window_methods
+
+            // ==================== Row locking ====================
+            #lock_methods
+
             // ==================== Execution ====================
-            fn build_base_sql(&self) -> pgorm::Sql {
-                let mut q = pgorm::sql(#base_sql);
+            fn build_base_sql(&self, column: ::std::option::Option<pgorm::Sql>) -> pgorm::Sql {
+                let mut q = pgorm::sql("SELECT ");
+                q.push(#model_name::SELECT_LIST);
+                if let ::std::option::Option::Some(top_n) = &self.top_n {
+                    q.push(", ");
+                    top_n.row_number().append_to_sql(&mut q);
+                }
+                for window in &self.windows {
+                    q.push(", ");
+                    window.append_to_sql(&mut q);
+                }
+                if let ::std::option::Option::Some(column) = column {
+                    q.push(", ");
+                    q.push_sql(column);
+                }
+                q.push(" FROM ");
+                q.push(#model_name::TABLE);
+                #push_join_clause
                 if !self.where_expr.is_trivially_true() {
                     q.push(" WHERE ");
                     self.where_expr.append_to_sql(&mut q);
                 }
-                q
+                self.named_windows.append_to_sql(&mut q);
+                match &self.top_n {
+                    ::std::option::Option::Some(top_n) => top_n.wrap(q),
+                    ::std::option::Option::None => q,
+                }
             }
 
-            fn build_find_sql(&self) -> pgorm::Sql {
-                let mut q = self.build_base_sql();
+            /// Postgres rejects `FOR UPDATE` / `FOR SHARE` alongside window functions.
+            fn check_lock(&self) -> pgorm::OrmResult<()> {
+                if self.lock.is_some() && (self.top_n.is_some() || !self.windows.is_empty()) {
+                    return ::std::result::Result::Err(pgorm::OrmError::validation(
+                        "Row locks cannot be combined with window functions or top_n_per_group",
+                    ));
+                }
+                ::std::result::Result::Ok(())
+            }
+
+            fn build_find_sql(
+                &self,
+                column: ::std::option::Option<pgorm::Sql>,
+            ) -> pgorm::OrmResult<pgorm::Sql> {
+                self.check_lock()?;
+                let mut q = self.build_base_sql(column);
                 self.order_by.append_to_sql(&mut q);
                 self.pagination.append_to_sql(&mut q);
-                q
+                if let ::std::option::Option::Some(lock) = &self.lock {
+                    #append_lock
+                }
+                ::std::result::Result::Ok(q)
             }
 
-            fn build_first_sql(&self) -> pgorm::Sql {
-                let mut q = self.build_base_sql();
+            fn build_first_sql(
+                &self,
+                column: ::std::option::Option<pgorm::Sql>,
+            ) -> pgorm::OrmResult<pgorm::Sql> {
+                self.check_lock()?;
+                let mut q = self.build_base_sql(column);
                 self.order_by.append_to_sql(&mut q);
                 q.limit(1);
-                q
+                if let ::std::option::Option::Some(lock) = &self.lock {
+                    #append_lock
+                }
+                ::std::result::Result::Ok(q)
             }
 
             #execution_methods
         }
 
+        impl pgorm::preload::ModelQuery<#model_name> for #query_name {
+            const TABLE: &'static str = #model_name::TABLE;
+            const ID: ::std::option::Option<&'static str> = #id_const;
+
+            fn find_sql(
+                &self,
+                column: ::std::option::Option<pgorm::Sql>,
+            ) -> pgorm::OrmResult<pgorm::Sql> {
+                self.build_find_sql(column)
+            }
+
+            fn first_sql(
+                &self,
+                column: ::std::option::Option<pgorm::Sql>,
+            ) -> pgorm::OrmResult<pgorm::Sql> {
+                self.build_first_sql(column)
+            }
+        }
+
         impl #model_name {
             /// Create a new query builder for dynamic queries.
             pub fn query() -> #query_name {
@@ -254,6 +363,25 @@ fn gen_column_consts(
                     | "count"
                     | "find_one"
                     | "find_one_opt"
+                    | "into_where_expr"
+                    | "preload"
+                    | "window"
+                    | "define_window"
+                    | "top_n_per_group"
+                    | "find_as"
+                    | "search"
+                    | "search_ranked"
+                    | "paginate_with_total"
+                    | "lock"
+                    | "lock_strength"
+                    | "check_lock"
+                    | "for_update"
+                    | "for_no_key_update"
+                    | "for_share"
+                    | "for_key_share"
+                    | "skip_locked"
+                    | "nowait"
+                    | "of"
             );
 
             if is_reserved {
@@ -838,7 +966,124 @@ fn gen_pagination_methods() -> TokenStream {
     }
 }
 
-/// Generate execution methods (find, find_one, find_one_opt, count)
+/// Generate row-locking methods (for_update, for_share, skip_locked, nowait, of)
+fn gen_lock_methods() -> TokenStream {
+    quote! {
+        /// Replace the row-locking clause.
+        pub fn lock(mut self, lock: pgorm::RowLock) -> Self {
+            self.lock = ::std::option::Option::Some(lock);
+            self
+        }
+
+        fn lock_strength(mut self, strength: pgorm::LockStrength) -> Self {
+            self.lock = ::std::option::Option::Some(match self.lock.take() {
+                ::std::option::Option::Some(lock) => lock.strength(strength),
+                ::std::option::Option::None => pgorm::RowLock::new(strength),
+            });
+            self
+        }
+
+        /// Lock the selected rows with `FOR UPDATE` (run inside a transaction).
+        ///
+        /// On models with joins only the model's own table is locked
+        /// (`FOR UPDATE OF <table>`) unless [`Self::of`] names other tables.
+        pub fn for_update(self) -> Self {
+            self.lock_strength(pgorm::LockStrength::Update)
+        }
+
+        /// Lock the selected rows with `FOR NO KEY UPDATE`.
+        pub fn for_no_key_update(self) -> Self {
+            self.lock_strength(pgorm::LockStrength::NoKeyUpdate)
+        }
+
+        /// Lock the selected rows with `FOR SHARE`.
+        pub fn for_share(self) -> Self {
+            self.lock_strength(pgorm::LockStrength::Share)
+        }
+
+        /// Lock the selected rows with `FOR KEY SHARE`.
+        pub fn for_key_share(self) -> Self {
+            self.lock_strength(pgorm::LockStrength::KeyShare)
+        }
+
+        /// Skip rows locked by other transactions (`SKIP LOCKED`).
+        ///
+        /// Implies `FOR UPDATE` when no lock strength was chosen.
+        pub fn skip_locked(mut self) -> Self {
+            self.lock = ::std::option::Option::Some(
+                self.lock.take().unwrap_or_else(pgorm::RowLock::for_update).skip_locked(),
+            );
+            self
+        }
+
+        /// Fail with `OrmError::LockNotAvailable` instead of waiting (`NOWAIT`).
+        ///
+        /// Implies `FOR UPDATE` when no lock strength was chosen.
+        pub fn nowait(mut self) -> Self {
+            self.lock = ::std::option::Option::Some(
+                self.lock.take().unwrap_or_else(pgorm::RowLock::for_update).nowait(),
+            );
+            self
+        }
+
+        /// Restrict the lock to the given tables or aliases (`OF t1, t2`).
+        ///
+        /// Implies `FOR UPDATE` when no lock strength was chosen.
+        pub fn of<I, T>(mut self, tables: I) -> pgorm::OrmResult<Self>
+        where
+            I: ::core::iter::IntoIterator<Item = T>,
+            T: pgorm::IntoIdent,
+        {
+            self.lock = ::std::option::Option::Some(
+                self.lock.take().unwrap_or_else(pgorm::RowLock::for_update).of(tables)?,
+            );
+            ::std::result::Result::Ok(self)
+        }
+    }
+}
+
+/// Generate window methods (window, define_window, top_n_per_group)
+fn gen_window_methods(model_name: &syn::Ident) -> TokenStream {
+    quote! {
+        /// Add a window function column to the SELECT list.
+        ///
+        /// Give it an alias and read it back with [`Self::find_as`].
+        pub fn window(mut self, expr: pgorm::WindowExpr) -> Self {
+            self.windows.push(expr);
+            self
+        }
+
+        /// Define a named window (`WINDOW name AS (...)`) usable via `over_named`.
+        pub fn define_window(
+            mut self,
+            name: impl pgorm::IntoIdent,
+            spec: pgorm::WindowSpec,
+        ) -> pgorm::OrmResult<Self> {
+            let windows = self.named_windows;
+            self.named_windows = windows.define(name, spec)?;
+            ::std::result::Result::Ok(self)
+        }
+
+        /// Keep only the first `n` rows of each `partition_by` group, ranked by `order_by`.
+        ///
+        /// Filters apply before ranking; ORDER BY / pagination apply to the kept rows.
+        pub fn top_n_per_group(
+            mut self,
+            partition_by: impl pgorm::IntoIdent,
+            order_by: pgorm::OrderBy,
+            n: i64,
+        ) -> pgorm::OrmResult<Self> {
+            let table = #model_name::TABLE;
+            let alias = table.rsplit('.').next().unwrap_or(table);
+            self.top_n = ::std::option::Option::Some(
+                pgorm::TopNPerGroup::new(partition_by, order_by, n)?.alias(alias)?,
+            );
+            ::std::result::Result::Ok(self)
+        }
+    }
+}
+
+/// Generate execution methods (find, find_one, find_one_opt, count, paginate_with_total)
 fn gen_execution_methods(model_name: &syn::Ident, has_joins: bool) -> TokenStream {
     quote! {
         /// Execute the query and return matching records.
@@ -849,12 +1094,19 @@ fn gen_execution_methods(model_name: &syn::Ident, has_joins: bool) -> TokenStrea
         where
             #model_name: pgorm::FromRow,
         {
-            let q = self.build_find_sql();
+            let q = self.build_find_sql(::std::option::Option::None)?;
             q.fetch_all_as(conn).await
         }
 
         /// Count the number of matching records.
         pub async fn count(&self, conn: &impl pgorm::GenericClient) -> pgorm::OrmResult<i64> {
+            if self.top_n.is_some() {
+                let mut q = pgorm::sql("SELECT COUNT(*) FROM (");
+                q.push_sql(self.build_base_sql(::std::option::Option::None));
+                q.push(") AS __pgorm_count");
+                return q.fetch_scalar_one(conn).await;
+            }
+
             let mut q = pgorm::sql(if #has_joins {
                 ::std::format!(
                     "SELECT COUNT(*) FROM {} {}",
@@ -873,6 +1125,38 @@ fn gen_execution_methods(model_name: &syn::Ident, has_joins: bool) -> TokenStrea
             q.fetch_scalar_one(conn).await
         }
 
+        /// Execute the query as one offset page, returning the total count from the same statement.
+        ///
+        /// Replaces any `limit` / `offset` / `page` set on the builder.
+        pub async fn paginate_with_total(
+            &self,
+            conn: &impl pgorm::GenericClient,
+            page: i64,
+            per_page: i64,
+            count: pgorm::CountMode,
+        ) -> pgorm::OrmResult<pgorm::Paginated<#model_name>>
+        where
+            #model_name: pgorm::FromRow,
+        {
+            let mut q = self.build_base_sql(::std::option::Option::None);
+            self.order_by.append_to_sql(&mut q);
+            q.paginate_with_total(conn, page, per_page, count).await
+        }
+
+        /// Execute the query and map rows into a custom type.
+        ///
+        /// Useful when the projection includes window columns added via [`Self::window`].
+        pub async fn find_as<T>(
+            &self,
+            conn: &impl pgorm::GenericClient,
+        ) -> pgorm::OrmResult<::std::vec::Vec<T>>
+        where
+            T: pgorm::FromRow,
+        {
+            let q = self.build_find_sql(::std::option::Option::None)?;
+            q.fetch_all_as(conn).await
+        }
+
         /// Execute the query and return the first matching record.
         pub async fn find_one(
             &self,
@@ -881,7 +1165,7 @@ fn gen_execution_methods(model_name: &syn::Ident, has_joins: bool) -> TokenStrea
         where
             #model_name: pgorm::FromRow,
         {
-            let q = self.build_first_sql();
+            let q = self.build_first_sql(::std::option::Option::None)?;
             q.fetch_one_as(conn).await
         }
 
@@ -893,7 +1177,7 @@ fn gen_execution_methods(model_name: &syn::Ident, has_joins: bool) -> TokenStrea
         where
             #model_name: pgorm::FromRow,
         {
-            let q = self.build_first_sql();
+            let q = self.build_first_sql(::std::option::Option::None)?;
             q.fetch_opt_as(conn).await
         }
 
@@ -902,8 +1186,203 @@ fn gen_execution_methods(model_name: &syn::Ident, has_joins: bool) -> TokenStrea
             &self,
             conn: &impl pgorm::GenericClient,
         ) -> pgorm::OrmResult<bool> {
-            let q = self.build_first_sql();
+            let q = self.build_first_sql(::std::option::Option::None)?;
             q.exists(conn).await
         }
+
+        /// Preload a relation (and its nested `then` levels) for every matching record.
+        ///
+        /// Each level runs one extra query; see [`pgorm::preload`].
+        pub fn preload<R>(self, relation: R) -> pgorm::preload::PreloadQuery<Self, #model_name, R>
+        where
+            #model_name: pgorm::FromRow,
+            R: pgorm::Preload<#model_name>,
+        {
+            pgorm::preload::PreloadQuery::new(self, relation)
+        }
+    }
+}
+
+/// Build the query struct path of a related model (e.g. `crate::Order` -> `crate::OrderQuery`).
+fn related_query_path(model: &syn::Path) -> syn::Path {
+    let mut path = model.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.ident = format_ident!("{}Query", last.ident);
+        last.arguments = syn::PathArguments::None;
+    }
+    path
+}
+
+/// Generate `where_has_<rel>` / `where_doesnt_have_<rel>` methods for a single relation.
+///
+/// `exists_expr` must evaluate to a `pgorm::WhereExpr` and may reference `filter`.
+fn gen_relation_filter(
+    method_name: &str,
+    related_model: &syn::Path,
+    exists_expr: TokenStream,
+) -> TokenStream {
+    let related_query = related_query_path(related_model);
+    let has_name = format_ident!("where_has_{}", method_name);
+    let has_with_name = format_ident!("where_has_{}_with", method_name);
+    let doesnt_have_name = format_ident!("where_doesnt_have_{}", method_name);
+    let doesnt_have_with_name = format_ident!("where_doesnt_have_{}_with", method_name);
+
+    quote! {
+        /// Keep rows that have at least one related record (`EXISTS` subquery).
+        pub fn #has_name(self) -> Self {
+            let filter = pgorm::WhereExpr::And(::std::vec::Vec::new());
+            self.and(#exists_expr)
+        }
+
+        /// Like [`Self::#has_name`], but only counts related records matching the
+        /// filter built on the related model's query builder.
+        ///
+        /// Ordering and pagination of the nested query are ignored.
+        pub fn #has_with_name(
+            self,
+            f: impl FnOnce(#related_query) -> pgorm::OrmResult<#related_query>,
+        ) -> pgorm::OrmResult<Self> {
+            let filter = f(#related_query::new())?.into_where_expr();
+            ::std::result::Result::Ok(self.and(#exists_expr))
+        }
+
+        /// Keep rows that have no related record (`NOT EXISTS` subquery).
+        pub fn #doesnt_have_name(self) -> Self {
+            let filter = pgorm::WhereExpr::And(::std::vec::Vec::new());
+            self.and(pgorm::WhereExpr::not(#exists_expr))
+        }
+
+        /// Like [`Self::#doesnt_have_name`], but only considers related records
+        /// matching the filter built on the related model's query builder.
+        pub fn #doesnt_have_with_name(
+            self,
+            f: impl FnOnce(#related_query) -> pgorm::OrmResult<#related_query>,
+        ) -> pgorm::OrmResult<Self> {
+            let filter = f(#related_query::new())?.into_where_expr();
+            ::std::result::Result::Ok(self.and(pgorm::WhereExpr::not(#exists_expr)))
+        }
+    }
+}
+
+/// Generate relation-aware filters on the Query struct.
+///
+/// has_many / has_one / many_to_many require a single `#[orm(id)]` field;
+/// belongs_to requires the foreign key to be a column of the model's main table.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn generate_relation_filters(
+    model_name: &syn::Ident,
+    has_single_id: bool,
+    has_many: &[HasManyRelation],
+    has_one: &[HasOneRelation],
+    many_to_many: &[ManyToManyRelation],
+    has_many_through: &[ThroughRelation],
+    has_one_through: &[ThroughRelation],
+    belongs_to: &[BelongsToRelation],
+    main_columns: &HashMap<String, String>,
+) -> TokenStream {
+    let query_name = format_ident!("{}Query", model_name);
+    let mut methods = Vec::new();
+
+    if has_single_id {
+        let child_rels = has_many
+            .iter()
+            .map(|r| (&r.method_name, &r.model, &r.foreign_key))
+            .chain(
+                has_one
+                    .iter()
+                    .map(|r| (&r.method_name, &r.model, &r.foreign_key)),
+            );
+        for (method_name, related_model, fk) in child_rels {
+            methods.push(gen_relation_filter(
+                method_name,
+                related_model,
+                quote! {
+                    pgorm::relation::has_many_exists(
+                        #model_name::TABLE,
+                        #model_name::ID,
+                        #related_model::TABLE,
+                        #related_model::JOIN_CLAUSE,
+                        #fk,
+                        filter,
+                    )
+                },
+            ));
+        }
+
+        for rel in many_to_many {
+            let related_model = &rel.model;
+            let through = &rel.through;
+            let self_key = &rel.self_key;
+            let other_key = &rel.other_key;
+            methods.push(gen_relation_filter(
+                &rel.method_name,
+                related_model,
+                quote! {
+                    pgorm::relation::many_to_many_exists(
+                        #model_name::TABLE,
+                        #model_name::ID,
+                        #related_model::TABLE,
+                        #related_model::JOIN_CLAUSE,
+                        #related_model::ID,
+                        #through,
+                        #self_key,
+                        #other_key,
+                        filter,
+                    )
+                },
+            ));
+        }
+
+        for rel in has_many_through.iter().chain(has_one_through) {
+            let related_model = &rel.model;
+            let (hops, target_key) = through_hops(rel);
+            methods.push(gen_relation_filter(
+                &rel.method_name,
+                related_model,
+                quote! {
+                    pgorm::relation::has_many_through_exists(
+                        #model_name::TABLE,
+                        #model_name::ID,
+                        #related_model::TABLE,
+                        #related_model::JOIN_CLAUSE,
+                        #target_key,
+                        &#hops,
+                        filter,
+                    )
+                },
+            ));
+        }
+    }
+
+    for rel in belongs_to {
+        let Some(fk_column) = main_columns.get(&rel.foreign_key) else {
+            continue;
+        };
+        let related_model = &rel.model;
+        methods.push(gen_relation_filter(
+            &rel.method_name,
+            related_model,
+            quote! {
+                pgorm::relation::belongs_to_exists(
+                    #model_name::TABLE,
+                    #fk_column,
+                    #related_model::TABLE,
+                    #related_model::JOIN_CLAUSE,
+                    #related_model::ID,
+                    filter,
+                )
+            },
+        ));
+    }
+
+    if methods.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        impl #query_name {
+            // ==================== Relation filters ====================
+            #(#methods)*
+        }
     }
 }
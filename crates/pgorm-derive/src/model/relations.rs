@@ -5,6 +5,7 @@
 //! - `#[orm(has_one(...))]`
 //! - `#[orm(belongs_to(...))]`
 //! - `#[orm(many_to_many(...))]`
+//! - `#[orm(has_many_through(...))]` / `#[orm(has_one_through(...))]`
 
 use proc_macro2::Span;
 use syn::ext::IdentExt;
@@ -50,10 +51,28 @@ pub(super) struct ManyToManyRelation {
     pub self_key: String,
     /// Join table column that references the other model (e.g., "tag_id")
     pub other_key: String,
+    /// Optional `FromRow` type for extra join table columns (e.g., PostTag)
+    pub pivot: Option<syn::Path>,
     /// The method name to generate (e.g., "tags" -> load_tags_map)
     pub method_name: String,
 }
 
+/// Represents a has_many_through / has_one_through relationship: Parent reaches the
+/// related model through one or more intermediate models.
+pub(super) struct ThroughRelation {
+    /// The related model type (e.g., Post)
+    pub model: syn::Path,
+    /// Intermediate models, from Self towards the related model (e.g., [User])
+    pub through: Vec<syn::Path>,
+    /// Column of the first intermediate model that references Self (e.g., "country_id")
+    pub foreign_key: String,
+    /// One column per intermediate model, referencing it from the next model in the
+    /// chain; the last one lives on the related model (e.g., ["user_id"])
+    pub through_keys: Vec<String>,
+    /// The method name to generate (e.g., "posts" -> load_posts_map)
+    pub method_name: String,
+}
+
 /// Helper struct for parsing has_many attribute
 struct HasManyAttr {
     model: syn::Path,
@@ -132,6 +151,7 @@ struct ManyToManyAttr {
     through: String,
     self_key: String,
     other_key: String,
+    pivot: Option<syn::Path>,
     method_name: String,
 }
 
@@ -254,6 +274,7 @@ impl syn::parse::Parse for ManyToManyAttr {
         let mut through: Option<String> = None;
         let mut self_key: Option<String> = None;
         let mut other_key: Option<String> = None;
+        let mut pivot: Option<syn::Path> = None;
         let mut method_name: Option<String> = None;
 
         while content.peek(syn::Token![,]) {
@@ -272,6 +293,8 @@ impl syn::parse::Parse for ManyToManyAttr {
                 self_key = Some(value.value());
             } else if key == "other_key" {
                 other_key = Some(value.value());
+            } else if key == "pivot" {
+                pivot = Some(value.parse()?);
             } else if key == "as" || key == "name" {
                 method_name = Some(value.value());
             }
@@ -304,11 +327,131 @@ impl syn::parse::Parse for ManyToManyAttr {
             through,
             self_key,
             other_key,
+            pivot,
             method_name: name,
         })
     }
 }
 
+/// Helper struct for parsing has_many_through / has_one_through attributes
+struct ThroughAttr {
+    has_one: bool,
+    relation: ThroughRelation,
+}
+
+impl syn::parse::Parse for ThroughAttr {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        let has_one = if ident == "has_many_through" {
+            false
+        } else if ident == "has_one_through" {
+            true
+        } else {
+            return Err(syn::Error::new(
+                ident.span(),
+                "expected has_many_through or has_one_through",
+            ));
+        };
+
+        let content;
+        syn::parenthesized!(content in input);
+
+        let model: syn::Path = content.parse()?;
+
+        let mut through: Vec<syn::Path> = Vec::new();
+        let mut foreign_key: Option<String> = None;
+        let mut through_keys: Vec<String> = Vec::new();
+        let mut method_name: Option<String> = None;
+
+        while content.peek(syn::Token![,]) {
+            let _: syn::Token![,] = content.parse()?;
+            if content.is_empty() {
+                break;
+            }
+
+            let key = syn::Ident::parse_any(&content)?;
+            let _: syn::Token![=] = content.parse()?;
+
+            if key == "through" {
+                through = parse_one_or_list(&content, |c| {
+                    if c.peek(syn::LitStr) {
+                        c.parse::<syn::LitStr>()?.parse()
+                    } else {
+                        c.parse()
+                    }
+                })?;
+                continue;
+            }
+            if key == "through_key" {
+                through_keys =
+                    parse_one_or_list(&content, |c| Ok(c.parse::<syn::LitStr>()?.value()))?;
+                continue;
+            }
+
+            let value: syn::LitStr = content.parse()?;
+            if key == "foreign_key" {
+                foreign_key = Some(value.value());
+            } else if key == "as" || key == "name" {
+                method_name = Some(value.value());
+            }
+        }
+
+        if through.is_empty() {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("{ident} requires through = Model or through = [Model, ...]"),
+            ));
+        }
+        let foreign_key = foreign_key.ok_or_else(|| {
+            syn::Error::new(
+                ident.span(),
+                format!("{ident} requires foreign_key = \"...\""),
+            )
+        })?;
+        if through_keys.len() != through.len() {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("{ident} requires one through_key per through model"),
+            ));
+        }
+
+        // Default method name: lowercase model name (+ 's' for has_many_through)
+        let name = method_name.unwrap_or_else(|| {
+            let model_name = model.segments.last().unwrap().ident.to_string();
+            if has_one {
+                model_name.to_lowercase()
+            } else {
+                format!("{}s", model_name.to_lowercase())
+            }
+        });
+
+        Ok(ThroughAttr {
+            has_one,
+            relation: ThroughRelation {
+                model,
+                through,
+                foreign_key,
+                through_keys,
+                method_name: name,
+            },
+        })
+    }
+}
+
+/// Parse either a single value or a bracketed, comma-separated list of values.
+fn parse_one_or_list<T>(
+    input: syn::parse::ParseStream,
+    parse: fn(syn::parse::ParseStream) -> Result<T>,
+) -> Result<Vec<T>> {
+    if !input.peek(syn::token::Bracket) {
+        return Ok(vec![parse(input)?]);
+    }
+    let content;
+    syn::bracketed!(content in input);
+    let items = content.parse_terminated(parse, syn::Token![,])?;
+    Ok(items.into_iter().collect())
+}
+
 /// Parse has_many relations from struct attributes.
 ///
 /// Example: `#[orm(has_many(Review, foreign_key = "product_id", as = "reviews"))]`
@@ -387,6 +530,8 @@ pub(super) fn get_belongs_to_relations(input: &DeriveInput) -> Result<Vec<Belong
 ///
 /// Example:
 /// `#[orm(many_to_many(Tag, through = "post_tags", self_key = "post_id", other_key = "tag_id", as = "tags"))]`
+///
+/// An optional `pivot = "PostTag"` names a `FromRow` + `Model` type for extra join table columns.
 pub(super) fn get_many_to_many_relations(input: &DeriveInput) -> Result<Vec<ManyToManyRelation>> {
     let mut relations = Vec::new();
 
@@ -400,6 +545,7 @@ pub(super) fn get_many_to_many_relations(input: &DeriveInput) -> Result<Vec<Many
                         through: parsed.through,
                         self_key: parsed.self_key,
                         other_key: parsed.other_key,
+                        pivot: parsed.pivot,
                         method_name: parsed.method_name,
                     });
                 }
@@ -409,3 +555,43 @@ pub(super) fn get_many_to_many_relations(input: &DeriveInput) -> Result<Vec<Many
 
     Ok(relations)
 }
+
+/// Parse has_many_through and has_one_through relations from struct attributes,
+/// returned as `(has_many_through, has_one_through)`.
+///
+/// Example:
+/// `#[orm(has_many_through(Post, through = User, foreign_key = "country_id", through_key = "user_id", as = "posts"))]`
+///
+/// Longer chains list every intermediate model and key:
+/// `through = [User, Post], through_key = ["user_id", "post_id"]`.
+pub(super) fn get_through_relations(
+    input: &DeriveInput,
+) -> Result<(Vec<ThroughRelation>, Vec<ThroughRelation>)> {
+    let mut has_many = Vec::new();
+    let mut has_one = Vec::new();
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("orm") {
+            if let syn::Meta::List(meta_list) = &attr.meta {
+                let tokens = meta_list.tokens.clone();
+                let is_through = matches!(
+                    tokens.clone().into_iter().next(),
+                    Some(proc_macro2::TokenTree::Ident(ident))
+                        if ident == "has_many_through" || ident == "has_one_through"
+                );
+                if !is_through {
+                    continue;
+                }
+                // Matched by name first, so a malformed through relation is an error, not skipped.
+                let parsed = syn::parse2::<ThroughAttr>(tokens)?;
+                if parsed.has_one {
+                    has_one.push(parsed.relation);
+                } else {
+                    has_many.push(parsed.relation);
+                }
+            }
+        }
+    }
+
+    Ok((has_many, has_one))
+}
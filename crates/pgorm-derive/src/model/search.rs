@@ -0,0 +1,256 @@
+//! Full-text search support for Model derive macro.
+//!
+//! Parses `#[orm(search(fields = "title, body", weights = "A, B", config = "english"))]`
+//! and generates `search` / `search_ranked` on the Query struct.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::{DeriveInput, Result};
+
+/// Parsed `#[orm(search(...))]` attribute.
+pub(super) struct SearchConfig {
+    /// Field or column names making up the document.
+    pub fields: Vec<String>,
+    /// Optional `setweight` labels, one per field.
+    pub weights: Vec<String>,
+    /// Optional text search configuration, e.g. `english`.
+    pub config: Option<String>,
+}
+
+impl syn::parse::Parse for SearchConfig {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        if ident != "search" {
+            return Err(syn::Error::new(ident.span(), "expected search"));
+        }
+
+        let content;
+        syn::parenthesized!(content in input);
+
+        let mut fields: Option<syn::LitStr> = None;
+        let mut weights: Option<syn::LitStr> = None;
+        let mut config: Option<String> = None;
+
+        loop {
+            if content.is_empty() {
+                break;
+            }
+
+            let key = syn::Ident::parse_any(&content)?;
+            let _: syn::Token![=] = content.parse()?;
+            let value: syn::LitStr = content.parse()?;
+
+            if key == "fields" {
+                fields = Some(value);
+            } else if key == "weights" {
+                weights = Some(value);
+            } else if key == "config" {
+                let name = value.value();
+                // Same rule as `TsQuery::config` / `TsVector::config` at runtime.
+                let valid = !name.is_empty()
+                    && !name.starts_with('.')
+                    && !name.ends_with('.')
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+                if !valid {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        format!("invalid text search configuration '{name}'"),
+                    ));
+                }
+                config = Some(name);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "unknown search option (expected fields, weights or config)",
+                ));
+            }
+
+            if content.peek(syn::Token![,]) {
+                let _: syn::Token![,] = content.parse()?;
+            } else {
+                break;
+            }
+        }
+
+        let fields_lit = fields.ok_or_else(|| {
+            syn::Error::new(Span::call_site(), "search requires fields = \"...\"")
+        })?;
+        let fields = split_list(&fields_lit.value());
+        if fields.is_empty() {
+            return Err(syn::Error::new(
+                fields_lit.span(),
+                "search fields cannot be empty",
+            ));
+        }
+
+        let weights = match weights {
+            Some(lit) => {
+                let weights = split_list(&lit.value());
+                if weights.len() != fields.len() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "search weights must list one weight per field",
+                    ));
+                }
+                if let Some(bad) = weights
+                    .iter()
+                    .find(|w| !matches!(w.as_str(), "A" | "B" | "C" | "D" | "a" | "b" | "c" | "d"))
+                {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!("invalid search weight '{bad}', expected A, B, C or D"),
+                    ));
+                }
+                weights
+            }
+            None => Vec::new(),
+        };
+
+        Ok(SearchConfig {
+            fields,
+            weights,
+            config,
+        })
+    }
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Parse the `#[orm(search(...))]` attribute, if present.
+pub(super) fn get_search_config(input: &DeriveInput) -> Result<Option<SearchConfig>> {
+    let mut found = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("orm") {
+            continue;
+        }
+        if let syn::Meta::List(meta_list) = &attr.meta {
+            let is_search = matches!(
+                meta_list.tokens.clone().into_iter().next(),
+                Some(proc_macro2::TokenTree::Ident(ident)) if ident == "search"
+            );
+            if !is_search {
+                continue;
+            }
+            if found.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "only one #[orm(search(...))] attribute is allowed",
+                ));
+            }
+            found = Some(syn::parse2::<SearchConfig>(meta_list.tokens.clone())?);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Generate `Model::search_vector()` and `search` / `search_ranked` on the Query struct.
+pub(super) fn generate_search_methods(
+    model_name: &syn::Ident,
+    table_name: &str,
+    has_joins: bool,
+    search: &SearchConfig,
+    columns: &HashMap<String, String>,
+) -> Result<TokenStream> {
+    let query_name = format_ident!("{}Query", model_name);
+
+    let mut builders = Vec::with_capacity(search.fields.len());
+    for (i, field) in search.fields.iter().enumerate() {
+        let column = columns.get(field).ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                format!("search field '{field}' is not a column of the model's main table"),
+            )
+        })?;
+        let column = if has_joins && !column.contains('.') {
+            format!("{table_name}.{column}")
+        } else {
+            column.clone()
+        };
+        builders.push(match search.weights.get(i) {
+            Some(weight) => {
+                let weight = format_ident!("{}", weight.to_uppercase());
+                quote! { .weighted(#column, pgorm::TsWeight::#weight)? }
+            }
+            None => quote! { .field(#column)? },
+        });
+    }
+
+    let vector_config = search.config.as_ref().map(|c| quote! { .config(#c)? });
+    let query_config = search
+        .config
+        .as_ref()
+        .map(|c| quote! { let query = query.config(#c)?; });
+
+    Ok(quote! {
+        impl #model_name {
+            /// The full-text document declared by `#[orm(search(...))]`.
+            pub fn search_vector() -> pgorm::OrmResult<pgorm::TsVector> {
+                let vector = pgorm::TsVector::new() #(#builders)* #vector_config;
+                ::std::result::Result::Ok(vector)
+            }
+        }
+
+        impl #query_name {
+            // ==================== Full-text search ====================
+
+            /// Filter by full-text search over the `#[orm(search(...))]` document
+            /// (`websearch_to_tsquery` syntax).
+            pub fn search(
+                self,
+                q: impl ::core::convert::Into<::std::string::String>,
+            ) -> pgorm::OrmResult<Self> {
+                let query = pgorm::TsQuery::websearch(q);
+                #query_config
+                let cond = pgorm::Condition::ts_search(&#model_name::search_vector()?, &query);
+                ::std::result::Result::Ok(self.and(cond.into()))
+            }
+
+            /// Like [`search`](Self::search), and also sort by `ts_rank` (most relevant first).
+            ///
+            /// The rank comes before any existing ordering, which then only breaks ties.
+            pub fn search_ranked(
+                mut self,
+                q: impl ::core::convert::Into<::std::string::String>,
+            ) -> pgorm::OrmResult<Self> {
+                let query = pgorm::TsQuery::websearch(q);
+                #query_config
+                let vector = #model_name::search_vector()?;
+                let cond = pgorm::Condition::ts_search(&vector, &query);
+                let order = self.order_by;
+                self.order_by = order.prepend(pgorm::OrderItem::rank(
+                    pgorm::TsRank::new(vector, query),
+                    pgorm::SortDir::Desc,
+                ));
+                ::std::result::Result::Ok(self.and(cond.into()))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_is_validated() {
+        let parsed: SearchConfig =
+            syn::parse_str(r#"search(fields = "title", config = "pg_catalog.english")"#).unwrap();
+        assert_eq!(parsed.config.as_deref(), Some("pg_catalog.english"));
+
+        for bad in ["", "english'; DROP", ".english", "simple "] {
+            let attr = format!(r#"search(fields = "title", config = {bad:?})"#);
+            assert!(syn::parse_str::<SearchConfig>(&attr).is_err(), "{bad:?}");
+        }
+    }
+}
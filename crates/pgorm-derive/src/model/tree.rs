@@ -0,0 +1,175 @@
+//! Tree (adjacency list) support for Model derive macro.
+//!
+//! Parses `#[orm(tree(parent = "parent_id"))]`, implements `pgorm::TreeModel` and
+//! generates `roots` / `ancestors` / `path_to_root` / `descendants` / `subtree_as_nested`.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::{DeriveInput, Result};
+
+/// Parsed `#[orm(tree(...))]` attribute.
+pub(super) struct TreeConfig {
+    /// Field or column name of the parent reference.
+    pub parent: String,
+    pub span: Span,
+}
+
+impl syn::parse::Parse for TreeConfig {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        if ident != "tree" {
+            return Err(syn::Error::new(ident.span(), "expected tree"));
+        }
+
+        let content;
+        syn::parenthesized!(content in input);
+
+        let mut parent: Option<syn::LitStr> = None;
+        loop {
+            if content.is_empty() {
+                break;
+            }
+
+            let key = syn::Ident::parse_any(&content)?;
+            let _: syn::Token![=] = content.parse()?;
+            let value: syn::LitStr = content.parse()?;
+
+            if key == "parent" {
+                parent = Some(value);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "unknown tree option (expected parent)",
+                ));
+            }
+
+            if content.peek(syn::Token![,]) {
+                let _: syn::Token![,] = content.parse()?;
+            } else {
+                break;
+            }
+        }
+
+        let parent = parent.ok_or_else(|| {
+            syn::Error::new(ident.span(), "tree requires parent = \"parent_column\"")
+        })?;
+        Ok(TreeConfig {
+            parent: parent.value(),
+            span: parent.span(),
+        })
+    }
+}
+
+/// Parse the `#[orm(tree(...))]` attribute, if present.
+pub(super) fn get_tree_config(input: &DeriveInput) -> Result<Option<TreeConfig>> {
+    let mut found = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("orm") {
+            continue;
+        }
+        if let syn::Meta::List(meta_list) = &attr.meta {
+            let is_tree = matches!(
+                meta_list.tokens.clone().into_iter().next(),
+                Some(proc_macro2::TokenTree::Ident(ident)) if ident == "tree"
+            );
+            if !is_tree {
+                continue;
+            }
+            if found.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "only one #[orm(tree(...))] attribute is allowed",
+                ));
+            }
+            found = Some(syn::parse2::<TreeConfig>(meta_list.tokens.clone())?);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Generate `impl pgorm::TreeModel` and the tree helpers on the model.
+pub(super) fn generate_tree_methods(
+    model_name: &syn::Ident,
+    has_joins: bool,
+    tree: &TreeConfig,
+    id: Option<(&str, &syn::Type)>,
+    columns: &HashMap<String, String>,
+) -> Result<TokenStream> {
+    let Some((id_column, id_ty)) = id else {
+        return Err(syn::Error::new(
+            tree.span,
+            "tree requires exactly one #[orm(id)] field",
+        ));
+    };
+    if has_joins {
+        return Err(syn::Error::new(
+            tree.span,
+            "tree is not supported on models with JOINs",
+        ));
+    }
+    let parent_column = columns.get(&tree.parent).ok_or_else(|| {
+        syn::Error::new(
+            tree.span,
+            format!(
+                "tree parent '{}' is not a column of the model's main table",
+                tree.parent
+            ),
+        )
+    })?;
+
+    Ok(quote! {
+        impl pgorm::TreeModel for #model_name {
+            const ID_COLUMN: &'static str = #id_column;
+            const PARENT_COLUMN: &'static str = #parent_column;
+        }
+
+        impl #model_name {
+            // ==================== Tree ====================
+
+            /// Rows without a parent, ordered by id.
+            pub async fn roots(
+                conn: &impl pgorm::GenericClient,
+            ) -> pgorm::OrmResult<::std::vec::Vec<Self>> {
+                pgorm::tree::roots::<Self>(conn).await
+            }
+
+            /// Ancestors of `id`, nearest first (the parent has depth 1).
+            pub async fn ancestors(
+                conn: &impl pgorm::GenericClient,
+                id: #id_ty,
+            ) -> pgorm::OrmResult<::std::vec::Vec<pgorm::TreeNode<Self>>> {
+                pgorm::tree::ancestors::<Self>(conn, id).await
+            }
+
+            /// `id` followed by its ancestors up to the root.
+            pub async fn path_to_root(
+                conn: &impl pgorm::GenericClient,
+                id: #id_ty,
+            ) -> pgorm::OrmResult<::std::vec::Vec<Self>> {
+                pgorm::tree::path_to_root::<Self>(conn, id).await
+            }
+
+            /// Descendants of `id` in depth-first order, at most `max_depth` levels below it.
+            pub async fn descendants(
+                conn: &impl pgorm::GenericClient,
+                id: #id_ty,
+                max_depth: ::core::option::Option<i32>,
+            ) -> pgorm::OrmResult<::std::vec::Vec<pgorm::TreeNode<Self>>> {
+                pgorm::tree::descendants::<Self>(conn, id, max_depth).await
+            }
+
+            /// `id` and its descendants as a nested tree (`None` if `id` does not exist).
+            pub async fn subtree_as_nested(
+                conn: &impl pgorm::GenericClient,
+                id: #id_ty,
+                max_depth: ::core::option::Option<i32>,
+            ) -> pgorm::OrmResult<::core::option::Option<pgorm::Nested<Self>>> {
+                pgorm::tree::subtree_as_nested::<Self>(conn, id, max_depth).await
+            }
+        }
+    })
+}
@@ -95,6 +95,96 @@ fn parse_struct_model_attr(input: &DeriveInput) -> Result<syn::Path> {
     ))
 }
 
+/// Parse `#[orm(query_string)]` / `#[orm(query_string(sort(COL, ...)))]`.
+///
+/// Returns the sortable column expressions when the attribute is present.
+fn parse_struct_query_string_attr(input: &DeriveInput) -> Result<Option<Vec<syn::Expr>>> {
+    let mut result = None;
+    for attr in &input.attrs {
+        if !attr.path().is_ident("orm") {
+            continue;
+        }
+
+        let items = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+        )?;
+
+        for meta in items {
+            match meta {
+                syn::Meta::Path(p) if p.is_ident("query_string") => {
+                    result.get_or_insert_with(Vec::new);
+                }
+                syn::Meta::List(list) if list.path.is_ident("query_string") => {
+                    let sorts = result.get_or_insert_with(Vec::new);
+                    let items = list.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                    )?;
+                    for meta in items {
+                        let syn::Meta::List(sort) = meta else {
+                            return Err(syn::Error::new_spanned(
+                                meta,
+                                "query_string(...) only supports sort(COL, ...)",
+                            ));
+                        };
+                        if !sort.path.is_ident("sort") {
+                            return Err(syn::Error::new_spanned(
+                                sort,
+                                "query_string(...) only supports sort(COL, ...)",
+                            ));
+                        }
+                        let cols = sort.parse_args_with(
+                            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+                        )?;
+                        sorts.extend(cols);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Build the `QueryAllowList` entry for a filter, if it maps to a query-string operator.
+///
+/// Filters with `map(...)`, reference-only types, ranges and non-filter operations
+/// have no query-string form and are left out.
+fn allow_list_stmt(filter: &FilterOp, field_ty: &syn::Type) -> Option<TokenStream> {
+    let col = filter.col.as_ref()?;
+    if filter.map.is_some() {
+        return None;
+    }
+    let base = option_inner(field_ty).unwrap_or(field_ty);
+
+    let (op, value_ty) = match filter.kind {
+        FilterOpKind::IsNull | FilterOpKind::IsNotNull => {
+            return Some(quote! { let list = list.nullable(#col)?; });
+        }
+        FilterOpKind::InList => (quote!(In), vec_inner(base)?),
+        FilterOpKind::NotIn => (quote!(NotIn), vec_inner(base)?),
+        FilterOpKind::Eq => (quote!(Eq), base),
+        FilterOpKind::Ne => (quote!(Ne), base),
+        FilterOpKind::Gt => (quote!(Gt), base),
+        FilterOpKind::Gte => (quote!(Gte), base),
+        FilterOpKind::Lt => (quote!(Lt), base),
+        FilterOpKind::Lte => (quote!(Lte), base),
+        FilterOpKind::Like => (quote!(Like), base),
+        FilterOpKind::Ilike => (quote!(Ilike), base),
+        FilterOpKind::NotLike => (quote!(NotLike), base),
+        FilterOpKind::NotIlike => (quote!(NotIlike), base),
+        _ => return None,
+    };
+
+    let value_ty = if filter.force_string || is_stringish(value_ty) {
+        quote!(::std::string::String)
+    } else if is_reference_type(value_ty) {
+        return None;
+    } else {
+        quote!(#value_ty)
+    };
+    Some(quote! { let list = list.filter::<#value_ty>(#col, [pgorm::FilterOp::#op])?; })
+}
+
 fn query_path_from_model(model: &syn::Path) -> Result<syn::Path> {
     let mut query_path = model.clone();
     let Some(last) = query_path.segments.last_mut() else {
@@ -319,6 +409,7 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
 
     let model = parse_struct_model_attr(&input)?;
     let query = query_path_from_model(&model)?;
+    let query_string = parse_struct_query_string_attr(&input)?;
 
     let fields = match &input.data {
         Data::Struct(data) => match &data.fields {
@@ -340,6 +431,7 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
 
     let all_field_idents: Vec<syn::Ident> = fields.iter().filter_map(|f| f.ident.clone()).collect();
     let mut apply_stmts: Vec<TokenStream> = Vec::new();
+    let mut allow_stmts: Vec<TokenStream> = Vec::new();
 
     for field in fields {
         let Some(field_ident) = field.ident.clone() else {
@@ -355,6 +447,10 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
         let opt_inner = option_inner(field_ty);
         let field_is_option = opt_inner.is_some();
 
+        if query_string.is_some() {
+            allow_stmts.extend(filters.iter().filter_map(|f| allow_list_stmt(f, field_ty)));
+        }
+
         for filter in filters {
             let kind = filter.kind;
             let col = filter.col.clone();
@@ -822,6 +918,21 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
 
     let destructure = quote! { let Self { #(#all_field_idents,)* } = self; };
 
+    let allow_list_fn = query_string.map(|sorts| {
+        let sort_stmt =
+            (!sorts.is_empty()).then(|| quote! { let list = list.sort([#(#sorts),*])?; });
+        quote! {
+            /// The filters and sort columns this struct declares, as an allow-list for
+            /// parsing HTTP query strings.
+            pub fn allow_list() -> pgorm::OrmResult<pgorm::QueryAllowList> {
+                let list = pgorm::QueryAllowList::new();
+                #(#allow_stmts)*
+                #sort_stmt
+                ::std::result::Result::Ok(list)
+            }
+        }
+    });
+
     Ok(quote! {
         impl #impl_generics #name #ty_generics #where_clause {
             /// Apply the params to an existing query builder.
@@ -836,6 +947,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
             pub fn into_query(self) -> pgorm::OrmResult<#query> {
                 self.apply(#model::query())
             }
+
+            #allow_list_fn
         }
     })
 }
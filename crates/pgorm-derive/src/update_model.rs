@@ -10,11 +10,13 @@
 //! - `graph_parse`: Graph declaration parsing
 //! - `types`: Type helper functions (option_inner)
 //! - `gen_base`: Base update_by_id/update_by_ids code generation
+//! - `gen_batch`: Per-row update_many_by_id code generation (UPDATE ... FROM UNNEST)
 //! - `gen_children`: has_many/has_one child update code generation
 //! - `gen_graph`: Graph update methods code generation
 
 mod attrs;
 mod gen_base;
+mod gen_batch;
 mod gen_children;
 mod gen_graph;
 mod graph_decl;
@@ -25,6 +27,7 @@ use attrs::{get_field_attrs, get_struct_attrs};
 use gen_base::{
     generate_update_by_id_methods, generate_update_force_methods, generate_update_returning_methods,
 };
+use gen_batch::{BatchSetField, BatchSetValue, generate_update_many_by_id_methods};
 use gen_graph::generate_update_graph_methods;
 use types::{AutoTimestampKind, detect_auto_timestamp_type, option_inner};
 
@@ -74,6 +77,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
     let mut set_stmts: Vec<TokenStream> = Vec::new();
     let mut has_auto_now = false;
     let mut version_field: Option<(syn::Ident, String)> = None; // (field_ident, column_name)
+    let mut version_ty: Option<&syn::Type> = None;
+    let mut batch_fields: Vec<BatchSetField> = Vec::new();
 
     // Get field names used by graph declarations
     let graph_field_names = attrs.graph.graph_field_names();
@@ -119,6 +124,7 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
             }
             let col = field_attrs.column.clone().unwrap_or(field_name.clone());
             version_field = Some((field_ident.clone(), col));
+            version_ty = Some(field_ty);
             destructure_idents.push(field_ident.clone());
             // Version field does NOT generate a regular SET statement
             // It will be handled specially as `version = version + 1`
@@ -137,6 +143,10 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
                 q.push(#column_name);
                 q.push(" = DEFAULT");
             });
+            batch_fields.push(BatchSetField {
+                column: column_name,
+                value: BatchSetValue::Default,
+            });
             continue;
         }
 
@@ -162,6 +172,15 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
                     quote! { #field_ident.unwrap_or_else(|| __pgorm_now.naive_utc()) }
                 }
             };
+            batch_fields.push(BatchSetField {
+                column: column_name.clone(),
+                value: BatchSetValue::Value {
+                    elem_ty: Box::new(option_inner(field_ty).unwrap_or(field_ty).clone()),
+                    value: bind_expr.clone(),
+                    flag: None,
+                    jsonb_merge: false,
+                },
+            });
 
             set_stmts.push(quote! {
                 if !first {
@@ -179,6 +198,94 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
         // Non-default fields need the value.
         destructure_idents.push(field_ident.clone());
 
+        // Batch updates send `Option` fields with an "is set" flag; `Option<Option<T>>`
+        // is flattened so `Some(None)` writes NULL.
+        batch_fields.push(BatchSetField {
+            column: column_name.clone(),
+            value: match option_inner(field_ty) {
+                Some(inner) if option_inner(inner).is_some() => BatchSetValue::Value {
+                    elem_ty: Box::new(inner.clone()),
+                    value: quote! { #field_ident.flatten() },
+                    flag: Some(quote! { #field_ident.is_some() }),
+                    jsonb_merge: field_attrs.jsonb_merge,
+                },
+                Some(_) => BatchSetValue::Value {
+                    elem_ty: Box::new(field_ty.clone()),
+                    value: quote! { #field_ident },
+                    flag: Some(quote! { #field_ident.is_some() }),
+                    jsonb_merge: field_attrs.jsonb_merge,
+                },
+                None => BatchSetValue::Value {
+                    elem_ty: Box::new(field_ty.clone()),
+                    value: quote! { #field_ident },
+                    flag: None,
+                    jsonb_merge: field_attrs.jsonb_merge,
+                },
+            },
+        });
+
+        // jsonb_merge fields merge into the stored object instead of replacing it.
+        if field_attrs.jsonb_merge {
+            let merge_lhs = format!("{column_name} = COALESCE({column_name}, '{{}}'::jsonb) || ");
+            let push_merge = |value: TokenStream| {
+                quote! {
+                    q.push(#merge_lhs);
+                    q.push_bind(#value);
+                    q.push("::jsonb");
+                }
+            };
+            let stmt = match option_inner(field_ty) {
+                Some(inner) if option_inner(inner).is_some() => {
+                    // Option<Option<T>>: Some(Some(v)) => merge; Some(None) => NULL; None => skip.
+                    let merge = push_merge(quote! { vv });
+                    quote! {
+                        if let Some(v) = #field_ident {
+                            if !first {
+                                q.push(", ");
+                            } else {
+                                first = false;
+                            }
+                            match v {
+                                Some(vv) => {
+                                    #merge
+                                }
+                                None => {
+                                    q.push(#column_name);
+                                    q.push(" = NULL");
+                                }
+                            }
+                        }
+                    }
+                }
+                Some(_) => {
+                    let merge = push_merge(quote! { v });
+                    quote! {
+                        if let Some(v) = #field_ident {
+                            if !first {
+                                q.push(", ");
+                            } else {
+                                first = false;
+                            }
+                            #merge
+                        }
+                    }
+                }
+                None => {
+                    let merge = push_merge(quote! { #field_ident });
+                    quote! {
+                        if !first {
+                            q.push(", ");
+                        } else {
+                            first = false;
+                        }
+                        #merge
+                    }
+                }
+            };
+            set_stmts.push(stmt);
+            continue;
+        }
+
         if let Some(inner) = option_inner(field_ty) {
             if option_inner(inner).is_some() {
                 // Option<Option<T>>: Some(Some(v)) => bind; Some(None) => NULL; None => skip.
@@ -238,6 +345,11 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
     } else {
         quote! { let Self { #(#destructure_idents),*, .. } = self; }
     };
+    let row_destructure = if destructure_idents.is_empty() {
+        quote! { let _ = __pgorm_row; }
+    } else {
+        quote! { let Self { #(#destructure_idents),*, .. } = __pgorm_row; }
+    };
 
     // Generate methods using submodules
     let update_by_id_methods = generate_update_by_id_methods(
@@ -259,6 +371,19 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
         version_field.as_ref(),
     );
 
+    let update_many_by_id_methods = generate_update_many_by_id_methods(
+        &attrs,
+        table_name,
+        &id_col_expr,
+        &row_destructure,
+        &batch_fields,
+        has_auto_now,
+        version_field
+            .as_ref()
+            .zip(version_ty)
+            .map(|((ident, col), ty)| (ident, ty, col.as_str())),
+    );
+
     let update_graph_methods = generate_update_graph_methods(&attrs, &id_col_expr)?;
 
     // Generate force methods only when version field exists
@@ -290,6 +415,8 @@ pub fn expand(input: DeriveInput) -> Result<TokenStream> {
 
             #update_returning_methods
 
+            #update_many_by_id_methods
+
             #update_force_methods
 
             #update_graph_methods
@@ -123,6 +123,7 @@ pub(super) struct FieldAttrs {
     pub(super) default: bool,
     pub(super) auto_now: bool,
     pub(super) version: bool,
+    pub(super) jsonb_merge: bool,
     pub(super) table: Option<String>,
     pub(super) column: Option<String>,
     pub(super) skip_input: bool,
@@ -147,6 +148,7 @@ impl syn::parse::Parse for FieldAttrs {
             default: false,
             auto_now: false,
             version: false,
+            jsonb_merge: false,
             table: None,
             column: None,
             skip_input: false,
@@ -176,6 +178,7 @@ impl syn::parse::Parse for FieldAttrs {
                 "default" => attrs.default = true,
                 "auto_now" => attrs.auto_now = true,
                 "version" => attrs.version = true,
+                "jsonb_merge" => attrs.jsonb_merge = true,
                 "skip_input" => attrs.skip_input = true,
                 "required" => attrs.required = true,
                 "email" => attrs.email = true,
@@ -232,6 +235,7 @@ pub(super) fn get_field_attrs(field: &syn::Field) -> Result<FieldAttrs> {
         default: false,
         auto_now: false,
         version: false,
+        jsonb_merge: false,
         table: None,
         column: None,
         skip_input: false,
@@ -258,6 +262,7 @@ pub(super) fn get_field_attrs(field: &syn::Field) -> Result<FieldAttrs> {
             merged.default |= parsed.default;
             merged.auto_now |= parsed.auto_now;
             merged.version |= parsed.version;
+            merged.jsonb_merge |= parsed.jsonb_merge;
             merged.skip_input |= parsed.skip_input;
             merged.required |= parsed.required;
             merged.email |= parsed.email;
@@ -335,6 +340,13 @@ pub(super) fn get_field_attrs(field: &syn::Field) -> Result<FieldAttrs> {
         ));
     }
 
+    if merged.jsonb_merge && (merged.default || merged.auto_now || merged.version) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "jsonb_merge cannot be combined with default, auto_now or version",
+        ));
+    }
+
     Ok(merged)
 }
 
@@ -0,0 +1,324 @@
+//! Per-row batch update code generation.
+//!
+//! This module contains code generation for:
+//! - `update_many_by_id` method (`UPDATE ... FROM UNNEST(...)`)
+//! - `update_many_by_id_returning` method (when a returning type is configured)
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::attrs::StructAttrs;
+
+/// How one patch field is written by `update_many_by_id`.
+pub(super) enum BatchSetValue {
+    /// `col = DEFAULT` for every row.
+    Default,
+    /// `col = <value>` from an UNNEST array of `elem_ty`.
+    Value {
+        /// Element type of the bound array (its `PgType` gives the cast).
+        elem_ty: Box<syn::Type>,
+        /// Expression turning the destructured field into one array element.
+        value: TokenStream,
+        /// Expression telling whether the field is set (`Option` fields); evaluated
+        /// before `value`. Unset rows keep the stored column value.
+        flag: Option<TokenStream>,
+        /// Merge into the stored jsonb object instead of replacing it.
+        jsonb_merge: bool,
+    },
+}
+
+/// A column written by `update_many_by_id`.
+pub(super) struct BatchSetField {
+    pub column: String,
+    pub value: BatchSetValue,
+}
+
+/// Generate update_many_by_id (and update_many_by_id_returning) methods.
+pub(super) fn generate_update_many_by_id_methods(
+    attrs: &StructAttrs,
+    table_name: &str,
+    id_col_expr: &TokenStream,
+    row_destructure: &TokenStream,
+    fields: &[BatchSetField],
+    has_auto_now: bool,
+    version_field: Option<(&syn::Ident, &syn::Type, &str)>,
+) -> TokenStream {
+    let now_init = if has_auto_now {
+        quote! { let __pgorm_now = ::chrono::Utc::now(); }
+    } else {
+        quote! {}
+    };
+
+    // One UNNEST column per array; `arrays` holds (list ident, element type, alias).
+    let mut arrays: Vec<(syn::Ident, TokenStream, String)> = Vec::new();
+    let mut pushes: Vec<TokenStream> = Vec::new();
+    let mut set_exprs: Vec<TokenStream> = Vec::new();
+    let mut bounds: Vec<TokenStream> = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let column = &field.column;
+        match &field.value {
+            BatchSetValue::Default => {
+                let set = format!("{column} = DEFAULT");
+                set_exprs.push(quote! { #set.to_string() });
+            }
+            BatchSetValue::Value {
+                elem_ty,
+                value,
+                flag,
+                jsonb_merge,
+            } => {
+                let list = format_ident!("__pgorm_v{}", i);
+                let alias = format!("__pgorm_v{i}");
+                arrays.push((list.clone(), quote! { #elem_ty }, alias.clone()));
+                // Higher-ranked so field types without `PgType` only fail when this is called.
+                bounds.push(quote! {
+                    for<'__pgorm> #elem_ty: pgorm::PgType,
+                    for<'__pgorm> ::std::vec::Vec<#elem_ty>: ::pgorm::tokio_postgres::types::ToSql
+                        + ::core::marker::Send
+                        + ::core::marker::Sync
+                        + 'static,
+                });
+
+                let new_value = if *jsonb_merge {
+                    format!("COALESCE({{table}}.{column}, '{{{{}}}}'::jsonb) || __pgorm_u.{alias}")
+                } else {
+                    format!("__pgorm_u.{alias}")
+                };
+                match flag {
+                    Some(flag) => {
+                        let flag_list = format_ident!("__pgorm_s{}", i);
+                        let flag_alias = format!("__pgorm_s{i}");
+                        arrays.push((flag_list.clone(), quote! { bool }, flag_alias.clone()));
+                        pushes.push(quote! {
+                            #flag_list.push(#flag);
+                            #list.push(#value);
+                        });
+                        let set = format!(
+                            "{column} = CASE WHEN __pgorm_u.{flag_alias} THEN {new_value} \
+                             ELSE {{table}}.{column} END"
+                        );
+                        set_exprs.push(set_expr(&set, table_name));
+                    }
+                    None => {
+                        pushes.push(quote! { #list.push(#value); });
+                        let set = format!("{column} = {new_value}");
+                        set_exprs.push(set_expr(&set, table_name));
+                    }
+                }
+            }
+        }
+    }
+
+    let (version_list, version_where) = match version_field {
+        Some((version_ident, version_ty, version_col)) => {
+            let list = format_ident!("__pgorm_version");
+            arrays.push((
+                list.clone(),
+                quote! { #version_ty },
+                "__pgorm_version".into(),
+            ));
+            pushes.push(quote! { #list.push(#version_ident); });
+            let set = format!("{version_col} = {{table}}.{version_col} + 1");
+            set_exprs.push(set_expr(&set, table_name));
+            let cond = format!(" AND {{table}}.{version_col} = __pgorm_u.__pgorm_version");
+            (Some(list), set_expr(&cond, table_name))
+        }
+        None => (None, quote! { ::std::string::String::new() }),
+    };
+
+    let list_idents: Vec<&syn::Ident> = arrays.iter().map(|(list, _, _)| list).collect();
+    let list_tys: Vec<&TokenStream> = arrays.iter().map(|(_, ty, _)| ty).collect();
+    let aliases: Vec<&String> = arrays.iter().map(|(_, _, alias)| alias).collect();
+
+    // `UPDATE t SET ... FROM UNNEST($1::id[], $2::a[], ...) AS __pgorm_u(__pgorm_id, ...)
+    //  WHERE t.id = __pgorm_u.__pgorm_id`
+    let build_sql = quote! {
+        let __pgorm_sets: ::std::vec::Vec<::std::string::String> = ::std::vec![#(#set_exprs),*];
+        let __pgorm_casts: ::std::vec::Vec<::std::string::String> = ::std::iter::once(
+            <I as pgorm::PgType>::pg_array_type(),
+        )
+        .chain([#(<#list_tys as pgorm::PgType>::pg_array_type()),*])
+        .enumerate()
+        .map(|(i, ty)| ::std::format!("${}::{}", i + 1, ty))
+        .collect();
+        let __pgorm_aliases: ::std::vec::Vec<&str> = ::std::vec!["__pgorm_id", #(#aliases),*];
+        let __pgorm_update = ::std::format!(
+            "UPDATE {table} SET {} FROM UNNEST({}) AS __pgorm_u({}) WHERE {table}.{} = __pgorm_u.__pgorm_id{}",
+            __pgorm_sets.join(", "),
+            __pgorm_casts.join(", "),
+            __pgorm_aliases.join(", "),
+            #id_col_expr,
+            #version_where,
+            table = #table_name,
+        );
+    };
+
+    let collect_lists = quote! {
+        let mut __pgorm_ids: ::std::vec::Vec<I> = ::std::vec::Vec::with_capacity(rows.len());
+        #(
+            let mut #list_idents: ::std::vec::Vec<#list_tys> =
+                ::std::vec::Vec::with_capacity(rows.len());
+        )*
+        for (__pgorm_id, __pgorm_row) in rows {
+            #row_destructure
+            __pgorm_ids.push(__pgorm_id);
+            #(#pushes)*
+        }
+    };
+
+    let bind_lists = quote! { .bind(__pgorm_ids) #(.bind(#list_idents))* };
+
+    // Optimistic locking: every existing target row must have been updated.
+    let (precheck, check_count) = if let Some(version_list) = &version_list {
+        (
+            quote! {
+                let __target_sql = ::std::format!(
+                    "SELECT COUNT(*) FROM {} WHERE {}.{} = ANY($1)",
+                    #table_name,
+                    #table_name,
+                    #id_col_expr
+                );
+                let __target_row = conn.query_one(&__target_sql, &[&__pgorm_ids]).await?;
+                let __target_count: i64 = __target_row.get(0);
+                let __version_val = #version_list.first().map_or(0, |v| *v as i64);
+            },
+            quote! {
+                if (__pgorm_written as i64) < __target_count {
+                    return Err(pgorm::OrmError::stale_record(
+                        #table_name,
+                        format!("bulk(count={})", __target_count),
+                        __version_val,
+                    ));
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    if fields.is_empty() && version_field.is_none() {
+        let returning_method = attrs.returning.as_ref().map(|returning_ty| {
+            quote! {
+                /// Always fails: this patch has no fields to update.
+                pub async fn update_many_by_id_returning<I>(
+                    _conn: &impl pgorm::GenericClient,
+                    _rows: ::std::vec::Vec<(I, Self)>,
+                ) -> pgorm::OrmResult<::std::vec::Vec<#returning_ty>> {
+                    Err(pgorm::OrmError::Validation(
+                        "UpdateModel: no fields to update".to_string(),
+                    ))
+                }
+            }
+        });
+        return quote! {
+            /// Always fails: this patch has no fields to update.
+            pub async fn update_many_by_id<I>(
+                _conn: &impl pgorm::GenericClient,
+                _rows: ::std::vec::Vec<(I, Self)>,
+            ) -> pgorm::OrmResult<u64> {
+                Err(pgorm::OrmError::Validation(
+                    "UpdateModel: no fields to update".to_string(),
+                ))
+            }
+
+            #returning_method
+        };
+    }
+
+    let returning_method = attrs.returning.as_ref().map(|returning_ty| {
+        quote! {
+            /// Like [`Self::update_many_by_id`], but returns the updated rows mapped as the
+            /// configured returning type (in no particular order).
+            pub async fn update_many_by_id_returning<I>(
+                conn: &impl pgorm::GenericClient,
+                rows: ::std::vec::Vec<(I, Self)>,
+            ) -> pgorm::OrmResult<::std::vec::Vec<#returning_ty>>
+            where
+                I: pgorm::PgType,
+                ::std::vec::Vec<I>: ::pgorm::tokio_postgres::types::ToSql
+                    + ::core::marker::Send
+                    + ::core::marker::Sync
+                    + 'static,
+                #returning_ty: pgorm::FromRow,
+                #(#bounds)*
+            {
+                if rows.is_empty() {
+                    return ::std::result::Result::Ok(::std::vec::Vec::new());
+                }
+
+                #now_init
+                #collect_lists
+                #precheck
+                #build_sql
+
+                let __pgorm_sql = ::std::format!(
+                    "WITH {table} AS ({} RETURNING {table}.*) SELECT {} FROM {table} {}",
+                    __pgorm_update,
+                    #returning_ty::SELECT_LIST,
+                    #returning_ty::JOIN_CLAUSE,
+                    table = #table_name,
+                );
+                let __pgorm_rows = pgorm::query(__pgorm_sql)
+                    #bind_lists
+                    .fetch_all_as::<#returning_ty>(conn)
+                    .await?;
+                let __pgorm_written = __pgorm_rows.len();
+                #check_count
+                Ok(__pgorm_rows)
+            }
+        }
+    });
+
+    quote! {
+        /// Update many rows by primary key, each with its own patch, in one statement
+        /// (`UPDATE ... FROM UNNEST(...)`).
+        ///
+        /// `None` fields are left unchanged per row: every `Option` field is sent with a
+        /// boolean "is set" array and written as `CASE WHEN set THEN new ELSE old END`.
+        /// `Option<Option<T>>` fields set `NULL` for `Some(None)`. Each id should appear
+        /// at most once; PostgreSQL applies only one of several patches for the same row.
+        ///
+        /// If the struct has a `#[orm(version)]` field, each row is matched on its own
+        /// version and `OrmError::StaleRecord` is returned if any existing row was not updated.
+        pub async fn update_many_by_id<I>(
+            conn: &impl pgorm::GenericClient,
+            rows: ::std::vec::Vec<(I, Self)>,
+        ) -> pgorm::OrmResult<u64>
+        where
+            I: pgorm::PgType,
+            ::std::vec::Vec<I>: ::pgorm::tokio_postgres::types::ToSql
+                + ::core::marker::Send
+                + ::core::marker::Sync
+                + 'static,
+            #(#bounds)*
+        {
+            if rows.is_empty() {
+                return ::std::result::Result::Ok(0);
+            }
+
+            #now_init
+            #collect_lists
+            #precheck
+            #build_sql
+
+            let __pgorm_written = pgorm::query(__pgorm_update)
+                #bind_lists
+                .execute(conn)
+                .await?;
+            #check_count
+            Ok(__pgorm_written)
+        }
+
+        #returning_method
+    }
+}
+
+/// Render a SET / WHERE fragment, substituting `{table}` at runtime when present.
+fn set_expr(template: &str, table_name: &str) -> TokenStream {
+    if template.contains("{table}") {
+        quote! { ::std::format!(#template, table = #table_name) }
+    } else {
+        quote! { #template.to_string() }
+    }
+}
@@ -8,10 +8,21 @@
 //! - [`Ident`]: Safe SQL identifier handling (see [`crate::Ident`])
 
 use crate::Ident;
-use crate::condition::Condition;
+#[cfg(feature = "cursor")]
+use crate::client::GenericClient;
+use crate::condition::{Condition, ConditionPart, element_type};
 use crate::error::{OrmError, OrmResult};
+use crate::expr_json::param_to_json;
 use crate::ident::IntoIdent;
+#[cfg(feature = "cursor")]
+use crate::page::{self, CursorCodec, CursorKey, CursorSide, Page};
+#[cfg(feature = "cursor")]
+use crate::row::FromRow;
+use crate::row::PgType;
+use crate::search::TsRank;
 use crate::sql::Sql;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
 use std::sync::Arc;
 use tokio_postgres::types::ToSql;
 
@@ -56,6 +67,15 @@ pub enum WhereExpr {
         template: String,
         params: Vec<Arc<dyn ToSql + Send + Sync>>,
     },
+    /// Correlated subquery: `EXISTS (SELECT 1 FROM <from> WHERE <filter>)`.
+    ///
+    /// `from` is emitted verbatim (a table name, optionally followed by JOINs), so it
+    /// must come from trusted metadata such as `Model::TABLE` / `Model::JOIN_CLAUSE`.
+    /// See [`crate::relation`] for helpers that build these from relation metadata.
+    Exists {
+        from: String,
+        filter: Box<WhereExpr>,
+    },
 }
 
 impl WhereExpr {
@@ -111,6 +131,21 @@ impl WhereExpr {
         }
     }
 
+    /// Create an `EXISTS (SELECT 1 FROM <from> WHERE <filter>)` expression.
+    ///
+    /// **Warning**: `from` is not escaped. Only use with trusted, hardcoded SQL.
+    pub fn exists(from: impl Into<String>, filter: WhereExpr) -> Self {
+        WhereExpr::Exists {
+            from: from.into(),
+            filter: Box::new(filter),
+        }
+    }
+
+    /// Create a `NOT EXISTS (...)` expression (see [`WhereExpr::exists`]).
+    pub fn not_exists(from: impl Into<String>, filter: WhereExpr) -> Self {
+        WhereExpr::not(WhereExpr::exists(from, filter))
+    }
+
     /// Combine this expression with another using AND.
     pub fn and_with(self, other: WhereExpr) -> WhereExpr {
         match self {
@@ -202,6 +237,15 @@ impl WhereExpr {
                     }
                 }
             }
+            WhereExpr::Exists { from, filter } => {
+                sql.push("EXISTS (SELECT 1 FROM ");
+                sql.push(from);
+                if !filter.is_trivially_true() {
+                    sql.push(" WHERE ");
+                    filter.append_to_sql(sql);
+                }
+                sql.push(")");
+            }
         }
     }
 }
@@ -215,7 +259,8 @@ impl From<Condition> for WhereExpr {
 // ==================== OrderBy: Structured ORDER BY builder ====================
 
 /// Sort direction for ORDER BY.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortDir {
     #[default]
     Asc,
@@ -229,10 +274,19 @@ impl SortDir {
             SortDir::Desc => "DESC",
         }
     }
+
+    /// The opposite direction.
+    pub fn reverse(self) -> Self {
+        match self {
+            SortDir::Asc => SortDir::Desc,
+            SortDir::Desc => SortDir::Asc,
+        }
+    }
 }
 
 /// NULLS ordering for ORDER BY.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NullsOrder {
     First,
     Last,
@@ -245,6 +299,14 @@ impl NullsOrder {
             NullsOrder::Last => "NULLS LAST",
         }
     }
+
+    /// The opposite placement.
+    pub fn reverse(self) -> Self {
+        match self {
+            NullsOrder::First => NullsOrder::Last,
+            NullsOrder::Last => NullsOrder::First,
+        }
+    }
 }
 
 /// A single ORDER BY item.
@@ -255,6 +317,20 @@ pub enum OrderItem {
         dir: SortDir,
         nulls: Option<NullsOrder>,
     },
+    /// A jsonb value addressed by a key path: `column #>> '{a,b}'`, optionally cast.
+    JsonPath {
+        column: Ident,
+        path: Vec<String>,
+        cast: Option<&'static str>,
+        dir: SortDir,
+        nulls: Option<NullsOrder>,
+    },
+    /// Full-text relevance: `ts_rank(...)` / `ts_rank_cd(...)` (query text is bound).
+    Rank {
+        rank: TsRank,
+        dir: SortDir,
+        nulls: Option<NullsOrder>,
+    },
     /// Raw SQL (escape hatch - use with extreme caution).
     Raw(String),
 }
@@ -269,6 +345,50 @@ impl OrderItem {
         }
     }
 
+    /// Create an order item over a jsonb key path, compared as text.
+    ///
+    /// The path is rendered inline as an escaped `text[]` literal.
+    pub fn json_path<P, S>(column: Ident, path: P, dir: SortDir) -> Self
+    where
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::JsonPath {
+            column,
+            path: path.into_iter().map(Into::into).collect(),
+            cast: None,
+            dir,
+            nulls: None,
+        }
+    }
+
+    /// Create an order item over a jsonb key path, cast to `T`'s SQL type.
+    ///
+    /// e.g. `(column #>> '{stats,score}')::bigint DESC` for `T = i64`.
+    pub fn json_path_as<T, P, S>(column: Ident, path: P, dir: SortDir) -> Self
+    where
+        T: PgType,
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::JsonPath {
+            column,
+            path: path.into_iter().map(Into::into).collect(),
+            cast: Some(element_type::<T>()),
+            dir,
+            nulls: None,
+        }
+    }
+
+    /// Create an order item over a full-text rank.
+    pub fn rank(rank: TsRank, dir: SortDir) -> Self {
+        Self::Rank {
+            rank,
+            dir,
+            nulls: None,
+        }
+    }
+
     /// Create a raw SQL order item.
     pub fn raw(sql: impl Into<String>) -> Self {
         Self::Raw(sql.into())
@@ -276,8 +396,13 @@ impl OrderItem {
 
     /// Set NULLS ordering (no-op for raw items).
     pub fn nulls(mut self, order: NullsOrder) -> Self {
-        if let OrderItem::Column { nulls, .. } = &mut self {
-            *nulls = Some(order);
+        match &mut self {
+            OrderItem::Column { nulls, .. }
+            | OrderItem::JsonPath { nulls, .. }
+            | OrderItem::Rank { nulls, .. } => {
+                *nulls = Some(order);
+            }
+            OrderItem::Raw(_) => {}
         }
         self
     }
@@ -293,6 +418,39 @@ impl OrderItem {
                     sql.push(nulls.to_sql());
                 }
             }
+            OrderItem::JsonPath {
+                column,
+                path,
+                cast,
+                dir,
+                nulls,
+            } => {
+                if cast.is_some() {
+                    sql.push("(");
+                }
+                sql.push_ident_ref(column);
+                sql.push(" #>> ");
+                sql.push(&json_path_literal(path));
+                if let Some(cast) = cast {
+                    sql.push(")::");
+                    sql.push(cast);
+                }
+                sql.push(" ");
+                sql.push(dir.to_sql());
+                if let Some(nulls) = nulls {
+                    sql.push(" ");
+                    sql.push(nulls.to_sql());
+                }
+            }
+            OrderItem::Rank { rank, dir, nulls } => {
+                rank.append_expr_to_sql(sql);
+                sql.push(" ");
+                sql.push(dir.to_sql());
+                if let Some(nulls) = nulls {
+                    sql.push(" ");
+                    sql.push(nulls.to_sql());
+                }
+            }
             OrderItem::Raw(s) => {
                 sql.push(s);
             }
@@ -300,6 +458,30 @@ impl OrderItem {
     }
 }
 
+/// Render a key path as a quoted `text[]` literal, e.g. `'{"a","b"}'`.
+fn json_path_literal(path: &[String]) -> String {
+    let mut out = String::from("'{");
+    for (i, key) in path.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('"');
+        for ch in key.chars() {
+            match ch {
+                '"' | '\\' => {
+                    out.push('\\');
+                    out.push(ch);
+                }
+                '\'' => out.push_str("''"),
+                _ => out.push(ch),
+            }
+        }
+        out.push('"');
+    }
+    out.push_str("}'");
+    out
+}
+
 /// ORDER BY clause builder.
 ///
 /// # Example
@@ -348,6 +530,52 @@ impl OrderBy {
         Ok(self)
     }
 
+    /// Add a sort over a jsonb key path, compared as text: `column #>> '{a,b}'`.
+    pub fn json_path<P, S>(
+        mut self,
+        column: impl IntoIdent,
+        path: P,
+        dir: SortDir,
+    ) -> OrmResult<Self>
+    where
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.items
+            .push(OrderItem::json_path(column.into_ident()?, path, dir));
+        Ok(self)
+    }
+
+    /// Add a sort over a jsonb key path cast to `T`'s SQL type:
+    /// `(column #>> '{a,b}')::<type>`.
+    pub fn json_path_as<T, P, S>(
+        mut self,
+        column: impl IntoIdent,
+        path: P,
+        dir: SortDir,
+    ) -> OrmResult<Self>
+    where
+        T: PgType,
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.items.push(OrderItem::json_path_as::<T, _, _>(
+            column.into_ident()?,
+            path,
+            dir,
+        ));
+        Ok(self)
+    }
+
+    /// Add a sort by full-text relevance (`ts_rank` / `ts_rank_cd`).
+    ///
+    /// The query text is bound, so render with [`append_to_sql`](Self::append_to_sql)
+    /// rather than [`to_sql`](Self::to_sql).
+    pub fn rank(mut self, rank: TsRank, dir: SortDir) -> Self {
+        self.items.push(OrderItem::rank(rank, dir));
+        self
+    }
+
     /// Add a custom order item.
     #[allow(clippy::should_implement_trait)]
     pub fn add(mut self, item: OrderItem) -> Self {
@@ -355,6 +583,12 @@ impl OrderBy {
         self
     }
 
+    /// Insert an order item before all existing items.
+    pub fn prepend(mut self, item: OrderItem) -> Self {
+        self.items.insert(0, item);
+        self
+    }
+
     /// Check if this OrderBy is empty.
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
@@ -367,7 +601,13 @@ impl OrderBy {
         if self.items.is_empty() {
             return;
         }
-        sql.push(" ORDER BY ");
+        sql.push(" ");
+        self.append_clause_to_sql(sql);
+    }
+
+    /// Append `ORDER BY <items>` without a leading space (the caller checks emptiness).
+    pub(crate) fn append_clause_to_sql(&self, sql: &mut Sql) {
+        sql.push("ORDER BY ");
         for (i, item) in self.items.iter().enumerate() {
             if i > 0 {
                 sql.push(", ");
@@ -377,18 +617,15 @@ impl OrderBy {
     }
 
     /// Build the ORDER BY clause as a string.
+    ///
+    /// Values bound by [`rank`](Self::rank) items are not part of the string; use
+    /// [`append_to_sql`](Self::append_to_sql) for those.
     pub fn to_sql(&self) -> String {
         if self.items.is_empty() {
             return String::new();
         }
         let mut sql = Sql::empty();
-        sql.push("ORDER BY ");
-        for (i, item) in self.items.iter().enumerate() {
-            if i > 0 {
-                sql.push(", ");
-            }
-            item.append_to_sql(&mut sql);
-        }
+        self.append_clause_to_sql(&mut sql);
         sql.to_sql()
     }
 }
@@ -471,7 +708,10 @@ type DynValue = Arc<dyn ToSql + Send + Sync>;
 const DEFAULT_KEYSET_LIMIT: i64 = 50;
 
 /// Cursor position for keyset pagination.
-#[derive(Debug, Clone)]
+///
+/// Serialized as `{"after": value}` / `{"before": value}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Cursor<T> {
     After(T),
     Before(T),
@@ -736,22 +976,135 @@ impl_into_keyset_cursor_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
 /// - `OR ...`
 /// - `OR (c1 = v1 AND ... AND cN op vN)`
 ///
-/// where `op` is chosen by sort direction and cursor side (`after` / `before`).
+/// where `op` is chosen per column by its sort direction and the cursor side
+/// (`after` / `before`), so mixed orderings such as `created_at DESC, id ASC` work.
+///
+/// Columns with an explicit [`NullsOrder`] get NULL-aware comparisons (`IS NOT DISTINCT
+/// FROM` for equality, and NULL rows placed on the correct side of the cursor, including
+/// NULL cursor values). Columns without one are assumed to be `NOT NULL`.
 #[derive(Debug, Clone)]
 pub struct KeysetN {
-    columns: Vec<Ident>,
-    dir: SortDir,
+    columns: Vec<KeysetColumn>,
     cursor: Option<Cursor<Vec<DynValue>>>,
     limit: i64,
 }
 
+#[derive(Debug, Clone)]
+struct KeysetColumn {
+    column: Ident,
+    dir: SortDir,
+    nulls: Option<NullsOrder>,
+}
+
+impl KeysetColumn {
+    /// Equality term used for the columns preceding the one being compared.
+    fn eq_condition(&self, v: DynValue) -> Condition {
+        match self.nulls {
+            None => Condition::cmp_dyn(self.column.clone(), "=", v),
+            Some(_) => Condition::cmp_dyn(self.column.clone(), "IS NOT DISTINCT FROM", v),
+        }
+    }
+
+    /// Strict "comes after the cursor in the direction of travel" term.
+    fn seek_condition(&self, cursor: &Cursor<Vec<DynValue>>, v: DynValue) -> WhereExpr {
+        let op = seek_cmp_op(self.dir, cursor);
+        let Some(nulls) = self.nulls else {
+            return WhereExpr::atom(Condition::cmp_dyn(self.column.clone(), op, v));
+        };
+
+        let nulls_ahead = match cursor {
+            Cursor::After(_) => nulls == NullsOrder::Last,
+            Cursor::Before(_) => nulls == NullsOrder::First,
+        };
+        let col = || ConditionPart::Ident(self.column.clone());
+        if nulls_ahead {
+            // (c op v OR (c IS NULL AND c IS DISTINCT FROM v)):
+            // NULL rows follow any non-NULL cursor; nothing follows a NULL cursor.
+            WhereExpr::or(vec![
+                WhereExpr::atom(Condition::cmp_dyn(self.column.clone(), op, v.clone())),
+                WhereExpr::atom(Condition::from_parts(vec![
+                    ConditionPart::Raw("("),
+                    col(),
+                    ConditionPart::Raw(" IS NULL AND "),
+                    col(),
+                    ConditionPart::Raw(" IS DISTINCT FROM "),
+                    ConditionPart::Param(v),
+                    ConditionPart::Raw(")"),
+                ])),
+            ])
+        } else {
+            // (c IS NOT NULL AND (c op v) IS NOT FALSE):
+            // NULL rows are behind the cursor; every non-NULL row follows a NULL cursor.
+            WhereExpr::atom(Condition::from_parts(vec![
+                ConditionPart::Raw("("),
+                col(),
+                ConditionPart::Raw(" IS NOT NULL AND ("),
+                col(),
+                ConditionPart::Raw(" "),
+                ConditionPart::Raw(op),
+                ConditionPart::Raw(" "),
+                ConditionPart::Param(v),
+                ConditionPart::Raw(") IS NOT FALSE)"),
+            ]))
+        }
+    }
+}
+
 impl KeysetN {
+    /// Build from per-column order items, e.g.
+    /// `[OrderItem::new(created_at, Desc).nulls(Last), OrderItem::new(id, Asc)]`.
+    ///
+    /// Only plain column items ([`OrderItem::Column`]) are supported.
+    pub fn new<I>(items: I) -> OrmResult<Self>
+    where
+        I: IntoIterator<Item = OrderItem>,
+    {
+        let columns = items
+            .into_iter()
+            .map(|item| match item {
+                OrderItem::Column { column, dir, nulls } => Ok(KeysetColumn { column, dir, nulls }),
+                other => Err(OrmError::validation(format!(
+                    "KeysetN only supports column order items, got {other:?}"
+                ))),
+            })
+            .collect::<OrmResult<Vec<_>>>()?;
+        Self::from_columns(columns)
+    }
+
+    /// Build the keyset for `T`'s cursor columns, positioned by an opaque `token`
+    /// from a previous [`Page`] (`None` for the first page).
+    ///
+    /// Tokens issued for a different column set, or failing the codec's signature
+    /// check, are rejected with [`OrmError::Validation`].
+    #[cfg(feature = "cursor")]
+    pub fn from_cursor<T: CursorKey>(codec: &CursorCodec, token: Option<&str>) -> OrmResult<Self> {
+        let columns = T::CURSOR_COLUMNS
+            .iter()
+            .map(|c| {
+                Ok(KeysetColumn {
+                    column: Ident::parse(c.name)?,
+                    dir: c.dir,
+                    nulls: c.nulls,
+                })
+            })
+            .collect::<OrmResult<Vec<_>>>()?;
+        let mut keyset = Self::from_columns(columns)?;
+        if let Some(token) = token {
+            let (side, values) = page::decode_for::<T>(codec, token)?;
+            keyset.cursor = Some(match side {
+                CursorSide::After => Cursor::After(values),
+                CursorSide::Before => Cursor::Before(values),
+            });
+        }
+        Ok(keyset)
+    }
+
     pub fn asc<I, C>(columns: I) -> OrmResult<Self>
     where
         I: IntoIterator<Item = C>,
         C: IntoIdent,
     {
-        Self::new(columns, SortDir::Asc)
+        Self::uniform(columns, SortDir::Asc)
     }
 
     pub fn desc<I, C>(columns: I) -> OrmResult<Self>
@@ -759,18 +1112,28 @@ impl KeysetN {
         I: IntoIterator<Item = C>,
         C: IntoIdent,
     {
-        Self::new(columns, SortDir::Desc)
+        Self::uniform(columns, SortDir::Desc)
     }
 
-    fn new<I, C>(columns: I, dir: SortDir) -> OrmResult<Self>
+    fn uniform<I, C>(columns: I, dir: SortDir) -> OrmResult<Self>
     where
         I: IntoIterator<Item = C>,
         C: IntoIdent,
     {
-        let columns: Vec<Ident> = columns
+        let columns = columns
             .into_iter()
-            .map(IntoIdent::into_ident)
+            .map(|c| {
+                Ok(KeysetColumn {
+                    column: c.into_ident()?,
+                    dir,
+                    nulls: None,
+                })
+            })
             .collect::<OrmResult<Vec<_>>>()?;
+        Self::from_columns(columns)
+    }
+
+    fn from_columns(columns: Vec<KeysetColumn>) -> OrmResult<Self> {
         if columns.len() < 2 {
             return Err(OrmError::validation(
                 "KeysetN requires at least 2 columns".to_string(),
@@ -779,7 +1142,6 @@ impl KeysetN {
 
         Ok(Self {
             columns,
-            dir,
             cursor: None,
             limit: DEFAULT_KEYSET_LIMIT,
         })
@@ -807,12 +1169,13 @@ impl KeysetN {
     }
 
     pub fn order_by(&self) -> OrderBy {
-        self.columns
-            .iter()
-            .cloned()
-            .fold(OrderBy::new(), |acc, col| {
-                acc.add(OrderItem::new(col, self.dir))
+        self.columns.iter().fold(OrderBy::new(), |acc, col| {
+            acc.add(OrderItem::Column {
+                column: col.column.clone(),
+                dir: col.dir,
+                nulls: col.nulls,
             })
+        })
     }
 
     pub fn into_where_expr(&self) -> OrmResult<WhereExpr> {
@@ -820,7 +1183,6 @@ impl KeysetN {
             return Ok(WhereExpr::and(Vec::new()));
         };
 
-        let op = seek_cmp_op(self.dir, cursor);
         let values = match cursor {
             Cursor::After(values) | Cursor::Before(values) => values,
         };
@@ -834,20 +1196,12 @@ impl KeysetN {
         }
 
         let mut or_terms = Vec::with_capacity(self.columns.len());
-        for idx in 0..self.columns.len() {
+        for (idx, column) in self.columns.iter().enumerate() {
             let mut and_terms = Vec::with_capacity(idx + 1);
-            for eq_idx in 0..idx {
-                and_terms.push(WhereExpr::atom(Condition::cmp_dyn(
-                    self.columns[eq_idx].clone(),
-                    "=",
-                    values[eq_idx].clone(),
-                )));
+            for (prev, value) in self.columns[..idx].iter().zip(values) {
+                and_terms.push(WhereExpr::atom(prev.eq_condition(value.clone())));
             }
-            and_terms.push(WhereExpr::atom(Condition::cmp_dyn(
-                self.columns[idx].clone(),
-                op,
-                values[idx].clone(),
-            )));
+            and_terms.push(column.seek_condition(cursor, values[idx].clone()));
 
             if and_terms.len() == 1 {
                 or_terms.push(and_terms.remove(0));
@@ -879,6 +1233,255 @@ impl KeysetN {
         }
         self.append_order_by_limit_to_sql(sql)
     }
+
+    // ==================== Pages ====================
+
+    /// Append ORDER BY and `LIMIT limit + 1` for fetching a [`Page`].
+    ///
+    /// A `before` cursor is fetched in reverse order (nearest rows first);
+    /// [`into_page`](Self::into_page) flips the rows back.
+    pub fn append_page_order_by_limit_to_sql(&self, sql: &mut Sql) -> OrmResult<()> {
+        if self.limit < 1 {
+            return Err(OrmError::validation(format!(
+                "keyset limit must be >= 1, got {}",
+                self.limit
+            )));
+        }
+        let backward = matches!(self.cursor, Some(Cursor::Before(_)));
+        self.columns
+            .iter()
+            .fold(OrderBy::new(), |acc, col| {
+                let (dir, nulls) = if backward {
+                    (col.dir.reverse(), col.nulls.map(NullsOrder::reverse))
+                } else {
+                    (col.dir, col.nulls)
+                };
+                acc.add(OrderItem::Column {
+                    column: col.column.clone(),
+                    dir,
+                    nulls,
+                })
+            })
+            .append_to_sql(sql);
+        sql.limit(self.limit.saturating_add(1));
+        Ok(())
+    }
+
+    /// Like [`append_to_sql`](Self::append_to_sql), but for fetching a [`Page`].
+    pub fn append_page_to_sql(&self, sql: &mut Sql) -> OrmResult<()> {
+        let seek = self.into_where_expr()?;
+        if !seek.is_trivially_true() {
+            sql.push(" WHERE ");
+            seek.append_to_sql(sql);
+        }
+        self.append_page_order_by_limit_to_sql(sql)
+    }
+
+    /// Turn rows fetched with [`append_page_order_by_limit_to_sql`](Self::append_page_order_by_limit_to_sql)
+    /// into a [`Page`] with cursor tokens.
+    #[cfg(feature = "cursor")]
+    pub fn into_page<T: CursorKey>(&self, rows: Vec<T>, codec: &CursorCodec) -> OrmResult<Page<T>> {
+        let side = self.cursor.as_ref().map(|c| match c {
+            Cursor::After(_) => CursorSide::After,
+            Cursor::Before(_) => CursorSide::Before,
+        });
+        let limit = usize::try_from(self.limit).unwrap_or(usize::MAX);
+        page::build_page(rows, limit, side, codec)
+    }
+
+    /// Run `sql` (a query without a WHERE clause) as one page of this keyset.
+    ///
+    /// For filtered queries, compose [`into_where_expr`](Self::into_where_expr) with your
+    /// filters, then call [`append_page_order_by_limit_to_sql`](Self::append_page_order_by_limit_to_sql)
+    /// and [`into_page`](Self::into_page).
+    #[cfg(feature = "cursor")]
+    pub async fn fetch_page<T>(
+        &self,
+        conn: &impl GenericClient,
+        mut sql: Sql,
+        codec: &CursorCodec,
+    ) -> OrmResult<Page<T>>
+    where
+        T: FromRow + CursorKey,
+    {
+        self.append_page_to_sql(&mut sql)?;
+        let rows = sql.fetch_all_as::<T>(conn).await?;
+        self.into_page(rows, codec)
+    }
+}
+
+// ==================== JSON form (see crate::expr_json) ====================
+
+fn serialize_json<S: serde::Serializer>(
+    json: Result<Value, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    json.map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+fn cursor_json<T>(
+    cursor: &Cursor<T>,
+    f: impl FnOnce(&T) -> Result<Value, String>,
+) -> Result<Value, String> {
+    Ok(match cursor {
+        Cursor::After(v) => json!({ "after": f(v)? }),
+        Cursor::Before(v) => json!({ "before": f(v)? }),
+    })
+}
+
+fn values_json(values: &[DynValue]) -> Result<Value, String> {
+    values
+        .iter()
+        .map(param_to_json)
+        .collect::<Result<_, _>>()
+        .map(Value::Array)
+}
+
+/// `{"column", "dir", "nulls"?}` with an optional cursor and limit appended.
+fn column_json(column: &Ident, dir: SortDir, nulls: Option<NullsOrder>) -> Map<String, Value> {
+    let mut obj = Map::new();
+    obj.insert("column".into(), column.to_sql().into());
+    obj.insert("dir".into(), json!(dir));
+    if let Some(nulls) = nulls {
+        obj.insert("nulls".into(), json!(nulls));
+    }
+    obj
+}
+
+impl WhereExpr {
+    fn to_json(&self) -> Result<Value, String> {
+        let list = |items: &[WhereExpr]| {
+            items
+                .iter()
+                .map(WhereExpr::to_json)
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match self {
+            WhereExpr::Atom(c) => c.to_json()?,
+            WhereExpr::And(items) => json!({ "and": list(items)? }),
+            WhereExpr::Or(items) => json!({ "or": list(items)? }),
+            WhereExpr::Not(inner) => json!({ "not": inner.to_json()? }),
+            WhereExpr::Raw(sql) => json!({ "raw": sql }),
+            WhereExpr::RawBind { template, params } => {
+                json!({ "raw": template, "params": values_json(params)? })
+            }
+            WhereExpr::Exists { from, filter } => {
+                json!({ "exists": { "from": from, "filter": filter.to_json()? } })
+            }
+        })
+    }
+}
+
+impl Serialize for WhereExpr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_json(self.to_json(), serializer)
+    }
+}
+
+impl OrderItem {
+    fn to_json(&self) -> Result<Value, String> {
+        match self {
+            OrderItem::Column { column, dir, nulls } => {
+                Ok(Value::Object(column_json(column, *dir, *nulls)))
+            }
+            OrderItem::JsonPath {
+                column,
+                path,
+                cast,
+                dir,
+                nulls,
+            } => {
+                let mut obj = column_json(column, *dir, *nulls);
+                obj.insert("path".into(), json!(path));
+                if let Some(cast) = cast {
+                    obj.insert("cast".into(), json!(cast));
+                }
+                Ok(Value::Object(obj))
+            }
+            OrderItem::Rank { .. } => Err("ts_rank ordering has no JSON form".to_string()),
+            OrderItem::Raw(sql) => Ok(json!({ "raw": sql })),
+        }
+    }
+}
+
+impl Serialize for OrderBy {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = self
+            .items
+            .iter()
+            .map(OrderItem::to_json)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+        serialize_json(items, serializer)
+    }
+}
+
+impl Keyset1 {
+    pub(crate) fn cursor_dyn(mut self, cursor: Cursor<DynValue>) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+impl Serialize for Keyset1 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let json = (|| {
+            let mut obj = column_json(&self.column, self.dir, None);
+            if let Some(cursor) = &self.cursor {
+                obj.insert("cursor".into(), cursor_json(cursor, param_to_json)?);
+            }
+            obj.insert("limit".into(), self.limit.into());
+            Ok(Value::Object(obj))
+        })();
+        serialize_json(json, serializer)
+    }
+}
+
+impl Keyset2 {
+    pub(crate) fn cursor_dyn(mut self, cursor: Cursor<(DynValue, DynValue)>) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+impl Serialize for Keyset2 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let json = (|| {
+            let mut obj = Map::new();
+            obj.insert("columns".into(), json!([self.a.to_sql(), self.b.to_sql()]));
+            obj.insert("dir".into(), json!(self.dir));
+            if let Some(cursor) = &self.cursor {
+                let cursor = cursor_json(cursor, |(a, b)| {
+                    Ok(json!([param_to_json(a)?, param_to_json(b)?]))
+                })?;
+                obj.insert("cursor".into(), cursor);
+            }
+            obj.insert("limit".into(), self.limit.into());
+            Ok(Value::Object(obj))
+        })();
+        serialize_json(json, serializer)
+    }
+}
+
+impl Serialize for KeysetN {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let json = (|| {
+            let columns: Vec<Value> = self
+                .columns
+                .iter()
+                .map(|c| Value::Object(column_json(&c.column, c.dir, c.nulls)))
+                .collect();
+            let mut obj = Map::new();
+            obj.insert("columns".into(), columns.into());
+            if let Some(cursor) = &self.cursor {
+                obj.insert("cursor".into(), cursor_json(cursor, |v| values_json(v))?);
+            }
+            obj.insert("limit".into(), self.limit.into());
+            Ok(Value::Object(obj))
+        })();
+        serialize_json(json, serializer)
+    }
 }
 
 #[cfg(test)]
@@ -941,6 +1544,30 @@ mod tests {
         assert_eq!(sql.to_sql(), "(status = $1 AND (role = $2 OR role = $3))");
     }
 
+    #[test]
+    fn where_exists() {
+        let expr = WhereExpr::exists(
+            "orders",
+            WhereExpr::raw("orders.user_id = users.id")
+                .and_with(Condition::eq("status", "unpaid").unwrap().into()),
+        );
+        let mut sql = Sql::empty();
+        expr.append_to_sql(&mut sql);
+        assert_eq!(
+            sql.to_sql(),
+            "EXISTS (SELECT 1 FROM orders WHERE (orders.user_id = users.id AND status = $1))"
+        );
+        assert_eq!(sql.params_ref().len(), 1);
+    }
+
+    #[test]
+    fn where_not_exists_without_filter() {
+        let expr = WhereExpr::not_exists("orders", WhereExpr::And(vec![]));
+        let mut sql = Sql::empty();
+        expr.append_to_sql(&mut sql);
+        assert_eq!(sql.to_sql(), "(NOT EXISTS (SELECT 1 FROM orders))");
+    }
+
     #[test]
     fn where_empty_and_is_true() {
         let expr = WhereExpr::And(vec![]);
@@ -1028,6 +1655,25 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn order_by_json_path() {
+        let order = OrderBy::new()
+            .json_path("profile", ["address", "city"], SortDir::Asc)
+            .unwrap()
+            .json_path_as::<i64, _, _>("stats", ["score"], SortDir::Desc)
+            .unwrap()
+            .add(
+                OrderItem::json_path(Ident::parse("meta").unwrap(), ["o'k \"x\""], SortDir::Asc)
+                    .nulls(NullsOrder::Last),
+            );
+        assert_eq!(
+            order.to_sql(),
+            "ORDER BY profile #>> '{\"address\",\"city\"}' ASC, \
+             (stats #>> '{\"score\"}')::bigint DESC, \
+             meta #>> '{\"o''k \\\"x\\\"\"}' ASC NULLS LAST"
+        );
+    }
+
     // ==================== Keyset tests ====================
 
     #[test]
@@ -1133,6 +1779,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keysetn_mixed_directions_before() {
+        let keyset = KeysetN::new([
+            OrderItem::new(Ident::parse("created_at").unwrap(), SortDir::Desc),
+            OrderItem::new(Ident::parse("score").unwrap(), SortDir::Desc),
+            OrderItem::new(Ident::parse("id").unwrap(), SortDir::Asc),
+        ])
+        .unwrap()
+        .before((100_i64, 5_i32, 42_i64))
+        .limit(10);
+        let mut sql = Sql::new("SELECT * FROM t");
+        keyset.append_to_sql(&mut sql).unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "SELECT * FROM t WHERE (created_at > $1 OR (created_at = $2 AND score > $3) OR (created_at = $4 AND score = $5 AND id < $6)) ORDER BY created_at DESC, score DESC, id ASC LIMIT $7"
+        );
+    }
+
+    #[test]
+    fn keysetn_nulls_aware_seek() {
+        let keyset = KeysetN::new([
+            OrderItem::new(Ident::parse("due_at").unwrap(), SortDir::Asc).nulls(NullsOrder::Last),
+            OrderItem::new(Ident::parse("id").unwrap(), SortDir::Asc),
+        ])
+        .unwrap();
+
+        let after = keyset.clone().after((Some(7_i64), 1_i64));
+        let mut sql = Sql::empty();
+        after.into_where_expr().unwrap().append_to_sql(&mut sql);
+        assert_eq!(
+            sql.to_sql(),
+            "((due_at > $1 OR (due_at IS NULL AND due_at IS DISTINCT FROM $2)) OR (due_at IS NOT DISTINCT FROM $3 AND id > $4))"
+        );
+        assert_eq!(
+            keyset.order_by().to_sql(),
+            "ORDER BY due_at ASC NULLS LAST, id ASC"
+        );
+
+        let before = keyset.before((None::<i64>, 1_i64));
+        let mut sql = Sql::empty();
+        before.into_where_expr().unwrap().append_to_sql(&mut sql);
+        assert_eq!(
+            sql.to_sql(),
+            "((due_at IS NOT NULL AND (due_at < $1) IS NOT FALSE) OR (due_at IS NOT DISTINCT FROM $2 AND id < $3))"
+        );
+    }
+
+    #[test]
+    fn keysetn_rejects_non_column_items() {
+        assert!(
+            KeysetN::new([
+                OrderItem::Raw("random()".into()),
+                OrderItem::Raw("id".into())
+            ])
+            .is_err()
+        );
+    }
+
     #[test]
     fn keysetn_rejects_mismatched_cursor_len() {
         let keyset = KeysetN::asc(["a", "b", "c"]).unwrap().after((1_i32, 2_i32));
@@ -1,7 +1,8 @@
-//! Bulk update and delete operations.
+//! Bulk update, delete, merge and insert-select operations.
 //!
-//! This module provides [`SetExpr`], [`UpdateManyBuilder`], and [`DeleteManyBuilder`]
-//! for performing batch UPDATE and DELETE operations with type-safe conditions.
+//! This module provides [`SetExpr`], [`UpdateManyBuilder`], [`DeleteManyBuilder`],
+//! [`MergeBuilder`], [`InsertBuilder`] and [`InsertSelectBuilder`] for performing
+//! batch writes with type-safe conditions.
 //!
 //! # Example
 //! ```ignore
@@ -23,13 +24,23 @@
 //!     .filter(Condition::lt("expires_at", now)?)
 //!     .execute(&client)
 //!     .await?;
+//!
+//! // Upsert with a conflict target chosen at runtime
+//! let written = pgorm::sql("products")
+//!     .insert(["sku", "price"])?
+//!     .values(("A-1", 100_i64))
+//!     .values(("B-2", 250_i64))
+//!     .on_conflict(OnConflict::columns(["sku"])?.do_update_excluded(["price"])?)
+//!     .execute(&client)
+//!     .await?;
 //! ```
 
 use crate::builder::WhereExpr;
 use crate::client::{GenericClient, StreamingClient};
+use crate::condition::element_type;
 use crate::error::{OrmError, OrmResult};
 use crate::ident::{Ident, IntoIdent};
-use crate::row::FromRow;
+use crate::row::{FromRow, PgType};
 use crate::sql::{FromRowStream, Sql};
 use std::sync::Arc;
 use tokio_postgres::types::ToSql;
@@ -50,6 +61,9 @@ use tokio_postgres::types::ToSql;
 ///
 /// // Raw SQL expression: SET updated_at = NOW()
 /// SetExpr::raw("updated_at = NOW()");
+///
+/// // JSONB: SET settings = jsonb_set(COALESCE(settings, '{}'::jsonb), $1::text[], $2::jsonb, true)
+/// SetExpr::jsonb_set("settings", ["theme"], serde_json::json!("dark"))?;
 /// ```
 pub enum SetExpr {
     /// `column = $n` (parameterized value)
@@ -59,6 +73,42 @@ pub enum SetExpr {
     },
     /// `column = column + amount` (increment/decrement)
     Increment { column: Ident, amount: i64 },
+    /// `column = jsonb_set(COALESCE(column, '{}'::jsonb), $n::text[], $m::jsonb, true)`
+    JsonbSet {
+        column: Ident,
+        path: Vec<String>,
+        value: Arc<dyn ToSql + Send + Sync>,
+    },
+    /// `column = COALESCE(column, '{}'::jsonb) || $n::jsonb`
+    JsonbMerge {
+        column: Ident,
+        value: Arc<dyn ToSql + Send + Sync>,
+    },
+    /// `column = column - $n::text` (remove a top-level key)
+    JsonbRemoveKey { column: Ident, key: String },
+    /// `column = column #- $n::text[]` (remove the value at a key path)
+    JsonbRemovePath { column: Ident, path: Vec<String> },
+    /// `column = array_append(column, $n::<type>)`
+    ArrayAppend {
+        column: Ident,
+        value: Arc<dyn ToSql + Send + Sync>,
+        element_type: &'static str,
+    },
+    /// `column = array_remove(column, $n::<type>)`
+    ArrayRemove {
+        column: Ident,
+        value: Arc<dyn ToSql + Send + Sync>,
+        element_type: &'static str,
+    },
+    /// `column = array_cat(column, $n::<type>[])`
+    ArrayCat {
+        column: Ident,
+        values: Arc<dyn ToSql + Send + Sync>,
+        array_type: &'static str,
+    },
+    /// `column = source`, where `source` is another (possibly qualified) column,
+    /// e.g. `s.price` in a MERGE or `EXCLUDED.price` in an upsert
+    Column { column: Ident, source: Ident },
     /// Raw SQL expression (escape hatch), e.g. `"updated_at = NOW()"`
     Raw(String),
 }
@@ -85,6 +135,118 @@ impl SetExpr {
         })
     }
 
+    /// Create a SET clause that writes `value` at a jsonb key path, creating
+    /// missing keys: `col = jsonb_set(COALESCE(col, '{}'::jsonb), $1::text[], $2::jsonb, true)`
+    ///
+    /// `value` must encode as jsonb (`serde_json::Value` or `Json<T>`).
+    pub fn jsonb_set<P, S, T>(column: impl IntoIdent, path: P, value: T) -> OrmResult<Self>
+    where
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+        T: ToSql + Send + Sync + 'static,
+    {
+        Ok(SetExpr::JsonbSet {
+            column: column.into_ident()?,
+            path: path.into_iter().map(Into::into).collect(),
+            value: Arc::new(value),
+        })
+    }
+
+    /// Create a SET clause that shallow-merges a jsonb object into the column:
+    /// `col = COALESCE(col, '{}'::jsonb) || $1::jsonb`
+    ///
+    /// Keys in `value` overwrite existing keys; other keys are kept.
+    pub fn jsonb_merge<T: ToSql + Send + Sync + 'static>(
+        column: impl IntoIdent,
+        value: T,
+    ) -> OrmResult<Self> {
+        Ok(SetExpr::JsonbMerge {
+            column: column.into_ident()?,
+            value: Arc::new(value),
+        })
+    }
+
+    /// Create a SET clause that removes a top-level jsonb key: `col = col - $1::text`
+    pub fn jsonb_remove_key(column: impl IntoIdent, key: impl Into<String>) -> OrmResult<Self> {
+        Ok(SetExpr::JsonbRemoveKey {
+            column: column.into_ident()?,
+            key: key.into(),
+        })
+    }
+
+    /// Create a SET clause that removes the jsonb value at a key path:
+    /// `col = col #- $1::text[]`
+    pub fn jsonb_remove_path<P, S>(column: impl IntoIdent, path: P) -> OrmResult<Self>
+    where
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Ok(SetExpr::JsonbRemovePath {
+            column: column.into_ident()?,
+            path: path.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Create a SET clause that appends an element to an array column:
+    /// `col = array_append(col, $1::<type>)`
+    ///
+    /// The element is cast using `T`'s [`PgType`] array name (`text[]` -> `text`).
+    pub fn array_append<T>(column: impl IntoIdent, value: T) -> OrmResult<Self>
+    where
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(SetExpr::ArrayAppend {
+            column: column.into_ident()?,
+            value: Arc::new(value),
+            element_type: element_type::<T>(),
+        })
+    }
+
+    /// Create a SET clause that removes every occurrence of an element from an
+    /// array column: `col = array_remove(col, $1::<type>)`
+    pub fn array_remove<T>(column: impl IntoIdent, value: T) -> OrmResult<Self>
+    where
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(SetExpr::ArrayRemove {
+            column: column.into_ident()?,
+            value: Arc::new(value),
+            element_type: element_type::<T>(),
+        })
+    }
+
+    /// Create a SET clause that concatenates values onto an array column:
+    /// `col = array_cat(col, $1::<type>[])`
+    pub fn array_cat<T>(column: impl IntoIdent, values: Vec<T>) -> OrmResult<Self>
+    where
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(SetExpr::ArrayCat {
+            column: column.into_ident()?,
+            values: Arc::new(values),
+            array_type: T::pg_array_type(),
+        })
+    }
+
+    /// Create a SET clause that copies another column: `col = source`
+    ///
+    /// `source` may be qualified, e.g. the MERGE source alias: `SetExpr::column("price", "s.price")`.
+    pub fn column(column: impl IntoIdent, source: impl IntoIdent) -> OrmResult<Self> {
+        Ok(SetExpr::Column {
+            column: column.into_ident()?,
+            source: source.into_ident()?,
+        })
+    }
+
+    /// Create a SET clause that takes the proposed row's value in an upsert:
+    /// `col = EXCLUDED.col`
+    pub fn excluded(column: impl IntoIdent) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        let mut source = Ident::parse("EXCLUDED")?;
+        source.parts.extend(column.parts.iter().cloned());
+        Ok(SetExpr::Column { column, source })
+    }
+
     /// Create a SET clause with a raw SQL expression.
     ///
     /// The string should be a complete assignment expression, e.g. `"updated_at = NOW()"`.
@@ -94,7 +256,12 @@ impl SetExpr {
         SetExpr::Raw(expr.into())
     }
 
-    fn append_to_sql(&self, sql: &mut Sql) {
+    /// Append `column = <expr>`.
+    ///
+    /// `target` qualifies the current value of the column on the right-hand side,
+    /// for statements where another relation with the same columns is in scope
+    /// (the MERGE source).
+    fn append_to_sql(&self, sql: &mut Sql, target: Option<&Ident>) {
         match self {
             SetExpr::Value { column, value } => {
                 sql.push_ident_ref(column);
@@ -104,7 +271,7 @@ impl SetExpr {
             SetExpr::Increment { column, amount } => {
                 sql.push_ident_ref(column);
                 sql.push(" = ");
-                sql.push_ident_ref(column);
+                push_current(sql, target, column);
                 if *amount >= 0 {
                     let s = format!(" + {amount}");
                     sql.push(&s);
@@ -113,11 +280,133 @@ impl SetExpr {
                     sql.push(&s);
                 }
             }
+            SetExpr::JsonbSet {
+                column,
+                path,
+                value,
+            } => {
+                sql.push_ident_ref(column);
+                sql.push(" = jsonb_set(COALESCE(");
+                push_current(sql, target, column);
+                sql.push(", '{}'::jsonb), ");
+                sql.push_bind(path.clone());
+                sql.push("::text[], ");
+                sql.push_bind_value(value.clone());
+                sql.push("::jsonb, true)");
+            }
+            SetExpr::JsonbMerge { column, value } => {
+                sql.push_ident_ref(column);
+                sql.push(" = COALESCE(");
+                push_current(sql, target, column);
+                sql.push(", '{}'::jsonb) || ");
+                sql.push_bind_value(value.clone());
+                sql.push("::jsonb");
+            }
+            SetExpr::JsonbRemoveKey { column, key } => {
+                sql.push_ident_ref(column);
+                sql.push(" = ");
+                push_current(sql, target, column);
+                sql.push(" - ");
+                sql.push_bind(key.clone());
+                sql.push("::text");
+            }
+            SetExpr::JsonbRemovePath { column, path } => {
+                sql.push_ident_ref(column);
+                sql.push(" = ");
+                push_current(sql, target, column);
+                sql.push(" #- ");
+                sql.push_bind(path.clone());
+                sql.push("::text[]");
+            }
+            SetExpr::ArrayAppend {
+                column,
+                value,
+                element_type,
+            } => push_array_fn(sql, target, column, "array_append", value, element_type),
+            SetExpr::ArrayRemove {
+                column,
+                value,
+                element_type,
+            } => push_array_fn(sql, target, column, "array_remove", value, element_type),
+            SetExpr::ArrayCat {
+                column,
+                values,
+                array_type,
+            } => push_array_fn(sql, target, column, "array_cat", values, array_type),
+            SetExpr::Column { column, source } => {
+                sql.push_ident_ref(column);
+                sql.push(" = ");
+                sql.push_ident_ref(source);
+            }
             SetExpr::Raw(expr) => {
                 sql.push(expr);
             }
         }
     }
+
+    /// Target column and value of an INSERT arm (only plain values and column copies).
+    fn insert_parts(&self) -> OrmResult<(&Ident, InsertValue<'_>)> {
+        match self {
+            SetExpr::Value { column, value } => Ok((column, InsertValue::Param(value))),
+            SetExpr::Column { column, source } => Ok((column, InsertValue::Column(source))),
+            _ => Err(OrmError::Validation(
+                "MERGE INSERT values must be SetExpr::set or SetExpr::column".to_string(),
+            )),
+        }
+    }
+}
+
+enum InsertValue<'a> {
+    Param(&'a Arc<dyn ToSql + Send + Sync>),
+    Column(&'a Ident),
+}
+
+fn push_set_list(sql: &mut Sql, sets: &[SetExpr], target: Option<&Ident>) {
+    for (i, set) in sets.iter().enumerate() {
+        if i > 0 {
+            sql.push(", ");
+        }
+        set.append_to_sql(sql, target);
+    }
+}
+
+/// The current value of `column`, qualified with `target` when given.
+fn push_current(sql: &mut Sql, target: Option<&Ident>, column: &Ident) {
+    if let Some(target) = target {
+        sql.push_ident_ref(target);
+        sql.push(".");
+    }
+    sql.push_ident_ref(column);
+}
+
+fn push_ident_list(sql: &mut Sql, idents: &[Ident]) {
+    for (i, ident) in idents.iter().enumerate() {
+        if i > 0 {
+            sql.push(", ");
+        }
+        sql.push_ident_ref(ident);
+    }
+}
+
+/// `column = function(column, $n::cast)`
+fn push_array_fn(
+    sql: &mut Sql,
+    target: Option<&Ident>,
+    column: &Ident,
+    function: &str,
+    value: &Arc<dyn ToSql + Send + Sync>,
+    cast: &str,
+) {
+    sql.push_ident_ref(column);
+    sql.push(" = ");
+    sql.push(function);
+    sql.push("(");
+    push_current(sql, target, column);
+    sql.push(", ");
+    sql.push_bind_value(value.clone());
+    sql.push("::");
+    sql.push(cast);
+    sql.push(")");
 }
 
 // ==================== UpdateManyBuilder ====================
@@ -178,13 +467,7 @@ impl UpdateManyBuilder {
         let mut sql = Sql::new("UPDATE ");
         sql.push_ident_ref(&self.table);
         sql.push(" SET ");
-
-        for (i, set) in self.sets.iter().enumerate() {
-            if i > 0 {
-                sql.push(", ");
-            }
-            set.append_to_sql(&mut sql);
-        }
+        push_set_list(&mut sql, &self.sets, None);
 
         if let Some(ref where_clause) = self.where_clause {
             sql.push(" WHERE ");
@@ -301,151 +584,990 @@ impl DeleteManyBuilder {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::condition::Condition;
+// ==================== OnConflict ====================
 
-    #[test]
-    fn update_many_basic_sql() {
-        let builder = UpdateManyBuilder {
-            table: Ident::parse("users").unwrap(),
-            sets: vec![SetExpr::set("status", "inactive").unwrap()],
-            where_clause: Some(WhereExpr::Atom(Condition::eq("active", true).unwrap())),
-            all_rows: false,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(
-            sql.to_sql(),
-            "UPDATE users SET status = $1 WHERE active = $2"
-        );
-        assert_eq!(sql.params_ref().len(), 2);
-    }
+enum ConflictTarget {
+    Any,
+    Columns(Vec<Ident>),
+    Constraint(Ident),
+}
 
-    #[test]
-    fn update_many_multiple_sets() {
-        let builder = UpdateManyBuilder {
-            table: Ident::parse("orders").unwrap(),
-            sets: vec![
-                SetExpr::set("status", "shipped").unwrap(),
-                SetExpr::raw("shipped_at = NOW()"),
-            ],
-            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 1_i64).unwrap())),
-            all_rows: false,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(
-            sql.to_sql(),
-            "UPDATE orders SET status = $1, shipped_at = NOW() WHERE id = $2"
-        );
-        assert_eq!(sql.params_ref().len(), 2);
-    }
+enum ConflictAction {
+    Nothing,
+    Update(Vec<SetExpr>),
+}
 
-    #[test]
-    fn update_many_increment() {
-        let builder = UpdateManyBuilder {
-            table: Ident::parse("products").unwrap(),
-            sets: vec![SetExpr::increment("view_count", 1).unwrap()],
-            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 42_i64).unwrap())),
-            all_rows: false,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(
-            sql.to_sql(),
-            "UPDATE products SET view_count = view_count + 1 WHERE id = $1"
-        );
-        assert_eq!(sql.params_ref().len(), 1);
+/// An `ON CONFLICT` clause for inserts.
+///
+/// # Example
+/// ```ignore
+/// use pgorm::{OnConflict, SetExpr};
+///
+/// // ON CONFLICT (sku) DO UPDATE SET price = EXCLUDED.price WHERE products.locked = $1
+/// OnConflict::columns(["sku"])?
+///     .do_update([SetExpr::excluded("price")?])
+///     .filter(Condition::eq("products.locked", false)?);
+///
+/// // ON CONFLICT ON CONSTRAINT products_sku_key DO NOTHING
+/// OnConflict::constraint("products_sku_key")?.do_nothing();
+/// ```
+#[must_use]
+pub struct OnConflict {
+    target: ConflictTarget,
+    action: ConflictAction,
+    where_clause: Option<WhereExpr>,
+}
+
+impl OnConflict {
+    fn new(target: ConflictTarget) -> Self {
+        Self {
+            target,
+            action: ConflictAction::Nothing,
+            where_clause: None,
+        }
     }
 
-    #[test]
-    fn update_many_decrement() {
-        let builder = UpdateManyBuilder {
-            table: Ident::parse("products").unwrap(),
-            sets: vec![SetExpr::increment("stock", -5).unwrap()],
-            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 1_i64).unwrap())),
-            all_rows: false,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(
-            sql.to_sql(),
-            "UPDATE products SET stock = stock - 5 WHERE id = $1"
-        );
+    /// Conflict on any unique constraint (`ON CONFLICT DO NOTHING` only).
+    pub fn any() -> Self {
+        Self::new(ConflictTarget::Any)
     }
 
-    #[test]
-    fn update_many_all_rows() {
-        let builder = UpdateManyBuilder {
-            table: Ident::parse("temp_data").unwrap(),
-            sets: vec![SetExpr::set("status", "archived").unwrap()],
-            where_clause: None,
-            all_rows: true,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(sql.to_sql(), "UPDATE temp_data SET status = $1");
+    /// Conflict on a unique index over these columns: `ON CONFLICT (a, b)`.
+    pub fn columns<I, T>(columns: I) -> OrmResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        let columns = columns
+            .into_iter()
+            .map(IntoIdent::into_ident)
+            .collect::<OrmResult<Vec<_>>>()?;
+        if columns.is_empty() {
+            return Err(OrmError::Validation(
+                "OnConflict::columns requires at least one column".to_string(),
+            ));
+        }
+        Ok(Self::new(ConflictTarget::Columns(columns)))
     }
 
-    #[test]
-    fn update_many_rejects_no_where() {
-        let builder = UpdateManyBuilder {
-            table: Ident::parse("users").unwrap(),
-            sets: vec![SetExpr::set("status", "x").unwrap()],
-            where_clause: None,
-            all_rows: false,
-        };
-        assert!(builder.build_sql().is_err());
+    /// Conflict on a named constraint: `ON CONFLICT ON CONSTRAINT name`.
+    pub fn constraint(name: impl IntoIdent) -> OrmResult<Self> {
+        Ok(Self::new(ConflictTarget::Constraint(name.into_ident()?)))
     }
 
-    #[test]
-    fn delete_many_basic_sql() {
-        let builder = DeleteManyBuilder {
-            table: Ident::parse("sessions").unwrap(),
-            where_clause: Some(WhereExpr::raw("expires_at < NOW()")),
-            all_rows: false,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(
-            sql.to_sql(),
-            "DELETE FROM sessions WHERE expires_at < NOW()"
-        );
+    /// Skip conflicting rows (`DO NOTHING`, the default).
+    pub fn do_nothing(mut self) -> Self {
+        self.action = ConflictAction::Nothing;
+        self
     }
 
-    #[test]
-    fn delete_many_with_condition() {
-        let builder = DeleteManyBuilder {
-            table: Ident::parse("audit_logs").unwrap(),
-            where_clause: Some(WhereExpr::And(vec![
-                WhereExpr::Atom(Condition::eq("level", "debug").unwrap()),
-                WhereExpr::Atom(Condition::eq("archived", true).unwrap()),
-            ])),
-            all_rows: false,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(
-            sql.to_sql(),
-            "DELETE FROM audit_logs WHERE (level = $1 AND archived = $2)"
-        );
-        assert_eq!(sql.params_ref().len(), 2);
+    /// Update the existing row (`DO UPDATE SET ...`); use [`SetExpr::excluded`]
+    /// to take values from the proposed row.
+    pub fn do_update(mut self, sets: impl IntoIterator<Item = SetExpr>) -> Self {
+        self.action = ConflictAction::Update(sets.into_iter().collect());
+        self
     }
 
-    #[test]
-    fn delete_many_all_rows() {
-        let builder = DeleteManyBuilder {
-            table: Ident::parse("temp_data").unwrap(),
-            where_clause: None,
-            all_rows: true,
-        };
-        let sql = builder.build_sql().unwrap();
-        assert_eq!(sql.to_sql(), "DELETE FROM temp_data");
+    /// Update the given columns from the proposed row:
+    /// `DO UPDATE SET a = EXCLUDED.a, b = EXCLUDED.b`.
+    pub fn do_update_excluded<I, T>(self, columns: I) -> OrmResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        let sets = columns
+            .into_iter()
+            .map(SetExpr::excluded)
+            .collect::<OrmResult<Vec<_>>>()?;
+        Ok(self.do_update(sets))
     }
 
-    #[test]
-    fn delete_many_rejects_no_where() {
-        let builder = DeleteManyBuilder {
-            table: Ident::parse("users").unwrap(),
-            where_clause: None,
-            all_rows: false,
-        };
-        assert!(builder.build_sql().is_err());
+    /// Only update rows matching this condition (`DO UPDATE ... WHERE ...`).
+    pub fn filter(mut self, condition: impl Into<WhereExpr>) -> Self {
+        let new_where = condition.into();
+        self.where_clause = Some(match self.where_clause.take() {
+            Some(existing) => existing.and_with(new_where),
+            None => new_where,
+        });
+        self
+    }
+
+    /// Append ` ON CONFLICT ...` to a SQL builder.
+    ///
+    /// `target` is the insert table; `DO UPDATE` expressions that read the current
+    /// value (`increment`, `jsonb_*`, `array_*`) qualify it with this name, since an
+    /// unqualified column would be ambiguous with `EXCLUDED`.
+    pub fn append_to_sql(&self, sql: &mut Sql, target: &Ident) -> OrmResult<()> {
+        sql.push(" ON CONFLICT");
+        match &self.target {
+            ConflictTarget::Any => {}
+            ConflictTarget::Columns(columns) => {
+                sql.push(" (");
+                push_ident_list(sql, columns);
+                sql.push(")");
+            }
+            ConflictTarget::Constraint(name) => {
+                sql.push(" ON CONSTRAINT ");
+                sql.push_ident_ref(name);
+            }
+        }
+        match &self.action {
+            ConflictAction::Nothing => {
+                if self.where_clause.is_some() {
+                    return Err(OrmError::Validation(
+                        "OnConflict::filter only applies to do_update".to_string(),
+                    ));
+                }
+                sql.push(" DO NOTHING");
+            }
+            ConflictAction::Update(sets) => {
+                if matches!(self.target, ConflictTarget::Any) {
+                    return Err(OrmError::Validation(
+                        "ON CONFLICT DO UPDATE requires a conflict target (columns or constraint)"
+                            .to_string(),
+                    ));
+                }
+                if sets.is_empty() {
+                    return Err(OrmError::Validation(
+                        "OnConflict::do_update requires at least one SetExpr".to_string(),
+                    ));
+                }
+                sql.push(" DO UPDATE SET ");
+                push_set_list(sql, sets, Some(target));
+                if let Some(where_clause) = &self.where_clause {
+                    sql.push(" WHERE ");
+                    where_clause.append_to_sql(sql);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ==================== InsertBuilder ====================
+
+/// Conversion of a heterogeneous row of values for [`InsertBuilder::values`].
+///
+/// Implemented for tuples of up to 8 values and for pre-erased `Vec<Arc<dyn ToSql>>`.
+pub trait IntoInsertRow {
+    fn into_insert_row(self) -> Vec<Arc<dyn ToSql + Send + Sync>>;
+}
+
+impl IntoInsertRow for Vec<Arc<dyn ToSql + Send + Sync>> {
+    fn into_insert_row(self) -> Vec<Arc<dyn ToSql + Send + Sync>> {
+        self
+    }
+}
+
+macro_rules! impl_into_insert_row_tuple {
+    ($($name:ident : $idx:tt),+ $(,)?) => {
+        impl<$($name),+> IntoInsertRow for ($($name,)+)
+        where
+            $($name: ToSql + Send + Sync + 'static),+
+        {
+            fn into_insert_row(self) -> Vec<Arc<dyn ToSql + Send + Sync>> {
+                vec![
+                    $(Arc::new(self.$idx) as Arc<dyn ToSql + Send + Sync>,)+
+                ]
+            }
+        }
+    };
+}
+
+impl_into_insert_row_tuple!(A: 0);
+impl_into_insert_row_tuple!(A: 0, B: 1);
+impl_into_insert_row_tuple!(A: 0, B: 1, C: 2);
+impl_into_insert_row_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_into_insert_row_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_into_insert_row_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_into_insert_row_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_into_insert_row_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+struct UnnestArray {
+    values: Arc<dyn ToSql + Send + Sync>,
+    array_type: &'static str,
+    len: usize,
+}
+
+/// Builder for `INSERT INTO t (cols) VALUES ...` / `SELECT * FROM UNNEST(...)`
+/// statements with a runtime column list and an optional [`OnConflict`] clause.
+///
+/// Created via [`Sql::insert`]. Rows are given either one at a time with
+/// [`values`](Self::values) (`VALUES ($1, $2), ($3, $4)`), or as one array per column
+/// with [`unnest`](Self::unnest) (`UNNEST($1::text[], $2::bigint[])`), which keeps
+/// the parameter count fixed for large batches. The two styles cannot be mixed.
+///
+/// # Example
+/// ```ignore
+/// let written: Vec<Product> = pgorm::sql("products")
+///     .insert(["sku", "price"])?
+///     .unnest(skus)
+///     .unnest(prices)
+///     .on_conflict(
+///         OnConflict::columns(["sku"])?
+///             .do_update_excluded(["price"])?
+///             .filter(Condition::eq("products.locked", false)?),
+///     )
+///     .returning(&client)
+///     .await?;
+/// ```
+#[must_use]
+pub struct InsertBuilder {
+    pub(crate) table: Ident,
+    pub(crate) columns: Vec<Ident>,
+    rows: Vec<Vec<Arc<dyn ToSql + Send + Sync>>>,
+    arrays: Vec<UnnestArray>,
+    on_conflict: Option<OnConflict>,
+}
+
+impl InsertBuilder {
+    pub(crate) fn new(table: Ident, columns: Vec<Ident>) -> Self {
+        Self {
+            table,
+            columns,
+            rows: Vec::new(),
+            arrays: Vec::new(),
+            on_conflict: None,
+        }
+    }
+
+    /// Add one row of values, in column order: `.values(("A-1", 100_i64))`.
+    pub fn values(mut self, row: impl IntoInsertRow) -> Self {
+        self.rows.push(row.into_insert_row());
+        self
+    }
+
+    /// Add the values of the next column as one array, cast with `T`'s [`PgType`].
+    ///
+    /// Call once per column, in column order; all arrays must have the same length.
+    pub fn unnest<T>(mut self, values: Vec<T>) -> Self
+    where
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        self.arrays.push(UnnestArray {
+            len: values.len(),
+            values: Arc::new(values),
+            array_type: T::pg_array_type(),
+        });
+        self
+    }
+
+    /// Add an `ON CONFLICT` clause.
+    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+        self.on_conflict = Some(on_conflict);
+        self
+    }
+
+    /// Number of rows this statement inserts.
+    pub fn row_count(&self) -> usize {
+        match self.arrays.first() {
+            Some(array) => array.len,
+            None => self.rows.len(),
+        }
+    }
+
+    /// Check that the rows or arrays match the column list; an empty insert passes.
+    fn check_shape(&self) -> OrmResult<()> {
+        if self.columns.is_empty() {
+            return Err(OrmError::Validation(
+                "insert requires at least one column".to_string(),
+            ));
+        }
+        if !self.rows.is_empty() && !self.arrays.is_empty() {
+            return Err(OrmError::Validation(
+                "insert cannot mix .values() rows with .unnest() arrays".to_string(),
+            ));
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            if row.len() != self.columns.len() {
+                return Err(OrmError::Validation(format!(
+                    "insert row {i} has {} values, expected {} (one per column)",
+                    row.len(),
+                    self.columns.len()
+                )));
+            }
+        }
+        if !self.arrays.is_empty() {
+            if self.arrays.len() != self.columns.len() {
+                return Err(OrmError::Validation(format!(
+                    "insert has {} unnest arrays, expected {} (one per column)",
+                    self.arrays.len(),
+                    self.columns.len()
+                )));
+            }
+            if self.arrays.iter().any(|a| a.len != self.arrays[0].len) {
+                return Err(OrmError::Validation(
+                    "insert unnest arrays must all have the same length".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the SQL statement without executing it.
+    ///
+    /// Useful for inspecting the generated SQL.
+    pub fn build_sql(&self) -> OrmResult<Sql> {
+        self.check_shape()?;
+        if self.rows.is_empty() && self.arrays.is_empty() {
+            return Err(OrmError::Validation(
+                "insert requires at least one row: call .values() or .unnest()".to_string(),
+            ));
+        }
+
+        let mut sql = Sql::new("INSERT INTO ");
+        sql.push_ident_ref(&self.table);
+        sql.push(" (");
+        push_ident_list(&mut sql, &self.columns);
+        sql.push(")");
+
+        if self.arrays.is_empty() {
+            sql.push(" VALUES ");
+            for (i, row) in self.rows.iter().enumerate() {
+                if i > 0 {
+                    sql.push(", ");
+                }
+                sql.push("(");
+                for (j, value) in row.iter().enumerate() {
+                    if j > 0 {
+                        sql.push(", ");
+                    }
+                    sql.push_bind_value(Arc::clone(value));
+                }
+                sql.push(")");
+            }
+        } else {
+            sql.push(" SELECT * FROM UNNEST(");
+            for (i, array) in self.arrays.iter().enumerate() {
+                if i > 0 {
+                    sql.push(", ");
+                }
+                sql.push_bind_value(Arc::clone(&array.values));
+                sql.push("::");
+                sql.push(array.array_type);
+            }
+            sql.push(")");
+        }
+
+        if let Some(on_conflict) = &self.on_conflict {
+            on_conflict.append_to_sql(&mut sql, &self.table)?;
+        }
+        Ok(sql)
+    }
+
+    /// Execute the insert, returning the number of inserted (or updated) rows.
+    ///
+    /// Returns `Ok(0)` without a round trip when there are no rows; a mismatched
+    /// shape is still reported as a validation error.
+    pub async fn execute(self, conn: &impl GenericClient) -> OrmResult<u64> {
+        self.check_shape()?;
+        if self.row_count() == 0 {
+            return Ok(0);
+        }
+        let sql = self.build_sql()?;
+        sql.execute(conn).await
+    }
+
+    /// Execute the insert and return the written rows.
+    ///
+    /// Appends `RETURNING *` to the query. Rows skipped by `DO NOTHING` (or by the
+    /// `DO UPDATE ... WHERE` filter) are not returned.
+    pub async fn returning<T: FromRow>(self, conn: &impl GenericClient) -> OrmResult<Vec<T>> {
+        self.check_shape()?;
+        if self.row_count() == 0 {
+            return Ok(Vec::new());
+        }
+        let mut sql = self.build_sql()?;
+        sql.push(" RETURNING *");
+        sql.fetch_all_as(conn).await
+    }
+}
+
+// ==================== InsertSelectBuilder ====================
+
+/// Builder for `INSERT INTO t (cols) SELECT ...` statements.
+///
+/// Created via [`Sql::insert_select`].
+///
+/// # Example
+/// ```ignore
+/// let copied = pgorm::sql("products")
+///     .insert_select(
+///         ["sku", "name", "price"],
+///         pgorm::sql("SELECT sku, name, price FROM staging_products WHERE batch_id = ").bind(batch),
+///     )?
+///     .on_conflict(OnConflict::columns(["sku"])?.do_update([SetExpr::excluded("price")?]))
+///     .execute(&client)
+///     .await?;
+/// ```
+#[must_use]
+pub struct InsertSelectBuilder {
+    pub(crate) table: Ident,
+    pub(crate) columns: Vec<Ident>,
+    pub(crate) query: Sql,
+    pub(crate) on_conflict: Option<OnConflict>,
+}
+
+impl InsertSelectBuilder {
+    /// Add an `ON CONFLICT` clause.
+    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+        self.on_conflict = Some(on_conflict);
+        self
+    }
+
+    /// Build the SQL statement, consuming the builder (the `SELECT` is moved in).
+    pub fn build_sql(self) -> OrmResult<Sql> {
+        let mut sql = Sql::new("INSERT INTO ");
+        sql.push_ident_ref(&self.table);
+        if !self.columns.is_empty() {
+            sql.push(" (");
+            push_ident_list(&mut sql, &self.columns);
+            sql.push(")");
+        }
+        sql.push(" ");
+        sql.push_sql(self.query);
+        if let Some(on_conflict) = &self.on_conflict {
+            on_conflict.append_to_sql(&mut sql, &self.table)?;
+        }
+        Ok(sql)
+    }
+
+    /// Execute the insert, returning the number of inserted (or updated) rows.
+    pub async fn execute(self, conn: &impl GenericClient) -> OrmResult<u64> {
+        let sql = self.build_sql()?;
+        sql.execute(conn).await
+    }
+
+    /// Execute the insert and return the written rows.
+    ///
+    /// Appends `RETURNING *` to the query.
+    pub async fn returning<T: FromRow>(self, conn: &impl GenericClient) -> OrmResult<Vec<T>> {
+        let mut sql = self.build_sql()?;
+        sql.push(" RETURNING *");
+        sql.fetch_all_as(conn).await
+    }
+}
+
+// ==================== MergeBuilder ====================
+
+enum MergeSource {
+    Table { table: Ident, alias: Ident },
+    Query { query: Sql, alias: Ident },
+}
+
+enum MergeAction {
+    Update(Vec<SetExpr>),
+    Delete,
+    Insert(Vec<SetExpr>),
+    DoNothing,
+}
+
+struct MergeClause {
+    matched: bool,
+    condition: Option<WhereExpr>,
+    action: MergeAction,
+}
+
+/// Builder for `MERGE` statements (PostgreSQL 15+).
+///
+/// Created via [`Sql::merge`]. Arms are evaluated in the order they are added;
+/// the first arm whose condition holds wins.
+///
+/// # Example
+/// ```ignore
+/// // MERGE INTO products AS t USING staging_products AS s ON t.sku = s.sku
+/// // WHEN MATCHED AND s.discontinued = $1 THEN DELETE
+/// // WHEN MATCHED THEN UPDATE SET price = s.price
+/// // WHEN NOT MATCHED THEN INSERT (sku, price) VALUES (s.sku, s.price)
+/// let affected = pgorm::sql("products")
+///     .merge()?
+///     .alias("t")?
+///     .using("staging_products", "s")?
+///     .on_columns(["sku"])?
+///     .when_matched_and_delete(Condition::eq("s.discontinued", true)?)
+///     .when_matched_update([SetExpr::column("price", "s.price")?])
+///     .when_not_matched_insert([
+///         SetExpr::column("sku", "s.sku")?,
+///         SetExpr::column("price", "s.price")?,
+///     ])
+///     .execute(&client)
+///     .await?;
+/// ```
+#[must_use]
+pub struct MergeBuilder {
+    pub(crate) target: Ident,
+    pub(crate) alias: Option<Ident>,
+    source: Option<MergeSource>,
+    on_columns: Vec<Ident>,
+    on: Option<WhereExpr>,
+    clauses: Vec<MergeClause>,
+}
+
+impl MergeBuilder {
+    pub(crate) fn new(target: Ident) -> Self {
+        Self {
+            target,
+            alias: None,
+            source: None,
+            on_columns: Vec::new(),
+            on: None,
+            clauses: Vec::new(),
+        }
+    }
+
+    /// Alias the target table (`MERGE INTO target AS alias`).
+    pub fn alias(mut self, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.alias = Some(alias.into_ident()?);
+        Ok(self)
+    }
+
+    /// Merge from a table: `USING table AS alias`.
+    pub fn using(mut self, table: impl IntoIdent, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.source = Some(MergeSource::Table {
+            table: table.into_ident()?,
+            alias: alias.into_ident()?,
+        });
+        Ok(self)
+    }
+
+    /// Merge from a subquery: `USING (query) AS alias`.
+    pub fn using_query(mut self, query: Sql, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.source = Some(MergeSource::Query {
+            query,
+            alias: alias.into_ident()?,
+        });
+        Ok(self)
+    }
+
+    /// Join target and source on equal columns: `t.a = s.a AND t.b = s.b`.
+    pub fn on_columns<I, T>(mut self, columns: I) -> OrmResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        for column in columns {
+            self.on_columns.push(column.into_ident()?);
+        }
+        Ok(self)
+    }
+
+    /// Add a join condition (combined with AND), e.g. `WhereExpr::raw("t.id = s.product_id")`.
+    pub fn on(mut self, condition: impl Into<WhereExpr>) -> Self {
+        let new_on = condition.into();
+        self.on = Some(match self.on.take() {
+            Some(existing) => existing.and_with(new_on),
+            None => new_on,
+        });
+        self
+    }
+
+    fn clause(mut self, matched: bool, condition: Option<WhereExpr>, action: MergeAction) -> Self {
+        self.clauses.push(MergeClause {
+            matched,
+            condition,
+            action,
+        });
+        self
+    }
+
+    /// `WHEN MATCHED THEN UPDATE SET ...`
+    pub fn when_matched_update(self, sets: impl IntoIterator<Item = SetExpr>) -> Self {
+        self.clause(true, None, MergeAction::Update(sets.into_iter().collect()))
+    }
+
+    /// `WHEN MATCHED AND <condition> THEN UPDATE SET ...`
+    pub fn when_matched_and_update(
+        self,
+        condition: impl Into<WhereExpr>,
+        sets: impl IntoIterator<Item = SetExpr>,
+    ) -> Self {
+        let action = MergeAction::Update(sets.into_iter().collect());
+        self.clause(true, Some(condition.into()), action)
+    }
+
+    /// `WHEN MATCHED THEN DELETE`
+    pub fn when_matched_delete(self) -> Self {
+        self.clause(true, None, MergeAction::Delete)
+    }
+
+    /// `WHEN MATCHED AND <condition> THEN DELETE`
+    pub fn when_matched_and_delete(self, condition: impl Into<WhereExpr>) -> Self {
+        self.clause(true, Some(condition.into()), MergeAction::Delete)
+    }
+
+    /// `WHEN MATCHED THEN DO NOTHING`
+    pub fn when_matched_do_nothing(self) -> Self {
+        self.clause(true, None, MergeAction::DoNothing)
+    }
+
+    /// `WHEN NOT MATCHED THEN INSERT (cols) VALUES (...)`
+    ///
+    /// Each value is a [`SetExpr::set`] (bound parameter) or [`SetExpr::column`]
+    /// (usually a source column).
+    pub fn when_not_matched_insert(self, values: impl IntoIterator<Item = SetExpr>) -> Self {
+        self.clause(
+            false,
+            None,
+            MergeAction::Insert(values.into_iter().collect()),
+        )
+    }
+
+    /// `WHEN NOT MATCHED AND <condition> THEN INSERT (cols) VALUES (...)`
+    pub fn when_not_matched_and_insert(
+        self,
+        condition: impl Into<WhereExpr>,
+        values: impl IntoIterator<Item = SetExpr>,
+    ) -> Self {
+        let action = MergeAction::Insert(values.into_iter().collect());
+        self.clause(false, Some(condition.into()), action)
+    }
+
+    /// `WHEN NOT MATCHED THEN DO NOTHING`
+    pub fn when_not_matched_do_nothing(self) -> Self {
+        self.clause(false, None, MergeAction::DoNothing)
+    }
+
+    /// Build the SQL statement, consuming the builder (a `using_query` source is moved in).
+    pub fn build_sql(self) -> OrmResult<Sql> {
+        let Some(source) = self.source else {
+            return Err(OrmError::Validation(
+                "merge requires a source: call .using() or .using_query()".to_string(),
+            ));
+        };
+        if self.on_columns.is_empty() && self.on.is_none() {
+            return Err(OrmError::Validation(
+                "merge requires a join condition: call .on_columns() or .on()".to_string(),
+            ));
+        }
+        if self.clauses.is_empty() {
+            return Err(OrmError::Validation(
+                "merge requires at least one WHEN MATCHED / WHEN NOT MATCHED arm".to_string(),
+            ));
+        }
+
+        let mut sql = Sql::new("MERGE INTO ");
+        sql.push_ident_ref(&self.target);
+        if let Some(alias) = &self.alias {
+            sql.push(" AS ");
+            sql.push_ident_ref(alias);
+        }
+
+        sql.push(" USING ");
+        let source_alias = match source {
+            MergeSource::Table { table, alias } => {
+                sql.push_ident_ref(&table);
+                alias
+            }
+            MergeSource::Query { query, alias } => {
+                sql.push("(");
+                sql.push_sql(query);
+                sql.push(")");
+                alias
+            }
+        };
+        sql.push(" AS ");
+        sql.push_ident_ref(&source_alias);
+
+        sql.push(" ON ");
+        let target_ref = self.alias.as_ref().unwrap_or(&self.target);
+        for (i, column) in self.on_columns.iter().enumerate() {
+            if i > 0 {
+                sql.push(" AND ");
+            }
+            sql.push_ident_ref(target_ref);
+            sql.push(".");
+            sql.push_ident_ref(column);
+            sql.push(" = ");
+            sql.push_ident_ref(&source_alias);
+            sql.push(".");
+            sql.push_ident_ref(column);
+        }
+        if let Some(on) = &self.on {
+            if !self.on_columns.is_empty() {
+                sql.push(" AND ");
+            }
+            on.append_to_sql(&mut sql);
+        }
+
+        for clause in &self.clauses {
+            sql.push(if clause.matched {
+                " WHEN MATCHED"
+            } else {
+                " WHEN NOT MATCHED"
+            });
+            if let Some(condition) = &clause.condition {
+                sql.push(" AND ");
+                condition.append_to_sql(&mut sql);
+            }
+            sql.push(" THEN ");
+            match &clause.action {
+                MergeAction::Update(sets) => {
+                    if sets.is_empty() {
+                        return Err(OrmError::Validation(
+                            "merge UPDATE arm requires at least one SetExpr".to_string(),
+                        ));
+                    }
+                    sql.push("UPDATE SET ");
+                    push_set_list(&mut sql, sets, Some(target_ref));
+                }
+                MergeAction::Delete => {
+                    sql.push("DELETE");
+                }
+                MergeAction::Insert(values) => {
+                    if values.is_empty() {
+                        return Err(OrmError::Validation(
+                            "merge INSERT arm requires at least one value".to_string(),
+                        ));
+                    }
+                    let parts = values
+                        .iter()
+                        .map(SetExpr::insert_parts)
+                        .collect::<OrmResult<Vec<_>>>()?;
+                    sql.push("INSERT (");
+                    for (i, (column, _)) in parts.iter().enumerate() {
+                        if i > 0 {
+                            sql.push(", ");
+                        }
+                        sql.push_ident_ref(column);
+                    }
+                    sql.push(") VALUES (");
+                    for (i, (_, value)) in parts.iter().enumerate() {
+                        if i > 0 {
+                            sql.push(", ");
+                        }
+                        match value {
+                            InsertValue::Param(value) => {
+                                sql.push_bind_value(Arc::clone(value));
+                            }
+                            InsertValue::Column(source) => {
+                                sql.push_ident_ref(source);
+                            }
+                        }
+                    }
+                    sql.push(")");
+                }
+                MergeAction::DoNothing => {
+                    sql.push("DO NOTHING");
+                }
+            }
+        }
+
+        Ok(sql)
+    }
+
+    /// Execute the merge, returning the number of inserted, updated or deleted rows.
+    pub async fn execute(self, conn: &impl GenericClient) -> OrmResult<u64> {
+        let sql = self.build_sql()?;
+        sql.execute(conn).await
+    }
+
+    /// Execute the merge and return the written target rows (PostgreSQL 17+).
+    ///
+    /// Appends `RETURNING <target>.*`; deleted rows are returned as they were.
+    pub async fn returning<T: FromRow>(self, conn: &impl GenericClient) -> OrmResult<Vec<T>> {
+        let target_ref = self.alias.clone().unwrap_or_else(|| self.target.clone());
+        let mut sql = self.build_sql()?;
+        sql.push(" RETURNING ");
+        sql.push_ident_ref(&target_ref);
+        sql.push(".*");
+        sql.fetch_all_as(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::condition::Condition;
+    use crate::test_client::RecordingClient;
+
+    #[test]
+    fn update_many_basic_sql() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("users").unwrap(),
+            sets: vec![SetExpr::set("status", "inactive").unwrap()],
+            where_clause: Some(WhereExpr::Atom(Condition::eq("active", true).unwrap())),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "UPDATE users SET status = $1 WHERE active = $2"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn update_many_multiple_sets() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("orders").unwrap(),
+            sets: vec![
+                SetExpr::set("status", "shipped").unwrap(),
+                SetExpr::raw("shipped_at = NOW()"),
+            ],
+            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 1_i64).unwrap())),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "UPDATE orders SET status = $1, shipped_at = NOW() WHERE id = $2"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn update_many_increment() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("products").unwrap(),
+            sets: vec![SetExpr::increment("view_count", 1).unwrap()],
+            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 42_i64).unwrap())),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "UPDATE products SET view_count = view_count + 1 WHERE id = $1"
+        );
+        assert_eq!(sql.params_ref().len(), 1);
+    }
+
+    #[test]
+    fn update_many_decrement() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("products").unwrap(),
+            sets: vec![SetExpr::increment("stock", -5).unwrap()],
+            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 1_i64).unwrap())),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "UPDATE products SET stock = stock - 5 WHERE id = $1"
+        );
+    }
+
+    #[test]
+    fn update_many_jsonb_expressions() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("users").unwrap(),
+            sets: vec![
+                SetExpr::jsonb_set("settings", ["ui", "theme"], serde_json::json!("dark")).unwrap(),
+                SetExpr::jsonb_merge("metadata", serde_json::json!({"beta": true})).unwrap(),
+                SetExpr::jsonb_remove_key("flags", "legacy").unwrap(),
+                SetExpr::jsonb_remove_path("profile", ["address", "zip"]).unwrap(),
+            ],
+            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 1_i64).unwrap())),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "UPDATE users SET \
+             settings = jsonb_set(COALESCE(settings, '{}'::jsonb), $1::text[], $2::jsonb, true), \
+             metadata = COALESCE(metadata, '{}'::jsonb) || $3::jsonb, \
+             flags = flags - $4::text, \
+             profile = profile #- $5::text[] \
+             WHERE id = $6"
+        );
+        assert_eq!(sql.params_ref().len(), 6);
+    }
+
+    #[test]
+    fn update_many_array_expressions() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("posts").unwrap(),
+            sets: vec![
+                SetExpr::array_append("tags", "rust").unwrap(),
+                SetExpr::array_remove("labels", "draft".to_string()).unwrap(),
+                SetExpr::array_cat("scores", vec![1_i64, 2]).unwrap(),
+            ],
+            where_clause: Some(WhereExpr::Atom(Condition::eq("id", 1_i64).unwrap())),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "UPDATE posts SET tags = array_append(tags, $1::text), \
+             labels = array_remove(labels, $2::text), \
+             scores = array_cat(scores, $3::bigint[]) WHERE id = $4"
+        );
+        assert_eq!(sql.params_ref().len(), 4);
+    }
+
+    #[test]
+    fn update_many_all_rows() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("temp_data").unwrap(),
+            sets: vec![SetExpr::set("status", "archived").unwrap()],
+            where_clause: None,
+            all_rows: true,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(sql.to_sql(), "UPDATE temp_data SET status = $1");
+    }
+
+    #[test]
+    fn update_many_rejects_no_where() {
+        let builder = UpdateManyBuilder {
+            table: Ident::parse("users").unwrap(),
+            sets: vec![SetExpr::set("status", "x").unwrap()],
+            where_clause: None,
+            all_rows: false,
+        };
+        assert!(builder.build_sql().is_err());
+    }
+
+    #[test]
+    fn delete_many_basic_sql() {
+        let builder = DeleteManyBuilder {
+            table: Ident::parse("sessions").unwrap(),
+            where_clause: Some(WhereExpr::raw("expires_at < NOW()")),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "DELETE FROM sessions WHERE expires_at < NOW()"
+        );
+    }
+
+    #[test]
+    fn delete_many_with_condition() {
+        let builder = DeleteManyBuilder {
+            table: Ident::parse("audit_logs").unwrap(),
+            where_clause: Some(WhereExpr::And(vec![
+                WhereExpr::Atom(Condition::eq("level", "debug").unwrap()),
+                WhereExpr::Atom(Condition::eq("archived", true).unwrap()),
+            ])),
+            all_rows: false,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "DELETE FROM audit_logs WHERE (level = $1 AND archived = $2)"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn delete_many_all_rows() {
+        let builder = DeleteManyBuilder {
+            table: Ident::parse("temp_data").unwrap(),
+            where_clause: None,
+            all_rows: true,
+        };
+        let sql = builder.build_sql().unwrap();
+        assert_eq!(sql.to_sql(), "DELETE FROM temp_data");
+    }
+
+    #[test]
+    fn delete_many_rejects_no_where() {
+        let builder = DeleteManyBuilder {
+            table: Ident::parse("users").unwrap(),
+            where_clause: None,
+            all_rows: false,
+        };
+        assert!(builder.build_sql().is_err());
     }
 
     #[test]
@@ -510,4 +1632,356 @@ mod tests {
         assert!(SetExpr::increment("valid_col", 1).is_ok());
         assert!(SetExpr::increment("bad;col", 1).is_err());
     }
+
+    #[test]
+    fn merge_full_sql() {
+        let sql = crate::sql("products")
+            .merge()
+            .unwrap()
+            .alias("t")
+            .unwrap()
+            .using("staging_products", "s")
+            .unwrap()
+            .on_columns(["sku"])
+            .unwrap()
+            .when_matched_and_delete(Condition::eq("s.discontinued", true).unwrap())
+            .when_matched_update([
+                SetExpr::column("price", "s.price").unwrap(),
+                SetExpr::raw("updated_at = NOW()"),
+            ])
+            .when_not_matched_insert([
+                SetExpr::column("sku", "s.sku").unwrap(),
+                SetExpr::set("source", "import").unwrap(),
+            ])
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "MERGE INTO products AS t USING staging_products AS s ON t.sku = s.sku \
+             WHEN MATCHED AND s.discontinued = $1 THEN DELETE \
+             WHEN MATCHED THEN UPDATE SET price = s.price, updated_at = NOW() \
+             WHEN NOT MATCHED THEN INSERT (sku, source) VALUES (s.sku, $2)"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn merge_using_query_renumbers_params() {
+        let sql = crate::sql("inventory")
+            .merge()
+            .unwrap()
+            .using_query(
+                crate::sql("SELECT sku, qty FROM deliveries WHERE batch = ").bind(7_i64),
+                "d",
+            )
+            .unwrap()
+            .on(WhereExpr::raw("inventory.sku = d.sku"))
+            .when_matched_update([SetExpr::raw("qty = inventory.qty + d.qty")])
+            .when_not_matched_and_insert(
+                Condition::gt("d.qty", 0_i32).unwrap(),
+                [
+                    SetExpr::column("sku", "d.sku").unwrap(),
+                    SetExpr::column("qty", "d.qty").unwrap(),
+                ],
+            )
+            .when_not_matched_do_nothing()
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "MERGE INTO inventory USING (SELECT sku, qty FROM deliveries WHERE batch = $1) AS d \
+             ON inventory.sku = d.sku \
+             WHEN MATCHED THEN UPDATE SET qty = inventory.qty + d.qty \
+             WHEN NOT MATCHED AND d.qty > $2 THEN INSERT (sku, qty) VALUES (d.sku, d.qty) \
+             WHEN NOT MATCHED THEN DO NOTHING"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn merge_update_qualifies_current_values_with_target() {
+        let sql = crate::sql("stock")
+            .merge()
+            .unwrap()
+            .alias("t")
+            .unwrap()
+            .using("deliveries", "s")
+            .unwrap()
+            .on_columns(["sku"])
+            .unwrap()
+            .when_matched_update([
+                SetExpr::increment("qty", 1).unwrap(),
+                SetExpr::jsonb_merge("meta", serde_json::json!({ "seen": true })).unwrap(),
+                SetExpr::array_append("tags", "restocked".to_string()).unwrap(),
+            ])
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "MERGE INTO stock AS t USING deliveries AS s ON t.sku = s.sku \
+             WHEN MATCHED THEN UPDATE SET qty = t.qty + 1, \
+             meta = COALESCE(t.meta, '{}'::jsonb) || $1::jsonb, \
+             tags = array_append(t.tags, $2::text)"
+        );
+
+        // Without an alias the target table name qualifies the column.
+        let sql = crate::sql("stock")
+            .merge()
+            .unwrap()
+            .using("deliveries", "s")
+            .unwrap()
+            .on_columns(["sku"])
+            .unwrap()
+            .when_matched_update([SetExpr::increment("qty", -2).unwrap()])
+            .build_sql()
+            .unwrap();
+        assert!(sql.to_sql().ends_with("UPDATE SET qty = stock.qty - 2"));
+    }
+
+    #[test]
+    fn merge_validates_shape() {
+        let base = || crate::sql("products").merge().unwrap();
+        assert!(base().when_matched_delete().build_sql().is_err());
+        assert!(
+            base()
+                .using("staging", "s")
+                .unwrap()
+                .when_matched_delete()
+                .build_sql()
+                .is_err()
+        );
+        assert!(
+            base()
+                .using("staging", "s")
+                .unwrap()
+                .on_columns(["id"])
+                .unwrap()
+                .build_sql()
+                .is_err()
+        );
+        assert!(
+            base()
+                .using("staging", "s")
+                .unwrap()
+                .on_columns(["id"])
+                .unwrap()
+                .when_not_matched_insert([SetExpr::increment("n", 1).unwrap()])
+                .build_sql()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn insert_select_with_on_conflict() {
+        let sql = crate::sql("products")
+            .insert_select(
+                ["sku", "price"],
+                crate::sql("SELECT sku, price FROM staging WHERE batch = ").bind(3_i64),
+            )
+            .unwrap()
+            .on_conflict(
+                OnConflict::columns(["sku"])
+                    .unwrap()
+                    .do_update([SetExpr::excluded("price").unwrap()])
+                    .filter(Condition::eq("products.locked", false).unwrap()),
+            )
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "INSERT INTO products (sku, price) SELECT sku, price FROM staging WHERE batch = $1 \
+             ON CONFLICT (sku) DO UPDATE SET price = EXCLUDED.price WHERE products.locked = $2"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+
+        let sql = crate::sql("archive")
+            .insert_select(Vec::<&str>::new(), crate::sql("SELECT * FROM orders"))
+            .unwrap()
+            .on_conflict(OnConflict::constraint("archive_pkey").unwrap())
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "INSERT INTO archive SELECT * FROM orders ON CONFLICT ON CONSTRAINT archive_pkey DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn on_conflict_update_qualifies_current_values_with_target() {
+        let sql = crate::sql("products")
+            .insert_select(
+                ["sku", "qty"],
+                crate::sql("SELECT sku, qty FROM deliveries"),
+            )
+            .unwrap()
+            .on_conflict(OnConflict::columns(["sku"]).unwrap().do_update([
+                SetExpr::increment("qty", 1).unwrap(),
+                SetExpr::jsonb_merge("meta", serde_json::json!({ "seen": true })).unwrap(),
+                SetExpr::array_append("tags", "restocked".to_string()).unwrap(),
+            ]))
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "INSERT INTO products (sku, qty) SELECT sku, qty FROM deliveries \
+             ON CONFLICT (sku) DO UPDATE SET qty = products.qty + 1, \
+             meta = COALESCE(products.meta, '{}'::jsonb) || $1::jsonb, \
+             tags = array_append(products.tags, $2::text)"
+        );
+    }
+
+    #[test]
+    fn on_conflict_validation() {
+        let render = |c: OnConflict| {
+            let mut sql = Sql::empty();
+            c.append_to_sql(&mut sql, &Ident::parse("t").unwrap())
+                .map(|_| sql.to_sql())
+        };
+        assert_eq!(
+            render(OnConflict::any()).unwrap(),
+            " ON CONFLICT DO NOTHING"
+        );
+        assert!(render(OnConflict::any().do_update([SetExpr::excluded("a").unwrap()])).is_err());
+        assert!(render(OnConflict::columns(["a"]).unwrap().do_update([])).is_err());
+        assert!(OnConflict::columns(Vec::<&str>::new()).is_err());
+    }
+
+    #[test]
+    fn insert_values_rows_with_upsert() {
+        let sql = crate::sql("products")
+            .insert(["sku", "price"])
+            .unwrap()
+            .values(("A-1", 100_i64))
+            .values(("B-2", 250_i64))
+            .on_conflict(
+                OnConflict::columns(["sku"])
+                    .unwrap()
+                    .do_update_excluded(["price"])
+                    .unwrap()
+                    .filter(Condition::eq("products.locked", false).unwrap()),
+            )
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "INSERT INTO products (sku, price) VALUES ($1, $2), ($3, $4) \
+             ON CONFLICT (sku) DO UPDATE SET price = EXCLUDED.price WHERE products.locked = $5"
+        );
+        assert_eq!(sql.params_ref().len(), 5);
+    }
+
+    #[test]
+    fn insert_upsert_reads_current_row_values() {
+        let sql = crate::sql("stock")
+            .insert(["sku", "qty"])
+            .unwrap()
+            .unnest(vec!["A-1".to_string()])
+            .unnest(vec![5_i64])
+            .on_conflict(OnConflict::columns(["sku"]).unwrap().do_update([
+                SetExpr::increment("qty", 5).unwrap(),
+                SetExpr::jsonb_set("meta", ["restocked"], serde_json::json!(true)).unwrap(),
+            ]))
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "INSERT INTO stock (sku, qty) SELECT * FROM UNNEST($1::text[], $2::bigint[]) \
+             ON CONFLICT (sku) DO UPDATE SET qty = stock.qty + 5, \
+             meta = jsonb_set(COALESCE(stock.meta, '{}'::jsonb), $3::text[], $4::jsonb, true)"
+        );
+        assert_eq!(sql.params_ref().len(), 4);
+    }
+
+    #[test]
+    fn insert_unnest_arrays() {
+        let sql = crate::sql("products")
+            .insert(["sku", "price"])
+            .unwrap()
+            .unnest(vec!["A-1".to_string(), "B-2".to_string()])
+            .unnest(vec![100_i64, 250])
+            .on_conflict(OnConflict::constraint("products_sku_key").unwrap())
+            .build_sql()
+            .unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "INSERT INTO products (sku, price) SELECT * FROM UNNEST($1::text[], $2::bigint[]) \
+             ON CONFLICT ON CONSTRAINT products_sku_key DO NOTHING"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn insert_validates_shape() {
+        let base = || crate::sql("products").insert(["sku", "price"]).unwrap();
+        assert!(base().build_sql().is_err());
+        assert!(base().values(("A-1",)).build_sql().is_err());
+        assert!(base().unnest(vec![1_i64]).build_sql().is_err());
+        assert!(
+            base()
+                .unnest(vec![1_i64])
+                .unnest(vec![1_i64, 2])
+                .build_sql()
+                .is_err()
+        );
+        assert!(
+            base()
+                .values((1_i64, 2_i64))
+                .unnest(vec![1_i64])
+                .unnest(vec![2_i64])
+                .build_sql()
+                .is_err()
+        );
+        assert!(crate::sql("products").insert(Vec::<&str>::new()).is_err());
+        assert_eq!(base().unnest(Vec::<i64>::new()).row_count(), 0);
+    }
+
+    struct NoRow;
+
+    impl FromRow for NoRow {
+        fn from_row(_row: &tokio_postgres::Row) -> OrmResult<Self> {
+            panic!("unexpected NoRow::from_row() call")
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_insert_still_validates_shape() {
+        let base = || crate::sql("products").insert(["sku", "price"]).unwrap();
+        let conn = RecordingClient::new();
+
+        assert_eq!(base().execute(&conn).await.unwrap(), 0);
+        assert_eq!(
+            base()
+                .unnest(Vec::<i64>::new())
+                .unnest(Vec::<i64>::new())
+                .execute(&conn)
+                .await
+                .unwrap(),
+            0
+        );
+        // The first array is empty, but the second is not.
+        assert!(
+            base()
+                .unnest(Vec::<i64>::new())
+                .unnest(vec![1_i64])
+                .execute(&conn)
+                .await
+                .is_err()
+        );
+        assert!(
+            base()
+                .values((1_i64, 2_i64))
+                .unnest(Vec::<i64>::new())
+                .execute(&conn)
+                .await
+                .is_err()
+        );
+        assert!(
+            base()
+                .unnest(Vec::<i64>::new())
+                .returning::<NoRow>(&conn)
+                .await
+                .is_err()
+        );
+        assert!(conn.statements().is_empty());
+    }
 }
@@ -5,8 +5,13 @@
 
 use crate::Ident;
 use crate::error::OrmResult;
+use crate::expr_json::{op_name, param_to_json};
 use crate::ident::IntoIdent;
+use crate::row::PgType;
+use crate::search::{TsQuery, TsVector};
 use crate::sql::Sql;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::sync::Arc;
 use tokio_postgres::types::ToSql;
 
@@ -42,7 +47,11 @@ use tokio_postgres::types::ToSql;
 /// // Range
 /// Op::between(10, 20)
 /// ```
-#[derive(Debug, Clone)]
+///
+/// Serialized as `{"op": "gte", "value": 18}` (`value` is omitted for `is_null` /
+/// `is_not_null`, and is a `[from, to]` pair for `between`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
 pub enum Op<T> {
     /// Equal: column = value
     Eq(T),
@@ -174,8 +183,10 @@ enum ConditionValue {
 /// This is used internally by specific helpers (e.g. `ANY/ALL`, full-text search)
 /// where the shape cannot be expressed as `col <op> $n`.
 #[derive(Debug, Clone)]
-enum ConditionPart {
+pub(crate) enum ConditionPart {
     Raw(&'static str),
+    /// SQL generated by pgorm from validated input (never user-provided text).
+    Trusted(String),
     Ident(Ident),
     Param(Arc<dyn ToSql + Send + Sync>),
 }
@@ -270,6 +281,10 @@ impl Condition {
         Condition(ConditionInner::Raw(sql.into()))
     }
 
+    pub(crate) fn from_parts(parts: Vec<ConditionPart>) -> Self {
+        Condition(ConditionInner::Parts(parts))
+    }
+
     pub(crate) fn cmp_dyn(
         column: Ident,
         operator: &'static str,
@@ -282,6 +297,31 @@ impl Condition {
         })
     }
 
+    pub(crate) fn list_dyn(
+        column: Ident,
+        operator: &'static str,
+        values: Vec<Arc<dyn ToSql + Send + Sync>>,
+    ) -> Self {
+        Condition(ConditionInner::Expr {
+            column,
+            operator,
+            value: ConditionValue::List(values),
+        })
+    }
+
+    pub(crate) fn pair_dyn(
+        column: Ident,
+        operator: &'static str,
+        from: Arc<dyn ToSql + Send + Sync>,
+        to: Arc<dyn ToSql + Send + Sync>,
+    ) -> Self {
+        Condition(ConditionInner::Expr {
+            column,
+            operator,
+            value: ConditionValue::Pair(from, to),
+        })
+    }
+
     pub(crate) fn tuple2_cmp_dyn(
         a: Ident,
         b: Ident,
@@ -475,6 +515,9 @@ impl Condition {
     }
 
     /// Create a "contains" condition: column @> value
+    ///
+    /// Works for ranges and jsonb; for array columns prefer [`Condition::array_contains`],
+    /// which casts the parameter to the column's array type.
     pub fn contains<I, T>(column: I, value: T) -> OrmResult<Self>
     where
         I: IntoIdent,
@@ -560,6 +603,145 @@ impl Condition {
         Ok(Self::cmp_dyn(column.into_ident()?, "?&", Arc::new(keys)))
     }
 
+    /// Compare a top-level jsonb field against a typed value.
+    ///
+    /// The field is extracted as text and cast to `T`'s SQL type:
+    /// `(column ->> $1::text)::bigint > $2`. For `serde_json::Value` / `Json<T>`
+    /// the field is compared as jsonb instead: `column -> $1::text = $2`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use pgorm::{Condition, Op};
+    ///
+    /// // (metadata ->> $1::text)::bigint >= $2
+    /// Condition::json_get("metadata", "age", Op::gte(18_i64))?;
+    /// ```
+    pub fn json_get<I, T>(column: I, key: impl Into<String>, op: Op<T>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        let column = column.into_ident()?;
+        let key: Arc<dyn ToSql + Send + Sync> = Arc::new(key.into());
+        let parts = match json_cast::<T>() {
+            None => vec![
+                ConditionPart::Ident(column),
+                ConditionPart::Raw(" -> "),
+                ConditionPart::Param(key),
+                ConditionPart::Raw("::text"),
+            ],
+            Some(cast) => vec![
+                ConditionPart::Raw("("),
+                ConditionPart::Ident(column),
+                ConditionPart::Raw(" ->> "),
+                ConditionPart::Param(key),
+                ConditionPart::Raw("::text)::"),
+                ConditionPart::Raw(cast),
+            ],
+        };
+        Ok(Condition(with_op(parts, op)))
+    }
+
+    /// Compare a nested jsonb value (addressed by a key path) against a typed value.
+    ///
+    /// Renders `(column #>> $1::text[])::<type> <op> ...`, or
+    /// `column #> $1::text[] <op> ...` for jsonb values (see [`Condition::json_get`]).
+    ///
+    /// # Example
+    /// ```ignore
+    /// // (profile #>> $1::text[])::text = $2
+    /// Condition::json_get_path("profile", ["address", "city"], Op::eq("Berlin".to_string()))?;
+    /// ```
+    pub fn json_get_path<I, P, S, T>(column: I, path: P, op: Op<T>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        let column = column.into_ident()?;
+        let path: Vec<String> = path.into_iter().map(Into::into).collect();
+        let path: Arc<dyn ToSql + Send + Sync> = Arc::new(path);
+        let parts = match json_cast::<T>() {
+            None => vec![
+                ConditionPart::Ident(column),
+                ConditionPart::Raw(" #> "),
+                ConditionPart::Param(path),
+                ConditionPart::Raw("::text[]"),
+            ],
+            Some(cast) => vec![
+                ConditionPart::Raw("("),
+                ConditionPart::Ident(column),
+                ConditionPart::Raw(" #>> "),
+                ConditionPart::Param(path),
+                ConditionPart::Raw("::text[])::"),
+                ConditionPart::Raw(cast),
+            ],
+        };
+        Ok(Condition(with_op(parts, op)))
+    }
+
+    /// Create a jsonpath existence condition: `column @? $1::text::jsonpath`
+    ///
+    /// True when the jsonpath returns any item, e.g. `$.tags[*] ? (@ == "rust")`.
+    pub fn jsonpath_exists<I>(column: I, path: impl Into<String>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+    {
+        Ok(Self::jsonpath_op(column.into_ident()?, " @? ", path.into()))
+    }
+
+    /// Create a jsonpath predicate condition: `column @@ $1::text::jsonpath`
+    ///
+    /// The jsonpath must be a predicate, e.g. `$.price > 100`.
+    pub fn jsonpath_match<I>(column: I, path: impl Into<String>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+    {
+        Ok(Self::jsonpath_op(column.into_ident()?, " @@ ", path.into()))
+    }
+
+    /// Create a `jsonb_path_exists(column, $1::text::jsonpath, $2::jsonb)` condition.
+    ///
+    /// Unlike [`Condition::jsonpath_exists`], this accepts `vars` that the jsonpath can
+    /// reference as `$name`, so user input never has to be spliced into the path.
+    ///
+    /// # Example
+    /// ```ignore
+    /// Condition::jsonb_path_exists(
+    ///     "metadata",
+    ///     "$.tags[*] ? (@ == $tag)",
+    ///     serde_json::json!({ "tag": "rust" }),
+    /// )?;
+    /// ```
+    pub fn jsonb_path_exists<I>(
+        column: I,
+        path: impl Into<String>,
+        vars: serde_json::Value,
+    ) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+    {
+        Ok(Condition(ConditionInner::Parts(vec![
+            ConditionPart::Raw("jsonb_path_exists("),
+            ConditionPart::Ident(column.into_ident()?),
+            ConditionPart::Raw(", "),
+            ConditionPart::Param(Arc::new(path.into())),
+            ConditionPart::Raw("::text::jsonpath, "),
+            ConditionPart::Param(Arc::new(vars)),
+            ConditionPart::Raw("::jsonb)"),
+        ])))
+    }
+
+    fn jsonpath_op(column: Ident, operator: &'static str, path: String) -> Self {
+        Condition(ConditionInner::Parts(vec![
+            ConditionPart::Ident(column),
+            ConditionPart::Raw(operator),
+            ConditionPart::Param(Arc::new(path)),
+            ConditionPart::Raw("::text::jsonpath"),
+        ]))
+    }
+
     /// Create a Postgres `= ANY($n)` condition, binding the values as a single array parameter.
     pub fn eq_any<I, T>(column: I, values: Vec<T>) -> OrmResult<Self>
     where
@@ -592,6 +774,95 @@ impl Condition {
         ])))
     }
 
+    // ==================== Array column helpers ====================
+
+    /// Create an array "contains all of" condition: `column @> $1::<type>[]`
+    ///
+    /// The parameter is cast to `T`'s array type (see [`PgType`]), e.g. `text[]`.
+    pub fn array_contains<I, T>(column: I, values: Vec<T>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(Self::array_op(column.into_ident()?, " @> ", values))
+    }
+
+    /// Create an array "contained by" condition: `column <@ $1::<type>[]`
+    pub fn array_contained_by<I, T>(column: I, values: Vec<T>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(Self::array_op(column.into_ident()?, " <@ ", values))
+    }
+
+    /// Create an array "contains any of" condition: `column && $1::<type>[]`
+    pub fn array_overlaps<I, T>(column: I, values: Vec<T>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(Self::array_op(column.into_ident()?, " && ", values))
+    }
+
+    /// Create a "value is an element of the array column" condition:
+    /// `$1::<type> = ANY(column)`
+    pub fn any_eq<I, T>(column: I, value: T) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Ok(Condition(ConditionInner::Parts(vec![
+            ConditionPart::Param(Arc::new(value)),
+            ConditionPart::Raw("::"),
+            ConditionPart::Raw(element_type::<T>()),
+            ConditionPart::Raw(" = ANY("),
+            ConditionPart::Ident(column.into_ident()?),
+            ConditionPart::Raw(")"),
+        ])))
+    }
+
+    /// Compare the number of elements of an array column: `cardinality(column) <op> ...`
+    ///
+    /// Uses `cardinality()` rather than `array_length(column, 1)` so that empty
+    /// arrays count as `0` instead of `NULL`. A `NULL` column still yields `NULL`.
+    pub fn array_length<I>(column: I, op: Op<i32>) -> OrmResult<Self>
+    where
+        I: IntoIdent,
+    {
+        let lhs = vec![
+            ConditionPart::Raw("cardinality("),
+            ConditionPart::Ident(column.into_ident()?),
+            ConditionPart::Raw(")"),
+        ];
+        Ok(Condition(with_op(lhs, op)))
+    }
+
+    fn array_op<T>(column: Ident, operator: &'static str, values: Vec<T>) -> Self
+    where
+        T: ToSql + PgType + Send + Sync + 'static,
+    {
+        Condition(ConditionInner::Parts(vec![
+            ConditionPart::Ident(column),
+            ConditionPart::Raw(operator),
+            ConditionPart::Param(Arc::new(values)),
+            ConditionPart::Raw("::"),
+            ConditionPart::Raw(T::pg_array_type()),
+        ]))
+    }
+
+    /// Create a full-text search condition: `<vector> @@ <query>`
+    ///
+    /// See [`crate::search`] for building weighted documents and query modes
+    /// (`websearch_to_tsquery`, `phraseto_tsquery`, ...).
+    pub fn ts_search(vector: &TsVector, query: &TsQuery) -> Self {
+        let mut parts = Vec::new();
+        vector.push_parts(&mut parts);
+        parts.push(ConditionPart::Raw(" @@ "));
+        query.push_parts(&mut parts);
+        Self::from_parts(parts)
+    }
+
     /// Create a Postgres full-text search condition:
     /// `to_tsvector(column) @@ plainto_tsquery($n)`
     pub fn ts_match<I>(column: I, query: impl Into<String>) -> OrmResult<Self>
@@ -647,6 +918,7 @@ impl Condition {
                 for part in parts {
                     match part {
                         ConditionPart::Raw(s) => out.push_str(s),
+                        ConditionPart::Trusted(s) => out.push_str(s),
                         ConditionPart::Ident(ident) => ident.write_sql(&mut out),
                         ConditionPart::Param(v) => {
                             *param_idx += 1;
@@ -740,21 +1012,7 @@ impl Condition {
             ConditionInner::Raw(s) => {
                 sql.push(s);
             }
-            ConditionInner::Parts(parts) => {
-                for part in parts {
-                    match part {
-                        ConditionPart::Raw(s) => {
-                            sql.push(s);
-                        }
-                        ConditionPart::Ident(ident) => {
-                            sql.push_ident_ref(ident);
-                        }
-                        ConditionPart::Param(v) => {
-                            sql.push_bind_value(v.clone());
-                        }
-                    }
-                }
-            }
+            ConditionInner::Parts(parts) => append_parts(parts, sql),
             ConditionInner::Expr {
                 column,
                 operator,
@@ -824,6 +1082,140 @@ impl Condition {
     }
 }
 
+impl Condition {
+    /// JSON form used by `Serialize` (see [`crate::expr_json`]).
+    pub(crate) fn to_json(&self) -> Result<Value, String> {
+        match &self.0 {
+            ConditionInner::Raw(sql) => Ok(json!({ "raw": sql })),
+            ConditionInner::Expr {
+                column,
+                operator,
+                value,
+            } => {
+                let mut obj = serde_json::Map::new();
+                obj.insert("column".into(), column.to_sql().into());
+                obj.insert("op".into(), op_name(operator)?.into());
+                let value = match value {
+                    ConditionValue::Single(v) => Some(param_to_json(v)?),
+                    ConditionValue::Pair(a, b) => {
+                        Some(json!([param_to_json(a)?, param_to_json(b)?]))
+                    }
+                    ConditionValue::List(vs) => Some(Value::Array(
+                        vs.iter().map(param_to_json).collect::<Result<_, _>>()?,
+                    )),
+                    ConditionValue::None => None,
+                };
+                if let Some(value) = value {
+                    obj.insert("value".into(), value);
+                }
+                Ok(Value::Object(obj))
+            }
+            ConditionInner::Tuple2 {
+                columns: (a, b),
+                operator,
+                values: (va, vb),
+            } => Ok(json!({
+                "columns": [a.to_sql(), b.to_sql()],
+                "op": op_name(operator)?,
+                "value": [param_to_json(va)?, param_to_json(vb)?],
+            })),
+            ConditionInner::Parts(_) => Err(
+                "this condition has no JSON form (only column comparisons and raw SQL do)"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+impl Serialize for Condition {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// Render condition parts into a [`Sql`] builder.
+pub(crate) fn append_parts(parts: &[ConditionPart], sql: &mut Sql) {
+    for part in parts {
+        match part {
+            ConditionPart::Raw(s) => {
+                sql.push(s);
+            }
+            ConditionPart::Trusted(s) => {
+                sql.push(s);
+            }
+            ConditionPart::Ident(ident) => {
+                sql.push_ident_ref(ident);
+            }
+            ConditionPart::Param(v) => {
+                sql.push_bind_value(v.clone());
+            }
+        }
+    }
+}
+
+/// Element type name for `T`, derived from its [`PgType`] array name (`text[]` -> `text`).
+pub(crate) fn element_type<T: PgType>() -> &'static str {
+    let ty = T::pg_array_type();
+    ty.strip_suffix("[]").unwrap_or(ty)
+}
+
+/// SQL type that extracted jsonb text is cast to, or `None` to compare as jsonb.
+fn json_cast<T: PgType>() -> Option<&'static str> {
+    let ty = element_type::<T>();
+    (ty != "jsonb").then_some(ty)
+}
+
+/// Complete a parts-based condition: `<lhs> <op> <values>`.
+fn with_op<T>(mut parts: Vec<ConditionPart>, op: Op<T>) -> ConditionInner
+where
+    T: ToSql + Send + Sync + 'static,
+{
+    fn param<T: ToSql + Send + Sync + 'static>(v: T) -> ConditionPart {
+        ConditionPart::Param(Arc::new(v))
+    }
+
+    let (operator, values) = match op {
+        Op::Eq(v) => (" = ", vec![param(v)]),
+        Op::Ne(v) => (" != ", vec![param(v)]),
+        Op::Gt(v) => (" > ", vec![param(v)]),
+        Op::Gte(v) => (" >= ", vec![param(v)]),
+        Op::Lt(v) => (" < ", vec![param(v)]),
+        Op::Lte(v) => (" <= ", vec![param(v)]),
+        Op::Like(v) => (" LIKE ", vec![param(v)]),
+        Op::Ilike(v) => (" ILIKE ", vec![param(v)]),
+        Op::NotLike(v) => (" NOT LIKE ", vec![param(v)]),
+        Op::NotIlike(v) => (" NOT ILIKE ", vec![param(v)]),
+        Op::IsNull => (" IS NULL", Vec::new()),
+        Op::IsNotNull => (" IS NOT NULL", Vec::new()),
+        Op::Between(from, to) => (" BETWEEN ", vec![param(from), param(to)]),
+        Op::NotBetween(from, to) => (" NOT BETWEEN ", vec![param(from), param(to)]),
+        // Empty IN list - always false / true, matching `Expr` conditions.
+        Op::In(vals) if vals.is_empty() => {
+            return ConditionInner::Parts(vec![ConditionPart::Raw("1=0")]);
+        }
+        Op::NotIn(vals) if vals.is_empty() => {
+            return ConditionInner::Parts(vec![ConditionPart::Raw("1=1")]);
+        }
+        Op::In(vals) => (" IN (", vals.into_iter().map(param).collect()),
+        Op::NotIn(vals) => (" NOT IN (", vals.into_iter().map(param).collect()),
+    };
+
+    let is_list = operator.ends_with('(');
+    parts.push(ConditionPart::Raw(operator));
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            parts.push(ConditionPart::Raw(if is_list { ", " } else { " AND " }));
+        }
+        parts.push(value);
+    }
+    if is_list {
+        parts.push(ConditionPart::Raw(")"));
+    }
+    ConditionInner::Parts(parts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -912,6 +1304,93 @@ mod tests {
         assert_condition_sql(&cond, "during -|- $1", 1);
     }
 
+    #[test]
+    fn condition_json_get_casts_to_value_type() {
+        let cond = Condition::json_get("metadata", "age", Op::gte(18_i64)).unwrap();
+        assert_condition_sql(&cond, "(metadata ->> $1::text)::bigint >= $2", 2);
+    }
+
+    #[test]
+    fn condition_json_get_jsonb_value_uses_arrow() {
+        let cond =
+            Condition::json_get("metadata", "flags", Op::eq(serde_json::json!(["a"]))).unwrap();
+        assert_condition_sql(&cond, "metadata -> $1::text = $2", 2);
+    }
+
+    #[test]
+    fn condition_json_get_path_ops() {
+        let cond =
+            Condition::json_get_path("profile", ["address", "city"], Op::eq("Berlin")).unwrap();
+        assert_condition_sql(&cond, "(profile #>> $1::text[])::text = $2", 2);
+
+        let cond = Condition::json_get_path("profile", ["score"], Op::between(1_i32, 5)).unwrap();
+        assert_condition_sql(
+            &cond,
+            "(profile #>> $1::text[])::integer BETWEEN $2 AND $3",
+            3,
+        );
+
+        let cond =
+            Condition::json_get_path("profile", ["tier"], Op::in_list(vec!["a", "b"])).unwrap();
+        assert_condition_sql(&cond, "(profile #>> $1::text[])::text IN ($2, $3)", 3);
+
+        let cond = Condition::json_get_path("profile", ["tier"], Op::<String>::is_null()).unwrap();
+        assert_condition_sql(&cond, "(profile #>> $1::text[])::text IS NULL", 1);
+
+        let cond =
+            Condition::json_get_path("profile", ["tier"], Op::<&str>::in_list(vec![])).unwrap();
+        assert_condition_sql(&cond, "1=0", 0);
+    }
+
+    #[test]
+    fn condition_jsonpath_operators() {
+        let cond = Condition::jsonpath_exists("metadata", "$.tags[*]").unwrap();
+        assert_condition_sql(&cond, "metadata @? $1::text::jsonpath", 1);
+
+        let cond = Condition::jsonpath_match("metadata", "$.price > 100").unwrap();
+        assert_condition_sql(&cond, "metadata @@ $1::text::jsonpath", 1);
+
+        let cond = Condition::jsonb_path_exists(
+            "metadata",
+            "$.tags[*] ? (@ == $tag)",
+            serde_json::json!({ "tag": "rust" }),
+        )
+        .unwrap();
+        assert_condition_sql(
+            &cond,
+            "jsonb_path_exists(metadata, $1::text::jsonpath, $2::jsonb)",
+            2,
+        );
+    }
+
+    #[test]
+    fn condition_array_operators_cast_to_array_type() {
+        let tags = vec!["rust".to_string(), "sql".to_string()];
+        let cond = Condition::array_contains("tags", tags.clone()).unwrap();
+        assert_condition_sql(&cond, "tags @> $1::text[]", 1);
+
+        let cond = Condition::array_contained_by("tags", tags.clone()).unwrap();
+        assert_condition_sql(&cond, "tags <@ $1::text[]", 1);
+
+        let cond = Condition::array_overlaps("scores", vec![1_i64, 2]).unwrap();
+        assert_condition_sql(&cond, "scores && $1::bigint[]", 1);
+    }
+
+    #[test]
+    fn condition_any_eq() {
+        let cond = Condition::any_eq("tags", "rust").unwrap();
+        assert_condition_sql(&cond, "$1::text = ANY(tags)", 1);
+    }
+
+    #[test]
+    fn condition_array_length() {
+        let cond = Condition::array_length("tags", Op::gte(2)).unwrap();
+        assert_condition_sql(&cond, "cardinality(tags) >= $1", 1);
+
+        let cond = Condition::array_length("tags", Op::eq(0)).unwrap();
+        assert_condition_sql(&cond, "cardinality(tags) = $1", 1);
+    }
+
     #[test]
     fn condition_build_respects_param_idx() {
         let c1 = Condition::eq_any("id", vec![1_i32, 2]).unwrap();
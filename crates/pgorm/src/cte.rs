@@ -430,4 +430,23 @@ mod tests {
         assert!(sql.to_sql().contains(" UNION SELECT"));
         assert!(!sql.to_sql().contains("UNION ALL"));
     }
+
+    #[test]
+    fn cte_from_set_operation() {
+        let sql = crate::sql("")
+            .with(
+                "people",
+                crate::sql("SELECT id FROM users WHERE status = ")
+                    .bind("active")
+                    .union_all(crate::sql("SELECT id FROM admins WHERE level > ").bind(2_i32)),
+            )
+            .unwrap()
+            .select(crate::sql("SELECT * FROM people WHERE id > ").bind(5_i64));
+
+        assert_eq!(
+            sql.to_sql(),
+            "WITH people AS ((SELECT id FROM users WHERE status = $1) UNION ALL (SELECT id FROM admins WHERE level > $2)) SELECT * FROM people WHERE id > $3"
+        );
+        assert_eq!(sql.params_ref().len(), 3);
+    }
 }
@@ -2,9 +2,19 @@
 //!
 //! This module provides small, explicit building blocks:
 //! - `load_*_map*` helpers that run exactly one extra query per relation.
+//! - `load_*_map_limited` helpers that keep only the first `n` children per parent
+//!   (ranked with `ROW_NUMBER()`, see [`TopNPerGroup`]).
+//! - `load_has_{many,one}_through_map*` helpers that follow a chain of intermediate
+//!   models (see [`ThroughHop`]) with one joined query.
+//! - `load_*_{count,sum,exists}_map` helpers that aggregate children per parent
+//!   with one `GROUP BY` query, without loading them.
 //! - `Loaded<M, R>` wrapper for the optional "attach" style.
 
-use crate::{FromRow, GenericClient, ModelPk, OrmError, OrmResult, RowExt, Sql, sql};
+use crate::relation::{ThroughHop, through_joins};
+use crate::window::{ROW_NUMBER_ALIAS, TopNPerGroup};
+use crate::{
+    FromRow, GenericClient, Ident, ModelPk, OrderBy, OrmError, OrmResult, RowExt, Sql, sql,
+};
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use tokio_postgres::types::{FromSqlOwned, ToSql};
@@ -14,6 +24,7 @@ pub type BelongsToMap<Id, Parent> = HashMap<Id, Parent>;
 pub type HasOneMap<Id, Child> = HashMap<Id, Child>;
 
 const MANY_TO_MANY_PARENT_ID_ALIAS: &str = "__pgorm_parent_id";
+const AGGREGATE_ALIAS: &str = "__pgorm_aggregate";
 
 /// A wrapper returned by "attach" style eager loading.
 #[derive(Debug, Clone)]
@@ -117,6 +128,66 @@ where
     Ok(out)
 }
 
+/// Batch load at most `n` children per parent, in `order_by` order.
+///
+/// Children are ranked per parent with `ROW_NUMBER() OVER (PARTITION BY <fk> ORDER BY ...)`
+/// and only the first `n` are kept, so "latest 3 comments per post" is one query.
+/// `order_by` should end with a unique column to make the cut deterministic.
+#[allow(clippy::too_many_arguments)]
+pub async fn load_has_many_map_limited<Child, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &'static str,
+    table: &'static str,
+    join_clause: &'static str,
+    fk_col: &'static str,
+    n: i64,
+    order_by: OrderBy,
+) -> OrmResult<HasManyMap<Id, Child>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+{
+    let top = TopNPerGroup::new(format!("{table}.{fk_col}"), order_by, n)?;
+    let parent_ids = unique_ids(parent_ids);
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut inner = sql("SELECT ");
+    inner.push(select_list);
+    inner.push(", ");
+    top.row_number().append_to_sql(&mut inner);
+    inner.push(" FROM ");
+    inner.push_ident(table)?;
+    if !join_clause.trim().is_empty() {
+        inner.push(" ");
+        inner.push(join_clause);
+    }
+    inner.push(" WHERE ");
+    inner.push_ident(table)?;
+    inner.push(".");
+    inner.push_ident(fk_col)?;
+    inner.push(" = ANY(");
+    inner.push_bind(parent_ids);
+    inner.push(")");
+
+    let mut q = top.wrap(inner);
+    q.push(" ORDER BY ");
+    q.push(ROW_NUMBER_ALIAS);
+
+    let rows = q.fetch_all(conn).await?;
+
+    let mut out: HashMap<Id, Vec<Child>> = HashMap::new();
+    for row in rows {
+        let fk: Option<Id> = row.try_get_column(fk_col)?;
+        let Some(fk) = fk else { continue };
+        let child = Child::from_row(&row)?;
+        out.entry(fk).or_default().push(child);
+    }
+    Ok(out)
+}
+
 pub async fn load_belongs_to_map<Parent, Id>(
     conn: &impl GenericClient,
     ids: Vec<Id>,
@@ -419,6 +490,139 @@ where
         return Ok(HashMap::new());
     }
 
+    let mut q = many_to_many_sql(
+        select_list,
+        child_table,
+        child_join_clause,
+        join_table,
+        self_key,
+        other_key,
+        child_pk_col,
+        parent_ids,
+    )?;
+    with(&mut q);
+
+    let rows = q.fetch_all(conn).await?;
+
+    let mut out: HashMap<Id, Vec<Child>> = HashMap::new();
+    for row in rows {
+        let parent_id: Id = row.try_get_column(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+        let child = Child::from_row(&row)?;
+        out.entry(parent_id).or_default().push(child);
+    }
+    Ok(out)
+}
+
+/// Prefix of the pivot column aliases selected by the many_to_many pivot loaders,
+/// e.g. `jt.role AS __pgorm_pivot_role`.
+pub const PIVOT_COLUMN_PREFIX: &str = "__pgorm_pivot_";
+
+/// Batch load related rows through a join table together with the join-table row.
+///
+/// `pivot_select_list` selects the pivot columns from the join table aliased as `jt`,
+/// renamed with [`PIVOT_COLUMN_PREFIX`] so they never collide with child columns
+/// (e.g. `Pivot::select_list_prefixed("jt", PIVOT_COLUMN_PREFIX)`); `decode_pivot`
+/// reads them back (e.g. `Pivot::from_row_prefixed(row, PIVOT_COLUMN_PREFIX)`).
+#[allow(clippy::too_many_arguments)]
+pub async fn load_many_to_many_pivot_map<Child, Pivot, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    pivot_select_list: &str,
+    decode_pivot: impl Fn(&tokio_postgres::Row) -> OrmResult<Pivot>,
+    child_table: &'static str,
+    child_join_clause: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+) -> OrmResult<HasManyMap<Id, (Child, Pivot)>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+{
+    load_many_to_many_pivot_map_with(
+        conn,
+        parent_ids,
+        select_list,
+        pivot_select_list,
+        decode_pivot,
+        child_table,
+        child_join_clause,
+        join_table,
+        self_key,
+        other_key,
+        child_pk_col,
+        |_| {},
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn load_many_to_many_pivot_map_with<Child, Pivot, Id, F>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    pivot_select_list: &str,
+    decode_pivot: impl Fn(&tokio_postgres::Row) -> OrmResult<Pivot>,
+    child_table: &'static str,
+    child_join_clause: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+    with: F,
+) -> OrmResult<HasManyMap<Id, (Child, Pivot)>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+    F: FnOnce(&mut Sql),
+{
+    let parent_ids = unique_ids(parent_ids);
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let select_list = format!("{select_list}, {pivot_select_list}");
+    let mut q = many_to_many_sql(
+        &select_list,
+        child_table,
+        child_join_clause,
+        join_table,
+        self_key,
+        other_key,
+        child_pk_col,
+        parent_ids,
+    )?;
+    with(&mut q);
+
+    let rows = q.fetch_all(conn).await?;
+
+    let mut out: HashMap<Id, Vec<(Child, Pivot)>> = HashMap::new();
+    for row in rows {
+        let parent_id: Id = row.try_get_column(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+        let child = Child::from_row(&row)?;
+        let pivot = decode_pivot(&row)?;
+        out.entry(parent_id).or_default().push((child, pivot));
+    }
+    Ok(out)
+}
+
+/// `SELECT jt.<self_key>, <select_list> FROM <child> JOIN <join_table> jt ... WHERE jt.<self_key> = ANY($1)`.
+#[allow(clippy::too_many_arguments)]
+fn many_to_many_sql<Id>(
+    select_list: &str,
+    child_table: &'static str,
+    child_join_clause: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+    parent_ids: Vec<Id>,
+) -> OrmResult<Sql>
+where
+    Id: ToSql + Send + Sync + 'static,
+{
     let mut q = sql("SELECT jt.");
     q.push_ident(self_key)?;
     q.push(" AS ");
@@ -444,6 +648,141 @@ where
     q.push(" = ANY(");
     q.push_bind(parent_ids);
     q.push(")");
+    Ok(q)
+}
+
+/// Batch load at most `n` related rows per parent through a join table, in `order_by` order.
+///
+/// See [`load_has_many_map_limited`]; rows are ranked per `join_table.self_key`.
+#[allow(clippy::too_many_arguments)]
+pub async fn load_many_to_many_map_limited<Child, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    child_table: &'static str,
+    child_join_clause: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+    n: i64,
+    order_by: OrderBy,
+) -> OrmResult<HasManyMap<Id, Child>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+{
+    let top = TopNPerGroup::new(format!("jt.{self_key}"), order_by, n)?;
+    let parent_ids = unique_ids(parent_ids);
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut inner = sql("SELECT jt.");
+    inner.push_ident(self_key)?;
+    inner.push(" AS ");
+    inner.push_ident(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+    inner.push(", ");
+    inner.push(select_list);
+    inner.push(", ");
+    top.row_number().append_to_sql(&mut inner);
+    inner.push(" FROM ");
+    inner.push_ident(child_table)?;
+    inner.push(" JOIN ");
+    inner.push_ident(join_table)?;
+    inner.push(" jt ON jt.");
+    inner.push_ident(other_key)?;
+    inner.push(" = ");
+    inner.push_ident(child_table)?;
+    inner.push(".");
+    inner.push_ident(child_pk_col)?;
+    if !child_join_clause.trim().is_empty() {
+        inner.push(" ");
+        inner.push(child_join_clause);
+    }
+    inner.push(" WHERE jt.");
+    inner.push_ident(self_key)?;
+    inner.push(" = ANY(");
+    inner.push_bind(parent_ids);
+    inner.push(")");
+
+    let mut q = top.wrap(inner);
+    q.push(" ORDER BY ");
+    q.push(ROW_NUMBER_ALIAS);
+
+    let rows = q.fetch_all(conn).await?;
+
+    let mut out: HashMap<Id, Vec<Child>> = HashMap::new();
+    for row in rows {
+        let parent_id: Id = row.try_get_column(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+        let child = Child::from_row(&row)?;
+        out.entry(parent_id).or_default().push(child);
+    }
+    Ok(out)
+}
+
+/// Batch load rows reached through one or more intermediate models
+/// (`has_many_through`), keyed by parent id.
+///
+/// `select_list` must be qualified with `table` (e.g. `Child::select_list_as(Child::TABLE)`),
+/// because the intermediate tables are joined in. See [`ThroughHop`] for the chain layout;
+/// `target_key` is the column of `table` that references the last hop.
+#[allow(clippy::too_many_arguments)]
+pub async fn load_has_many_through_map<Child, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    table: &'static str,
+    join_clause: &'static str,
+    hops: &[ThroughHop],
+    target_key: &'static str,
+) -> OrmResult<HasManyMap<Id, Child>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+{
+    load_has_many_through_map_with(
+        conn,
+        parent_ids,
+        select_list,
+        table,
+        join_clause,
+        hops,
+        target_key,
+        |_| {},
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn load_has_many_through_map_with<Child, Id, F>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    table: &'static str,
+    join_clause: &'static str,
+    hops: &[ThroughHop],
+    target_key: &'static str,
+    with: F,
+) -> OrmResult<HasManyMap<Id, Child>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+    F: FnOnce(&mut Sql),
+{
+    let parent_ids = unique_ids(parent_ids);
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut q = through_sql(
+        select_list,
+        table,
+        join_clause,
+        hops,
+        target_key,
+        parent_ids,
+    )?;
     with(&mut q);
 
     let rows = q.fetch_all(conn).await?;
@@ -457,9 +796,411 @@ where
     Ok(out)
 }
 
+/// Like [`load_has_many_through_map`], but keeps one row per parent (`has_one_through`).
+///
+/// If several rows match a parent, the first row returned wins; use the `_with`
+/// variant to add an `ORDER BY`.
+#[allow(clippy::too_many_arguments)]
+pub async fn load_has_one_through_map<Child, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    table: &'static str,
+    join_clause: &'static str,
+    hops: &[ThroughHop],
+    target_key: &'static str,
+) -> OrmResult<HasOneMap<Id, Child>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+{
+    load_has_one_through_map_with(
+        conn,
+        parent_ids,
+        select_list,
+        table,
+        join_clause,
+        hops,
+        target_key,
+        |_| {},
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn load_has_one_through_map_with<Child, Id, F>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    select_list: &str,
+    table: &'static str,
+    join_clause: &'static str,
+    hops: &[ThroughHop],
+    target_key: &'static str,
+    with: F,
+) -> OrmResult<HasOneMap<Id, Child>>
+where
+    Child: FromRow,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Send + Sync + 'static,
+    F: FnOnce(&mut Sql),
+{
+    let parent_ids = unique_ids(parent_ids);
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut q = through_sql(
+        select_list,
+        table,
+        join_clause,
+        hops,
+        target_key,
+        parent_ids,
+    )?;
+    with(&mut q);
+
+    let rows = q.fetch_all(conn).await?;
+
+    let mut out: HashMap<Id, Child> = HashMap::new();
+    for row in rows {
+        let parent_id: Id = row.try_get_column(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+        if out.contains_key(&parent_id) {
+            continue;
+        }
+        let child = Child::from_row(&row)?;
+        out.insert(parent_id, child);
+    }
+    Ok(out)
+}
+
+/// `SELECT __pgorm_through_0.<fk>, <select_list> FROM <table> INNER JOIN ... WHERE __pgorm_through_0.<fk> = ANY($1)`.
+fn through_sql<Id>(
+    select_list: &str,
+    table: &'static str,
+    join_clause: &'static str,
+    hops: &[ThroughHop],
+    target_key: &'static str,
+    parent_ids: Vec<Id>,
+) -> OrmResult<Sql>
+where
+    Id: ToSql + Send + Sync + 'static,
+{
+    if hops.is_empty() {
+        return Err(OrmError::validation(
+            "through relations need at least one intermediate model",
+        ));
+    }
+    for hop in hops {
+        Ident::parse(hop.table)?;
+        Ident::parse(hop.id)?;
+        Ident::parse(hop.foreign_key)?;
+    }
+    Ident::parse(table)?;
+    Ident::parse(target_key)?;
+    let (joins, parent_key) = through_joins(table, target_key, hops);
+
+    let mut q = sql("SELECT ");
+    q.push(&parent_key);
+    q.push(" AS ");
+    q.push_ident(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+    q.push(", ");
+    q.push(select_list);
+    q.push(" FROM ");
+    q.push_ident(table)?;
+    q.push(&joins);
+    if !join_clause.trim().is_empty() {
+        q.push(" ");
+        q.push(join_clause);
+    }
+    q.push(" WHERE ");
+    q.push(&parent_key);
+    q.push(" = ANY(");
+    q.push_bind(parent_ids);
+    q.push(")");
+    Ok(q)
+}
+
+/// Aggregate computed per parent by the `load_*_{count,sum,exists}_map` helpers.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Aggregate<'a> {
+    Count,
+    Sum(&'a str),
+    Exists,
+}
+
+/// Where the related rows of an aggregate live.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum AggregateSource {
+    HasMany {
+        table: &'static str,
+        fk_col: &'static str,
+    },
+    ManyToMany {
+        child_table: &'static str,
+        join_table: &'static str,
+        self_key: &'static str,
+        other_key: &'static str,
+        child_pk_col: &'static str,
+    },
+}
+
+impl AggregateSource {
+    /// Table the aggregated rows come from.
+    fn child_table(&self) -> &'static str {
+        match *self {
+            Self::HasMany { table, .. } => table,
+            Self::ManyToMany { child_table, .. } => child_table,
+        }
+    }
+
+    fn push_parent_key(&self, q: &mut Sql) -> OrmResult<()> {
+        match *self {
+            Self::HasMany { table, fk_col } => {
+                q.push_ident(table)?;
+                q.push(".");
+                q.push_ident(fk_col)?;
+            }
+            Self::ManyToMany { self_key, .. } => {
+                q.push("jt.");
+                q.push_ident(self_key)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn push_from(&self, q: &mut Sql) -> OrmResult<()> {
+        q.push(" FROM ");
+        match *self {
+            Self::HasMany { table, .. } => {
+                q.push_ident(table)?;
+            }
+            Self::ManyToMany {
+                child_table,
+                join_table,
+                other_key,
+                child_pk_col,
+                ..
+            } => {
+                q.push_ident(child_table)?;
+                q.push(" JOIN ");
+                q.push_ident(join_table)?;
+                q.push(" jt ON jt.");
+                q.push_ident(other_key)?;
+                q.push(" = ");
+                q.push_ident(child_table)?;
+                q.push(".");
+                q.push_ident(child_pk_col)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Count related rows per parent with one `GROUP BY` query.
+///
+/// Every parent id is present in the result; parents without children map to `0`.
+pub async fn load_has_many_count_map<Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    table: &'static str,
+    fk_col: &'static str,
+) -> OrmResult<HashMap<Id, i64>>
+where
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let source = AggregateSource::HasMany { table, fk_col };
+    load_aggregate_map_with(conn, parent_ids, source, Aggregate::Count, |_| {}).await
+}
+
+/// Sum `column` of the related rows per parent with one `GROUP BY` query.
+///
+/// A bare `column` is qualified with the related table. Parents without children
+/// map to `T::default()`. `T` must match the type PostgreSQL returns for `SUM`
+/// (`i64` for `int4`, `Decimal` for `int8`/`numeric`, ...).
+pub async fn load_has_many_sum_map<T, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    table: &'static str,
+    fk_col: &'static str,
+    column: &str,
+) -> OrmResult<HashMap<Id, T>>
+where
+    T: FromSqlOwned + Default,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let source = AggregateSource::HasMany { table, fk_col };
+    load_aggregate_map_with(conn, parent_ids, source, Aggregate::Sum(column), |_| {}).await
+}
+
+/// Check per parent whether any related row exists, with one query.
+///
+/// Parents without children map to `false`.
+pub async fn load_has_many_exists_map<Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    table: &'static str,
+    fk_col: &'static str,
+) -> OrmResult<HashMap<Id, bool>>
+where
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let source = AggregateSource::HasMany { table, fk_col };
+    load_aggregate_map_with(conn, parent_ids, source, Aggregate::Exists, |_| {}).await
+}
+
+/// Count related rows per parent through a join table, see [`load_has_many_count_map`].
+pub async fn load_many_to_many_count_map<Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    child_table: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+) -> OrmResult<HashMap<Id, i64>>
+where
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let source = AggregateSource::ManyToMany {
+        child_table,
+        join_table,
+        self_key,
+        other_key,
+        child_pk_col,
+    };
+    load_aggregate_map_with(conn, parent_ids, source, Aggregate::Count, |_| {}).await
+}
+
+/// Sum `column` of the related rows per parent through a join table, see [`load_has_many_sum_map`].
+#[allow(clippy::too_many_arguments)]
+pub async fn load_many_to_many_sum_map<T, Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    child_table: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+    column: &str,
+) -> OrmResult<HashMap<Id, T>>
+where
+    T: FromSqlOwned + Default,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let source = AggregateSource::ManyToMany {
+        child_table,
+        join_table,
+        self_key,
+        other_key,
+        child_pk_col,
+    };
+    load_aggregate_map_with(conn, parent_ids, source, Aggregate::Sum(column), |_| {}).await
+}
+
+/// Check per parent whether any related row exists through a join table,
+/// see [`load_has_many_exists_map`].
+pub async fn load_many_to_many_exists_map<Id>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    child_table: &'static str,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    child_pk_col: &'static str,
+) -> OrmResult<HashMap<Id, bool>>
+where
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+{
+    let source = AggregateSource::ManyToMany {
+        child_table,
+        join_table,
+        self_key,
+        other_key,
+        child_pk_col,
+    };
+    load_aggregate_map_with(conn, parent_ids, source, Aggregate::Exists, |_| {}).await
+}
+
+/// `SELECT <parent key>, <aggregate> FROM <children> WHERE <parent key> = ANY($1) ... GROUP BY <parent key>`.
+///
+/// `with` is appended to the `WHERE` clause (before `GROUP BY`); the result is zero-filled.
+pub(crate) async fn load_aggregate_map_with<T, Id, F>(
+    conn: &impl GenericClient,
+    parent_ids: Vec<Id>,
+    source: AggregateSource,
+    aggregate: Aggregate<'_>,
+    with: F,
+) -> OrmResult<HashMap<Id, T>>
+where
+    T: FromSqlOwned + Default,
+    Id: ToSql + FromSqlOwned + Eq + Hash + Clone + Send + Sync + 'static,
+    F: FnOnce(&mut Sql),
+{
+    let parent_ids = unique_ids(parent_ids);
+    if parent_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut q = sql("SELECT ");
+    source.push_parent_key(&mut q)?;
+    q.push(" AS ");
+    q.push_ident(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+    q.push(", ");
+    match aggregate {
+        Aggregate::Count => {
+            q.push("COUNT(*)");
+        }
+        Aggregate::Sum(column) => {
+            // Qualify bare columns: the join table may have a column of the same name.
+            let column = Ident::parse(column)?;
+            q.push("COALESCE(SUM(");
+            if column.parts.len() == 1 {
+                q.push_ident(source.child_table())?;
+                q.push(".");
+            }
+            q.push_ident_ref(&column);
+            q.push("), 0)");
+        }
+        Aggregate::Exists => {
+            q.push("TRUE");
+        }
+    }
+    q.push(" AS ");
+    q.push_ident(AGGREGATE_ALIAS)?;
+    source.push_from(&mut q)?;
+    q.push(" WHERE ");
+    source.push_parent_key(&mut q)?;
+    q.push(" = ANY(");
+    q.push_bind(parent_ids.clone());
+    q.push(")");
+    with(&mut q);
+    q.push(" GROUP BY ");
+    source.push_parent_key(&mut q)?;
+
+    let rows = q.fetch_all(conn).await?;
+
+    let mut out: HashMap<Id, T> = parent_ids
+        .into_iter()
+        .map(|id| (id, T::default()))
+        .collect();
+    for row in rows {
+        let parent_id: Id = row.try_get_column(MANY_TO_MANY_PARENT_ID_ALIAS)?;
+        out.insert(parent_id, row.try_get_column(AGGREGATE_ALIAS)?);
+    }
+    Ok(out)
+}
+
+/// Deduplicate parent ids before binding them.
+fn unique_ids<Id: Eq + Hash>(ids: Vec<Id>) -> Vec<Id> {
+    ids.into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_client::RecordingClient;
     use crate::{OrmError, OrmResult};
     use tokio_postgres::Row;
     use tokio_postgres::types::ToSql;
@@ -547,4 +1288,133 @@ mod tests {
         .unwrap();
         assert!(m2m.is_empty());
     }
+
+    #[tokio::test]
+    async fn limited_loaders_rank_children_per_parent() {
+        let conn = RecordingClient::new();
+        let order = || OrderBy::new().desc("comments.created_at").unwrap();
+
+        let _: HasManyMap<i64, DummyChild> = load_has_many_map_limited(
+            &conn,
+            vec![1, 2, 1],
+            "comments.id, comments.post_id",
+            "comments",
+            "",
+            "post_id",
+            3,
+            order(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            conn.last().0,
+            "SELECT * FROM (SELECT comments.id, comments.post_id, ROW_NUMBER() OVER \
+             (PARTITION BY comments.post_id ORDER BY comments.created_at DESC) AS __pgorm_rn \
+             FROM comments WHERE comments.post_id = ANY($1)) AS __pgorm_ranked \
+             WHERE __pgorm_rn <= $2 ORDER BY __pgorm_rn"
+        );
+
+        let _: HasManyMap<i64, DummyChild> = load_many_to_many_map_limited(
+            &conn,
+            vec![1],
+            "tags.id",
+            "tags",
+            "",
+            "post_tags",
+            "post_id",
+            "tag_id",
+            "id",
+            2,
+            OrderBy::new().asc("tags.name").unwrap(),
+        )
+        .await
+        .unwrap();
+        let sql = conn.last().0;
+        assert!(sql.contains("ROW_NUMBER() OVER (PARTITION BY jt.post_id ORDER BY tags.name ASC)"));
+        assert!(sql.contains("WHERE jt.post_id = ANY($1)"));
+
+        let err = load_has_many_map_limited::<DummyChild, i64>(
+            &PanicClient,
+            vec![1],
+            "*",
+            "comments",
+            "",
+            "post_id",
+            0,
+            order(),
+        )
+        .await;
+        assert!(matches!(err, Err(OrmError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn aggregate_loaders_group_by_parent_and_zero_fill() {
+        let conn = RecordingClient::new();
+
+        let counts = load_has_many_count_map(&conn, vec![1_i64, 2, 1], "posts", "user_id")
+            .await
+            .unwrap();
+        assert_eq!(counts, HashMap::from([(1, 0), (2, 0)]));
+        assert_eq!(
+            conn.last().0,
+            "SELECT posts.user_id AS __pgorm_parent_id, COUNT(*) AS __pgorm_aggregate \
+             FROM posts WHERE posts.user_id = ANY($1) GROUP BY posts.user_id"
+        );
+
+        let sums: HashMap<i64, i64> =
+            load_has_many_sum_map(&conn, vec![7_i64], "orders", "customer_id", "total")
+                .await
+                .unwrap();
+        assert_eq!(sums, HashMap::from([(7, 0)]));
+        assert!(
+            conn.last()
+                .0
+                .contains("COALESCE(SUM(orders.total), 0) AS __pgorm_aggregate")
+        );
+
+        let sums: HashMap<i64, i64> = load_many_to_many_sum_map(
+            &conn,
+            vec![3_i64],
+            "tags",
+            "post_tags",
+            "post_id",
+            "tag_id",
+            "id",
+            "weight",
+        )
+        .await
+        .unwrap();
+        assert_eq!(sums, HashMap::from([(3, 0)]));
+        assert_eq!(
+            conn.last().0,
+            "SELECT jt.post_id AS __pgorm_parent_id, \
+             COALESCE(SUM(tags.weight), 0) AS __pgorm_aggregate \
+             FROM tags JOIN post_tags jt ON jt.tag_id = tags.id \
+             WHERE jt.post_id = ANY($1) GROUP BY jt.post_id"
+        );
+
+        let exists = load_many_to_many_exists_map(
+            &conn,
+            vec![3_i64],
+            "tags",
+            "post_tags",
+            "post_id",
+            "tag_id",
+            "id",
+        )
+        .await
+        .unwrap();
+        assert_eq!(exists, HashMap::from([(3, false)]));
+        assert_eq!(
+            conn.last().0,
+            "SELECT jt.post_id AS __pgorm_parent_id, TRUE AS __pgorm_aggregate \
+             FROM tags JOIN post_tags jt ON jt.tag_id = tags.id \
+             WHERE jt.post_id = ANY($1) GROUP BY jt.post_id"
+        );
+
+        let empty = load_has_many_count_map::<i64>(&PanicClient, vec![], "posts", "user_id")
+            .await
+            .unwrap();
+        assert!(empty.is_empty());
+    }
 }
@@ -95,6 +95,13 @@ pub enum OrmError {
     #[error("Deadlock detected: {0}")]
     DeadlockDetected(String),
 
+    /// Lock not available (DB error code 55P03).
+    ///
+    /// Returned by `NOWAIT` row locks (and `lock_timeout`) when a row or table is
+    /// locked by another transaction. Retry later or skip the work item.
+    #[error("Lock not available: {0}")]
+    LockNotAvailable(String),
+
     /// Input validation error.
     #[error("Validation error: {0}")]
     Validation(String),
@@ -149,7 +156,7 @@ impl OrmError {
     ///
     /// Recoverable errors include: `NotFound`, `TooManyRows`, `UniqueViolation`,
     /// `ForeignKeyViolation`, `CheckViolation`, `SerializationFailure`,
-    /// `DeadlockDetected`, `StaleRecord`, `Timeout`, `Validation`.
+    /// `DeadlockDetected`, `LockNotAvailable`, `StaleRecord`, `Timeout`, `Validation`.
     pub fn is_recoverable(&self) -> bool {
         matches!(
             self,
@@ -160,6 +167,7 @@ impl OrmError {
                 | Self::CheckViolation(_)
                 | Self::SerializationFailure(_)
                 | Self::DeadlockDetected(_)
+                | Self::LockNotAvailable(_)
                 | Self::StaleRecord { .. }
                 | Self::Timeout(_)
                 | Self::Validation(_)
@@ -196,6 +204,11 @@ impl OrmError {
         matches!(self, Self::Timeout(_))
     }
 
+    /// Check if this is a lock-not-available (`NOWAIT`) error
+    pub fn is_lock_not_available(&self) -> bool {
+        matches!(self, Self::LockNotAvailable(_))
+    }
+
     /// Check if this is a stale record (optimistic lock) error
     pub fn is_stale_record(&self) -> bool {
         matches!(self, Self::StaleRecord { .. })
@@ -222,6 +235,7 @@ impl OrmError {
             Self::CheckViolation(_) => Some("23514"),
             Self::SerializationFailure(_) => Some("40001"),
             Self::DeadlockDetected(_) => Some("40P01"),
+            Self::LockNotAvailable(_) => Some("55P03"),
             _ => None,
         }
     }
@@ -244,6 +258,8 @@ impl OrmError {
                 // Transaction rollback (class 40)
                 "40001" => return Self::SerializationFailure(message.to_string()),
                 "40P01" => return Self::DeadlockDetected(message.to_string()),
+                // Object not in prerequisite state (class 55)
+                "55P03" => return Self::LockNotAvailable(message.to_string()),
                 // Connection failure (class 08)
                 "08000" | "08003" | "08006" => {
                     return Self::Connection(message.to_string());
@@ -0,0 +1,734 @@
+//! Stable JSON form of filters, sort orders and keysets (e.g. for saved filter presets).
+//!
+//! [`WhereExpr`], [`Condition`], [`OrderBy`], [`Keyset1`], [`Keyset2`] and [`KeysetN`]
+//! implement `Serialize`; [`Op`](crate::Op), [`SortDir`], [`NullsOrder`] and
+//! [`Cursor`](crate::Cursor) derive both directions. Decoding goes through an
+//! [`ExprDecoder`] obtained from a [`QueryAllowList`], which:
+//!
+//! - parses identifiers with [`Ident::parse`] and only accepts allow-listed columns
+//!   (filters for conditions and keyset values, sortable columns for orderings)
+//! - only accepts operators allowed for the column
+//! - parses values with the column's [`FilterValue`](crate::FilterValue) type
+//! - refuses `raw` SQL and `exists` subqueries unless [`ExprDecoder::allow_raw`] is set
+//!
+//! | JSON | Meaning |
+//! |------|---------|
+//! | `{"and": [..]}` / `{"or": [..]}` / `{"not": e}` | boolean composition |
+//! | `{"column": "age", "op": "gte", "value": 18}` | comparison (`eq`, `ne`, `gt`, `gte`, `lt`, `lte`, `like`, `ilike`, `not_like`, `not_ilike`, `is_distinct_from`, `is_not_distinct_from`) |
+//! | `{"column": "id", "op": "in", "value": [1, 2]}` | `in` / `not_in` |
+//! | `{"column": "age", "op": "between", "value": [18, 65]}` | `between` / `not_between` |
+//! | `{"column": "deleted_at", "op": "is_null"}` | `is_null` / `is_not_null` |
+//! | `{"columns": ["a", "b"], "op": "gt", "value": [1, 2]}` | row comparison `(a, b) > ($1, $2)` |
+//! | `{"raw": "sql"}` / `{"raw": "a = ?", "params": [1]}` | raw SQL (opt-in) |
+//! | `{"exists": {"from": "posts", "filter": e}}` | `EXISTS` subquery (opt-in) |
+//! | `[{"column": "name", "dir": "asc", "nulls": "last"}]` | `ORDER BY` (`path` / `cast` for jsonb paths) |
+//! | `{"column": "id", "dir": "desc", "cursor": {"after": 42}, "limit": 20}` | [`Keyset1`] |
+//! | `{"columns": ["created_at", "id"], "dir": "desc", "cursor": {"before": [..]}}` | [`Keyset2`] |
+//! | `{"columns": [{"column": "created_at", "dir": "desc"}, ..], "cursor": ..}` | [`KeysetN`] |
+//!
+//! Conditions built from helpers without a JSON form (full-text search, array and
+//! jsonb operators, ...) fail to serialize.
+//!
+//! # Example
+//! ```ignore
+//! let json = serde_json::to_value(&filter)?;
+//! // ... store and load the preset ...
+//! let allow = QueryAllowList::new()
+//!     .filter::<String>("status", [FilterOp::Eq, FilterOp::In])?
+//!     .filter::<i32>("age", FilterOp::COMPARISON)?;
+//! let filter = allow.decoder().where_expr(json)?;
+//! ```
+
+use crate::builder::{
+    Cursor, Keyset1, Keyset2, KeysetN, NullsOrder, OrderBy, OrderItem, SortDir, WhereExpr,
+};
+use crate::condition::Condition;
+use crate::ident::Ident;
+use crate::query_string::{DynValue, FilterField, FilterOp, ParseFn, QueryAllowList};
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// JSON operator names and the SQL operators they stand for.
+const OPERATORS: &[(&str, &str)] = &[
+    ("eq", "="),
+    ("ne", "!="),
+    ("gt", ">"),
+    ("gte", ">="),
+    ("lt", "<"),
+    ("lte", "<="),
+    ("like", "LIKE"),
+    ("ilike", "ILIKE"),
+    ("not_like", "NOT LIKE"),
+    ("not_ilike", "NOT ILIKE"),
+    ("is_null", "IS NULL"),
+    ("is_not_null", "IS NOT NULL"),
+    ("in", "IN"),
+    ("not_in", "NOT IN"),
+    ("between", "BETWEEN"),
+    ("not_between", "NOT BETWEEN"),
+    ("is_distinct_from", "IS DISTINCT FROM"),
+    ("is_not_distinct_from", "IS NOT DISTINCT FROM"),
+];
+
+/// SQL types accepted as the `cast` of a jsonb path ordering.
+const JSON_PATH_CASTS: &[&str] = &[
+    "smallint",
+    "integer",
+    "bigint",
+    "real",
+    "double precision",
+    "numeric",
+    "boolean",
+    "text",
+    "uuid",
+    "date",
+    "time",
+    "timestamp",
+    "timestamptz",
+    "jsonb",
+];
+
+/// JSON name of a SQL operator, for serialization.
+pub(crate) fn op_name(sql_operator: &str) -> Result<&'static str, String> {
+    OPERATORS
+        .iter()
+        .find(|(_, sql)| *sql == sql_operator)
+        .map(|(name, _)| *name)
+        .ok_or_else(|| format!("operator `{sql_operator}` has no JSON form"))
+}
+
+/// Recover a bound value as JSON, for serialization.
+pub(crate) fn param_to_json(value: &DynValue) -> Result<Value, String> {
+    crate::sql::param_json(&**value).ok_or_else(|| format!("value {value:?} has no JSON form"))
+}
+
+/// Decodes the JSON form of filters, orderings and keysets against a [`QueryAllowList`].
+///
+/// Created with [`QueryAllowList::decoder`]. Works with any serde `Deserializer`,
+/// including an owned `serde_json::Value`.
+#[derive(Debug, Clone, Copy)]
+pub struct ExprDecoder<'a> {
+    allow: &'a QueryAllowList,
+    allow_raw: bool,
+}
+
+impl QueryAllowList {
+    /// A decoder for saved filters, restricted to this allow-list.
+    pub fn decoder(&self) -> ExprDecoder<'_> {
+        ExprDecoder {
+            allow: self,
+            allow_raw: false,
+        }
+    }
+}
+
+fn decode<'de, D, T>(
+    deserializer: D,
+    f: impl FnOnce(&Value) -> Result<T, String>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    f(&value).map_err(D::Error::custom)
+}
+
+impl<'a> ExprDecoder<'a> {
+    /// Also accept `raw` SQL (filters and ordering items) and `exists` subqueries.
+    ///
+    /// Only enable this for JSON written by trusted code: raw SQL is inlined verbatim.
+    pub fn allow_raw(mut self) -> Self {
+        self.allow_raw = true;
+        self
+    }
+
+    /// Decode a [`WhereExpr`].
+    pub fn where_expr<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<WhereExpr, D::Error> {
+        decode(deserializer, |v| self.decode_where(v))
+    }
+
+    /// Decode a single [`Condition`].
+    pub fn condition<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<Condition, D::Error> {
+        decode(deserializer, |v| {
+            self.decode_condition(object(v, "condition")?)
+        })
+    }
+
+    /// Decode an [`OrderBy`].
+    pub fn order_by<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<OrderBy, D::Error> {
+        decode(deserializer, |v| self.decode_order_by(v))
+    }
+
+    /// Decode a [`Keyset1`].
+    pub fn keyset1<'de, D: Deserializer<'de>>(&self, deserializer: D) -> Result<Keyset1, D::Error> {
+        decode(deserializer, |v| self.decode_keyset1(object(v, "keyset")?))
+    }
+
+    /// Decode a [`Keyset2`].
+    pub fn keyset2<'de, D: Deserializer<'de>>(&self, deserializer: D) -> Result<Keyset2, D::Error> {
+        decode(deserializer, |v| self.decode_keyset2(object(v, "keyset")?))
+    }
+
+    /// Decode a [`KeysetN`].
+    pub fn keyset_n<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<KeysetN, D::Error> {
+        decode(deserializer, |v| self.decode_keyset_n(object(v, "keyset")?))
+    }
+
+    fn check_raw(&self, what: &str) -> Result<(), String> {
+        if self.allow_raw {
+            Ok(())
+        } else {
+            Err(format!(
+                "{what} is not allowed (see ExprDecoder::allow_raw)"
+            ))
+        }
+    }
+
+    fn decode_where(&self, value: &Value) -> Result<WhereExpr, String> {
+        let obj = object(value, "filter")?;
+        if let Some(items) = obj.get("and") {
+            return Ok(WhereExpr::And(self.decode_list(items)?));
+        }
+        if let Some(items) = obj.get("or") {
+            return Ok(WhereExpr::Or(self.decode_list(items)?));
+        }
+        if let Some(inner) = obj.get("not") {
+            return Ok(WhereExpr::Not(Box::new(self.decode_where(inner)?)));
+        }
+        if let Some(sql) = obj.get("raw") {
+            self.check_raw("raw SQL")?;
+            let template = string(sql, "raw")?;
+            return Ok(match obj.get("params") {
+                Some(params) => WhereExpr::RawBind {
+                    template,
+                    params: array(params, "params")?.iter().map(raw_param).collect(),
+                },
+                None => WhereExpr::Raw(template),
+            });
+        }
+        if let Some(exists) = obj.get("exists") {
+            self.check_raw("exists")?;
+            let exists = object(exists, "exists")?;
+            let from = string(field(exists, "from")?, "from")?;
+            let filter = self.decode_where(field(exists, "filter")?)?;
+            return Ok(WhereExpr::Exists {
+                from,
+                filter: Box::new(filter),
+            });
+        }
+        self.decode_condition(obj).map(WhereExpr::Atom)
+    }
+
+    fn decode_list(&self, items: &Value) -> Result<Vec<WhereExpr>, String> {
+        array(items, "filter list")?
+            .iter()
+            .map(|item| self.decode_where(item))
+            .collect()
+    }
+
+    fn filter_field(&self, column: &Value) -> Result<&'a FilterField, String> {
+        let name = string(column, "column")?;
+        let ident = Ident::parse(&name).map_err(|e| format!("invalid column `{name}`: {e}"))?;
+        self.allow
+            .filter_for(&ident)
+            .ok_or_else(|| format!("column `{name}` is not allowed"))
+    }
+
+    fn decode_condition(&self, obj: &Map<String, Value>) -> Result<Condition, String> {
+        if let Some(sql) = obj.get("raw") {
+            self.check_raw("raw SQL")?;
+            return Ok(Condition::raw(string(sql, "raw")?));
+        }
+
+        let op = string(field(obj, "op")?, "op")?;
+        let (op, sql_op) = OPERATORS
+            .iter()
+            .copied()
+            .find(|(name, _)| *name == op)
+            .ok_or_else(|| format!("unknown operator `{op}`"))?;
+
+        if let Some(columns) = obj.get("columns") {
+            let columns = array(columns, "columns")?;
+            let values = array(field(obj, "value")?, "value")?;
+            let ([a, b], [va, vb]) = (columns.as_slice(), values.as_slice()) else {
+                return Err("row comparisons take exactly 2 columns and 2 values".to_string());
+            };
+            let (a, b) = (self.filter_field(a)?, self.filter_field(b)?);
+            let va = self.value(a, op, va)?;
+            let vb = self.value(b, op, vb)?;
+            return Ok(Condition::tuple2_cmp_dyn(
+                a.column.clone(),
+                b.column.clone(),
+                sql_op,
+                va,
+                vb,
+            ));
+        }
+
+        let column = self.filter_field(field(obj, "column")?)?;
+        let ident = column.column.clone();
+        match op {
+            "is_null" | "is_not_null" => {
+                if !column.nullable {
+                    return Err(format!(
+                        "operator `{op}` is not allowed for `{}`",
+                        ident.to_sql()
+                    ));
+                }
+                Ok(if op == "is_null" {
+                    Condition::is_null(ident)
+                } else {
+                    Condition::is_not_null(ident)
+                }
+                .map_err(|e| e.to_string())?)
+            }
+            "in" | "not_in" => {
+                let values = array(field(obj, "value")?, "value")?
+                    .iter()
+                    .map(|v| self.value(column, op, v))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Condition::list_dyn(ident, sql_op, values))
+            }
+            "between" | "not_between" => {
+                let values = array(field(obj, "value")?, "value")?;
+                let [from, to] = values.as_slice() else {
+                    return Err(format!("`{op}` takes exactly 2 values"));
+                };
+                let from = self.value(column, op, from)?;
+                let to = self.value(column, op, to)?;
+                Ok(Condition::pair_dyn(ident, sql_op, from, to))
+            }
+            _ => {
+                let value = self.value(column, op, field(obj, "value")?)?;
+                Ok(Condition::cmp_dyn(ident, sql_op, value))
+            }
+        }
+    }
+
+    /// Check `op` against the column's allowed operators and parse `value`.
+    fn value(&self, column: &FilterField, op: &str, value: &Value) -> Result<DynValue, String> {
+        let required: &[FilterOp] = match op {
+            "eq" | "is_not_distinct_from" => &[FilterOp::Eq],
+            "ne" | "is_distinct_from" => &[FilterOp::Ne],
+            "gt" => &[FilterOp::Gt],
+            "gte" => &[FilterOp::Gte],
+            "lt" => &[FilterOp::Lt],
+            "lte" => &[FilterOp::Lte],
+            "like" => &[FilterOp::Like],
+            "ilike" => &[FilterOp::Ilike],
+            "not_like" => &[FilterOp::NotLike],
+            "not_ilike" => &[FilterOp::NotIlike],
+            "in" => &[FilterOp::In],
+            "not_in" => &[FilterOp::NotIn],
+            "between" | "not_between" => &[FilterOp::Gte, FilterOp::Lte],
+            _ => &[],
+        };
+        if !required.iter().all(|op| column.ops.contains(op)) {
+            return Err(format!(
+                "operator `{op}` is not allowed for `{}`",
+                column.column.to_sql()
+            ));
+        }
+        let parse = column
+            .parse
+            .ok_or_else(|| format!("no value type registered for `{}`", column.column.to_sql()))?;
+        typed_value(parse, &column.column, value)
+    }
+
+    fn decode_order_by(&self, value: &Value) -> Result<OrderBy, String> {
+        array(value, "order by")?
+            .iter()
+            .try_fold(OrderBy::new(), |order_by, item| {
+                Ok(order_by.add(self.decode_order_item(object(item, "order item")?)?))
+            })
+    }
+
+    fn sort_column(&self, column: &Value) -> Result<Ident, String> {
+        let name = string(column, "column")?;
+        let ident = Ident::parse(&name).map_err(|e| format!("invalid column `{name}`: {e}"))?;
+        self.allow
+            .sort_for(&ident)
+            .cloned()
+            .ok_or_else(|| format!("cannot sort by `{name}`"))
+    }
+
+    fn decode_order_item(&self, obj: &Map<String, Value>) -> Result<OrderItem, String> {
+        if let Some(sql) = obj.get("raw") {
+            self.check_raw("raw SQL")?;
+            return Ok(OrderItem::raw(string(sql, "raw")?));
+        }
+        let column = self.sort_column(field(obj, "column")?)?;
+        let dir = optional::<SortDir>(obj, "dir")?.unwrap_or_default();
+        let nulls = optional::<NullsOrder>(obj, "nulls")?;
+
+        let Some(path) = obj.get("path") else {
+            return Ok(OrderItem::Column { column, dir, nulls });
+        };
+        let path = array(path, "path")?
+            .iter()
+            .map(|key| string(key, "path"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cast = match obj.get("cast") {
+            None | Some(Value::Null) => None,
+            Some(cast) => {
+                let cast = string(cast, "cast")?;
+                Some(
+                    *JSON_PATH_CASTS
+                        .iter()
+                        .find(|c| **c == cast)
+                        .ok_or_else(|| format!("unsupported cast `{cast}`"))?,
+                )
+            }
+        };
+        Ok(OrderItem::JsonPath {
+            column,
+            path,
+            cast,
+            dir,
+            nulls,
+        })
+    }
+
+    /// Sortable column plus the parser for its cursor values.
+    fn keyset_column(&self, column: &Value) -> Result<(Ident, ParseFn), String> {
+        let ident = self.sort_column(column)?;
+        let parse = self
+            .allow
+            .filter_for(&ident)
+            .and_then(|f| f.parse)
+            .ok_or_else(|| format!("no value type registered for `{}`", ident.to_sql()))?;
+        Ok((ident, parse))
+    }
+
+    fn decode_keyset1(&self, obj: &Map<String, Value>) -> Result<Keyset1, String> {
+        let (column, parse) = self.keyset_column(field(obj, "column")?)?;
+        let keyset = match optional::<SortDir>(obj, "dir")?.unwrap_or_default() {
+            SortDir::Asc => Keyset1::asc(&column),
+            SortDir::Desc => Keyset1::desc(&column),
+        }
+        .map_err(|e| e.to_string())?;
+        let keyset = match cursor(obj)? {
+            Some(cursor) => {
+                keyset.cursor_dyn(map_cursor(cursor, |v| typed_value(parse, &column, v))?)
+            }
+            None => keyset,
+        };
+        with_limit(obj, keyset, Keyset1::limit)
+    }
+
+    fn decode_keyset2(&self, obj: &Map<String, Value>) -> Result<Keyset2, String> {
+        let columns = array(field(obj, "columns")?, "columns")?;
+        let [a, b] = columns.as_slice() else {
+            return Err("Keyset2 takes exactly 2 columns".to_string());
+        };
+        let (a, parse_a) = self.keyset_column(a)?;
+        let (b, parse_b) = self.keyset_column(b)?;
+        let keyset = match optional::<SortDir>(obj, "dir")?.unwrap_or_default() {
+            SortDir::Asc => Keyset2::asc(&a, &b),
+            SortDir::Desc => Keyset2::desc(&a, &b),
+        }
+        .map_err(|e| e.to_string())?;
+        let keyset = match cursor(obj)? {
+            Some(cursor) => keyset.cursor_dyn(map_cursor(cursor, |v| {
+                let [va, vb] = array(v, "cursor")?.as_slice() else {
+                    return Err("Keyset2 cursors take exactly 2 values".to_string());
+                };
+                Ok((typed_value(parse_a, &a, va)?, typed_value(parse_b, &b, vb)?))
+            })?),
+            None => keyset,
+        };
+        with_limit(obj, keyset, Keyset2::limit)
+    }
+
+    fn decode_keyset_n(&self, obj: &Map<String, Value>) -> Result<KeysetN, String> {
+        let mut items = Vec::new();
+        let mut parsers = Vec::new();
+        for column in array(field(obj, "columns")?, "columns")? {
+            let column = object(column, "keyset column")?;
+            let (ident, parse) = self.keyset_column(field(column, "column")?)?;
+            let dir = optional::<SortDir>(column, "dir")?.unwrap_or_default();
+            let nulls = optional::<NullsOrder>(column, "nulls")?;
+            items.push(OrderItem::Column {
+                column: ident.clone(),
+                dir,
+                nulls,
+            });
+            parsers.push((ident, parse));
+        }
+        let keyset = KeysetN::new(items).map_err(|e| e.to_string())?;
+        let keyset = match cursor(obj)? {
+            Some(cursor) => {
+                let values = |v: &Value| -> Result<Vec<DynValue>, String> {
+                    let values = array(v, "cursor")?;
+                    if values.len() != parsers.len() {
+                        return Err(format!(
+                            "keyset cursor value count mismatch: expected {}, got {}",
+                            parsers.len(),
+                            values.len()
+                        ));
+                    }
+                    parsers
+                        .iter()
+                        .zip(values)
+                        .map(|((ident, parse), v)| typed_value(*parse, ident, v))
+                        .collect()
+                };
+                match map_cursor(cursor, values)? {
+                    Cursor::After(values) => keyset.after(values),
+                    Cursor::Before(values) => keyset.before(values),
+                }
+            }
+            None => keyset,
+        };
+        with_limit(obj, keyset, KeysetN::limit)
+    }
+}
+
+fn object<'v>(value: &'v Value, what: &str) -> Result<&'v Map<String, Value>, String> {
+    value
+        .as_object()
+        .ok_or_else(|| format!("expected a {what} object, got `{value}`"))
+}
+
+fn array<'v>(value: &'v Value, what: &str) -> Result<&'v Vec<Value>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| format!("expected `{what}` to be an array, got `{value}`"))
+}
+
+fn string(value: &Value, what: &str) -> Result<String, String> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("expected `{what}` to be a string, got `{value}`"))
+}
+
+fn field<'v>(obj: &'v Map<String, Value>, key: &str) -> Result<&'v Value, String> {
+    obj.get(key).ok_or_else(|| format!("missing `{key}`"))
+}
+
+fn optional<T: serde::de::DeserializeOwned>(
+    obj: &Map<String, Value>,
+    key: &str,
+) -> Result<Option<T>, String> {
+    match obj.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("invalid `{key}`: {e}")),
+    }
+}
+
+fn cursor(obj: &Map<String, Value>) -> Result<Option<Cursor<Value>>, String> {
+    optional(obj, "cursor")
+}
+
+fn map_cursor<T>(
+    cursor: Cursor<Value>,
+    f: impl FnOnce(&Value) -> Result<T, String>,
+) -> Result<Cursor<T>, String> {
+    Ok(match cursor {
+        Cursor::After(v) => Cursor::After(f(&v)?),
+        Cursor::Before(v) => Cursor::Before(f(&v)?),
+    })
+}
+
+fn with_limit<K>(obj: &Map<String, Value>, keyset: K, limit: fn(K, i64) -> K) -> Result<K, String> {
+    Ok(match optional::<i64>(obj, "limit")? {
+        Some(n) => limit(keyset, n),
+        None => keyset,
+    })
+}
+
+/// Parse a JSON scalar with a column's [`FilterValue`](crate::FilterValue) type.
+fn typed_value(parse: ParseFn, column: &Ident, value: &Value) -> Result<DynValue, String> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Null => {
+            return Err(format!(
+                "`{}`: null is not a value (use is_null)",
+                column.to_sql()
+            ));
+        }
+        other => other.to_string(),
+    };
+    parse(&text).map_err(|e| format!("`{}`: {e}", column.to_sql()))
+}
+
+/// Bind a `raw` template parameter by its JSON type.
+fn raw_param(value: &Value) -> DynValue {
+    match value {
+        Value::Null => Arc::new(None::<String>),
+        Value::Bool(b) => Arc::new(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Arc::new(i),
+            None => Arc::new(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => Arc::new(s.clone()),
+        other => Arc::new(other.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::WhereExpr;
+    use crate::sql::Sql;
+    use serde_json::json;
+
+    fn allow() -> QueryAllowList {
+        QueryAllowList::new()
+            .filter::<String>("status", [FilterOp::Eq, FilterOp::In])
+            .unwrap()
+            .filter::<i32>("age", FilterOp::COMPARISON)
+            .unwrap()
+            .filter::<i64>("id", [FilterOp::Gt, FilterOp::Lt])
+            .unwrap()
+            .nullable("deleted_at")
+            .unwrap()
+            .sort(["id", "age"])
+            .unwrap()
+    }
+
+    fn where_sql(expr: &WhereExpr) -> String {
+        let mut sql = Sql::empty();
+        expr.append_to_sql(&mut sql);
+        sql.to_sql()
+    }
+
+    #[test]
+    fn where_expr_round_trips() {
+        let expr = WhereExpr::and(vec![
+            WhereExpr::atom(Condition::in_list("status", vec!["active", "trial"]).unwrap()),
+            WhereExpr::or(vec![
+                WhereExpr::atom(Condition::new("age", crate::Op::between(18_i32, 65)).unwrap()),
+                WhereExpr::atom(Condition::is_null("deleted_at").unwrap()),
+            ]),
+            WhereExpr::not(WhereExpr::atom(Condition::eq("status", "banned").unwrap())),
+        ]);
+        let value = serde_json::to_value(&expr).unwrap();
+        assert_eq!(
+            value,
+            json!({"and": [
+                {"column": "status", "op": "in", "value": ["active", "trial"]},
+                {"or": [
+                    {"column": "age", "op": "between", "value": [18, 65]},
+                    {"column": "deleted_at", "op": "is_null"},
+                ]},
+                {"not": {"column": "status", "op": "eq", "value": "banned"}},
+            ]})
+        );
+
+        let decoded = allow().decoder().where_expr(value).unwrap();
+        assert_eq!(where_sql(&decoded), where_sql(&expr));
+    }
+
+    #[test]
+    fn rejects_unknown_columns_operators_and_raw() {
+        let allow = allow();
+        let decoder = allow.decoder();
+        let err = |v: Value| decoder.where_expr(v).unwrap_err().to_string();
+
+        assert!(
+            err(json!({"column": "password", "op": "eq", "value": "x"})).contains("not allowed")
+        );
+        assert!(err(json!({"column": "status", "op": "like", "value": "x"})).contains("`like`"));
+        assert!(err(json!({"column": "age", "op": "eq", "value": "old"})).contains("`age`"));
+        assert!(err(json!({"column": "age; drop", "op": "eq", "value": 1})).contains("age; drop"));
+        assert!(err(json!({"column": "age", "op": "is_null"})).contains("is_null"));
+        assert!(err(json!({"raw": "1 = 1"})).contains("allow_raw"));
+        assert!(
+            err(json!({"exists": {"from": "t", "filter": {"raw": "true"}}})).contains("allow_raw")
+        );
+
+        let raw = decoder
+            .allow_raw()
+            .where_expr(json!({"raw": "score > ?", "params": [3]}))
+            .unwrap();
+        assert_eq!(where_sql(&raw), "score > $1");
+    }
+
+    #[test]
+    fn order_by_and_keysets_round_trip() {
+        let allow = allow();
+        let decoder = allow.decoder();
+
+        let order = OrderBy::new()
+            .desc("age")
+            .unwrap()
+            .with_nulls("id", SortDir::Asc, NullsOrder::Last)
+            .unwrap();
+        let value = serde_json::to_value(&order).unwrap();
+        assert_eq!(
+            value,
+            json!([
+                {"column": "age", "dir": "desc"},
+                {"column": "id", "dir": "asc", "nulls": "last"},
+            ])
+        );
+        let decoded = decoder.order_by(value).unwrap();
+        assert_eq!(decoded.to_sql(), order.to_sql());
+        assert!(decoder.order_by(json!([{"column": "status"}])).is_err());
+        assert!(decoder.order_by(json!([{"raw": "random()"}])).is_err());
+
+        let keyset = Keyset1::desc("id").unwrap().after(42_i64).limit(20);
+        let value = serde_json::to_value(&keyset).unwrap();
+        assert_eq!(
+            value,
+            json!({"column": "id", "dir": "desc", "cursor": {"after": 42}, "limit": 20})
+        );
+        let decoded = decoder.keyset1(value).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&keyset).unwrap()
+        );
+
+        let keyset = Keyset2::asc("age", "id").unwrap().before(30_i32, 7_i64);
+        let value = serde_json::to_value(&keyset).unwrap();
+        assert_eq!(value["cursor"], json!({"before": [30, 7]}));
+        let decoded = decoder.keyset2(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), value);
+
+        let keyset = KeysetN::new([
+            OrderItem::new(Ident::parse("age").unwrap(), SortDir::Desc),
+            OrderItem::new(Ident::parse("id").unwrap(), SortDir::Asc),
+        ])
+        .unwrap()
+        .after((30_i32, 7_i64));
+        let value = serde_json::to_value(&keyset).unwrap();
+        let decoded = decoder.keyset_n(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), value);
+        assert!(
+            decoder
+                .keyset_n(json!({"columns": [{"column": "age"}, {"column": "id"}], "cursor": {"after": [1]}}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn op_and_helpers_without_json_form() {
+        let op = crate::Op::between(1_i32, 5);
+        assert_eq!(
+            serde_json::to_value(&op).unwrap(),
+            json!({"op": "between", "value": [1, 5]})
+        );
+        let back: crate::Op<i32> = serde_json::from_value(json!({"op": "is_null"})).unwrap();
+        assert!(matches!(back, crate::Op::IsNull));
+
+        let cond = Condition::array_contains("tags", vec!["a".to_string()]).unwrap();
+        assert!(serde_json::to_value(&cond).is_err());
+    }
+}
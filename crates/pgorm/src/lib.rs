@@ -15,14 +15,27 @@
 //!
 //! ## Modules
 //!
+//! - [`expr_json`] — stable JSON form of filters, sort orders and keysets, decoded against an allow-list
 //! - [`monitor`] — query monitoring, hooks, [`InstrumentedClient`]
 //! - [`check`] — SQL schema checking, linting, [`SchemaRegistry`]
+//! - [`pivot`] — join-table writes for `many_to_many` relations (`attach` / `detach` / `sync`)
+//! - [`preload`] — nested relation preloading (`User::query().preload(User::posts().then(...))`)
 //! - [`prelude`] — convenient `use pgorm::prelude::*` for daily use
+//! - [`page`] — page results: cursor pages ([`Page`], [`CursorCodec`]) and offset pages with totals ([`Paginated`])
 //! - [`qb`] — thin wrapper around `query()` for hand-written SQL
+//! - [`query_string`] — allow-listed filters and sorting parsed from HTTP query strings
+//! - [`relation`] — correlated `EXISTS` filters built from relation metadata
+//! - [`search`] — full-text search (`tsvector` / `tsquery`, ranking, headlines)
+//! - [`tree`] — adjacency-list trees walked with recursive CTEs (`#[orm(tree(...))]`)
+//! - [`window`] — window functions (`OVER (...)`) and top-N-per-group helpers
 //!
 //! > **Stability:** pgorm is pre-1.0. APIs may change between minor versions.
 //! > MSRV: 1.88+
 
+// Lets the shared test fixtures name the crate as `pgorm` from unit tests too.
+#[cfg(test)]
+extern crate self as pgorm;
+
 mod builder;
 mod bulk;
 pub mod changeset;
@@ -31,15 +44,28 @@ mod condition;
 mod cte;
 pub mod eager;
 mod error;
+pub mod expr_json;
 mod ident;
 mod listen;
+mod lock;
 pub mod monitor;
+pub mod page;
+pub mod pivot;
+pub mod preload;
 pub mod prelude;
 pub mod qb;
+pub mod query_string;
+pub mod relation;
 mod row;
+pub mod search;
 mod sql;
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod test_client;
 mod transaction;
+pub mod tree;
 pub mod types;
+pub mod window;
 
 #[cfg(feature = "validate")]
 pub mod validate;
@@ -59,7 +85,16 @@ pub use builder::{
 };
 pub use condition::{Condition, Op};
 pub use cte::WithBuilder;
-pub use sql::{FromRowStream, Query, Sql, query, sql};
+pub use expr_json::ExprDecoder;
+pub use lock::{LockStrength, LockWait, RowLock};
+pub use page::{CountMode, CursorColumn, CursorKey, Page, Paginated};
+pub use query_string::{FilterOp, FilterValue, ParsedQuery, QueryAllowList};
+pub use search::{TsHeadline, TsQuery, TsQueryMode, TsRank, TsVector, TsWeight};
+pub use sql::{FromRowStream, Query, RedactionPolicy, Sql, query, sql};
+pub use tree::{Nested, TreeModel, TreeNode};
+pub use window::{
+    FrameBound, FrameUnits, NamedWindows, TopNPerGroup, WindowExpr, WindowFrame, WindowSpec,
+};
 
 // Row mapping & types
 pub use row::{FromRow, PgType, RowExt};
@@ -75,18 +110,22 @@ pub use listen::{
 };
 
 // Bulk operations
-pub use bulk::{DeleteManyBuilder, SetExpr, UpdateManyBuilder};
+pub use bulk::{
+    DeleteManyBuilder, InsertBuilder, InsertSelectBuilder, IntoInsertRow, MergeBuilder, OnConflict,
+    SetExpr, UpdateManyBuilder,
+};
 
 // Identifiers
 pub use ident::{Ident, IdentPart, IntoIdent};
 
 // Eager loading
 pub use eager::{BelongsToMap, HasManyMap, HasOneMap, Loaded};
+pub use preload::{Preload, PreloadBelongsTo, PreloadCount, PreloadMany, PreloadOne};
 
 // Transactions
 pub use transaction::{
-    __next_savepoint_name, Savepoint, TransactionBeginExt, TransactionExt, TransactionIsolation,
-    TransactionOptions, begin_transaction, begin_transaction_with,
+    __next_savepoint_name, Savepoint, TransactionBeginExt, TransactionClient, TransactionExt,
+    TransactionIsolation, TransactionOptions, begin_transaction, begin_transaction_with,
 };
 
 // Validation
@@ -115,12 +154,20 @@ pub use pool::{create_pool, create_pool_with_config};
 #[cfg(feature = "pool")]
 pub use pool::{create_pool_with_manager_config, create_pool_with_tls};
 
+// Cursor tokens
+#[cfg(feature = "cursor")]
+pub use page::CursorCodec;
+
 // Derive macros
 #[cfg(feature = "derive")]
 pub use pgorm_derive::{
     FromRow, InsertModel, Model, PgComposite, PgEnum, QueryParams, UpdateModel, ViewModel,
 };
 
+// Compile-time checked SQL (needs the schema cache written by `pgorm schema`)
+#[cfg(all(feature = "derive", feature = "check"))]
+pub use pgorm_derive::checked_sql;
+
 // ─────────────────────────────────────────────────────────────────────────────
 // SQL checking and linting (public module: pgorm::check)
 // ─────────────────────────────────────────────────────────────────────────────
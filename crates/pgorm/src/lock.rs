@@ -0,0 +1,201 @@
+//! Row-level locking clauses (`FOR UPDATE` / `FOR SHARE` ...).
+//!
+//! # Example
+//! ```ignore
+//! use pgorm::{RowLock, sql};
+//!
+//! // Claim up to 10 jobs without blocking on rows other workers hold.
+//! let mut q = sql("SELECT * FROM jobs WHERE status = ");
+//! q.push_bind("queued").push(" ORDER BY id").limit(10);
+//! q.row_lock(&RowLock::for_update().skip_locked());
+//! let jobs: Vec<Job> = q.fetch_all_as(&tx).await?;
+//! ```
+
+use crate::error::OrmResult;
+use crate::ident::{Ident, IntoIdent};
+use crate::sql::Sql;
+
+/// Lock strength of a row-locking clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockStrength {
+    /// `FOR UPDATE`
+    Update,
+    /// `FOR NO KEY UPDATE`
+    NoKeyUpdate,
+    /// `FOR SHARE`
+    Share,
+    /// `FOR KEY SHARE`
+    KeyShare,
+}
+
+impl LockStrength {
+    fn to_sql(self) -> &'static str {
+        match self {
+            LockStrength::Update => "FOR UPDATE",
+            LockStrength::NoKeyUpdate => "FOR NO KEY UPDATE",
+            LockStrength::Share => "FOR SHARE",
+            LockStrength::KeyShare => "FOR KEY SHARE",
+        }
+    }
+}
+
+/// What to do when a row is already locked by another transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockWait {
+    /// Block until the lock is released (default).
+    #[default]
+    Wait,
+    /// Fail immediately with [`OrmError::LockNotAvailable`](crate::OrmError::LockNotAvailable).
+    NoWait,
+    /// Skip rows that cannot be locked immediately.
+    SkipLocked,
+}
+
+/// A row-locking clause: `FOR UPDATE [OF t1, t2] [NOWAIT | SKIP LOCKED]`.
+///
+/// Locks are held until the end of the transaction, so run locking queries
+/// inside one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowLock {
+    strength: LockStrength,
+    of: Vec<Ident>,
+    wait: LockWait,
+}
+
+impl RowLock {
+    /// Create a lock clause with the given strength.
+    pub fn new(strength: LockStrength) -> Self {
+        Self {
+            strength,
+            of: Vec::new(),
+            wait: LockWait::Wait,
+        }
+    }
+
+    /// `FOR UPDATE`
+    pub fn for_update() -> Self {
+        Self::new(LockStrength::Update)
+    }
+
+    /// `FOR NO KEY UPDATE`
+    pub fn for_no_key_update() -> Self {
+        Self::new(LockStrength::NoKeyUpdate)
+    }
+
+    /// `FOR SHARE`
+    pub fn for_share() -> Self {
+        Self::new(LockStrength::Share)
+    }
+
+    /// `FOR KEY SHARE`
+    pub fn for_key_share() -> Self {
+        Self::new(LockStrength::KeyShare)
+    }
+
+    /// Change the lock strength, keeping `OF` tables and the wait policy.
+    pub fn strength(mut self, strength: LockStrength) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Skip rows that are already locked (`SKIP LOCKED`).
+    pub fn skip_locked(mut self) -> Self {
+        self.wait = LockWait::SkipLocked;
+        self
+    }
+
+    /// Fail instead of waiting for locked rows (`NOWAIT`).
+    pub fn nowait(mut self) -> Self {
+        self.wait = LockWait::NoWait;
+        self
+    }
+
+    /// Only lock rows of these tables (`OF t1, t2`); use table names or aliases.
+    pub fn of<I, T>(mut self, tables: I) -> OrmResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        for table in tables {
+            self.of.push(table.into_ident()?);
+        }
+        Ok(self)
+    }
+
+    /// Whether the lock is restricted to specific tables with `OF`.
+    pub fn has_of_tables(&self) -> bool {
+        !self.of.is_empty()
+    }
+
+    /// Append ` FOR ...` to a SQL builder.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        sql.push(" ");
+        sql.push(self.strength.to_sql());
+        if !self.of.is_empty() {
+            sql.push(" OF ");
+            for (i, table) in self.of.iter().enumerate() {
+                if i > 0 {
+                    sql.push(", ");
+                }
+                sql.push_ident_ref(table);
+            }
+        }
+        match self.wait {
+            LockWait::Wait => {}
+            LockWait::NoWait => {
+                sql.push(" NOWAIT");
+            }
+            LockWait::SkipLocked => {
+                sql.push(" SKIP LOCKED");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(lock: &RowLock) -> String {
+        let mut sql = Sql::new("SELECT * FROM jobs");
+        lock.append_to_sql(&mut sql);
+        sql.to_sql()
+    }
+
+    #[test]
+    fn lock_strengths() {
+        assert_eq!(
+            render(&RowLock::for_update()),
+            "SELECT * FROM jobs FOR UPDATE"
+        );
+        assert_eq!(
+            render(&RowLock::for_no_key_update()),
+            "SELECT * FROM jobs FOR NO KEY UPDATE"
+        );
+        assert_eq!(
+            render(&RowLock::for_share()),
+            "SELECT * FROM jobs FOR SHARE"
+        );
+        assert_eq!(
+            render(&RowLock::for_key_share()),
+            "SELECT * FROM jobs FOR KEY SHARE"
+        );
+    }
+
+    #[test]
+    fn lock_modifiers() {
+        let lock = RowLock::for_update()
+            .of(["jobs", "workers"])
+            .unwrap()
+            .skip_locked();
+        assert_eq!(
+            render(&lock),
+            "SELECT * FROM jobs FOR UPDATE OF jobs, workers SKIP LOCKED"
+        );
+        assert_eq!(
+            render(&RowLock::for_share().nowait()),
+            "SELECT * FROM jobs FOR SHARE NOWAIT"
+        );
+        assert!(RowLock::for_update().of(["jobs; DROP"]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::sql::RedactionPolicy;
 use std::time::Duration;
 
 /// Configuration for query monitoring and timeouts.
@@ -11,6 +12,9 @@ pub struct MonitorConfig {
     pub slow_query_threshold: Option<Duration>,
     /// Whether monitoring is enabled.
     pub monitoring_enabled: bool,
+    /// Render [`QueryContext::debug_sql`](crate::QueryContext::debug_sql) with this policy.
+    /// `None` disables debug rendering (default).
+    pub debug_sql: Option<RedactionPolicy>,
 }
 
 impl MonitorConfig {
@@ -49,4 +53,13 @@ impl MonitorConfig {
         self.monitoring_enabled = false;
         self
     }
+
+    /// Render SQL with inlined parameter values into `QueryContext::debug_sql`.
+    ///
+    /// Hooks and monitors (e.g. `LoggingMonitor`, `TracingSqlHook`) then print it.
+    /// Values may be sensitive: redact them with `policy`.
+    pub fn with_debug_sql(mut self, policy: RedactionPolicy) -> Self {
+        self.debug_sql = Some(policy);
+        self
+    }
 }
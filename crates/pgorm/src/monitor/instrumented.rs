@@ -193,16 +193,19 @@ impl<C: GenericClient> InstrumentedClient<C> {
     }
 
     /// Common pre-execution setup: create context, apply hook, notify monitors.
-    fn prepare_ctx(
+    pub(super) fn prepare_ctx(
         &self,
         sql: &str,
-        param_count: usize,
+        params: &[&(dyn ToSql + Sync)],
         tag: Option<&str>,
     ) -> Result<QueryContext, OrmError> {
-        let mut ctx = QueryContext::new(sql, param_count);
+        let mut ctx = QueryContext::new(sql, params.len());
         if let Some(tag) = tag {
             ctx.tag = Some(tag.to_string());
         }
+        if let Some(policy) = &self.config.debug_sql {
+            ctx.debug_sql = Some(crate::sql::render_debug_sql(sql, params, policy));
+        }
         self.apply_hook(&mut ctx)?;
         if self.config.monitoring_enabled {
             self.monitor.on_query_start(&ctx);
@@ -216,7 +219,7 @@ impl<C: GenericClient> InstrumentedClient<C> {
         params: &[&(dyn ToSql + Sync)],
         tag: Option<&str>,
     ) -> OrmResult<Vec<Row>> {
-        let ctx = self.prepare_ctx(sql, params.len(), tag)?;
+        let ctx = self.prepare_ctx(sql, params, tag)?;
         let start = Instant::now();
         let result = self
             .execute_with_timeout(self.client.query(&ctx.exec_sql, params))
@@ -237,7 +240,7 @@ impl<C: GenericClient> InstrumentedClient<C> {
         params: &[&(dyn ToSql + Sync)],
         tag: Option<&str>,
     ) -> OrmResult<Row> {
-        let ctx = self.prepare_ctx(sql, params.len(), tag)?;
+        let ctx = self.prepare_ctx(sql, params, tag)?;
         let start = Instant::now();
         let result = self
             .execute_with_timeout(self.client.query_one(&ctx.exec_sql, params))
@@ -259,7 +262,7 @@ impl<C: GenericClient> InstrumentedClient<C> {
         params: &[&(dyn ToSql + Sync)],
         tag: Option<&str>,
     ) -> OrmResult<Option<Row>> {
-        let ctx = self.prepare_ctx(sql, params.len(), tag)?;
+        let ctx = self.prepare_ctx(sql, params, tag)?;
         let start = Instant::now();
         let result = self
             .execute_with_timeout(self.client.query_opt(&ctx.exec_sql, params))
@@ -281,7 +284,7 @@ impl<C: GenericClient> InstrumentedClient<C> {
         params: &[&(dyn ToSql + Sync)],
         tag: Option<&str>,
     ) -> OrmResult<u64> {
-        let ctx = self.prepare_ctx(sql, params.len(), tag)?;
+        let ctx = self.prepare_ctx(sql, params, tag)?;
         let start = Instant::now();
         let result = self
             .execute_with_timeout(self.client.execute(&ctx.exec_sql, params))
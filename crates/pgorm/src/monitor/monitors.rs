@@ -67,7 +67,7 @@ impl LoggingMonitor {
 impl LoggingMonitor {
     fn format_sql(&self, ctx: &QueryContext) -> String {
         let canonical = self.truncate_sql(&ctx.canonical_sql);
-        if ctx.exec_sql != ctx.canonical_sql {
+        let sql = if ctx.exec_sql != ctx.canonical_sql {
             format!(
                 "canonical: {} | exec: {}",
                 canonical,
@@ -75,6 +75,10 @@ impl LoggingMonitor {
             )
         } else {
             canonical
+        };
+        match &ctx.debug_sql {
+            Some(debug_sql) => format!("{sql} | debug: {}", self.truncate_sql(debug_sql)),
+            None => sql,
         }
     }
 }
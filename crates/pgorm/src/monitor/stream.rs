@@ -153,16 +153,7 @@ impl<C: GenericClient + StreamingClient> super::instrumented::InstrumentedClient
         params: &[&(dyn ToSql + Sync)],
         tag: Option<&str>,
     ) -> OrmResult<RowStream> {
-        let mut ctx = QueryContext::new(sql, params.len());
-        if let Some(tag) = tag {
-            ctx.tag = Some(tag.to_string());
-        }
-
-        self.apply_hook(&mut ctx)?;
-
-        if self.config.monitoring_enabled {
-            self.monitor.on_query_start(&ctx);
-        }
+        let ctx = self.prepare_ctx(sql, params, tag)?;
 
         let start = Instant::now();
         let result = self
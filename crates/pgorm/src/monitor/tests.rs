@@ -154,6 +154,40 @@ async fn tagged_queries_propagate_to_monitor() {
     assert_eq!(capture.0.lock().unwrap().as_deref(), Some("test-tag"));
 }
 
+#[tokio::test]
+async fn debug_sql_is_rendered_when_configured() {
+    #[derive(Default)]
+    struct DebugCapture(std::sync::Mutex<Option<String>>);
+
+    impl QueryMonitor for DebugCapture {
+        fn on_query_complete(&self, ctx: &QueryContext, _: Duration, _: &QueryResult) {
+            *self.0.lock().unwrap() = ctx.debug_sql.clone();
+        }
+    }
+
+    let capture = Arc::new(DebugCapture::default());
+    let client = InstrumentedClient::new(DummyClient)
+        .with_config(
+            MonitorConfig::new()
+                .enable_monitoring()
+                .with_debug_sql(crate::RedactionPolicy::new().column("password")),
+        )
+        .with_monitor_arc(capture.clone());
+
+    client
+        .execute(
+            "UPDATE users SET password = $1 WHERE name = $2",
+            &[&"secret", &"O'Brien"],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        capture.0.lock().unwrap().as_deref(),
+        Some("UPDATE users SET password = '<redacted>' WHERE name = 'O''Brien'")
+    );
+}
+
 #[tokio::test]
 async fn timeout_returns_error_and_attempts_cancellation() {
     struct HangingClient;
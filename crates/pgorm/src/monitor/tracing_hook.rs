@@ -7,6 +7,9 @@ use tracing::Level;
 /// This logs **before** the query is executed (via [`QueryHook::before_query`]), so it works even
 /// when monitoring is disabled (e.g. `InstrumentedClient` without `enable_monitoring()`).
 ///
+/// When debug rendering is enabled (`MonitorConfig::with_debug_sql` /
+/// `PgClientConfig::debug_sql`), the SQL with inlined values is emitted as `debug_sql`.
+///
 /// Enable via the crate feature: `pgorm = { features = ["tracing"] }`.
 #[derive(Debug, Clone)]
 pub struct TracingSqlHook {
@@ -56,7 +59,13 @@ impl TracingSqlHook {
         }
     }
 
-    fn emit(&self, ctx: &QueryContext, exec_sql: &str, canonical_sql: Option<&str>) {
+    fn emit(
+        &self,
+        ctx: &QueryContext,
+        exec_sql: &str,
+        canonical_sql: Option<&str>,
+        debug_sql: Option<&str>,
+    ) {
         /// Dispatch a tracing event at a runtime-determined level.
         macro_rules! emit_at_level {
             ($level:expr, $($field:tt)*) => {
@@ -81,6 +90,7 @@ impl TracingSqlHook {
                 param_count = ctx.param_count,
                 sql = %exec_sql,
                 canonical_sql = %canonical_sql,
+                debug_sql,
                 fields = fields,
             ),
             None => emit_at_level!(
@@ -90,6 +100,7 @@ impl TracingSqlHook {
                 tag,
                 param_count = ctx.param_count,
                 sql = %exec_sql,
+                debug_sql,
                 fields = fields,
             ),
         }
@@ -101,7 +112,13 @@ impl QueryHook for TracingSqlHook {
         let exec_sql = self.truncate_sql(&ctx.exec_sql);
         let canonical_sql =
             (ctx.exec_sql != ctx.canonical_sql).then(|| self.truncate_sql(&ctx.canonical_sql));
-        self.emit(ctx, &exec_sql, canonical_sql.as_deref());
+        let debug_sql = ctx.debug_sql.as_deref().map(|s| self.truncate_sql(s));
+        self.emit(
+            ctx,
+            &exec_sql,
+            canonical_sql.as_deref(),
+            debug_sql.as_deref(),
+        );
         HookAction::Continue
     }
 }
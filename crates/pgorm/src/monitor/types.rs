@@ -112,6 +112,9 @@ pub struct QueryContext {
     pub tag: Option<String>,
     /// Optional structured fields for observability (low-cardinality).
     pub fields: BTreeMap<String, String>,
+    /// SQL with parameter values inlined, when debug rendering is enabled
+    /// (see [`MonitorConfig::with_debug_sql`](crate::MonitorConfig::with_debug_sql)).
+    pub debug_sql: Option<String>,
 }
 
 impl QueryContext {
@@ -124,6 +127,7 @@ impl QueryContext {
             query_type: QueryType::from_sql(sql),
             tag: None,
             fields: BTreeMap::new(),
+            debug_sql: None,
         }
     }
 
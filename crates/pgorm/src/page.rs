@@ -0,0 +1,610 @@
+//! Page result types.
+//!
+//! - [`Page`]: one page of rows plus opaque `next` / `prev` cursor tokens (keyset pagination)
+//! - [`CursorKey`]: the sort columns of a row type (implemented by `#[derive(Model)]`
+//!   with `#[orm(cursor = "created_at DESC, id DESC")]`)
+//! - [`CursorCodec`]: encodes and decodes cursor tokens (base64, optionally HMAC-signed)
+//! - [`Paginated`] / [`CountMode`]: offset pages with a total count fetched in the same
+//!   statement (`Sql::paginate_with_total`)
+//!
+//! # Example
+//! ```ignore
+//! use pgorm::{CursorCodec, KeysetN, sql};
+//!
+//! let codec = CursorCodec::signed(secret);
+//! let page = KeysetN::from_cursor::<Post>(&codec, params.cursor.as_deref())?
+//!     .limit(20)
+//!     .fetch_page::<Post>(&client, sql("SELECT * FROM posts"), &codec)
+//!     .await?;
+//! // page.items, page.next_cursor, page.prev_cursor, page.has_more
+//! ```
+
+use crate::builder::{NullsOrder, SortDir};
+use crate::error::{OrmError, OrmResult};
+use crate::row::FromRow;
+use crate::sql::Sql;
+#[cfg(feature = "cursor")]
+use base64::Engine as _;
+#[cfg(feature = "cursor")]
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+#[cfg(feature = "cursor")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "cursor")]
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+#[cfg(feature = "cursor")]
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio_postgres::Row;
+use tokio_postgres::types::ToSql;
+
+/// A JSON-encoded sort key value carried inside a cursor token.
+pub type CursorValue = serde_json::Value;
+
+/// One page of rows from keyset pagination.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// Rows in the keyset's sort order.
+    pub items: Vec<T>,
+    /// Token for the page after the last item, if there is one.
+    pub next_cursor: Option<String>,
+    /// Token for the page before the first item, if there is one.
+    pub prev_cursor: Option<String>,
+    /// Whether more rows exist in the direction this page was fetched.
+    pub has_more: bool,
+}
+
+/// A sort column of a [`CursorKey`] type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorColumn {
+    /// Column name (as used in `ORDER BY`).
+    pub name: &'static str,
+    pub dir: SortDir,
+    pub nulls: Option<NullsOrder>,
+}
+
+/// A row type that can be paginated with opaque cursors.
+///
+/// Implemented by `#[derive(Model)]` for `#[orm(cursor = "...")]`; the last column
+/// should be unique (usually the primary key) so the ordering is total.
+pub trait CursorKey: Sized {
+    /// Sort columns, in `ORDER BY` order.
+    const CURSOR_COLUMNS: &'static [CursorColumn];
+
+    /// This row's values for [`CURSOR_COLUMNS`](Self::CURSOR_COLUMNS).
+    fn cursor_values(&self) -> OrmResult<Vec<CursorValue>>;
+
+    /// Convert decoded cursor values back into typed bind parameters.
+    fn cursor_params(values: Vec<CursorValue>) -> OrmResult<Vec<Arc<dyn ToSql + Send + Sync>>>;
+}
+
+#[doc(hidden)]
+pub fn __cursor_value<T: Serialize>(value: &T) -> OrmResult<CursorValue> {
+    serde_json::to_value(value)
+        .map_err(|e| OrmError::Serialization(format!("failed to encode cursor value: {e}")))
+}
+
+#[doc(hidden)]
+pub fn __cursor_param<T>(value: Option<CursorValue>) -> OrmResult<Arc<dyn ToSql + Send + Sync>>
+where
+    T: DeserializeOwned + ToSql + Send + Sync + 'static,
+{
+    let value = value.ok_or_else(|| OrmError::validation("invalid cursor: missing value"))?;
+    let value: T = serde_json::from_value(value)
+        .map_err(|e| OrmError::validation(format!("invalid cursor value: {e}")))?;
+    Ok(Arc::new(value))
+}
+
+/// Which side of the encoded row a cursor token points to.
+#[cfg(feature = "cursor")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum CursorSide {
+    #[serde(rename = "a")]
+    After,
+    #[serde(rename = "b")]
+    Before,
+}
+
+#[cfg(feature = "cursor")]
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CursorPayload {
+    #[serde(rename = "d")]
+    pub(crate) side: CursorSide,
+    #[serde(rename = "c")]
+    pub(crate) columns: Vec<String>,
+    #[serde(rename = "v")]
+    pub(crate) values: Vec<CursorValue>,
+}
+
+/// Encodes and decodes opaque cursor tokens.
+///
+/// Tokens are URL-safe base64 JSON. With [`CursorCodec::signed`] they carry an
+/// HMAC-SHA256 signature, so clients cannot forge or tamper with cursor values.
+#[cfg(feature = "cursor")]
+#[derive(Clone, Default)]
+pub struct CursorCodec {
+    key: Option<Arc<[u8]>>,
+}
+
+#[cfg(feature = "cursor")]
+impl std::fmt::Debug for CursorCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorCodec")
+            .field("signed", &self.key.is_some())
+            .finish()
+    }
+}
+
+#[cfg(feature = "cursor")]
+impl CursorCodec {
+    /// Unsigned tokens (opaque, but not tamper-proof).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// HMAC-SHA256 signed tokens.
+    pub fn signed(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: Some(Arc::from(key.as_ref())),
+        }
+    }
+
+    fn mac(&self, key: &[u8], payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    pub(crate) fn encode(&self, payload: &CursorPayload) -> OrmResult<String> {
+        let json = serde_json::to_vec(payload)
+            .map_err(|e| OrmError::Serialization(format!("failed to encode cursor: {e}")))?;
+        let mut token = URL_SAFE_NO_PAD.encode(json);
+        if let Some(key) = &self.key {
+            let sig = self.mac(key, &token).finalize().into_bytes();
+            token.push('.');
+            token.push_str(&URL_SAFE_NO_PAD.encode(sig));
+        }
+        Ok(token)
+    }
+
+    pub(crate) fn decode(&self, token: &str) -> OrmResult<CursorPayload> {
+        let body = match (&self.key, token.split_once('.')) {
+            (Some(key), Some((body, sig))) => {
+                let sig = URL_SAFE_NO_PAD
+                    .decode(sig)
+                    .map_err(|_| OrmError::validation("invalid cursor"))?;
+                self.mac(key, body)
+                    .verify_slice(&sig)
+                    .map_err(|_| OrmError::validation("invalid cursor signature"))?;
+                body
+            }
+            (Some(_), None) => return Err(OrmError::validation("cursor is not signed")),
+            (None, Some(_)) => return Err(OrmError::validation("invalid cursor")),
+            (None, None) => token,
+        };
+        let json = URL_SAFE_NO_PAD
+            .decode(body)
+            .map_err(|_| OrmError::validation("invalid cursor"))?;
+        serde_json::from_slice(&json).map_err(|_| OrmError::validation("invalid cursor"))
+    }
+}
+
+#[cfg(feature = "cursor")]
+fn column_names<T: CursorKey>() -> Vec<String> {
+    T::CURSOR_COLUMNS
+        .iter()
+        .map(|c| c.name.to_string())
+        .collect()
+}
+
+/// Decode `token` and check it was issued for `T`'s sort columns.
+#[cfg(feature = "cursor")]
+pub(crate) fn decode_for<T: CursorKey>(
+    codec: &CursorCodec,
+    token: &str,
+) -> OrmResult<(CursorSide, Vec<Arc<dyn ToSql + Send + Sync>>)> {
+    let payload = codec.decode(token)?;
+    if payload.columns != column_names::<T>() {
+        return Err(OrmError::validation(format!(
+            "cursor columns {:?} do not match the sort columns {:?}",
+            payload.columns,
+            column_names::<T>()
+        )));
+    }
+    if payload.values.len() != T::CURSOR_COLUMNS.len() {
+        return Err(OrmError::validation("invalid cursor: value count mismatch"));
+    }
+    Ok((payload.side, T::cursor_params(payload.values)?))
+}
+
+#[cfg(feature = "cursor")]
+fn encode_for<T: CursorKey>(codec: &CursorCodec, side: CursorSide, row: &T) -> OrmResult<String> {
+    codec.encode(&CursorPayload {
+        side,
+        columns: column_names::<T>(),
+        values: row.cursor_values()?,
+    })
+}
+
+/// Build a [`Page`] from up to `limit + 1` fetched rows.
+///
+/// `side` is the cursor the rows were fetched with; `Before` pages were fetched in
+/// reverse order and are flipped back here.
+#[cfg(feature = "cursor")]
+pub(crate) fn build_page<T: CursorKey>(
+    mut rows: Vec<T>,
+    limit: usize,
+    side: Option<CursorSide>,
+    codec: &CursorCodec,
+) -> OrmResult<Page<T>> {
+    let has_more = rows.len() > limit;
+    rows.truncate(limit);
+
+    let backward = side == Some(CursorSide::Before);
+    if backward {
+        rows.reverse();
+    }
+
+    let (first, last) = match (rows.first(), rows.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => {
+            return Ok(Page {
+                items: rows,
+                next_cursor: None,
+                prev_cursor: None,
+                has_more,
+            });
+        }
+    };
+
+    let has_next = if backward { true } else { has_more };
+    let has_prev = if backward { has_more } else { side.is_some() };
+
+    let next_cursor = if has_next {
+        Some(encode_for(codec, CursorSide::After, last)?)
+    } else {
+        None
+    };
+    let prev_cursor = if has_prev {
+        Some(encode_for(codec, CursorSide::Before, first)?)
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: rows,
+        next_cursor,
+        prev_cursor,
+        has_more,
+    })
+}
+
+// ==================== Offset pages with totals ====================
+
+/// One page of an offset-paginated query, with the total row count.
+#[derive(Debug, Clone)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// Total matching rows (see [`CountMode`] for capped / estimated totals).
+    pub total: i64,
+    /// Page number, starting at 1.
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
+    /// `false` when `total` was capped or estimated rather than counted.
+    pub total_is_exact: bool,
+}
+
+/// How [`Paginated::total`] is computed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CountMode {
+    /// Exact `COUNT(*)` over all matching rows.
+    #[default]
+    Exact,
+    /// Count at most this many rows; larger results report the cap as `total`.
+    Capped(i64),
+    /// Count exactly up to `threshold` rows; beyond that, use the planner's row
+    /// estimate for `table` (`pg_class.reltuples`), which ignores filters.
+    Estimated { table: String, threshold: i64 },
+}
+
+/// Wrap `inner` so one statement returns the requested page plus the total:
+///
+/// ```text
+/// SELECT __pgorm_page.*, __pgorm_count.__pgorm_total, __pgorm_count.__pgorm_exact
+/// FROM (<count over (<inner>)>) AS __pgorm_count
+/// LEFT JOIN (SELECT *, true AS __pgorm_hit FROM (<inner> LIMIT $n OFFSET $m) AS __pgorm_rows)
+///     AS __pgorm_page ON true
+/// ```
+///
+/// The page applies LIMIT/OFFSET to `inner` itself, so its ORDER BY selects and orders
+/// the rows, and joining the single count row keeps that order. The count runs over its
+/// own copy of `inner`; [`CountMode::Capped`] and [`CountMode::Estimated`] read at most
+/// `cap + 1` rows of it. The LEFT JOIN keeps one row (with `__pgorm_hit` NULL) when the
+/// page is empty, so the total is still reported past the last page.
+pub(crate) fn paginated_sql(
+    inner: Sql,
+    page: i64,
+    per_page: i64,
+    count: &CountMode,
+) -> OrmResult<Sql> {
+    if page < 1 {
+        return Err(OrmError::validation(format!(
+            "page must be >= 1, got {page}"
+        )));
+    }
+    if per_page < 1 {
+        return Err(OrmError::validation(format!(
+            "per_page must be >= 1, got {per_page}"
+        )));
+    }
+
+    let mut q = Sql::new(
+        "SELECT __pgorm_page.*, __pgorm_count.__pgorm_total, __pgorm_count.__pgorm_exact FROM (",
+    );
+    match count {
+        CountMode::Exact => {
+            q.push("SELECT COUNT(*) AS __pgorm_total, true AS __pgorm_exact FROM (");
+            q.push_sql(inner.duplicate());
+            q.push(") AS __pgorm_c");
+        }
+        CountMode::Capped(cap) => {
+            if *cap < 1 {
+                return Err(OrmError::validation(format!(
+                    "count cap must be >= 1, got {cap}"
+                )));
+            }
+            q.push("SELECT LEAST(COUNT(*), ");
+            q.push_bind(*cap);
+            q.push(") AS __pgorm_total, COUNT(*) <= ");
+            q.push_bind(*cap);
+            q.push(" AS __pgorm_exact FROM (SELECT 1 FROM (");
+            q.push_sql(inner.duplicate());
+            q.push(") AS __pgorm_rows LIMIT ");
+            q.push_bind(cap.saturating_add(1));
+            q.push(") AS __pgorm_c");
+        }
+        CountMode::Estimated { table, threshold } => {
+            if *threshold < 0 {
+                return Err(OrmError::validation(format!(
+                    "count threshold must be >= 0, got {threshold}"
+                )));
+            }
+            q.push("SELECT CASE WHEN COUNT(*) > ");
+            q.push_bind(*threshold);
+            q.push(
+                " THEN GREATEST(COUNT(*), (SELECT reltuples::bigint FROM pg_class WHERE oid = to_regclass(",
+            );
+            q.push_bind(table.clone());
+            q.push("))) ELSE COUNT(*) END AS __pgorm_total, COUNT(*) <= ");
+            q.push_bind(*threshold);
+            q.push(" AS __pgorm_exact FROM (SELECT 1 FROM (");
+            q.push_sql(inner.duplicate());
+            q.push(") AS __pgorm_rows LIMIT ");
+            q.push_bind(threshold.saturating_add(1));
+            q.push(") AS __pgorm_c");
+        }
+    }
+    q.push(") AS __pgorm_count LEFT JOIN (SELECT *, true AS __pgorm_hit FROM (");
+    q.push_sql(inner);
+    q.push(" LIMIT ");
+    q.push_bind(per_page);
+    q.push(" OFFSET ");
+    q.push_bind((page - 1).saturating_mul(per_page));
+    q.push(") AS __pgorm_rows) AS __pgorm_page ON true");
+    Ok(q)
+}
+
+fn decode_column<T>(row: &Row, column: &str) -> OrmResult<T>
+where
+    T: for<'a> tokio_postgres::types::FromSql<'a>,
+{
+    row.try_get(column)
+        .map_err(|e| OrmError::decode(column, e.to_string()))
+}
+
+/// Decode the rows of a [`paginated_sql`] statement.
+pub(crate) fn paginated_from_rows<T: FromRow>(
+    rows: &[Row],
+    page: i64,
+    per_page: i64,
+) -> OrmResult<Paginated<T>> {
+    let (total, total_is_exact) = match rows.first() {
+        Some(row) => (
+            decode_column::<i64>(row, "__pgorm_total")?,
+            decode_column::<bool>(row, "__pgorm_exact")?,
+        ),
+        None => (0, true),
+    };
+
+    let mut items = Vec::with_capacity(rows.len());
+    for row in rows {
+        if decode_column::<Option<bool>>(row, "__pgorm_hit")?.is_some() {
+            items.push(T::from_row(row)?);
+        }
+    }
+
+    Ok(Paginated {
+        items,
+        total,
+        page,
+        per_page,
+        total_pages: (total + per_page - 1) / per_page,
+        total_is_exact,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "cursor")]
+    #[derive(Debug, PartialEq)]
+    struct Row {
+        created_at: i64,
+        id: i64,
+    }
+
+    #[cfg(feature = "cursor")]
+    impl CursorKey for Row {
+        const CURSOR_COLUMNS: &'static [CursorColumn] = &[
+            CursorColumn {
+                name: "created_at",
+                dir: SortDir::Desc,
+                nulls: None,
+            },
+            CursorColumn {
+                name: "id",
+                dir: SortDir::Desc,
+                nulls: None,
+            },
+        ];
+
+        fn cursor_values(&self) -> OrmResult<Vec<CursorValue>> {
+            Ok(vec![
+                __cursor_value(&self.created_at)?,
+                __cursor_value(&self.id)?,
+            ])
+        }
+
+        fn cursor_params(values: Vec<CursorValue>) -> OrmResult<Vec<Arc<dyn ToSql + Send + Sync>>> {
+            let mut values = values.into_iter();
+            Ok(vec![
+                __cursor_param::<i64>(values.next())?,
+                __cursor_param::<i64>(values.next())?,
+            ])
+        }
+    }
+
+    #[cfg(feature = "cursor")]
+    fn rows(ids: &[i64]) -> Vec<Row> {
+        ids.iter()
+            .map(|&id| Row {
+                created_at: id * 10,
+                id,
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "cursor")]
+    #[test]
+    fn signed_round_trip_and_tamper_detection() {
+        let codec = CursorCodec::signed("secret");
+        let token = encode_for(
+            &codec,
+            CursorSide::After,
+            &Row {
+                created_at: 1,
+                id: 2,
+            },
+        )
+        .unwrap();
+        let (side, params) = decode_for::<Row>(&codec, &token).unwrap();
+        assert_eq!(side, CursorSide::After);
+        assert_eq!(params.len(), 2);
+
+        assert!(decode_for::<Row>(&CursorCodec::signed("other"), &token).is_err());
+        assert!(decode_for::<Row>(&CursorCodec::new(), &token).is_err());
+        let (body, _) = token.split_once('.').unwrap();
+        assert!(decode_for::<Row>(&codec, body).is_err());
+    }
+
+    #[cfg(feature = "cursor")]
+    #[test]
+    fn rejects_foreign_columns() {
+        let codec = CursorCodec::new();
+        let token = codec
+            .encode(&CursorPayload {
+                side: CursorSide::After,
+                columns: vec!["name".into(), "id".into()],
+                values: vec![CursorValue::from("x"), CursorValue::from(1)],
+            })
+            .unwrap();
+        let err = decode_for::<Row>(&codec, &token).unwrap_err();
+        assert!(matches!(err, OrmError::Validation(_)));
+        assert!(decode_for::<Row>(&codec, "not a cursor").is_err());
+    }
+
+    #[cfg(feature = "cursor")]
+    #[test]
+    fn first_page_uses_extra_row_for_has_more() {
+        let codec = CursorCodec::new();
+        let page = build_page(rows(&[5, 4, 3]), 2, None, &codec).unwrap();
+        assert_eq!(page.items, rows(&[5, 4]));
+        assert!(page.has_more);
+        assert!(page.prev_cursor.is_none());
+
+        let next = codec.decode(page.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(next.side, CursorSide::After);
+        assert_eq!(
+            next.values,
+            vec![CursorValue::from(40), CursorValue::from(4)]
+        );
+
+        let last = build_page(rows(&[2, 1]), 2, Some(CursorSide::After), &codec).unwrap();
+        assert!(!last.has_more);
+        assert!(last.next_cursor.is_none());
+        assert!(last.prev_cursor.is_some());
+    }
+
+    #[test]
+    fn paginated_sql_wraps_inner_query() {
+        let mut inner = Sql::new("SELECT id FROM users WHERE status = ");
+        inner.push_bind("active").push(" ORDER BY id");
+        let q = paginated_sql(inner, 3, 20, &CountMode::Exact).unwrap();
+        assert_eq!(
+            q.to_sql(),
+            "SELECT __pgorm_page.*, __pgorm_count.__pgorm_total, __pgorm_count.__pgorm_exact \
+             FROM (SELECT COUNT(*) AS __pgorm_total, true AS __pgorm_exact \
+             FROM (SELECT id FROM users WHERE status = $1 ORDER BY id) AS __pgorm_c) AS __pgorm_count \
+             LEFT JOIN (SELECT *, true AS __pgorm_hit \
+             FROM (SELECT id FROM users WHERE status = $2 ORDER BY id LIMIT $3 OFFSET $4) AS __pgorm_rows) \
+             AS __pgorm_page ON true"
+        );
+        assert_eq!(q.params_ref().len(), 4);
+
+        assert!(paginated_sql(Sql::new("SELECT 1"), 0, 20, &CountMode::Exact).is_err());
+        assert!(paginated_sql(Sql::new("SELECT 1"), 1, 0, &CountMode::Exact).is_err());
+    }
+
+    #[test]
+    fn paginated_sql_capped_and_estimated_counts() {
+        let q = paginated_sql(Sql::new("SELECT 1"), 1, 10, &CountMode::Capped(1000)).unwrap();
+        assert!(q.to_sql().contains(
+            "(SELECT LEAST(COUNT(*), $1) AS __pgorm_total, COUNT(*) <= $2 AS __pgorm_exact \
+             FROM (SELECT 1 FROM (SELECT 1) AS __pgorm_rows LIMIT $3) AS __pgorm_c)"
+        ));
+
+        let mode = CountMode::Estimated {
+            table: "public.events".into(),
+            threshold: 10_000,
+        };
+        let q = paginated_sql(Sql::new("SELECT 1"), 1, 10, &mode).unwrap();
+        assert!(q.to_sql().contains(
+            "(SELECT CASE WHEN COUNT(*) > $1 THEN GREATEST(COUNT(*), \
+             (SELECT reltuples::bigint FROM pg_class WHERE oid = to_regclass($2))) \
+             ELSE COUNT(*) END AS __pgorm_total, COUNT(*) <= $3 AS __pgorm_exact \
+             FROM (SELECT 1 FROM (SELECT 1) AS __pgorm_rows LIMIT $4) AS __pgorm_c)"
+        ));
+        assert_eq!(q.params_ref().len(), 6);
+        assert!(paginated_sql(Sql::new("SELECT 1"), 1, 10, &CountMode::Capped(0)).is_err());
+    }
+
+    #[cfg(feature = "cursor")]
+    #[test]
+    fn before_page_is_reversed() {
+        let codec = CursorCodec::new();
+        // Fetched in reverse order: the rows just before the cursor come first.
+        let page = build_page(rows(&[6, 7, 8]), 2, Some(CursorSide::Before), &codec).unwrap();
+        assert_eq!(page.items, rows(&[7, 6]));
+        assert!(page.has_more);
+
+        let prev = codec.decode(page.prev_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(prev.side, CursorSide::Before);
+        assert_eq!(prev.values[1], CursorValue::from(7));
+        let next = codec.decode(page.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(next.side, CursorSide::After);
+        assert_eq!(next.values[1], CursorValue::from(6));
+    }
+}
@@ -2,6 +2,7 @@
 pub use crate::checked_client::CheckMode;
 
 use crate::error::OrmError;
+use crate::sql::RedactionPolicy;
 use std::time::Duration;
 
 /// Configuration for `PgClient`.
@@ -30,6 +31,8 @@ pub struct PgClientConfig {
     pub logging_enabled: bool,
     /// Minimum duration to log (filters out fast queries).
     pub log_min_duration: Option<Duration>,
+    /// Render SQL with inlined parameter values for logging/tracing.
+    pub debug_sql: Option<RedactionPolicy>,
 }
 
 /// Prepared statement cache configuration (per-connection).
@@ -51,6 +54,7 @@ impl Default for PgClientConfig {
             stats_enabled: true,
             logging_enabled: false,
             log_min_duration: None,
+            debug_sql: None,
         }
     }
 }
@@ -182,6 +186,12 @@ impl PgClientConfig {
         self.log_min_duration = Some(min_duration);
         self
     }
+
+    /// Log/trace SQL with parameter values inlined, hiding values selected by `policy`.
+    pub fn debug_sql(mut self, policy: RedactionPolicy) -> Self {
+        self.debug_sql = Some(policy);
+        self
+    }
 }
 
 /// Policy for runtime SQL safety rules.
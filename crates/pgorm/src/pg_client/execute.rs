@@ -18,11 +18,29 @@ impl<C: GenericClient> super::PgClient<C> {
 
     #[cfg(feature = "tracing")]
     pub(super) fn emit_tracing_sql(&self, ctx: &QueryContext) {
+        use crate::monitor::QueryHook;
+
         if let Some(hook) = &self.tracing_sql_hook {
             let _ = hook.before_query(ctx);
         }
     }
 
+    pub(super) fn new_ctx(
+        &self,
+        tag: Option<&str>,
+        sql: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> QueryContext {
+        let mut ctx = QueryContext::new(sql, params.len());
+        if let Some(tag) = tag {
+            ctx.tag = Some(tag.to_string());
+        }
+        if let Some(policy) = &self.config.debug_sql {
+            ctx.debug_sql = Some(crate::sql::render_debug_sql(sql, params, policy));
+        }
+        ctx
+    }
+
     pub(super) fn apply_sql_policy(&self, ctx: &mut QueryContext) -> OrmResult<()> {
         use crate::check::StatementKind;
 
@@ -516,12 +534,9 @@ impl<C: GenericClient> super::PgClient<C> {
         &self,
         tag: Option<&str>,
         sql: &str,
-        param_count: usize,
+        params: &[&(dyn ToSql + Sync)],
     ) -> OrmResult<(QueryContext, StmtCacheProbe)> {
-        let mut ctx = QueryContext::new(sql, param_count);
-        if let Some(tag) = tag {
-            ctx.tag = Some(tag.to_string());
-        }
+        let mut ctx = self.new_ctx(tag, sql, params);
         self.apply_hook(&mut ctx)?;
         self.apply_sql_policy(&mut ctx)?;
         self.check_sql(&ctx.canonical_sql)?;
@@ -537,7 +552,7 @@ impl<C: GenericClient> super::PgClient<C> {
         sql: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> OrmResult<Vec<Row>> {
-        let (ctx, probe) = self.prepare_ctx(tag, sql, params.len())?;
+        let (ctx, probe) = self.prepare_ctx(tag, sql, params)?;
         let start = Instant::now();
         let result = stmt_cache_dispatch!(self, ctx, params, probe, query, query_prepared);
         let duration = start.elapsed();
@@ -557,7 +572,7 @@ impl<C: GenericClient> super::PgClient<C> {
         sql: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> OrmResult<Row> {
-        let (ctx, probe) = self.prepare_ctx(tag, sql, params.len())?;
+        let (ctx, probe) = self.prepare_ctx(tag, sql, params)?;
         let start = Instant::now();
         let result = stmt_cache_dispatch!(self, ctx, params, probe, query_one, query_one_prepared);
         let duration = start.elapsed();
@@ -578,7 +593,7 @@ impl<C: GenericClient> super::PgClient<C> {
         sql: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> OrmResult<Option<Row>> {
-        let (ctx, probe) = self.prepare_ctx(tag, sql, params.len())?;
+        let (ctx, probe) = self.prepare_ctx(tag, sql, params)?;
         let start = Instant::now();
         let result = stmt_cache_dispatch!(self, ctx, params, probe, query_opt, query_opt_prepared);
         let duration = start.elapsed();
@@ -599,7 +614,7 @@ impl<C: GenericClient> super::PgClient<C> {
         sql: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> OrmResult<u64> {
-        let (ctx, probe) = self.prepare_ctx(tag, sql, params.len())?;
+        let (ctx, probe) = self.prepare_ctx(tag, sql, params)?;
         let start = Instant::now();
         let result = stmt_cache_dispatch!(self, ctx, params, probe, execute, execute_prepared);
         let duration = start.elapsed();
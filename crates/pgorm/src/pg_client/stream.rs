@@ -181,10 +181,7 @@ where
         sql: &str,
         params: &[&(dyn ToSql + Sync)],
     ) -> OrmResult<RowStream> {
-        let mut ctx = QueryContext::new(sql, params.len());
-        if let Some(tag) = tag {
-            ctx.tag = Some(tag.to_string());
-        }
+        let mut ctx = self.new_ctx(tag, sql, params);
 
         // Process hook first, then check the canonical SQL.
         self.apply_hook(&mut ctx)?;
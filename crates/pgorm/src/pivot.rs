@@ -0,0 +1,330 @@
+//! Join-table (pivot) writes for `many_to_many` relations.
+//!
+//! `#[derive(Model)]` generates `attach_<rel>`, `detach_<rel>` and `sync_<rel>` on top of
+//! these helpers. Extra join-table columns are passed as [`SetExpr::set`] values:
+//!
+//! ```ignore
+//! post.attach_tags(&client, 7, [SetExpr::set("position", 1)?]).await?;
+//! post.detach_tags(&client, vec![3, 4]).await?;
+//!
+//! // Keep exactly tags 7 and 9: insert the missing ones, rewrite their pivot
+//! // columns and delete every other row of this post.
+//! let report = post
+//!     .sync_tags(&client, vec![
+//!         (7, vec![SetExpr::set("position", 1)?]),
+//!         (9, vec![SetExpr::set("position", 2)?]),
+//!     ])
+//!     .await?;
+//! ```
+//!
+//! Every helper is a single statement (`sync` uses data-modifying CTEs), so it is
+//! atomic on its own and becomes part of the caller's transaction when given one.
+//! `attach` and `sync` rely on `ON CONFLICT (self_key, other_key)`, so the join
+//! table needs a unique constraint (usually its primary key) on those two columns.
+
+use crate::{GenericClient, Ident, OrmError, OrmResult, RowExt, SetExpr, Sql, sql};
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio_postgres::types::ToSql;
+
+type Param = Arc<dyn ToSql + Send + Sync>;
+
+/// What [`sync`] changed in the join table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Rows inserted for ids that were not linked yet.
+    pub attached: u64,
+    /// Existing rows whose pivot columns were rewritten.
+    pub updated: u64,
+    /// Rows deleted because their id was not in the list.
+    pub detached: u64,
+}
+
+/// Link `other_id` to `self_id`, inserting `pivot` into the extra join-table columns.
+///
+/// If the pair is already linked, its pivot columns are updated (nothing happens
+/// when `pivot` is empty). Returns the number of affected rows.
+pub async fn attach<S, O>(
+    conn: &impl GenericClient,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    self_id: S,
+    other_id: O,
+    pivot: Vec<SetExpr>,
+) -> OrmResult<u64>
+where
+    S: ToSql + Send + Sync + 'static,
+    O: ToSql + Send + Sync + 'static,
+{
+    let self_id: Param = Arc::new(self_id);
+    let row = PivotRow::new(Arc::new(other_id), pivot)?;
+    let mut q = Sql::empty();
+    push_upsert(
+        &mut q,
+        join_table,
+        self_key,
+        other_key,
+        &self_id,
+        &row.columns(),
+        &[row],
+    )?;
+    q.execute(conn).await
+}
+
+/// Unlink `other_ids` from `self_id`. Returns the number of deleted rows.
+pub async fn detach<S, O>(
+    conn: &impl GenericClient,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    self_id: S,
+    other_ids: Vec<O>,
+) -> OrmResult<u64>
+where
+    S: ToSql + Send + Sync + 'static,
+    O: ToSql + Send + Sync + 'static,
+{
+    if other_ids.is_empty() {
+        return Ok(0);
+    }
+    let mut q = sql("DELETE FROM ");
+    q.push_ident(join_table)?;
+    q.push(" WHERE ");
+    q.push_ident(self_key)?;
+    q.push(" = ");
+    q.push_bind(self_id);
+    q.push(" AND ");
+    q.push_ident(other_key)?;
+    q.push(" = ANY(");
+    q.push_bind(other_ids);
+    q.push(")");
+    q.execute(conn).await
+}
+
+/// Make `rows` the complete set of links of `self_id`, in one statement.
+///
+/// Missing ids are inserted, linked ids get their pivot columns rewritten and
+/// all other rows of `self_id` are deleted. Rows may set different pivot
+/// columns; an empty list detaches everything. Repeated ids are rejected.
+pub async fn sync<S, O>(
+    conn: &impl GenericClient,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    self_id: S,
+    rows: Vec<(O, Vec<SetExpr>)>,
+) -> OrmResult<SyncReport>
+where
+    S: ToSql + Send + Sync + 'static,
+    O: ToSql + Clone + Eq + Hash + Send + Sync + 'static,
+{
+    let mut seen = HashSet::with_capacity(rows.len());
+    if !rows.iter().all(|(id, _)| seen.insert(id)) {
+        return Err(OrmError::validation(
+            "sync: the same related id is listed more than once",
+        ));
+    }
+
+    let self_id: Param = Arc::new(self_id);
+    let other_ids: Vec<O> = rows.iter().map(|(id, _)| id.clone()).collect();
+
+    // Rows setting the same pivot columns share one INSERT.
+    let mut groups: Vec<(Vec<Ident>, Vec<PivotRow>)> = Vec::new();
+    for (id, pivot) in rows {
+        let row = PivotRow::new(Arc::new(id), pivot)?;
+        let columns = row.columns();
+        match groups.iter_mut().find(|(c, _)| *c == columns) {
+            Some((_, group)) => group.push(row),
+            None => groups.push((columns, vec![row])),
+        }
+    }
+
+    let mut q = sql("WITH __pgorm_detached AS (DELETE FROM ");
+    q.push_ident(join_table)?;
+    q.push(" WHERE ");
+    q.push_ident(self_key)?;
+    q.push(" = ");
+    q.push_bind_value(self_id.clone());
+    q.push(" AND NOT (");
+    q.push_ident(other_key)?;
+    q.push(" = ANY(");
+    q.push_bind(other_ids);
+    q.push(")) RETURNING 1)");
+    for (i, (columns, rows)) in groups.iter().enumerate() {
+        q.push(&format!(", __pgorm_upsert_{i} AS ("));
+        push_upsert(
+            &mut q, join_table, self_key, other_key, &self_id, columns, rows,
+        )?;
+        q.push(" RETURNING (xmax = 0) AS inserted)");
+    }
+
+    q.push(
+        " SELECT (SELECT COUNT(*) FROM __pgorm_detached) AS detached, \
+         COUNT(*) FILTER (WHERE inserted) AS attached, \
+         COUNT(*) FILTER (WHERE NOT inserted) AS updated FROM (",
+    );
+    if groups.is_empty() {
+        q.push("SELECT TRUE AS inserted WHERE FALSE");
+    }
+    for i in 0..groups.len() {
+        if i > 0 {
+            q.push(" UNION ALL ");
+        }
+        q.push(&format!("SELECT inserted FROM __pgorm_upsert_{i}"));
+    }
+    q.push(") AS __pgorm_upserted");
+
+    let row = q.fetch_one(conn).await?;
+    let count = |column: &str| -> OrmResult<u64> {
+        let n: i64 = row.try_get_column(column)?;
+        Ok(n as u64)
+    };
+    Ok(SyncReport {
+        attached: count("attached")?,
+        updated: count("updated")?,
+        detached: count("detached")?,
+    })
+}
+
+/// One related id with its pivot values.
+struct PivotRow {
+    other_id: Param,
+    values: Vec<(Ident, Param)>,
+}
+
+impl PivotRow {
+    fn new(other_id: Param, pivot: Vec<SetExpr>) -> OrmResult<Self> {
+        let values = pivot
+            .into_iter()
+            .map(|set| match set {
+                SetExpr::Value { column, value } => Ok((column, value)),
+                _ => Err(OrmError::validation(
+                    "pivot values must be SetExpr::set(column, value)",
+                )),
+            })
+            .collect::<OrmResult<Vec<_>>>()?;
+        Ok(Self { other_id, values })
+    }
+
+    fn columns(&self) -> Vec<Ident> {
+        self.values
+            .iter()
+            .map(|(column, _)| column.clone())
+            .collect()
+    }
+}
+
+/// `INSERT INTO <join_table> (self_key, other_key, columns...) VALUES ... ON CONFLICT ...`.
+fn push_upsert(
+    q: &mut Sql,
+    join_table: &'static str,
+    self_key: &'static str,
+    other_key: &'static str,
+    self_id: &Param,
+    columns: &[Ident],
+    rows: &[PivotRow],
+) -> OrmResult<()> {
+    q.push("INSERT INTO ");
+    q.push_ident(join_table)?;
+    q.push(" (");
+    q.push_ident(self_key)?;
+    q.push(", ");
+    q.push_ident(other_key)?;
+    for column in columns {
+        q.push(", ");
+        q.push_ident_ref(column);
+    }
+    q.push(") VALUES ");
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            q.push(", ");
+        }
+        q.push("(");
+        q.push_bind_value(self_id.clone());
+        q.push(", ");
+        q.push_bind_value(row.other_id.clone());
+        for (_, value) in &row.values {
+            q.push(", ");
+            q.push_bind_value(value.clone());
+        }
+        q.push(")");
+    }
+
+    q.push(" ON CONFLICT (");
+    q.push_ident(self_key)?;
+    q.push(", ");
+    q.push_ident(other_key)?;
+    q.push(")");
+    if columns.is_empty() {
+        q.push(" DO NOTHING");
+        return Ok(());
+    }
+    q.push(" DO UPDATE SET ");
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            q.push(", ");
+        }
+        q.push_ident_ref(column);
+        q.push(" = EXCLUDED.");
+        q.push_ident_ref(column);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsert_updates_only_given_pivot_columns() {
+        let self_id: Param = Arc::new(1_i64);
+        let rows = vec![
+            PivotRow::new(Arc::new(7_i64), vec![SetExpr::set("position", 1).unwrap()]).unwrap(),
+            PivotRow::new(Arc::new(9_i64), vec![SetExpr::set("position", 2).unwrap()]).unwrap(),
+        ];
+        let mut q = Sql::empty();
+        push_upsert(
+            &mut q,
+            "post_tags",
+            "post_id",
+            "tag_id",
+            &self_id,
+            &rows[0].columns(),
+            &rows,
+        )
+        .unwrap();
+        assert_eq!(
+            q.to_sql(),
+            "INSERT INTO post_tags (post_id, tag_id, position) VALUES ($1, $2, $3), ($4, $5, $6) \
+             ON CONFLICT (post_id, tag_id) DO UPDATE SET position = EXCLUDED.position"
+        );
+
+        let row = PivotRow::new(Arc::new(7_i64), vec![]).unwrap();
+        let mut q = Sql::empty();
+        push_upsert(
+            &mut q,
+            "post_tags",
+            "post_id",
+            "tag_id",
+            &self_id,
+            &[],
+            &[row],
+        )
+        .unwrap();
+        assert!(
+            q.to_sql()
+                .ends_with("VALUES ($1, $2) ON CONFLICT (post_id, tag_id) DO NOTHING")
+        );
+    }
+
+    #[test]
+    fn pivot_values_must_be_plain_sets() {
+        let err = PivotRow::new(
+            Arc::new(7_i64),
+            vec![SetExpr::increment("position", 1).unwrap()],
+        );
+        assert!(matches!(err, Err(OrmError::Validation(_))));
+    }
+}
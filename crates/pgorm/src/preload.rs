@@ -0,0 +1,1143 @@
+//! Nested relation preloading on top of the [`eager`](crate::eager) loaders.
+//!
+//! `#[derive(Model)]` generates one descriptor per relation (`User::posts()`,
+//! `Post::comments()`, `Comment::author()`, ...). Descriptors are chained with
+//! `then` and handed to the query builder:
+//!
+//! ```ignore
+//! let users = User::query()
+//!     .eq("active", true)?
+//!     .preload(
+//!         User::posts()
+//!             .filter(|q| { q.push("posts.published = "); q.push_bind(true); })
+//!             .order_by(|q| { q.push("posts.created_at DESC"); })
+//!             .then(Post::comments().then(Comment::author())),
+//!     )
+//!     .find(&client)
+//!     .await?;
+//!
+//! // Vec<Loaded<User, Vec<Loaded<Post, Vec<Loaded<Comment, Option<User>>>>>>>
+//! for user in &users {
+//!     for post in &user.rel {
+//!         for comment in &post.rel {
+//!             let _author: Option<&User> = comment.rel.as_ref();
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Each level runs exactly one query for all rows of the level above it, so the
+//! example executes four queries no matter how many users, posts or comments
+//! are loaded.
+//!
+//! `filter` closures are appended as `AND (...)` after the relation's own
+//! `WHERE`; the `order_by` closure is appended after `ORDER BY`. Both write
+//! into the preload [`Sql`] and may bind parameters.
+//!
+//! A row reachable from several parents (a tag shared by two posts, an author
+//! of several comments) is loaded once and cloned into every parent, so related
+//! types must be `Clone`.
+//!
+//! # Strategies
+//!
+//! [`Strategy::Batched`] (the default) runs the `= ANY($1)` loaders above.
+//! [`Strategy::JsonAgg`] embeds the first level in the parent query as a
+//! correlated `json_agg(jsonb_build_object(...))` subquery, so parents and
+//! children arrive in one round-trip; children are decoded with serde:
+//!
+//! ```ignore
+//! let users = User::query()
+//!     .preload(User::posts().strategy(Strategy::JsonAgg))
+//!     .find(&client)
+//!     .await?;
+//! // SELECT users.id, ..., (SELECT COALESCE(json_agg(jsonb_build_object('id', posts.id, ...)), '[]'::json)
+//! //   FROM posts WHERE posts.user_id = users.id) AS __pgorm_json FROM users
+//! ```
+//!
+//! Only a level preloaded directly from a model query can be embedded; a
+//! `JsonAgg` level nested under another level (or passed to [`attach`]) is
+//! loaded batched.
+//!
+//! # Counts
+//!
+//! [`PreloadMany::with_count`] turns a has_many / many_to_many level into a
+//! per-parent `i64` computed with one `GROUP BY` query, without loading rows:
+//!
+//! ```ignore
+//! // Vec<Loaded<User, i64>>
+//! let users = User::query().preload(User::posts().with_count()).find(&client).await?;
+//! ```
+
+use crate::eager::{self, Aggregate, AggregateSource, HasManyMap, Loaded};
+use crate::{FromRow, GenericClient, ModelPk, OrmError, OrmResult, RowExt, Sql, sql};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use tokio_postgres::types::{FromSqlOwned, ToSql};
+
+/// Alias of the embedded JSON column ([`Strategy::JsonAgg`]).
+pub const EMBEDDED_ALIAS: &str = "__pgorm_json";
+
+type SqlFn = Box<dyn FnOnce(&mut Sql) + Send>;
+type DecodeJson<T> = fn(Value) -> serde_json::Result<T>;
+
+/// How a preload level fetches its rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// One `WHERE fk = ANY($1)` query per level, rows decoded with `FromRow`.
+    #[default]
+    Batched,
+    /// A correlated `json_agg` / `jsonb_build_object` subquery in the parent
+    /// query, decoded with serde. Falls back to `Batched` when the level is
+    /// not preloaded directly from a model query.
+    JsonAgg,
+}
+
+/// A relation that can be batch loaded for a slice of parents.
+pub trait Preload<P> {
+    /// What gets attached to each parent (`Vec<_>` or `Option<_>`).
+    type Output;
+
+    /// Load the relation for `parents`; the result is aligned with `parents`.
+    fn load(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+    ) -> impl Future<Output = OrmResult<Vec<Self::Output>>> + Send;
+
+    /// Take the subquery that embeds this level in the parent query.
+    ///
+    /// `Some` only for [`Strategy::JsonAgg`]. `parent_table` / `parent_pk` name the
+    /// parent query's main table and its single primary key column.
+    fn take_embedded(
+        &mut self,
+        parent_table: &'static str,
+        parent_pk: Option<&'static str>,
+    ) -> OrmResult<Option<Sql>> {
+        let _ = (parent_table, parent_pk);
+        Ok(None)
+    }
+
+    /// Finish loading from the embedded JSON values, one per parent.
+    fn load_embedded(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+        values: Vec<Value>,
+    ) -> impl Future<Output = OrmResult<Vec<Self::Output>>> + Send
+    where
+        Self: Sized,
+    {
+        let _ = values;
+        self.load(conn, parents)
+    }
+}
+
+/// The level below a relation: either nothing ([`NoNext`]) or another relation ([`Then`]).
+pub trait PreloadNext<C> {
+    /// What a loaded child becomes (`C` itself, or `Loaded<C, _>`).
+    type Item;
+
+    /// Load the next level for `items`, keeping their order.
+    fn attach(
+        self,
+        conn: &impl GenericClient,
+        items: Vec<C>,
+    ) -> impl Future<Output = OrmResult<Vec<Self::Item>>> + Send;
+}
+
+/// Marker for a relation without a nested level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoNext;
+
+impl<C: Send> PreloadNext<C> for NoNext {
+    type Item = C;
+
+    async fn attach(self, _conn: &impl GenericClient, items: Vec<C>) -> OrmResult<Vec<C>> {
+        Ok(items)
+    }
+}
+
+/// A nested relation, created by `then`.
+#[derive(Debug, Clone)]
+pub struct Then<T>(T);
+
+impl<C, T> PreloadNext<C> for Then<T>
+where
+    C: Send + Sync,
+    T: Preload<C> + Send,
+    T::Output: Send,
+{
+    type Item = Loaded<C, T::Output>;
+
+    async fn attach(
+        self,
+        conn: &impl GenericClient,
+        items: Vec<C>,
+    ) -> OrmResult<Vec<Loaded<C, T::Output>>> {
+        let rels = self.0.load(conn, &items).await?;
+        Ok(items
+            .into_iter()
+            .zip(rels)
+            .map(|(base, rel)| Loaded { base, rel })
+            .collect())
+    }
+}
+
+/// Filter / ordering closures of one preload level.
+#[derive(Default)]
+struct Clauses {
+    filters: Vec<SqlFn>,
+    order_by: Option<SqlFn>,
+}
+
+impl Clauses {
+    fn apply(self, q: &mut Sql) {
+        Self::push_filters(self.filters, q);
+        Self::push_order_by(self.order_by, q);
+    }
+
+    fn push_filters(filters: Vec<SqlFn>, q: &mut Sql) {
+        for filter in filters {
+            q.push(" AND (");
+            filter(q);
+            q.push(")");
+        }
+    }
+
+    fn push_order_by(order_by: Option<SqlFn>, q: &mut Sql) {
+        if let Some(order_by) = order_by {
+            q.push(" ORDER BY ");
+            order_by(q);
+        }
+    }
+}
+
+impl std::fmt::Debug for Clauses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clauses")
+            .field("filters", &self.filters.len())
+            .field("order_by", &self.order_by.is_some())
+            .finish()
+    }
+}
+
+macro_rules! impl_level_builders {
+    ($ty:ident) => {
+        impl<P, C, N> $ty<P, C, N> {
+            /// Add a condition, appended as `AND (...)` to this level's query.
+            pub fn filter(mut self, f: impl FnOnce(&mut Sql) + Send + 'static) -> Self {
+                self.clauses.filters.push(Box::new(f));
+                self
+            }
+
+            /// Set this level's ordering, appended after `ORDER BY`.
+            pub fn order_by(mut self, f: impl FnOnce(&mut Sql) + Send + 'static) -> Self {
+                self.clauses.order_by = Some(Box::new(f));
+                self
+            }
+        }
+
+        impl<P, C: DeserializeOwned, N> $ty<P, C, N> {
+            /// Choose how this level is loaded (see [`Strategy`]).
+            pub fn strategy(mut self, strategy: Strategy) -> Self {
+                self.json = match strategy {
+                    Strategy::Batched => None,
+                    Strategy::JsonAgg => Some(serde_json::from_value),
+                };
+                self
+            }
+        }
+
+        impl<P, C> $ty<P, C, NoNext> {
+            /// Preload `next` for the rows loaded by this level.
+            pub fn then<T: Preload<C>>(self, next: T) -> $ty<P, C, Then<T>> {
+                $ty {
+                    source: self.source,
+                    json: self.json,
+                    clauses: self.clauses,
+                    next: Then(next),
+                    _marker: PhantomData,
+                }
+            }
+        }
+    };
+}
+
+#[derive(Debug)]
+enum ManySource {
+    HasMany {
+        select_list: &'static str,
+        table: &'static str,
+        join_clause: &'static str,
+        fk_col: &'static str,
+        json_object: &'static str,
+    },
+    ManyToMany {
+        select_list: String,
+        table: &'static str,
+        join_clause: &'static str,
+        through: &'static str,
+        self_key: &'static str,
+        other_key: &'static str,
+        child_pk_col: &'static str,
+        json_object: &'static str,
+    },
+}
+
+/// A has_many or many_to_many relation of `P`; attaches `Vec<_>` to each parent.
+#[derive(Debug)]
+pub struct PreloadMany<P, C, N = NoNext> {
+    source: ManySource,
+    json: Option<DecodeJson<Vec<C>>>,
+    clauses: Clauses,
+    next: N,
+    _marker: PhantomData<fn() -> (P, C)>,
+}
+
+impl<P, C> PreloadMany<P, C> {
+    /// Children whose `fk_col` references the parent's primary key.
+    pub fn has_many(
+        select_list: &'static str,
+        table: &'static str,
+        join_clause: &'static str,
+        fk_col: &'static str,
+        json_object: &'static str,
+    ) -> Self {
+        Self {
+            source: ManySource::HasMany {
+                select_list,
+                table,
+                join_clause,
+                fk_col,
+                json_object,
+            },
+            json: None,
+            clauses: Clauses::default(),
+            next: NoNext,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Rows linked to the parent through the join table `through`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn many_to_many(
+        select_list: String,
+        table: &'static str,
+        join_clause: &'static str,
+        through: &'static str,
+        self_key: &'static str,
+        other_key: &'static str,
+        child_pk_col: &'static str,
+        json_object: &'static str,
+    ) -> Self {
+        Self {
+            source: ManySource::ManyToMany {
+                select_list,
+                table,
+                join_clause,
+                through,
+                self_key,
+                other_key,
+                child_pk_col,
+                json_object,
+            },
+            json: None,
+            clauses: Clauses::default(),
+            next: NoNext,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl_level_builders!(PreloadMany);
+
+impl<P, C, N> Preload<P> for PreloadMany<P, C, N>
+where
+    P: ModelPk + Sync,
+    P::Id: ToSql + FromSqlOwned + Eq + Hash,
+    C: FromRow + Send + Sync,
+    N: PreloadNext<C> + Send,
+    N::Item: Clone + Send,
+{
+    type Output = Vec<N::Item>;
+
+    async fn load(self, conn: &impl GenericClient, parents: &[P]) -> OrmResult<Vec<Vec<N::Item>>> {
+        let parent_ids: Vec<P::Id> = parents.iter().map(|p| p.pk().clone()).collect();
+        let clauses = self.clauses;
+        let with = move |q: &mut Sql| clauses.apply(q);
+
+        let map: HasManyMap<P::Id, C> = match self.source {
+            ManySource::HasMany {
+                select_list,
+                table,
+                join_clause,
+                fk_col,
+                ..
+            } => {
+                eager::load_has_many_map_with(
+                    conn,
+                    parent_ids,
+                    select_list,
+                    table,
+                    join_clause,
+                    fk_col,
+                    with,
+                )
+                .await?
+            }
+            ManySource::ManyToMany {
+                select_list,
+                table,
+                join_clause,
+                through,
+                self_key,
+                other_key,
+                child_pk_col,
+                ..
+            } => {
+                eager::load_many_to_many_map_with(
+                    conn,
+                    parent_ids,
+                    &select_list,
+                    table,
+                    join_clause,
+                    through,
+                    self_key,
+                    other_key,
+                    child_pk_col,
+                    with,
+                )
+                .await?
+            }
+        };
+
+        // Load the next level once for all children, then regroup.
+        let (keys, groups): (Vec<P::Id>, Vec<Vec<C>>) = map.into_iter().unzip();
+        let groups = attach_groups(conn, self.next, groups).await?;
+        let map: HashMap<P::Id, Vec<N::Item>> = keys.into_iter().zip(groups).collect();
+
+        Ok(distribute(parents.iter().map(|p| Some(p.pk())), map)
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect())
+    }
+
+    fn take_embedded(
+        &mut self,
+        parent_table: &'static str,
+        parent_pk: Option<&'static str>,
+    ) -> OrmResult<Option<Sql>> {
+        if self.json.is_none() {
+            return Ok(None);
+        }
+        let parent_pk = require_parent_pk(parent_pk)?;
+        let Clauses { filters, order_by } = std::mem::take(&mut self.clauses);
+
+        let mut q = sql("SELECT COALESCE(json_agg(");
+        match &self.source {
+            ManySource::HasMany {
+                table,
+                join_clause,
+                fk_col,
+                json_object,
+                ..
+            } => {
+                check_not_self_referential(table, parent_table)?;
+                q.push(json_object);
+                Clauses::push_order_by(order_by, &mut q);
+                q.push("), '[]'::json) FROM ");
+                q.push_ident(*table)?;
+                push_join_clause(&mut q, join_clause);
+                q.push(" WHERE ");
+                q.push_ident(*table)?;
+                q.push(".");
+                q.push_ident(*fk_col)?;
+            }
+            ManySource::ManyToMany {
+                table,
+                join_clause,
+                through,
+                self_key,
+                other_key,
+                child_pk_col,
+                json_object,
+                ..
+            } => {
+                check_not_self_referential(table, parent_table)?;
+                q.push(json_object);
+                Clauses::push_order_by(order_by, &mut q);
+                q.push("), '[]'::json) FROM ");
+                q.push_ident(*table)?;
+                q.push(" JOIN ");
+                q.push_ident(*through)?;
+                q.push(" jt ON jt.");
+                q.push_ident(*other_key)?;
+                q.push(" = ");
+                q.push_ident(*table)?;
+                q.push(".");
+                q.push_ident(*child_pk_col)?;
+                push_join_clause(&mut q, join_clause);
+                q.push(" WHERE jt.");
+                q.push_ident(*self_key)?;
+            }
+        }
+        q.push(" = ");
+        q.push_ident(parent_table)?;
+        q.push(".");
+        q.push_ident(parent_pk)?;
+        Clauses::push_filters(filters, &mut q);
+        Ok(Some(q))
+    }
+
+    async fn load_embedded(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+        values: Vec<Value>,
+    ) -> OrmResult<Vec<Vec<N::Item>>> {
+        let Some(decode) = self.json else {
+            return self.load(conn, parents).await;
+        };
+        let groups = values
+            .into_iter()
+            .map(|value| decode(value).map_err(decode_error))
+            .collect::<OrmResult<Vec<Vec<C>>>>()?;
+        attach_groups(conn, self.next, groups).await
+    }
+}
+
+impl<P, C> PreloadMany<P, C, NoNext> {
+    /// Load only the number of related rows per parent, as an `i64`.
+    ///
+    /// Runs one `GROUP BY` query; level filters still apply, ordering and the
+    /// strategy are ignored.
+    pub fn with_count(self) -> PreloadCount<P> {
+        let source = match self.source {
+            ManySource::HasMany { table, fk_col, .. } => AggregateSource::HasMany { table, fk_col },
+            ManySource::ManyToMany {
+                table,
+                through,
+                self_key,
+                other_key,
+                child_pk_col,
+                ..
+            } => AggregateSource::ManyToMany {
+                child_table: table,
+                join_table: through,
+                self_key,
+                other_key,
+                child_pk_col,
+            },
+        };
+        PreloadCount {
+            source,
+            filters: self.clauses.filters,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The number of related rows per parent, created by [`PreloadMany::with_count`].
+pub struct PreloadCount<P> {
+    source: AggregateSource,
+    filters: Vec<SqlFn>,
+    _marker: PhantomData<fn() -> P>,
+}
+
+impl<P> std::fmt::Debug for PreloadCount<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PreloadCount")
+            .field("source", &self.source)
+            .field("filters", &self.filters.len())
+            .finish()
+    }
+}
+
+impl<P> PreloadCount<P> {
+    /// Add a condition, appended as `AND (...)` to the counting query.
+    pub fn filter(mut self, f: impl FnOnce(&mut Sql) + Send + 'static) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+}
+
+impl<P> Preload<P> for PreloadCount<P>
+where
+    P: ModelPk + Sync,
+    P::Id: ToSql + FromSqlOwned + Eq + Hash,
+{
+    type Output = i64;
+
+    async fn load(self, conn: &impl GenericClient, parents: &[P]) -> OrmResult<Vec<i64>> {
+        let parent_ids: Vec<P::Id> = parents.iter().map(|p| p.pk().clone()).collect();
+        let filters = self.filters;
+        let counts: HashMap<P::Id, i64> = eager::load_aggregate_map_with(
+            conn,
+            parent_ids,
+            self.source,
+            Aggregate::Count,
+            move |q| Clauses::push_filters(filters, q),
+        )
+        .await?;
+        Ok(parents
+            .iter()
+            .map(|p| counts.get(p.pk()).copied().unwrap_or_default())
+            .collect())
+    }
+}
+
+/// A has_one relation of `P`; attaches `Option<_>` to each parent.
+#[derive(Debug)]
+pub struct PreloadOne<P, C, N = NoNext> {
+    source: OneSource,
+    json: Option<DecodeJson<Option<C>>>,
+    clauses: Clauses,
+    next: N,
+    _marker: PhantomData<fn() -> (P, C)>,
+}
+
+#[derive(Debug)]
+struct OneSource {
+    select_list: &'static str,
+    table: &'static str,
+    join_clause: &'static str,
+    col: &'static str,
+    json_object: &'static str,
+}
+
+impl OneSource {
+    /// `SELECT <json_object> FROM <table> WHERE <table>.<col> = <outer_table>.<outer_col> ... LIMIT 1`.
+    fn embedded_sql(
+        &self,
+        outer_table: &'static str,
+        outer_col: &'static str,
+        clauses: Clauses,
+    ) -> OrmResult<Sql> {
+        check_not_self_referential(self.table, outer_table)?;
+        let mut q = sql("SELECT ");
+        q.push(self.json_object);
+        q.push(" FROM ");
+        q.push_ident(self.table)?;
+        push_join_clause(&mut q, self.join_clause);
+        q.push(" WHERE ");
+        q.push_ident(self.table)?;
+        q.push(".");
+        q.push_ident(self.col)?;
+        q.push(" = ");
+        q.push_ident(outer_table)?;
+        q.push(".");
+        q.push_ident(outer_col)?;
+        clauses.apply(&mut q);
+        q.push(" LIMIT 1");
+        Ok(q)
+    }
+}
+
+impl<P, C> PreloadOne<P, C> {
+    /// The child whose `fk_col` references the parent's primary key.
+    pub fn has_one(
+        select_list: &'static str,
+        table: &'static str,
+        join_clause: &'static str,
+        fk_col: &'static str,
+        json_object: &'static str,
+    ) -> Self {
+        Self {
+            source: OneSource {
+                select_list,
+                table,
+                join_clause,
+                col: fk_col,
+                json_object,
+            },
+            json: None,
+            clauses: Clauses::default(),
+            next: NoNext,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl_level_builders!(PreloadOne);
+
+impl<P, C, N> Preload<P> for PreloadOne<P, C, N>
+where
+    P: ModelPk + Sync,
+    P::Id: ToSql + FromSqlOwned + Eq + Hash,
+    C: FromRow + Send + Sync,
+    N: PreloadNext<C> + Send,
+    N::Item: Clone + Send,
+{
+    type Output = Option<N::Item>;
+
+    async fn load(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+    ) -> OrmResult<Vec<Option<N::Item>>> {
+        let parent_ids: Vec<P::Id> = parents.iter().map(|p| p.pk().clone()).collect();
+        let clauses = self.clauses;
+        let OneSource {
+            select_list,
+            table,
+            join_clause,
+            col,
+            ..
+        } = self.source;
+        let map = eager::load_has_one_map_with::<C, P::Id, _>(
+            conn,
+            parent_ids,
+            select_list,
+            table,
+            join_clause,
+            col,
+            move |q| clauses.apply(q),
+        )
+        .await?;
+
+        let map = attach_next(conn, self.next, map).await?;
+        Ok(distribute(parents.iter().map(|p| Some(p.pk())), map))
+    }
+
+    fn take_embedded(
+        &mut self,
+        parent_table: &'static str,
+        parent_pk: Option<&'static str>,
+    ) -> OrmResult<Option<Sql>> {
+        if self.json.is_none() {
+            return Ok(None);
+        }
+        let parent_pk = require_parent_pk(parent_pk)?;
+        let clauses = std::mem::take(&mut self.clauses);
+        self.source
+            .embedded_sql(parent_table, parent_pk, clauses)
+            .map(Some)
+    }
+
+    async fn load_embedded(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+        values: Vec<Value>,
+    ) -> OrmResult<Vec<Option<N::Item>>> {
+        let Some(decode) = self.json else {
+            return self.load(conn, parents).await;
+        };
+        let rows = values
+            .into_iter()
+            .map(|value| decode(value).map_err(decode_error))
+            .collect::<OrmResult<Vec<Option<C>>>>()?;
+        attach_options(conn, self.next, rows).await
+    }
+}
+
+/// A belongs_to relation of `P`; attaches `Option<_>` to each child row.
+///
+/// `None` when the foreign key is NULL or the parent row is missing (or filtered out).
+#[derive(Debug)]
+pub struct PreloadBelongsTo<P, C: ModelPk, N = NoNext> {
+    source: OneSource,
+    fk_col: &'static str,
+    fk: fn(&P) -> Option<&C::Id>,
+    json: Option<DecodeJson<Option<C>>>,
+    clauses: Clauses,
+    next: N,
+    _marker: PhantomData<fn() -> (P, C)>,
+}
+
+impl<P, C: ModelPk> PreloadBelongsTo<P, C> {
+    /// The parent row whose `id_col` equals the value returned by `fk` (the `fk_col` column of `P`).
+    pub fn belongs_to(
+        select_list: &'static str,
+        table: &'static str,
+        join_clause: &'static str,
+        id_col: &'static str,
+        json_object: &'static str,
+        fk_col: &'static str,
+        fk: fn(&P) -> Option<&C::Id>,
+    ) -> Self {
+        Self {
+            source: OneSource {
+                select_list,
+                table,
+                join_clause,
+                col: id_col,
+                json_object,
+            },
+            fk_col,
+            fk,
+            json: None,
+            clauses: Clauses::default(),
+            next: NoNext,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<P, C: ModelPk, N> PreloadBelongsTo<P, C, N> {
+    /// Add a condition, appended as `AND (...)` to this level's query.
+    pub fn filter(mut self, f: impl FnOnce(&mut Sql) + Send + 'static) -> Self {
+        self.clauses.filters.push(Box::new(f));
+        self
+    }
+
+    /// Set this level's ordering, appended after `ORDER BY`.
+    pub fn order_by(mut self, f: impl FnOnce(&mut Sql) + Send + 'static) -> Self {
+        self.clauses.order_by = Some(Box::new(f));
+        self
+    }
+}
+
+impl<P, C: ModelPk + DeserializeOwned, N> PreloadBelongsTo<P, C, N> {
+    /// Choose how this level is loaded (see [`Strategy`]).
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.json = match strategy {
+            Strategy::Batched => None,
+            Strategy::JsonAgg => Some(serde_json::from_value),
+        };
+        self
+    }
+}
+
+impl<P, C: ModelPk> PreloadBelongsTo<P, C, NoNext> {
+    /// Preload `next` for the rows loaded by this level.
+    pub fn then<T: Preload<C>>(self, next: T) -> PreloadBelongsTo<P, C, Then<T>> {
+        PreloadBelongsTo {
+            source: self.source,
+            fk_col: self.fk_col,
+            fk: self.fk,
+            json: self.json,
+            clauses: self.clauses,
+            next: Then(next),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<P, C, N> Preload<P> for PreloadBelongsTo<P, C, N>
+where
+    P: Sync,
+    C: FromRow + ModelPk + Send + Sync,
+    C::Id: ToSql + Eq + Hash,
+    N: PreloadNext<C> + Send,
+    N::Item: Clone + Send,
+{
+    type Output = Option<N::Item>;
+
+    async fn load(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+    ) -> OrmResult<Vec<Option<N::Item>>> {
+        let fk = self.fk;
+        let ids: Vec<C::Id> = parents.iter().filter_map(|p| fk(p).cloned()).collect();
+        let clauses = self.clauses;
+        let OneSource {
+            select_list,
+            table,
+            join_clause,
+            col,
+            ..
+        } = self.source;
+        let map = eager::load_belongs_to_map_with::<C, C::Id, _>(
+            conn,
+            ids,
+            select_list,
+            table,
+            join_clause,
+            col,
+            move |q| clauses.apply(q),
+        )
+        .await?;
+
+        let map = attach_next(conn, self.next, map).await?;
+        Ok(distribute(parents.iter().map(fk), map))
+    }
+
+    fn take_embedded(
+        &mut self,
+        parent_table: &'static str,
+        _parent_pk: Option<&'static str>,
+    ) -> OrmResult<Option<Sql>> {
+        if self.json.is_none() {
+            return Ok(None);
+        }
+        let clauses = std::mem::take(&mut self.clauses);
+        self.source
+            .embedded_sql(parent_table, self.fk_col, clauses)
+            .map(Some)
+    }
+
+    async fn load_embedded(
+        self,
+        conn: &impl GenericClient,
+        parents: &[P],
+        values: Vec<Value>,
+    ) -> OrmResult<Vec<Option<N::Item>>> {
+        let Some(decode) = self.json else {
+            return self.load(conn, parents).await;
+        };
+        let rows = values
+            .into_iter()
+            .map(|value| decode(value).map_err(decode_error))
+            .collect::<OrmResult<Vec<Option<C>>>>()?;
+        attach_options(conn, self.next, rows).await
+    }
+}
+
+fn require_parent_pk(parent_pk: Option<&'static str>) -> OrmResult<&'static str> {
+    parent_pk.ok_or_else(|| {
+        OrmError::validation("JsonAgg preload requires a parent with a single #[orm(id)] column")
+    })
+}
+
+/// Inside the subquery the parent table name would resolve to the child table.
+fn check_not_self_referential(table: &str, parent_table: &str) -> OrmResult<()> {
+    if table == parent_table {
+        return Err(OrmError::validation(format!(
+            "JsonAgg preload does not support self-referential relations ({table})"
+        )));
+    }
+    Ok(())
+}
+
+fn push_join_clause(q: &mut Sql, join_clause: &str) {
+    if !join_clause.trim().is_empty() {
+        q.push(" ");
+        q.push(join_clause);
+    }
+}
+
+fn decode_error(e: serde_json::Error) -> OrmError {
+    OrmError::decode(EMBEDDED_ALIAS, e.to_string())
+}
+
+/// Load the next level for the values of a one-per-key map.
+async fn attach_next<K, C, N>(
+    conn: &impl GenericClient,
+    next: N,
+    map: HashMap<K, C>,
+) -> OrmResult<HashMap<K, N::Item>>
+where
+    K: Eq + Hash,
+    N: PreloadNext<C>,
+{
+    let (keys, rows): (Vec<K>, Vec<C>) = map.into_iter().unzip();
+    let items = next.attach(conn, rows).await?;
+    Ok(keys.into_iter().zip(items).collect())
+}
+
+/// Load the next level once for all groups, keeping the grouping.
+async fn attach_groups<C, N>(
+    conn: &impl GenericClient,
+    next: N,
+    groups: Vec<Vec<C>>,
+) -> OrmResult<Vec<Vec<N::Item>>>
+where
+    N: PreloadNext<C>,
+{
+    let lens: Vec<usize> = groups.iter().map(Vec::len).collect();
+    let children: Vec<C> = groups.into_iter().flatten().collect();
+    let mut items = next.attach(conn, children).await?.into_iter();
+    Ok(lens
+        .into_iter()
+        .map(|len| items.by_ref().take(len).collect())
+        .collect())
+}
+
+/// Load the next level once for all present rows, keeping the `None`s.
+async fn attach_options<C, N>(
+    conn: &impl GenericClient,
+    next: N,
+    rows: Vec<Option<C>>,
+) -> OrmResult<Vec<Option<N::Item>>>
+where
+    N: PreloadNext<C>,
+{
+    let present: Vec<bool> = rows.iter().map(Option::is_some).collect();
+    let mut items = next
+        .attach(conn, rows.into_iter().flatten().collect())
+        .await?
+        .into_iter();
+    Ok(present
+        .into_iter()
+        .map(|present| if present { items.next() } else { None })
+        .collect())
+}
+
+/// Hand out `map` values to `keys` in order, cloning only for keys that repeat.
+fn distribute<'k, K, V>(
+    keys: impl Iterator<Item = Option<&'k K>>,
+    mut map: HashMap<K, V>,
+) -> Vec<Option<V>>
+where
+    K: Eq + Hash + 'k,
+    V: Clone,
+{
+    let keys: Vec<Option<&K>> = keys.collect();
+    let mut remaining: HashMap<&K, usize> = HashMap::new();
+    for key in keys.iter().flatten() {
+        *remaining.entry(key).or_default() += 1;
+    }
+
+    keys.into_iter()
+        .map(|key| {
+            let key = key?;
+            let left = remaining.get_mut(key)?;
+            *left -= 1;
+            if *left == 0 {
+                map.remove(key)
+            } else {
+                map.get(key).cloned()
+            }
+        })
+        .collect()
+}
+
+/// Attach `relation` to already loaded rows.
+///
+/// Output order matches the input `base` order.
+pub async fn attach<P, R>(
+    conn: &impl GenericClient,
+    base: Vec<P>,
+    relation: R,
+) -> OrmResult<Vec<Loaded<P, R::Output>>>
+where
+    R: Preload<P>,
+{
+    let rels = relation.load(conn, &base).await?;
+    Ok(zip_loaded(base, rels))
+}
+
+fn zip_loaded<M, R>(base: Vec<M>, rels: Vec<R>) -> Vec<Loaded<M, R>> {
+    base.into_iter()
+        .zip(rels)
+        .map(|(base, rel)| Loaded { base, rel })
+        .collect()
+}
+
+/// SQL of a model query builder, used by [`PreloadQuery`].
+///
+/// Implemented by the `<Model>Query` builders generated by `#[derive(Model)]`;
+/// `M` is the model the query returns.
+pub trait ModelQuery<M> {
+    /// The model's main table.
+    const TABLE: &'static str;
+
+    /// The model's primary key column, if it has exactly one.
+    const ID: Option<&'static str>;
+
+    /// `SELECT` for all matching rows (ordering, pagination and locks included).
+    ///
+    /// `column` is appended to the select list.
+    fn find_sql(&self, column: Option<Sql>) -> OrmResult<Sql>;
+
+    /// `SELECT` for the first matching row.
+    fn first_sql(&self, column: Option<Sql>) -> OrmResult<Sql>;
+}
+
+/// A model query with a relation to preload, created by `<Model>Query::preload`.
+#[derive(Debug)]
+pub struct PreloadQuery<Q, M, R> {
+    query: Q,
+    relation: R,
+    _model: PhantomData<fn() -> M>,
+}
+
+impl<Q, M, R> PreloadQuery<Q, M, R>
+where
+    Q: ModelQuery<M>,
+    M: FromRow,
+    R: Preload<M>,
+{
+    /// Pair `query` with `relation`; usually called through `<Model>Query::preload`.
+    pub fn new(query: Q, relation: R) -> Self {
+        Self {
+            query,
+            relation,
+            _model: PhantomData,
+        }
+    }
+
+    /// Execute the query and attach the relation to every row.
+    pub async fn find(self, conn: &impl GenericClient) -> OrmResult<Vec<Loaded<M, R::Output>>> {
+        self.fetch(conn, false).await
+    }
+
+    /// Execute the query and attach the relation to the first row, or None if not found.
+    pub async fn find_one_opt(
+        self,
+        conn: &impl GenericClient,
+    ) -> OrmResult<Option<Loaded<M, R::Output>>> {
+        Ok(self.fetch(conn, true).await?.pop())
+    }
+
+    /// Execute the query and attach the relation to the first row.
+    pub async fn find_one(self, conn: &impl GenericClient) -> OrmResult<Loaded<M, R::Output>> {
+        self.find_one_opt(conn)
+            .await?
+            .ok_or_else(|| OrmError::not_found("Expected one row, got none"))
+    }
+
+    async fn fetch(
+        mut self,
+        conn: &impl GenericClient,
+        first: bool,
+    ) -> OrmResult<Vec<Loaded<M, R::Output>>> {
+        let build = |query: &Q, column: Option<Sql>| {
+            if first {
+                query.first_sql(column)
+            } else {
+                query.find_sql(column)
+            }
+        };
+
+        let Some(subquery) = self.relation.take_embedded(Q::TABLE, Q::ID)? else {
+            let base: Vec<M> = build(&self.query, None)?.fetch_all_as(conn).await?;
+            return attach(conn, base, self.relation).await;
+        };
+
+        let mut column = sql("(");
+        column.push_sql(subquery);
+        column.push(") AS ");
+        column.push(EMBEDDED_ALIAS);
+        let rows = build(&self.query, Some(column))?.fetch_all(conn).await?;
+
+        let mut base = Vec::with_capacity(rows.len());
+        let mut values = Vec::with_capacity(rows.len());
+        for row in &rows {
+            base.push(M::from_row(row)?);
+            let value: Option<Value> = row.try_get_column(EMBEDDED_ALIAS)?;
+            values.push(value.unwrap_or(Value::Null));
+        }
+        let rels = self.relation.load_embedded(conn, &base, values).await?;
+        Ok(zip_loaded(base, rels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribute_clones_only_repeated_keys() {
+        let map: HashMap<i64, Vec<&str>> = HashMap::from([(1, vec!["a", "b"]), (2, vec!["c"])]);
+        let keys = [Some(1), None, Some(2), Some(1), Some(3)];
+
+        let out = distribute(keys.iter().map(Option::as_ref), map);
+        assert_eq!(
+            out,
+            vec![
+                Some(vec!["a", "b"]),
+                None,
+                Some(vec!["c"]),
+                Some(vec!["a", "b"]),
+                None,
+            ]
+        );
+    }
+}
@@ -29,18 +29,31 @@ pub use crate::builder::{
 };
 pub use crate::condition::{Condition, Op};
 pub use crate::cte::WithBuilder;
+pub use crate::expr_json::ExprDecoder;
 pub use crate::ident::{Ident, IntoIdent};
+pub use crate::lock::{LockStrength, LockWait, RowLock};
+pub use crate::page::{CountMode, CursorKey, Page, Paginated};
+pub use crate::query_string::{FilterOp, FilterValue, ParsedQuery, QueryAllowList};
+pub use crate::search::{TsHeadline, TsQuery, TsQueryMode, TsRank, TsVector, TsWeight};
+pub use crate::tree::{Nested, TreeModel, TreeNode};
+pub use crate::window::{
+    FrameBound, FrameUnits, NamedWindows, TopNPerGroup, WindowExpr, WindowFrame, WindowSpec,
+};
 
 // ── Bulk operations ─────────────────────────────────────────────────────────
-pub use crate::bulk::{DeleteManyBuilder, SetExpr, UpdateManyBuilder};
+pub use crate::bulk::{
+    DeleteManyBuilder, InsertBuilder, InsertSelectBuilder, MergeBuilder, OnConflict, SetExpr,
+    UpdateManyBuilder,
+};
 
 // ── Eager loading ───────────────────────────────────────────────────────────
 pub use crate::eager::{BelongsToMap, HasManyMap, HasOneMap, Loaded};
+pub use crate::preload::{Preload, PreloadBelongsTo, PreloadCount, PreloadMany, PreloadOne};
 
 // ── Transactions ────────────────────────────────────────────────────────────
 pub use crate::transaction::{
-    Savepoint, TransactionBeginExt, TransactionExt, TransactionIsolation, TransactionOptions,
-    begin_transaction, begin_transaction_with,
+    Savepoint, TransactionBeginExt, TransactionClient, TransactionExt, TransactionIsolation,
+    TransactionOptions, begin_transaction, begin_transaction_with,
 };
 
 // ── Validation ──────────────────────────────────────────────────────────────
@@ -52,6 +65,10 @@ pub use crate::error::{OrmError, OrmResult};
 // ── Write graphs ────────────────────────────────────────────────────────────
 pub use crate::{ModelPk, WriteReport, WriteStepReport};
 
+// ── Cursor tokens (feature: cursor) ─────────────────────────────────────────
+#[cfg(feature = "cursor")]
+pub use crate::page::CursorCodec;
+
 // ── Connection pooling (feature: pool) ──────────────────────────────────────
 #[cfg(feature = "pool")]
 pub use crate::pool::{create_pool, create_pool_with_config};
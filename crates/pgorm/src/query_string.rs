@@ -0,0 +1,759 @@
+//! Allow-listed filtering and sorting parsed from HTTP query strings.
+//!
+//! Parses REST-style query strings such as
+//! `?sort=-created_at,name&status=eq.active&age=gte.18` into a [`WhereExpr`] and an
+//! [`OrderBy`]. Only columns and operators declared in a [`QueryAllowList`] are
+//! accepted; everything else is reported as [`ValidationErrors`] that can be
+//! returned to the API client as-is.
+//!
+//! | Query string                | SQL                        |
+//! |-----------------------------|----------------------------|
+//! | `col=eq.v` / `col=neq.v`    | `col = $1` / `col != $1`   |
+//! | `col=gt.v` / `gte` / `lt` / `lte` | `col > $1` ...       |
+//! | `col=like.a*` / `ilike.a*`  | `col LIKE $1` (`*` is `%`, other wildcards are literal) |
+//! | `col=not.like.v` / `not.ilike.v` | `col NOT LIKE $1` ... |
+//! | `col=in.(a,b,"c,d")` / `not.in.(...)` | `col IN ($1, $2, $3)` |
+//! | `col=is.null` / `not.is.null` | `col IS NULL` / `col IS NOT NULL` |
+//! | `sort=-created_at,name`     | `ORDER BY created_at DESC, name ASC` |
+//!
+//! Values are parsed with the [`FilterValue`] type registered for the column and
+//! always bound as parameters. Repeated keys (`age=gte.18&age=lte.65`) are combined
+//! with `AND`.
+//!
+//! # Example
+//! ```ignore
+//! use pgorm::{FilterOp, QueryAllowList};
+//!
+//! let allow = QueryAllowList::new()
+//!     .filter::<String>("status", [FilterOp::Eq, FilterOp::In])?
+//!     .filter::<i32>("age", FilterOp::COMPARISON)?
+//!     .nullable("deleted_at")?
+//!     .sort(["created_at", "name"])?;
+//!
+//! let parsed = allow.parse("sort=-created_at,name&status=eq.active&age=gte.18")?;
+//! let users = User::query()
+//!     .and(parsed.filter)
+//!     .order_by(parsed.order_by)
+//!     .find(&client)
+//!     .await?;
+//! ```
+
+use crate::builder::{OrderBy, OrderItem, SortDir, WhereExpr};
+use crate::changeset::{ValidationCode, ValidationError, ValidationErrors};
+use crate::condition::Condition;
+use crate::error::OrmResult;
+use crate::ident::{Ident, IdentPart, IntoIdent};
+use std::sync::Arc;
+use tokio_postgres::types::ToSql;
+
+pub(crate) type DynValue = Arc<dyn ToSql + Send + Sync>;
+pub(crate) type ParseFn = fn(&str) -> Result<DynValue, String>;
+
+/// A value operator that can be allowed for a query-string filter.
+///
+/// `is.null` / `not.is.null` are not value operators; allow them with
+/// [`QueryAllowList::nullable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    /// `eq.v` → `=`
+    Eq,
+    /// `neq.v` (or `not.eq.v`) → `!=`
+    Ne,
+    /// `gt.v` → `>`
+    Gt,
+    /// `gte.v` → `>=`
+    Gte,
+    /// `lt.v` → `<`
+    Lt,
+    /// `lte.v` → `<=`
+    Lte,
+    /// `like.v` → `LIKE`
+    Like,
+    /// `ilike.v` → `ILIKE`
+    Ilike,
+    /// `not.like.v` → `NOT LIKE`
+    NotLike,
+    /// `not.ilike.v` → `NOT ILIKE`
+    NotIlike,
+    /// `in.(a,b)` → `IN`
+    In,
+    /// `not.in.(a,b)` → `NOT IN`
+    NotIn,
+}
+
+impl FilterOp {
+    /// `eq`, `neq`, `gt`, `gte`, `lt`, `lte`.
+    pub const COMPARISON: [FilterOp; 6] = [
+        FilterOp::Eq,
+        FilterOp::Ne,
+        FilterOp::Gt,
+        FilterOp::Gte,
+        FilterOp::Lt,
+        FilterOp::Lte,
+    ];
+
+    /// The operator as written in a query string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "eq",
+            FilterOp::Ne => "neq",
+            FilterOp::Gt => "gt",
+            FilterOp::Gte => "gte",
+            FilterOp::Lt => "lt",
+            FilterOp::Lte => "lte",
+            FilterOp::Like => "like",
+            FilterOp::Ilike => "ilike",
+            FilterOp::NotLike => "not.like",
+            FilterOp::NotIlike => "not.ilike",
+            FilterOp::In => "in",
+            FilterOp::NotIn => "not.in",
+        }
+    }
+
+    pub(crate) fn from_parts(negated: bool, name: &str) -> Option<Self> {
+        Some(match (negated, name) {
+            (false, "eq") | (true, "neq") => FilterOp::Eq,
+            (false, "neq") | (true, "eq") => FilterOp::Ne,
+            (false, "gt") => FilterOp::Gt,
+            (false, "gte") => FilterOp::Gte,
+            (false, "lt") => FilterOp::Lt,
+            (false, "lte") => FilterOp::Lte,
+            (false, "like") => FilterOp::Like,
+            (false, "ilike") => FilterOp::Ilike,
+            (true, "like") => FilterOp::NotLike,
+            (true, "ilike") => FilterOp::NotIlike,
+            (false, "in") => FilterOp::In,
+            (true, "in") => FilterOp::NotIn,
+            _ => return None,
+        })
+    }
+
+    fn sql_operator(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::Ne => "!=",
+            FilterOp::Gt => ">",
+            FilterOp::Gte => ">=",
+            FilterOp::Lt => "<",
+            FilterOp::Lte => "<=",
+            FilterOp::Like => "LIKE",
+            FilterOp::Ilike => "ILIKE",
+            FilterOp::NotLike => "NOT LIKE",
+            FilterOp::NotIlike => "NOT ILIKE",
+            FilterOp::In => "IN",
+            FilterOp::NotIn => "NOT IN",
+        }
+    }
+
+    fn is_pattern(self) -> bool {
+        matches!(
+            self,
+            FilterOp::Like | FilterOp::Ilike | FilterOp::NotLike | FilterOp::NotIlike
+        )
+    }
+}
+
+/// A value type that can be parsed from query-string text and bound as a parameter.
+///
+/// Implement this for your own types (e.g. a `PgEnum`) to filter on them.
+pub trait FilterValue: ToSql + Send + Sync + Sized + 'static {
+    /// Parse the (already percent-decoded) value; the error is shown to the client.
+    fn parse_filter_value(s: &str) -> Result<Self, String>;
+}
+
+impl FilterValue for String {
+    fn parse_filter_value(s: &str) -> Result<Self, String> {
+        Ok(s.to_string())
+    }
+}
+
+impl FilterValue for bool {
+    fn parse_filter_value(s: &str) -> Result<Self, String> {
+        match s {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("expected `true` or `false`, got `{s}`")),
+        }
+    }
+}
+
+impl FilterValue for serde_json::Value {
+    fn parse_filter_value(s: &str) -> Result<Self, String> {
+        serde_json::from_str(s).map_err(|e| format!("expected JSON: {e}"))
+    }
+}
+
+macro_rules! impl_filter_value_from_str {
+    ($($ty:ty => $what:literal),* $(,)?) => {
+        $(
+            impl FilterValue for $ty {
+                fn parse_filter_value(s: &str) -> Result<Self, String> {
+                    s.parse()
+                        .map_err(|_| format!(concat!("expected ", $what, ", got `{}`"), s))
+                }
+            }
+        )*
+    };
+}
+
+impl_filter_value_from_str!(
+    i16 => "an integer",
+    i32 => "an integer",
+    i64 => "an integer",
+    f32 => "a number",
+    f64 => "a number",
+    uuid::Uuid => "a UUID",
+    chrono::NaiveDate => "a date (YYYY-MM-DD)",
+    chrono::NaiveTime => "a time (HH:MM:SS)",
+    chrono::NaiveDateTime => "a timestamp (YYYY-MM-DDTHH:MM:SS)",
+    chrono::DateTime<chrono::Utc> => "an RFC 3339 timestamp",
+    chrono::DateTime<chrono::FixedOffset> => "an RFC 3339 timestamp",
+);
+
+fn parse_dyn<T: FilterValue>(s: &str) -> Result<DynValue, String> {
+    T::parse_filter_value(s).map(|v| Arc::new(v) as DynValue)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FilterField {
+    name: String,
+    pub(crate) column: Ident,
+    pub(crate) ops: Vec<FilterOp>,
+    pub(crate) parse: Option<ParseFn>,
+    pub(crate) nullable: bool,
+}
+
+impl FilterField {
+    fn allowed(&self) -> Vec<&'static str> {
+        let mut allowed: Vec<&'static str> = self.ops.iter().map(|op| op.as_str()).collect();
+        if self.nullable {
+            allowed.extend(["is", "not.is"]);
+        }
+        allowed
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SortField {
+    name: String,
+    column: Ident,
+}
+
+/// The result of parsing a query string against a [`QueryAllowList`].
+#[derive(Debug, Clone)]
+pub struct ParsedQuery {
+    /// All filters combined with `AND` (`TRUE` when there are none).
+    pub filter: WhereExpr,
+    /// Sort order, in the order given by the client (empty when not requested).
+    pub order_by: OrderBy,
+}
+
+/// Columns and operators a client may use in a query string.
+///
+/// Keys are column names by default (the last part of a dotted identifier, so
+/// `users.status` is filtered as `status`); use [`filter_as`](Self::filter_as) /
+/// [`sort_as`](Self::sort_as) to expose a column under a different name. Unknown
+/// keys are ignored unless [`deny_unknown`](Self::deny_unknown) is set, so
+/// pagination parameters can share the query string.
+#[derive(Debug, Clone)]
+pub struct QueryAllowList {
+    filters: Vec<FilterField>,
+    sorts: Vec<SortField>,
+    sort_param: String,
+    deny_unknown: bool,
+}
+
+impl Default for QueryAllowList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryAllowList {
+    /// Create an empty allow-list (sorting via the `sort` key).
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+            sorts: Vec::new(),
+            sort_param: "sort".to_string(),
+            deny_unknown: false,
+        }
+    }
+
+    /// Allow filtering `column` with `ops`, parsing values as `T`.
+    ///
+    /// Calling this again for the same key adds operators and replaces the value type.
+    /// With no operators this only registers the column's value type (needed for
+    /// keyset cursors decoded by [`ExprDecoder`](crate::ExprDecoder)).
+    pub fn filter<T: FilterValue>(
+        self,
+        column: impl IntoIdent,
+        ops: impl IntoIterator<Item = FilterOp>,
+    ) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        let name = key_for(&column);
+        Ok(self.add_filter(name, column, ops, Some(parse_dyn::<T>), false))
+    }
+
+    /// Like [`filter`](Self::filter), but under the query-string key `name`.
+    pub fn filter_as<T: FilterValue>(
+        self,
+        name: impl Into<String>,
+        column: impl IntoIdent,
+        ops: impl IntoIterator<Item = FilterOp>,
+    ) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        Ok(self.add_filter(name.into(), column, ops, Some(parse_dyn::<T>), false))
+    }
+
+    /// Allow `column=is.null` and `column=not.is.null`.
+    pub fn nullable(self, column: impl IntoIdent) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        let name = key_for(&column);
+        Ok(self.add_filter(name, column, [], None, true))
+    }
+
+    fn add_filter(
+        mut self,
+        name: String,
+        column: Ident,
+        ops: impl IntoIterator<Item = FilterOp>,
+        parse: Option<ParseFn>,
+        nullable: bool,
+    ) -> Self {
+        let index = match self.filters.iter().position(|f| f.name == name) {
+            Some(index) => index,
+            None => {
+                self.filters.push(FilterField {
+                    name,
+                    column: column.clone(),
+                    ops: Vec::new(),
+                    parse: None,
+                    nullable: false,
+                });
+                self.filters.len() - 1
+            }
+        };
+        let field = &mut self.filters[index];
+        field.column = column;
+        for op in ops {
+            if !field.ops.contains(&op) {
+                field.ops.push(op);
+            }
+        }
+        field.parse = parse.or(field.parse);
+        field.nullable |= nullable;
+        self
+    }
+
+    /// Allow sorting by these columns.
+    pub fn sort<I, T>(mut self, columns: I) -> OrmResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        for column in columns {
+            let column = column.into_ident()?;
+            let name = key_for(&column);
+            self = self.add_sort(name, column);
+        }
+        Ok(self)
+    }
+
+    /// Allow sorting by `column` under the name `name`.
+    pub fn sort_as(self, name: impl Into<String>, column: impl IntoIdent) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        Ok(self.add_sort(name.into(), column))
+    }
+
+    fn add_sort(mut self, name: String, column: Ident) -> Self {
+        self.sorts.retain(|s| s.name != name);
+        self.sorts.push(SortField { name, column });
+        self
+    }
+
+    /// Use `name` instead of `sort` as the sorting key.
+    pub fn sort_param(mut self, name: impl Into<String>) -> Self {
+        self.sort_param = name.into();
+        self
+    }
+
+    /// Report keys that are neither filters nor the sort key as errors.
+    pub fn deny_unknown(mut self) -> Self {
+        self.deny_unknown = true;
+        self
+    }
+
+    /// Filter entry for `column`, matched by identifier or by query-string key.
+    pub(crate) fn filter_for(&self, column: &Ident) -> Option<&FilterField> {
+        self.filters
+            .iter()
+            .find(|f| f.column == *column)
+            .or_else(|| {
+                let key = (column.parts.len() == 1).then(|| key_for(column))?;
+                self.filters.iter().find(|f| f.name == key)
+            })
+    }
+
+    /// Sortable column matching `column`, by identifier or by query-string key.
+    pub(crate) fn sort_for(&self, column: &Ident) -> Option<&Ident> {
+        let key = (column.parts.len() == 1).then(|| key_for(column));
+        self.sorts
+            .iter()
+            .find(|s| s.column == *column || key.as_ref() == Some(&s.name))
+            .map(|s| &s.column)
+    }
+
+    /// Parse a raw query string (with or without the leading `?`).
+    ///
+    /// Keys and values are percent-decoded (`+` is a space).
+    pub fn parse(&self, query: &str) -> Result<ParsedQuery, ValidationErrors> {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let pairs = query.split('&').filter(|s| !s.is_empty()).map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        });
+        self.parse_pairs(pairs)
+    }
+
+    /// Parse already-decoded key/value pairs (e.g. from a web framework's query extractor).
+    pub fn parse_pairs<I, K, V>(&self, pairs: I) -> Result<ParsedQuery, ValidationErrors>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut errors = ValidationErrors::default();
+        let mut filters = Vec::new();
+        let mut order_by = OrderBy::new();
+
+        for (key, value) in pairs {
+            let (key, value) = (key.as_ref(), value.as_ref());
+            if key == self.sort_param {
+                order_by = self.parse_sort(value, order_by, &mut errors);
+            } else if let Some(field) = self.filters.iter().find(|f| f.name == key) {
+                match parse_filter(field, value) {
+                    Ok(expr) => filters.push(expr),
+                    Err(err) => errors.push(err),
+                }
+            } else if self.deny_unknown {
+                let mut allowed: Vec<&str> = self.filters.iter().map(|f| f.name.as_str()).collect();
+                if !self.sorts.is_empty() {
+                    allowed.push(&self.sort_param);
+                }
+                errors.push(
+                    ValidationError::new(
+                        key,
+                        ValidationCode::OneOf,
+                        format!("unknown query parameter `{key}`"),
+                    )
+                    .with_metadata("allowed", allowed),
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(ParsedQuery {
+                filter: WhereExpr::And(filters),
+                order_by,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn parse_sort(
+        &self,
+        value: &str,
+        mut order_by: OrderBy,
+        errors: &mut ValidationErrors,
+    ) -> OrderBy {
+        for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (name, dir) = match item.strip_prefix('-') {
+                Some(name) => (name, SortDir::Desc),
+                None => (item.strip_prefix('+').unwrap_or(item), SortDir::Asc),
+            };
+            match self.sorts.iter().find(|s| s.name == name) {
+                Some(sort) => order_by = order_by.add(OrderItem::new(sort.column.clone(), dir)),
+                None => {
+                    let allowed: Vec<&str> = self.sorts.iter().map(|s| s.name.as_str()).collect();
+                    errors.push(
+                        ValidationError::new(
+                            &self.sort_param,
+                            ValidationCode::OneOf,
+                            format!("cannot sort by `{name}`"),
+                        )
+                        .with_metadata("value", name)
+                        .with_metadata("allowed", allowed),
+                    );
+                }
+            }
+        }
+        order_by
+    }
+}
+
+/// Default query-string key for a column: its last identifier part.
+fn key_for(column: &Ident) -> String {
+    match column.parts.last() {
+        Some(IdentPart::Unquoted(name) | IdentPart::Quoted(name)) => name.clone(),
+        None => String::new(),
+    }
+}
+
+fn parse_filter(field: &FilterField, raw: &str) -> Result<WhereExpr, ValidationError> {
+    let name = field.name.as_str();
+    let (negated, rest) = match raw.strip_prefix("not.") {
+        Some(rest) => (true, rest),
+        None => (false, raw),
+    };
+    let Some((op_name, value)) = rest.split_once('.') else {
+        return Err(ValidationError::new(
+            name,
+            ValidationCode::Custom("invalid_filter".to_string()),
+            format!("expected `operator.value` (e.g. `eq.{raw}`)"),
+        ));
+    };
+
+    let not_allowed = |op: &str| {
+        ValidationError::new(
+            name,
+            ValidationCode::OneOf,
+            format!("operator `{op}` is not allowed for `{name}`"),
+        )
+        .with_metadata("allowed", field.allowed())
+    };
+    let invalid_value = |message: String| {
+        ValidationError::new(
+            name,
+            ValidationCode::Custom("invalid_value".to_string()),
+            message,
+        )
+    };
+
+    if op_name == "is" {
+        if !field.nullable {
+            return Err(not_allowed(if negated { "not.is" } else { "is" }));
+        }
+        if value != "null" {
+            return Err(invalid_value(format!("expected `null`, got `{value}`")));
+        }
+        let condition = if negated {
+            Condition::is_not_null(&field.column)
+        } else {
+            Condition::is_null(&field.column)
+        };
+        return Ok(WhereExpr::Atom(
+            condition.map_err(|e| invalid_value(e.to_string()))?,
+        ));
+    }
+
+    let op = FilterOp::from_parts(negated, op_name)
+        .filter(|op| field.ops.contains(op))
+        .ok_or_else(|| not_allowed(&raw[..raw.len() - value.len() - 1]))?;
+    let parse = field.parse.ok_or_else(|| not_allowed(op.as_str()))?;
+
+    let condition = match op {
+        FilterOp::In | FilterOp::NotIn => {
+            let items = split_list(value).ok_or_else(|| {
+                invalid_value(format!("expected a list like `({value})`, got `{value}`"))
+            })?;
+            let values = items
+                .iter()
+                .map(|item| parse(item))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid_value)?;
+            Condition::list_dyn(field.column.clone(), op.sql_operator(), values)
+        }
+        _ => {
+            let value = if op.is_pattern() {
+                parse(&like_pattern(value))
+            } else {
+                parse(value)
+            };
+            Condition::cmp_dyn(
+                field.column.clone(),
+                op.sql_operator(),
+                value.map_err(invalid_value)?,
+            )
+        }
+    };
+    Ok(WhereExpr::Atom(condition))
+}
+
+/// Turn a query-string pattern into a LIKE pattern: `*` matches anything, while
+/// literal `%`, `_` and `\` are escaped (`\` is the default LIKE escape character).
+fn like_pattern(value: &str) -> String {
+    let mut pattern = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '*' => pattern.push('%'),
+            '%' | '_' | '\\' => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+            _ => pattern.push(c),
+        }
+    }
+    pattern
+}
+
+/// Split `(a,b,"c,d")` into its items; double quotes protect commas and `\` escapes.
+fn split_list(value: &str) -> Option<Vec<String>> {
+    let inner = value.strip_prefix('(')?.strip_suffix(')')?;
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => current.extend(chars.next()),
+            ',' if !quoted => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if !inner.is_empty() {
+        items.push(current);
+    }
+    Some(items)
+}
+
+/// Decode `application/x-www-form-urlencoded` text; malformed escapes are kept as-is.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match bytes.get(i + 1..i + 3) {
+                Some(hex) if hex.iter().all(u8::is_ascii_hexdigit) => {
+                    let hex = std::str::from_utf8(hex).unwrap_or_default();
+                    out.push(u8::from_str_radix(hex, 16).unwrap_or_default());
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::Sql;
+
+    fn allow_list() -> QueryAllowList {
+        QueryAllowList::new()
+            .filter::<String>("users.status", [FilterOp::Eq, FilterOp::In])
+            .unwrap()
+            .filter::<i32>("age", FilterOp::COMPARISON)
+            .unwrap()
+            .filter_as::<String>("q", "name", [FilterOp::Ilike])
+            .unwrap()
+            .nullable("deleted_at")
+            .unwrap()
+            .sort(["created_at", "name"])
+            .unwrap()
+    }
+
+    fn render(parsed: &ParsedQuery) -> (String, usize) {
+        let mut sql = Sql::new("SELECT * FROM users WHERE ");
+        parsed.filter.append_to_sql(&mut sql);
+        parsed.order_by.append_to_sql(&mut sql);
+        (sql.to_sql(), sql.params_ref().len())
+    }
+
+    #[test]
+    fn parses_filters_and_sort() {
+        let parsed = allow_list()
+            .parse("?sort=-created_at,name&status=eq.active&age=gte.18&age=lt.65&page=2")
+            .unwrap();
+        assert_eq!(
+            render(&parsed),
+            (
+                "SELECT * FROM users WHERE (users.status = $1 AND age >= $2 AND age < $3) \
+                 ORDER BY created_at DESC, name ASC"
+                    .to_string(),
+                3
+            )
+        );
+
+        let parsed = allow_list()
+            .parse("status=in.(active,%22on+hold%22,%22a%2Cb%22)&q=ilike.*jo*&deleted_at=is.null")
+            .unwrap();
+        assert_eq!(
+            render(&parsed),
+            (
+                "SELECT * FROM users WHERE (users.status IN ($1, $2, $3) AND name ILIKE $4 \
+                 AND deleted_at IS NULL)"
+                    .to_string(),
+                4
+            )
+        );
+
+        let parsed = allow_list().parse("deleted_at=not.is.null").unwrap();
+        assert_eq!(
+            render(&parsed).0,
+            "SELECT * FROM users WHERE deleted_at IS NOT NULL"
+        );
+        assert!(allow_list().parse("").unwrap().filter.is_trivially_true());
+    }
+
+    #[test]
+    fn pattern_values_escape_like_wildcards() {
+        assert_eq!(like_pattern("*jo*"), "%jo%");
+        assert_eq!(like_pattern("100%_off\\*"), "100\\%\\_off\\\\%");
+    }
+
+    #[test]
+    fn rejects_what_is_not_allowed() {
+        let errors = allow_list()
+            .deny_unknown()
+            .parse(
+                "sort=password&status=like.a*&age=gte.old&name=eq.x&status=active&deleted_at=is.x",
+            )
+            .unwrap_err();
+        let summary: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|e| (e.field.as_str(), e.code.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("sort", "one_of"),
+                ("status", "one_of"),
+                ("age", "invalid_value"),
+                ("name", "one_of"),
+                ("status", "invalid_filter"),
+                ("deleted_at", "invalid_value"),
+            ]
+        );
+        assert_eq!(
+            errors.items[1].metadata["allowed"],
+            serde_json::json!(["eq", "in"])
+        );
+
+        // Unknown keys are ignored by default; `is` needs `nullable`.
+        assert!(allow_list().parse("name=eq.x").is_ok());
+        assert!(allow_list().parse("age=is.null").is_err());
+    }
+
+    #[test]
+    fn splits_lists_and_decodes() {
+        assert_eq!(
+            split_list(r#"(a,"b,c","d\"e")"#).unwrap(),
+            vec!["a", "b,c", "d\"e"]
+        );
+        assert_eq!(split_list("()").unwrap(), Vec::<String>::new());
+        assert!(split_list("a,b").is_none());
+        assert_eq!(percent_decode("a+b%20c%2x%E2%9C%93"), "a b c%2x✓");
+    }
+}
@@ -0,0 +1,455 @@
+//! Relationship-aware filters (correlated `EXISTS` subqueries).
+//!
+//! These helpers turn the relation metadata declared via `#[orm(has_many(...))]`,
+//! `#[orm(has_one(...))]`, `#[orm(belongs_to(...))]`, `#[orm(many_to_many(...))]` and
+//! `#[orm(has_many_through(...))]` / `#[orm(has_one_through(...))]` into
+//! [`WhereExpr::Exists`] expressions. `#[derive(Model)]` uses them to generate
+//! `where_has_<rel>` / `where_doesnt_have_<rel>` on the model query builder.
+//!
+//! All table/column arguments are emitted verbatim and are expected to come from
+//! derived model constants (`Model::TABLE`, `Model::ID`, `Model::JOIN_CLAUSE`).
+//!
+//! The related table always keeps its own name inside the subquery, so its
+//! `JOIN_CLAUSE` and table-qualified nested filters resolve against it. When the
+//! related table is the same as the outer table (self-referential relations), the
+//! outer key is first captured as [`OUTER_ALIAS`]`.`[`OUTER_KEY`] one level up,
+//! where the outer table is still visible. Join tables (many_to_many) stay one
+//! level above the related table, so unqualified columns in `filter` only see it.
+
+use crate::WhereExpr;
+
+/// Alias of the one-row derived table capturing the outer key in self-referential
+/// relations.
+pub const OUTER_ALIAS: &str = "__pgorm_outer";
+
+/// Column of [`OUTER_ALIAS`] holding the outer key.
+pub const OUTER_KEY: &str = "__pgorm_key";
+
+/// Alias used for the join table in many_to_many relations.
+pub const THROUGH_ALIAS: &str = "__pgorm_jt";
+
+/// Alias prefix for the intermediate tables of through relations
+/// (`__pgorm_through_0` is the one next to the parent).
+pub const THROUGH_HOP_ALIAS: &str = "__pgorm_through";
+
+/// One intermediate model of a `has_many_through` / `has_one_through` chain.
+///
+/// Hops are listed from the parent towards the target: for `Country -> User -> Post`
+/// the only hop is `users`, whose `foreign_key` (`country_id`) references the parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThroughHop {
+    /// Table of the intermediate model.
+    pub table: &'static str,
+    /// Primary key column of the intermediate model.
+    pub id: &'static str,
+    /// Column of this table that references the previous model of the chain.
+    pub foreign_key: &'static str,
+}
+
+/// Build the joins from `target_ref` back to the first hop.
+///
+/// `target_key` is the column of `target_ref` referencing the last hop. Returns the joins
+/// (each prefixed with a space) and the qualified column of the first hop that
+/// references the parent, e.g. `__pgorm_through_0.country_id`.
+pub fn through_joins(target_ref: &str, target_key: &str, hops: &[ThroughHop]) -> (String, String) {
+    let mut joins = String::new();
+    let mut prev_ref = target_ref.to_string();
+    let mut prev_key = target_key;
+    for (i, hop) in hops.iter().enumerate().rev() {
+        let alias = format!("{THROUGH_HOP_ALIAS}_{i}");
+        joins.push_str(&format!(
+            " INNER JOIN {} AS {alias} ON {alias}.{} = {prev_ref}.{prev_key}",
+            hop.table, hop.id
+        ));
+        prev_ref = alias;
+        prev_key = hop.foreign_key;
+    }
+    (joins, format!("{prev_ref}.{prev_key}"))
+}
+
+/// Build `EXISTS (SELECT 1 FROM child WHERE child.fk = parent.id AND <filter>)`.
+///
+/// Used for both has_many and has_one relations.
+pub fn has_many_exists(
+    parent_table: &str,
+    parent_id: &str,
+    child_table: &str,
+    child_join: &str,
+    foreign_key: &str,
+    filter: WhereExpr,
+) -> WhereExpr {
+    related_exists(
+        parent_table,
+        parent_id,
+        child_table,
+        child_join,
+        |parent_key| format!("{child_table}.{foreign_key} = {parent_key}"),
+        filter,
+    )
+}
+
+/// Build `EXISTS (SELECT 1 FROM parent WHERE parent.id = child.fk AND <filter>)`.
+pub fn belongs_to_exists(
+    child_table: &str,
+    foreign_key: &str,
+    parent_table: &str,
+    parent_join: &str,
+    parent_id: &str,
+    filter: WhereExpr,
+) -> WhereExpr {
+    related_exists(
+        child_table,
+        foreign_key,
+        parent_table,
+        parent_join,
+        |child_key| format!("{parent_table}.{parent_id} = {child_key}"),
+        filter,
+    )
+}
+
+/// Build a many_to_many `EXISTS` through a join table; the related table is kept in a
+/// nested `EXISTS` so unqualified columns in `filter` do not clash with the join table:
+///
+/// `EXISTS (SELECT 1 FROM through __pgorm_jt WHERE __pgorm_jt.self_key = self.id
+/// AND EXISTS (SELECT 1 FROM related WHERE related.id = __pgorm_jt.other_key AND <filter>))`.
+#[allow(clippy::too_many_arguments)]
+pub fn many_to_many_exists(
+    self_table: &str,
+    self_id: &str,
+    related_table: &str,
+    related_join: &str,
+    related_id: &str,
+    through: &str,
+    self_key: &str,
+    other_key: &str,
+    filter: WhereExpr,
+) -> WhereExpr {
+    // The related table is a level below the join table, so the outer table is
+    // never shadowed where it is referenced.
+    let related = correlated(
+        with_join(related_table, related_join),
+        format!("{related_table}.{related_id} = {THROUGH_ALIAS}.{other_key}"),
+        filter,
+    );
+    correlated(
+        format!("{through} {THROUGH_ALIAS}"),
+        format!("{THROUGH_ALIAS}.{self_key} = {self_table}.{self_id}"),
+        related,
+    )
+}
+
+/// Build a through-relation `EXISTS`; the intermediate tables are kept in a nested
+/// subquery so unqualified columns in `filter` only see the target:
+///
+/// `EXISTS (SELECT 1 FROM target WHERE target.key IN (SELECT __pgorm_through_0.id
+/// FROM hop AS __pgorm_through_0 WHERE __pgorm_through_0.fk = parent.id) AND <filter>)`.
+///
+/// Used for both has_many_through and has_one_through relations.
+pub fn has_many_through_exists(
+    parent_table: &str,
+    parent_id: &str,
+    target_table: &str,
+    target_join: &str,
+    target_key: &str,
+    hops: &[ThroughHop],
+    filter: WhereExpr,
+) -> WhereExpr {
+    related_exists(
+        parent_table,
+        parent_id,
+        target_table,
+        target_join,
+        |parent_key| {
+            let Some((last, rest)) = hops.split_last() else {
+                return format!("{target_table}.{target_key} = {parent_key}");
+            };
+            let last_alias = format!("{THROUGH_HOP_ALIAS}_{}", rest.len());
+            let (joins, hop_key) = through_joins(&last_alias, last.foreign_key, rest);
+            format!(
+                "{target_table}.{target_key} IN (SELECT {last_alias}.{} FROM {} AS {last_alias}{joins} \
+                 WHERE {hop_key} = {parent_key})",
+                last.id, last.table
+            )
+        },
+        filter,
+    )
+}
+
+/// Build `EXISTS (SELECT 1 FROM related <join> WHERE <correlation> AND <filter>)`.
+///
+/// `correlation` receives the reference to `outer_table.outer_key`. For
+/// self-referential relations the related table would shadow the outer one, so the
+/// outer key is captured one level up:
+///
+/// `EXISTS (SELECT 1 FROM (SELECT outer.key AS __pgorm_key) AS __pgorm_outer
+/// WHERE EXISTS (SELECT 1 FROM related ... __pgorm_outer.__pgorm_key ...))`.
+fn related_exists(
+    outer_table: &str,
+    outer_key: &str,
+    related_table: &str,
+    related_join: &str,
+    correlation: impl FnOnce(&str) -> String,
+    filter: WhereExpr,
+) -> WhereExpr {
+    let from = with_join(related_table, related_join);
+    if related_table != outer_table {
+        return correlated(
+            from,
+            correlation(&format!("{outer_table}.{outer_key}")),
+            filter,
+        );
+    }
+    let inner = correlated(
+        from,
+        correlation(&format!("{OUTER_ALIAS}.{OUTER_KEY}")),
+        filter,
+    );
+    WhereExpr::exists(
+        format!("(SELECT {outer_table}.{outer_key} AS {OUTER_KEY}) AS {OUTER_ALIAS}"),
+        inner,
+    )
+}
+
+/// The `FROM` item for a table followed by its join clause.
+fn with_join(table: &str, join: &str) -> String {
+    let mut from = table.to_string();
+    if !join.trim().is_empty() {
+        from.push(' ');
+        from.push_str(join);
+    }
+    from
+}
+
+fn correlated(from: String, correlation: String, filter: WhereExpr) -> WhereExpr {
+    let mut filter_expr = WhereExpr::raw(correlation);
+    if !filter.is_trivially_true() {
+        filter_expr = filter_expr.and_with(filter);
+    }
+    WhereExpr::exists(from, filter_expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Condition, Sql};
+
+    fn render(expr: &WhereExpr) -> Sql {
+        let mut sql = Sql::empty();
+        expr.append_to_sql(&mut sql);
+        sql
+    }
+
+    #[test]
+    fn has_many_without_filter() {
+        let expr = has_many_exists(
+            "users",
+            "id",
+            "orders",
+            "",
+            "user_id",
+            WhereExpr::And(vec![]),
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id)"
+        );
+    }
+
+    #[test]
+    fn has_many_with_filter_binds_params() {
+        let filter = Condition::eq("status", "unpaid").unwrap().into();
+        let expr = has_many_exists("users", "id", "orders", "", "user_id", filter);
+        let sql = render(&expr);
+        assert_eq!(
+            sql.to_sql(),
+            "EXISTS (SELECT 1 FROM orders WHERE (orders.user_id = users.id AND status = $1))"
+        );
+        assert_eq!(sql.params_ref().len(), 1);
+    }
+
+    #[test]
+    fn has_many_doesnt_have_wraps_in_not() {
+        let expr = WhereExpr::not(has_many_exists(
+            "users",
+            "id",
+            "orders",
+            "",
+            "user_id",
+            WhereExpr::And(vec![]),
+        ));
+        assert_eq!(
+            render(&expr).to_sql(),
+            "(NOT EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id))"
+        );
+    }
+
+    #[test]
+    fn has_many_keeps_child_join_clause() {
+        let expr = has_many_exists(
+            "users",
+            "id",
+            "orders",
+            "INNER JOIN shops ON shops.id = orders.shop_id",
+            "user_id",
+            WhereExpr::And(vec![]),
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM orders INNER JOIN shops ON shops.id = orders.shop_id \
+             WHERE orders.user_id = users.id)"
+        );
+    }
+
+    #[test]
+    fn self_referential_relation_captures_outer_key() {
+        let expr = has_many_exists(
+            "categories",
+            "id",
+            "categories",
+            "",
+            "parent_id",
+            WhereExpr::And(vec![]),
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM (SELECT categories.id AS __pgorm_key) AS __pgorm_outer \
+             WHERE EXISTS (SELECT 1 FROM categories \
+             WHERE categories.parent_id = __pgorm_outer.__pgorm_key))"
+        );
+    }
+
+    #[test]
+    fn self_referential_join_and_qualified_filter_see_the_related_row() {
+        // A model with joins qualifies its filters with the real table name.
+        let filter = WhereExpr::raw("categories.visible AND sections.public");
+        let expr = has_many_exists(
+            "categories",
+            "id",
+            "categories",
+            "INNER JOIN sections ON sections.id = categories.section_id",
+            "parent_id",
+            filter,
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM (SELECT categories.id AS __pgorm_key) AS __pgorm_outer \
+             WHERE EXISTS (SELECT 1 FROM categories \
+             INNER JOIN sections ON sections.id = categories.section_id \
+             WHERE (categories.parent_id = __pgorm_outer.__pgorm_key \
+             AND categories.visible AND sections.public)))"
+        );
+
+        let expr = belongs_to_exists(
+            "categories",
+            "parent_id",
+            "categories",
+            "",
+            "id",
+            WhereExpr::raw("categories.visible"),
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM (SELECT categories.parent_id AS __pgorm_key) AS __pgorm_outer \
+             WHERE EXISTS (SELECT 1 FROM categories \
+             WHERE (categories.id = __pgorm_outer.__pgorm_key AND categories.visible)))"
+        );
+    }
+
+    #[test]
+    fn belongs_to_correlates_on_foreign_key() {
+        let filter = Condition::eq("active", true).unwrap().into();
+        let expr = belongs_to_exists("orders", "user_id", "users", "", "id", filter);
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM users WHERE (users.id = orders.user_id AND active = $1))"
+        );
+    }
+
+    #[test]
+    fn many_to_many_goes_through_join_table() {
+        let filter = Condition::eq("name", "rust").unwrap().into();
+        let expr = many_to_many_exists(
+            "posts",
+            "id",
+            "tags",
+            "",
+            "id",
+            "post_tags",
+            "post_id",
+            "tag_id",
+            filter,
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM post_tags __pgorm_jt WHERE (__pgorm_jt.post_id = posts.id \
+             AND EXISTS (SELECT 1 FROM tags WHERE (tags.id = __pgorm_jt.tag_id AND name = $1))))"
+        );
+    }
+
+    #[test]
+    fn self_referential_many_to_many_keeps_outer_table_visible() {
+        let expr = many_to_many_exists(
+            "users",
+            "id",
+            "users",
+            "INNER JOIN teams ON teams.id = users.team_id",
+            "id",
+            "follows",
+            "follower_id",
+            "followee_id",
+            WhereExpr::raw("users.active"),
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM follows __pgorm_jt WHERE (__pgorm_jt.follower_id = users.id \
+             AND EXISTS (SELECT 1 FROM users INNER JOIN teams ON teams.id = users.team_id \
+             WHERE (users.id = __pgorm_jt.followee_id AND users.active))))"
+        );
+    }
+
+    #[test]
+    fn has_many_through_keeps_hops_in_subquery() {
+        let hops = [ThroughHop {
+            table: "users",
+            id: "id",
+            foreign_key: "country_id",
+        }];
+        let expr = has_many_through_exists(
+            "countries",
+            "id",
+            "posts",
+            "",
+            "user_id",
+            &hops,
+            WhereExpr::And(vec![]),
+        );
+        assert_eq!(
+            render(&expr).to_sql(),
+            "EXISTS (SELECT 1 FROM posts WHERE posts.user_id IN (SELECT __pgorm_through_0.id \
+             FROM users AS __pgorm_through_0 WHERE __pgorm_through_0.country_id = countries.id))"
+        );
+    }
+
+    #[test]
+    fn nested_through_chains_hops_from_the_target() {
+        let hops = [
+            ThroughHop {
+                table: "users",
+                id: "id",
+                foreign_key: "country_id",
+            },
+            ThroughHop {
+                table: "posts",
+                id: "id",
+                foreign_key: "user_id",
+            },
+        ];
+        let (joins, parent_key) = through_joins("comments", "post_id", &hops);
+        assert_eq!(
+            joins,
+            " INNER JOIN posts AS __pgorm_through_1 ON __pgorm_through_1.id = comments.post_id \
+             INNER JOIN users AS __pgorm_through_0 ON __pgorm_through_0.id = __pgorm_through_1.user_id"
+        );
+        assert_eq!(parent_key, "__pgorm_through_0.country_id");
+    }
+}
@@ -0,0 +1,523 @@
+//! Full-text search helpers (`tsvector` / `tsquery`).
+//!
+//! - [`TsQuery`] — `plainto_tsquery` / `phraseto_tsquery` / `websearch_to_tsquery` / `to_tsquery`
+//! - [`TsVector`] — a stored `tsvector` column, or `to_tsvector(...)` over one or more
+//!   text columns with optional `setweight` weights
+//! - [`TsRank`] — `ts_rank` / `ts_rank_cd`, usable in projections and [`OrderBy`](crate::OrderBy)
+//! - [`TsHeadline`] — `ts_headline` snippets
+//!
+//! Text search configurations (e.g. `english`) are validated and inlined as
+//! `'english'::regconfig` literals rather than bound, so the rendered expressions
+//! can match expression indexes such as `USING GIN (to_tsvector('english', body))`.
+//! Query text and headline options are always bound as parameters.
+//!
+//! # Example
+//! ```ignore
+//! use pgorm::{Condition, OrderBy, SortDir, TsQuery, TsRank, TsVector, TsWeight};
+//!
+//! let vector = TsVector::new()
+//!     .weighted("title", TsWeight::A)?
+//!     .weighted("body", TsWeight::B)?
+//!     .config("english")?;
+//! let query = TsQuery::websearch("rust orm -java").config("english")?;
+//!
+//! let mut q = pgorm::sql("SELECT id, title FROM posts WHERE ");
+//! Condition::ts_search(&vector, &query).append_to_sql(&mut q);
+//! OrderBy::new()
+//!     .rank(TsRank::new(vector, query), SortDir::Desc)
+//!     .append_to_sql(&mut q);
+//! ```
+
+use crate::condition::{ConditionPart, append_parts};
+use crate::error::{OrmError, OrmResult};
+use crate::ident::{Ident, IntoIdent};
+use crate::sql::Sql;
+use std::sync::Arc;
+
+/// `setweight` label for a tsvector component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsWeight {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl TsWeight {
+    /// Parse a weight label (`A`-`D`, case-insensitive).
+    pub fn parse(s: &str) -> OrmResult<Self> {
+        match s.trim() {
+            "A" | "a" => Ok(TsWeight::A),
+            "B" | "b" => Ok(TsWeight::B),
+            "C" | "c" => Ok(TsWeight::C),
+            "D" | "d" => Ok(TsWeight::D),
+            other => Err(OrmError::validation(format!(
+                "Invalid tsvector weight '{other}', expected A, B, C or D"
+            ))),
+        }
+    }
+
+    fn to_sql(self) -> &'static str {
+        match self {
+            TsWeight::A => "'A'",
+            TsWeight::B => "'B'",
+            TsWeight::C => "'C'",
+            TsWeight::D => "'D'",
+        }
+    }
+}
+
+/// How query text is parsed into a `tsquery`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TsQueryMode {
+    /// `plainto_tsquery`: all words must match.
+    #[default]
+    Plain,
+    /// `phraseto_tsquery`: words must appear in order.
+    Phrase,
+    /// `websearch_to_tsquery`: search-engine syntax (`"quoted phrase"`, `or`, `-word`).
+    Websearch,
+    /// `to_tsquery`: raw tsquery syntax (`rust & (orm | sql)`); invalid syntax is a database error.
+    Raw,
+}
+
+impl TsQueryMode {
+    fn function(self) -> &'static str {
+        match self {
+            TsQueryMode::Plain => "plainto_tsquery(",
+            TsQueryMode::Phrase => "phraseto_tsquery(",
+            TsQueryMode::Websearch => "websearch_to_tsquery(",
+            TsQueryMode::Raw => "to_tsquery(",
+        }
+    }
+}
+
+/// Validate a text search configuration name and render it as a literal.
+fn config_literal(config: &str) -> OrmResult<String> {
+    let valid = !config.is_empty()
+        && !config.starts_with('.')
+        && !config.ends_with('.')
+        && config
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid {
+        return Err(OrmError::validation(format!(
+            "Invalid text search configuration: {config:?}"
+        )));
+    }
+    Ok(format!("'{config}'::regconfig, "))
+}
+
+/// A `tsquery` built from user-provided text.
+///
+/// The text is always bound as a parameter.
+#[derive(Debug, Clone)]
+pub struct TsQuery {
+    mode: TsQueryMode,
+    text: String,
+    config: Option<String>,
+}
+
+impl TsQuery {
+    /// Create a query with an explicit parsing mode.
+    pub fn new(mode: TsQueryMode, text: impl Into<String>) -> Self {
+        Self {
+            mode,
+            text: text.into(),
+            config: None,
+        }
+    }
+
+    /// `plainto_tsquery($n)`
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(TsQueryMode::Plain, text)
+    }
+
+    /// `phraseto_tsquery($n)`
+    pub fn phrase(text: impl Into<String>) -> Self {
+        Self::new(TsQueryMode::Phrase, text)
+    }
+
+    /// `websearch_to_tsquery($n)`
+    pub fn websearch(text: impl Into<String>) -> Self {
+        Self::new(TsQueryMode::Websearch, text)
+    }
+
+    /// `to_tsquery($n)`
+    pub fn raw(text: impl Into<String>) -> Self {
+        Self::new(TsQueryMode::Raw, text)
+    }
+
+    /// Use a text search configuration, e.g. `"english"` or `"pg_catalog.simple"`.
+    pub fn config(mut self, config: &str) -> OrmResult<Self> {
+        self.config = Some(config_literal(config)?);
+        Ok(self)
+    }
+
+    /// The parsing mode of this query.
+    pub fn mode(&self) -> TsQueryMode {
+        self.mode
+    }
+
+    pub(crate) fn push_parts(&self, parts: &mut Vec<ConditionPart>) {
+        parts.push(ConditionPart::Raw(self.mode.function()));
+        if let Some(config) = &self.config {
+            parts.push(ConditionPart::Trusted(config.clone()));
+        }
+        parts.push(ConditionPart::Param(Arc::new(self.text.clone())));
+        parts.push(ConditionPart::Raw(")"));
+    }
+
+    /// Append this query expression to a SQL builder.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        let mut parts = Vec::new();
+        self.push_parts(&mut parts);
+        append_parts(&parts, sql);
+    }
+}
+
+/// A `tsvector` expression: a stored `tsvector` column, or `to_tsvector(...)` over
+/// one or more text columns.
+///
+/// Multiple columns are concatenated with `||`; each is wrapped in `coalesce(col, '')`
+/// so a `NULL` column does not null out the whole document.
+#[derive(Debug, Clone, Default)]
+pub struct TsVector {
+    stored: Option<Ident>,
+    fields: Vec<(Ident, Option<TsWeight>)>,
+    config: Option<String>,
+}
+
+impl TsVector {
+    /// Create an empty document; add columns with [`field`](Self::field) /
+    /// [`weighted`](Self::weighted).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a stored (e.g. generated) `tsvector` column as-is.
+    pub fn stored(column: impl IntoIdent) -> OrmResult<Self> {
+        Ok(Self {
+            stored: Some(column.into_ident()?),
+            ..Self::default()
+        })
+    }
+
+    /// Add an unweighted text column: `to_tsvector(coalesce(col, ''))`.
+    pub fn field(mut self, column: impl IntoIdent) -> OrmResult<Self> {
+        self.fields.push((column.into_ident()?, None));
+        Ok(self)
+    }
+
+    /// Add a weighted text column: `setweight(to_tsvector(coalesce(col, '')), 'A')`.
+    pub fn weighted(mut self, column: impl IntoIdent, weight: TsWeight) -> OrmResult<Self> {
+        self.fields.push((column.into_ident()?, Some(weight)));
+        Ok(self)
+    }
+
+    /// Use a text search configuration for `to_tsvector` (ignored for stored columns).
+    pub fn config(mut self, config: &str) -> OrmResult<Self> {
+        self.config = Some(config_literal(config)?);
+        Ok(self)
+    }
+
+    pub(crate) fn push_parts(&self, parts: &mut Vec<ConditionPart>) {
+        if let Some(column) = &self.stored {
+            parts.push(ConditionPart::Ident(column.clone()));
+            return;
+        }
+        if self.fields.is_empty() {
+            parts.push(ConditionPart::Raw("''::tsvector"));
+            return;
+        }
+
+        let grouped = self.fields.len() > 1;
+        if grouped {
+            parts.push(ConditionPart::Raw("("));
+        }
+        for (i, (column, weight)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                parts.push(ConditionPart::Raw(" || "));
+            }
+            if weight.is_some() {
+                parts.push(ConditionPart::Raw("setweight("));
+            }
+            parts.push(ConditionPart::Raw("to_tsvector("));
+            if let Some(config) = &self.config {
+                parts.push(ConditionPart::Trusted(config.clone()));
+            }
+            parts.push(ConditionPart::Raw("coalesce("));
+            parts.push(ConditionPart::Ident(column.clone()));
+            parts.push(ConditionPart::Raw(", ''))"));
+            if let Some(weight) = weight {
+                parts.push(ConditionPart::Raw(", "));
+                parts.push(ConditionPart::Raw(weight.to_sql()));
+                parts.push(ConditionPart::Raw(")"));
+            }
+        }
+        if grouped {
+            parts.push(ConditionPart::Raw(")"));
+        }
+    }
+
+    /// Append this tsvector expression to a SQL builder.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        let mut parts = Vec::new();
+        self.push_parts(&mut parts);
+        append_parts(&parts, sql);
+    }
+}
+
+/// Relevance ranking: `ts_rank(vector, query)` or `ts_rank_cd(vector, query)`.
+///
+/// Use it in projections via [`append_to_sql`](Self::append_to_sql) (with an optional
+/// alias), or for sorting via [`OrderBy::rank`](crate::OrderBy::rank).
+#[derive(Debug, Clone)]
+pub struct TsRank {
+    vector: TsVector,
+    query: TsQuery,
+    cover_density: bool,
+    normalization: Option<i32>,
+    alias: Option<Ident>,
+}
+
+impl TsRank {
+    /// `ts_rank(vector, query)`
+    pub fn new(vector: TsVector, query: TsQuery) -> Self {
+        Self {
+            vector,
+            query,
+            cover_density: false,
+            normalization: None,
+            alias: None,
+        }
+    }
+
+    /// `ts_rank_cd(vector, query)` (cover density ranking).
+    pub fn cover_density(vector: TsVector, query: TsQuery) -> Self {
+        Self {
+            cover_density: true,
+            ..Self::new(vector, query)
+        }
+    }
+
+    /// Set the normalization bitmask, e.g. `32` (`rank / (rank + 1)`).
+    pub fn normalization(mut self, bits: i32) -> Self {
+        self.normalization = Some(bits);
+        self
+    }
+
+    /// Project the rank as `AS alias` (ignored when used for sorting).
+    pub fn alias(mut self, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.alias = Some(alias.into_ident()?);
+        Ok(self)
+    }
+
+    fn push_parts(&self, parts: &mut Vec<ConditionPart>) {
+        parts.push(ConditionPart::Raw(if self.cover_density {
+            "ts_rank_cd("
+        } else {
+            "ts_rank("
+        }));
+        self.vector.push_parts(parts);
+        parts.push(ConditionPart::Raw(", "));
+        self.query.push_parts(parts);
+        if let Some(bits) = self.normalization {
+            parts.push(ConditionPart::Trusted(format!(", {bits}")));
+        }
+        parts.push(ConditionPart::Raw(")"));
+    }
+
+    /// Append the rank expression without its alias (used by ORDER BY).
+    pub(crate) fn append_expr_to_sql(&self, sql: &mut Sql) {
+        let mut parts = Vec::new();
+        self.push_parts(&mut parts);
+        append_parts(&parts, sql);
+    }
+
+    /// Append `ts_rank(...)[ AS alias]` to a SQL builder.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        self.append_expr_to_sql(sql);
+        if let Some(alias) = &self.alias {
+            sql.push(" AS ");
+            sql.push_ident_ref(alias);
+        }
+    }
+}
+
+/// A highlighted snippet: `ts_headline(column, query[, options])`.
+///
+/// Uses the query's text search configuration when one is set.
+#[derive(Debug, Clone)]
+pub struct TsHeadline {
+    column: Ident,
+    query: TsQuery,
+    options: Option<String>,
+    alias: Option<Ident>,
+}
+
+impl TsHeadline {
+    /// Highlight matches of `query` in `column`.
+    pub fn new(column: impl IntoIdent, query: TsQuery) -> OrmResult<Self> {
+        Ok(Self {
+            column: column.into_ident()?,
+            query,
+            options: None,
+            alias: None,
+        })
+    }
+
+    /// Set headline options (bound as a parameter), e.g.
+    /// `"StartSel=<mark>, StopSel=</mark>, MaxWords=35"`.
+    pub fn options(mut self, options: impl Into<String>) -> Self {
+        self.options = Some(options.into());
+        self
+    }
+
+    /// Project the snippet as `AS alias`.
+    pub fn alias(mut self, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.alias = Some(alias.into_ident()?);
+        Ok(self)
+    }
+
+    /// Append `ts_headline(...)[ AS alias]` to a SQL builder.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        let mut parts = vec![ConditionPart::Raw("ts_headline(")];
+        if let Some(config) = &self.query.config {
+            parts.push(ConditionPart::Trusted(config.clone()));
+        }
+        parts.push(ConditionPart::Ident(self.column.clone()));
+        parts.push(ConditionPart::Raw(", "));
+        self.query.push_parts(&mut parts);
+        if let Some(options) = &self.options {
+            parts.push(ConditionPart::Raw(", "));
+            parts.push(ConditionPart::Param(Arc::new(options.clone())));
+        }
+        parts.push(ConditionPart::Raw(")"));
+        append_parts(&parts, sql);
+
+        if let Some(alias) = &self.alias {
+            sql.push(" AS ");
+            sql.push_ident_ref(alias);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Condition;
+
+    fn render(f: impl FnOnce(&mut Sql)) -> Sql {
+        let mut sql = Sql::empty();
+        f(&mut sql);
+        sql
+    }
+
+    #[test]
+    fn query_modes() {
+        let sql = render(|s| TsQuery::plain("rust").append_to_sql(s));
+        assert_eq!(sql.to_sql(), "plainto_tsquery($1)");
+
+        let sql = render(|s| {
+            TsQuery::phrase("fast orm")
+                .config("english")
+                .unwrap()
+                .append_to_sql(s)
+        });
+        assert_eq!(sql.to_sql(), "phraseto_tsquery('english'::regconfig, $1)");
+
+        let sql = render(|s| TsQuery::websearch("\"rust orm\" -java").append_to_sql(s));
+        assert_eq!(sql.to_sql(), "websearch_to_tsquery($1)");
+
+        let sql = render(|s| TsQuery::raw("rust & orm").append_to_sql(s));
+        assert_eq!(sql.to_sql(), "to_tsquery($1)");
+    }
+
+    #[test]
+    fn config_is_validated() {
+        assert!(TsQuery::plain("x").config("pg_catalog.english").is_ok());
+        assert!(TsQuery::plain("x").config("english'; DROP").is_err());
+        assert!(TsVector::new().config("").is_err());
+    }
+
+    #[test]
+    fn weighted_vector() {
+        let vector = TsVector::new()
+            .weighted("title", TsWeight::A)
+            .unwrap()
+            .field("body")
+            .unwrap()
+            .config("english")
+            .unwrap();
+        let sql = render(|s| vector.append_to_sql(s));
+        assert_eq!(
+            sql.to_sql(),
+            "(setweight(to_tsvector('english'::regconfig, coalesce(title, '')), 'A') \
+             || to_tsvector('english'::regconfig, coalesce(body, '')))"
+        );
+        assert!(sql.params_ref().is_empty());
+    }
+
+    #[test]
+    fn ts_search_condition() {
+        let vector = TsVector::stored("search_vector").unwrap();
+        let query = TsQuery::websearch("rust").config("english").unwrap();
+        let cond = Condition::ts_search(&vector, &query);
+        let sql = render(|s| cond.append_to_sql(s));
+        assert_eq!(
+            sql.to_sql(),
+            "search_vector @@ websearch_to_tsquery('english'::regconfig, $1)"
+        );
+        assert_eq!(sql.params_ref().len(), 1);
+    }
+
+    #[test]
+    fn rank_projection_and_order() {
+        let rank = TsRank::cover_density(
+            TsVector::new().field("body").unwrap(),
+            TsQuery::plain("rust"),
+        )
+        .normalization(32)
+        .alias("rank")
+        .unwrap();
+        let sql = render(|s| rank.append_to_sql(s));
+        assert_eq!(
+            sql.to_sql(),
+            "ts_rank_cd(to_tsvector(coalesce(body, '')), plainto_tsquery($1), 32) AS rank"
+        );
+
+        let order = crate::OrderBy::new().rank(rank, crate::SortDir::Desc);
+        let sql = render(|s| order.append_to_sql(s));
+        assert_eq!(
+            sql.to_sql(),
+            " ORDER BY ts_rank_cd(to_tsvector(coalesce(body, '')), plainto_tsquery($1), 32) DESC"
+        );
+        assert_eq!(sql.params_ref().len(), 1);
+    }
+
+    #[test]
+    fn headline() {
+        let headline = TsHeadline::new(
+            "body",
+            TsQuery::websearch("rust").config("english").unwrap(),
+        )
+        .unwrap()
+        .options("MaxWords=20")
+        .alias("snippet")
+        .unwrap();
+        let sql = render(|s| headline.append_to_sql(s));
+        assert_eq!(
+            sql.to_sql(),
+            "ts_headline('english'::regconfig, body, \
+             websearch_to_tsquery('english'::regconfig, $1), $2) AS snippet"
+        );
+    }
+
+    #[test]
+    fn weight_parse() {
+        assert_eq!(TsWeight::parse("a").unwrap(), TsWeight::A);
+        assert_eq!(TsWeight::parse(" D ").unwrap(), TsWeight::D);
+        assert!(TsWeight::parse("E").is_err());
+    }
+}
@@ -1,9 +1,16 @@
 use super::parts::SqlPart;
-use crate::bulk::{DeleteManyBuilder, SetExpr, UpdateManyBuilder};
+use crate::builder::{OrderBy, Pagination};
+use crate::bulk::{
+    DeleteManyBuilder, InsertBuilder, InsertSelectBuilder, MergeBuilder, SetExpr, UpdateManyBuilder,
+};
+use crate::client::GenericClient;
 use crate::condition::Condition;
 use crate::cte::WithBuilder;
 use crate::error::{OrmError, OrmResult};
 use crate::ident::IntoIdent;
+use crate::lock::RowLock;
+use crate::page::{CountMode, Paginated, paginated_from_rows, paginated_sql};
+use crate::row::FromRow;
 use std::sync::Arc;
 use tokio_postgres::types::ToSql;
 
@@ -15,6 +22,7 @@ use tokio_postgres::types::ToSql;
 pub struct Sql {
     parts: Vec<SqlPart>,
     params: Vec<Arc<dyn ToSql + Sync + Send>>,
+    named: Vec<(String, Arc<dyn ToSql + Sync + Send>)>,
     tag: Option<String>,
 }
 
@@ -24,6 +32,7 @@ impl Sql {
         Self {
             parts: vec![SqlPart::Raw(initial_sql.into())],
             params: Vec::new(),
+            named: Vec::new(),
             tag: None,
         }
     }
@@ -33,6 +42,7 @@ impl Sql {
         Self {
             parts: Vec::new(),
             params: Vec::new(),
+            named: Vec::new(),
             tag: None,
         }
     }
@@ -123,16 +133,63 @@ impl Sql {
         self
     }
 
+    /// A copy of this builder sharing its bound values, for statements that repeat
+    /// the same subquery.
+    pub(crate) fn duplicate(&self) -> Sql {
+        Self {
+            parts: self.parts.clone(),
+            params: self.params.clone(),
+            named: self.named.clone(),
+            tag: self.tag.clone(),
+        }
+    }
+
     /// Append another `Sql` fragment, consuming it.
     pub fn push_sql(&mut self, mut other: Sql) -> &mut Self {
         self.parts.append(&mut other.parts);
         self.params.append(&mut other.params);
+        for (name, value) in other.named {
+            self.bind_named_value(name, value);
+        }
         if self.tag.is_none() {
             self.tag = other.tag;
         }
         self
     }
 
+    /// Bind a value to a named placeholder (`:name` or `@name`) in the raw SQL.
+    ///
+    /// Works like [`Query::bind_named`](crate::Query::bind_named): when the SQL is
+    /// rendered, named placeholders are rewritten to `$n`, numbered after the
+    /// positional parameters, and a name used several times is sent once. Names
+    /// are only looked for once a value has been bound by name. Executing fails
+    /// with `OrmError::Validation` if the SQL uses a name that was not bound, or a
+    /// bound name is not used.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut q = pgorm::sql("SELECT * FROM users WHERE org_id = :org");
+    /// if let Some(status) = status {
+    ///     q.push(" AND status = ").push_bind(status);
+    /// }
+    /// q.push(" AND (owner_id = :me OR reviewer_id = :me)");
+    /// q.bind_named("org", org_id).bind_named("me", user_id);
+    /// ```
+    pub fn bind_named<T>(&mut self, name: impl Into<String>, value: T) -> &mut Self
+    where
+        T: ToSql + Sync + Send + 'static,
+    {
+        self.bind_named_value(name.into(), Arc::new(value));
+        self
+    }
+
+    fn bind_named_value(&mut self, name: String, value: Arc<dyn ToSql + Sync + Send>) {
+        match self.named.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = value,
+            None => self.named.push((name, value)),
+        }
+    }
+
     /// Append a SQL identifier (schema/table/column) safely.
     ///
     /// This does **not** use parameters (Postgres doesn't allow parameterizing
@@ -168,7 +225,20 @@ impl Sql {
     }
 
     /// Render SQL with `$1, $2, ...` placeholders.
+    ///
+    /// Named placeholders bound with [`Sql::bind_named`] are rewritten to `$n`;
+    /// if they don't resolve, they are left as written.
     pub fn to_sql(&self) -> String {
+        if self.named.is_empty() {
+            return self.render();
+        }
+        match self.resolve() {
+            Ok((sql, _)) => sql,
+            Err(_) => self.render(),
+        }
+    }
+
+    fn render(&self) -> String {
         // Fast integer digit count (avoids division loop for common cases).
         #[inline]
         fn decimal_digits(n: usize) -> usize {
@@ -234,13 +304,66 @@ impl Sql {
     }
 
     /// Parameter refs compatible with `tokio-postgres`.
+    ///
+    /// Values bound with [`Sql::bind_named`] follow the positional ones, in the
+    /// order [`Sql::to_sql`] numbers them.
     pub fn params_ref(&self) -> Vec<&(dyn ToSql + Sync)> {
+        if self.named.is_empty() {
+            return self.positional_params();
+        }
+        match self.resolve() {
+            Ok((_, params)) => params,
+            Err(_) => self.positional_params(),
+        }
+    }
+
+    fn positional_params(&self) -> Vec<&(dyn ToSql + Sync)> {
         self.params
             .iter()
             .map(|p| p.as_ref() as &(dyn ToSql + Sync))
             .collect()
     }
 
+    /// The SQL and parameters to execute, with named placeholders rewritten to `$n`.
+    pub(super) fn resolve(&self) -> OrmResult<(String, Vec<&(dyn ToSql + Sync)>)> {
+        let sql = self.render();
+        let mut params = self.positional_params();
+        if self.named.is_empty() {
+            return Ok((sql, params));
+        }
+        let names: Vec<&str> = self.named.iter().map(|(n, _)| n.as_str()).collect();
+        match super::named::rewrite_named(&sql, params.len() + 1, &names)? {
+            None => Ok((sql, params)),
+            Some((rewritten, order)) => {
+                params.extend(
+                    order
+                        .into_iter()
+                        .map(|i| self.named[i].1.as_ref() as &(dyn ToSql + Sync)),
+                );
+                Ok((rewritten, params))
+            }
+        }
+    }
+
+    /// Render the SQL with bound values inlined as literals, for debugging.
+    ///
+    /// The result can be pasted into `psql`; it is not meant to be executed by
+    /// the application. See [`RedactionPolicy`](crate::RedactionPolicy) to hide
+    /// sensitive values.
+    pub fn to_debug_sql(&self) -> String {
+        self.to_debug_sql_with(&super::RedactionPolicy::default())
+    }
+
+    /// Like [`Sql::to_debug_sql`], hiding values selected by `policy`.
+    pub fn to_debug_sql_with(&self, policy: &super::RedactionPolicy) -> String {
+        match self.resolve() {
+            Ok((sql, params)) => super::debug::render_debug_sql(&sql, &params, policy),
+            Err(_) => {
+                super::debug::render_debug_sql(&self.render(), &self.positional_params(), policy)
+            }
+        }
+    }
+
     fn validate(&self) -> OrmResult<()> {
         let placeholder_count = self
             .parts
@@ -260,8 +383,7 @@ impl Sql {
     impl_query_exec! {
         prepare(self) {
             self.validate()?;
-            let sql = self.to_sql();
-            let params = self.params_ref();
+            let (sql, params) = self.resolve()?;
             let tag = self.tag.as_deref();
             (sql, params, tag)
         }
@@ -342,6 +464,36 @@ impl Sql {
             .push_bind(offset)
     }
 
+    /// Append a row-locking clause (`FOR UPDATE`, `FOR SHARE SKIP LOCKED`, ...).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let job: Option<Job> = sql("SELECT * FROM jobs WHERE status = 'queued' ORDER BY id")
+    ///     .limit(1)
+    ///     .row_lock(&RowLock::for_update().skip_locked())
+    ///     .fetch_opt_as(&tx)
+    ///     .await?;
+    /// ```
+    pub fn row_lock(&mut self, lock: &RowLock) -> &mut Self {
+        lock.append_to_sql(self);
+        self
+    }
+
+    /// Append an ORDER BY clause (nothing if `order_by` is empty).
+    ///
+    /// Handy after a set operation, where the ordering applies to the
+    /// combined result and must use output column names.
+    pub fn order_by(&mut self, order_by: &OrderBy) -> &mut Self {
+        order_by.append_to_sql(self);
+        self
+    }
+
+    /// Append LIMIT/OFFSET from a [`Pagination`] with bound parameters.
+    pub fn paginate(&mut self, pagination: &Pagination) -> &mut Self {
+        pagination.append_to_sql(self);
+        self
+    }
+
     /// Append pagination using page number and page size.
     ///
     /// Converts page-based pagination to LIMIT/OFFSET. Page numbers start at 1.
@@ -365,6 +517,31 @@ impl Sql {
         Ok(self.limit_offset(per_page, offset))
     }
 
+    /// Run this query as one offset page and return it with the total row count,
+    /// computed in the same statement.
+    ///
+    /// LIMIT/OFFSET is appended to the query, so its own ORDER BY picks the page;
+    /// do not add LIMIT/OFFSET yourself. The total is counted over a separate copy
+    /// of the query; use [`CountMode`] to cap or estimate it on huge tables.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let page: Paginated<User> = sql("SELECT * FROM users ORDER BY id")
+    ///     .paginate_with_total(&client, 3, 25, CountMode::Exact)
+    ///     .await?;
+    /// ```
+    pub async fn paginate_with_total<T: FromRow>(
+        self,
+        conn: &impl GenericClient,
+        page: i64,
+        per_page: i64,
+        count: CountMode,
+    ) -> OrmResult<Paginated<T>> {
+        let q = paginated_sql(self, page, per_page, &count)?;
+        let rows = q.fetch_all(conn).await?;
+        paginated_from_rows(&rows, page, per_page)
+    }
+
     // ==================== Consuming convenience APIs ====================
 
     /// Bind a parameter and return `self` (consuming version of [`push_bind`]).
@@ -383,6 +560,62 @@ impl Sql {
         self
     }
 
+    // ==================== Set operations ====================
+
+    fn set_operation(self, op: &str, other: Sql) -> Sql {
+        let mut combined = Sql::new("(");
+        combined.push_sql(self);
+        combined.push(") ");
+        combined.push(op);
+        combined.push(" (");
+        combined.push_sql(other);
+        combined.push(")");
+        combined
+    }
+
+    /// Combine two queries with `UNION` (duplicates removed).
+    ///
+    /// Both operands are parenthesized and their parameters renumbered, so each
+    /// side can carry its own `ORDER BY`/`LIMIT`. Use [`order_by`](Self::order_by) /
+    /// [`paginate`](Self::paginate) on the result to sort or page the combined rows.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut q = pgorm::sql("SELECT id, name FROM users WHERE status = ")
+    ///     .bind("active")
+    ///     .union_all(pgorm::sql("SELECT id, name FROM admins WHERE level > ").bind(2));
+    /// q.order_by(&OrderBy::new().desc("id")?).limit(20);
+    /// let people: Vec<Person> = q.fetch_all_as(&client).await?;
+    /// ```
+    pub fn union(self, other: Sql) -> Sql {
+        self.set_operation("UNION", other)
+    }
+
+    /// Combine two queries with `UNION ALL` (duplicates kept).
+    pub fn union_all(self, other: Sql) -> Sql {
+        self.set_operation("UNION ALL", other)
+    }
+
+    /// Rows returned by both queries (`INTERSECT`, duplicates removed).
+    pub fn intersect(self, other: Sql) -> Sql {
+        self.set_operation("INTERSECT", other)
+    }
+
+    /// Rows returned by both queries (`INTERSECT ALL`, duplicates kept).
+    pub fn intersect_all(self, other: Sql) -> Sql {
+        self.set_operation("INTERSECT ALL", other)
+    }
+
+    /// Rows of this query not returned by `other` (`EXCEPT`, duplicates removed).
+    pub fn except(self, other: Sql) -> Sql {
+        self.set_operation("EXCEPT", other)
+    }
+
+    /// Rows of this query not returned by `other` (`EXCEPT ALL`, duplicates kept).
+    pub fn except_all(self, other: Sql) -> Sql {
+        self.set_operation("EXCEPT ALL", other)
+    }
+
     // ==================== Bulk operations ====================
 
     /// Create a bulk UPDATE builder.
@@ -441,6 +674,98 @@ impl Sql {
         })
     }
 
+    /// Create a MERGE builder (PostgreSQL 15+).
+    ///
+    /// The initial SQL fragment is used as the target table name.
+    ///
+    /// # Example
+    /// ```ignore
+    /// pgorm::sql("products")
+    ///     .merge()?
+    ///     .using("staging_products", "s")?
+    ///     .on_columns(["sku"])?
+    ///     .when_matched_update([SetExpr::column("price", "s.price")?])
+    ///     .when_not_matched_insert([
+    ///         SetExpr::column("sku", "s.sku")?,
+    ///         SetExpr::column("price", "s.price")?,
+    ///     ])
+    ///     .execute(&client)
+    ///     .await?;
+    /// ```
+    pub fn merge(self) -> OrmResult<MergeBuilder> {
+        let table_name = self.to_sql();
+        let target = table_name.trim().into_ident()?;
+        Ok(MergeBuilder::new(target))
+    }
+
+    /// Create an `INSERT INTO <table> (columns) VALUES ...` builder.
+    ///
+    /// The initial SQL fragment is used as the table name. Rows are added with
+    /// `.values(...)` or, one array per column, with `.unnest(...)`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// pgorm::sql("products")
+    ///     .insert(["sku", "price"])?
+    ///     .values(("A-1", 100_i64))
+    ///     .on_conflict(OnConflict::columns(["sku"])?.do_update_excluded(["price"])?)
+    ///     .execute(&client)
+    ///     .await?;
+    /// ```
+    pub fn insert<I, T>(self, columns: I) -> OrmResult<InsertBuilder>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        let table_name = self.to_sql();
+        let table = table_name.trim().into_ident()?;
+        let columns = columns
+            .into_iter()
+            .map(IntoIdent::into_ident)
+            .collect::<OrmResult<Vec<_>>>()?;
+        if columns.is_empty() {
+            return Err(OrmError::Validation(
+                "insert requires at least one column".to_string(),
+            ));
+        }
+        Ok(InsertBuilder::new(table, columns))
+    }
+
+    /// Create an `INSERT INTO <table> (columns) <query>` builder.
+    ///
+    /// The initial SQL fragment is used as the table name. An empty column
+    /// list omits the column list.
+    ///
+    /// # Example
+    /// ```ignore
+    /// pgorm::sql("archived_orders")
+    ///     .insert_select(
+    ///         ["id", "total"],
+    ///         pgorm::sql("SELECT id, total FROM orders WHERE created_at < ").bind(cutoff),
+    ///     )?
+    ///     .on_conflict(OnConflict::columns(["id"])?.do_nothing())
+    ///     .execute(&client)
+    ///     .await?;
+    /// ```
+    pub fn insert_select<I, T>(self, columns: I, query: Sql) -> OrmResult<InsertSelectBuilder>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoIdent,
+    {
+        let table_name = self.to_sql();
+        let table = table_name.trim().into_ident()?;
+        let columns = columns
+            .into_iter()
+            .map(IntoIdent::into_ident)
+            .collect::<OrmResult<Vec<_>>>()?;
+        Ok(InsertSelectBuilder {
+            table,
+            columns,
+            query,
+            on_conflict: None,
+        })
+    }
+
     // ==================== CTE (WITH clause) ====================
 
     /// Start building a CTE (WITH clause) query.
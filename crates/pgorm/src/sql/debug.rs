@@ -0,0 +1,776 @@
+//! Debug rendering of SQL with parameter values inlined.
+//!
+//! [`Sql::to_debug_sql`](crate::Sql::to_debug_sql) and
+//! [`Query::to_debug_sql`](crate::Query::to_debug_sql) replace `$n` placeholders with
+//! SQL literals so a statement can be pasted into `psql`. The output is meant for
+//! humans only: never execute it.
+//!
+//! Values are recovered by encoding each parameter as a well-known Postgres type
+//! (text, integers, floats, bool, uuid, timestamps, json, bytea, arrays of those
+//! and ranges) and decoding it back. Anything else falls back to its `Debug` form
+//! as a quoted string.
+
+use crate::types::{Bound, Range};
+use bytes::BytesMut;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::collections::HashSet;
+use tokio_postgres::types::{FromSql, IsNull, Kind, ToSql, Type};
+
+/// Text written in place of redacted values.
+const REDACTED: &str = "'<redacted>'";
+
+/// Which parameter values to hide in debug SQL.
+///
+/// Columns are matched against the column a placeholder is compared with or
+/// assigned to (`password = $1`, `SET token = $2`, `INSERT INTO t (secret) VALUES ($3)`),
+/// ignoring case and table qualifiers. Parameter indexes are 1-based.
+///
+/// # Example
+/// ```ignore
+/// let policy = RedactionPolicy::new().columns(["password_hash", "api_token"]).param(3);
+/// println!("{}", q.to_debug_sql_with(&policy));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RedactionPolicy {
+    columns: HashSet<String>,
+    params: HashSet<usize>,
+    all: bool,
+}
+
+impl RedactionPolicy {
+    /// Create a policy that redacts nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redact values bound to this column.
+    pub fn column(mut self, column: impl AsRef<str>) -> Self {
+        self.columns.insert(normalize_column(column.as_ref()));
+        self
+    }
+
+    /// Redact values bound to any of these columns.
+    pub fn columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for column in columns {
+            self.columns.insert(normalize_column(column.as_ref()));
+        }
+        self
+    }
+
+    /// Redact the value of parameter `$index` (1-based).
+    pub fn param(mut self, index: usize) -> Self {
+        self.params.insert(index);
+        self
+    }
+
+    /// Redact every value (keeps the statement shape only).
+    pub fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    fn redacts(&self, index: usize, column: Option<&str>) -> bool {
+        self.all || self.params.contains(&index) || column.is_some_and(|c| self.columns.contains(c))
+    }
+}
+
+/// Last segment of a possibly qualified/quoted column name, lowercased.
+fn normalize_column(name: &str) -> String {
+    let last = name.rsplit('.').next().unwrap_or(name);
+    last.trim_matches('"').to_ascii_lowercase()
+}
+
+/// Render `sql` with its `$n` placeholders replaced by literals.
+pub(crate) fn render_debug_sql(
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+    policy: &RedactionPolicy,
+) -> String {
+    let placeholders = scan_placeholders(sql);
+    let mut out = String::with_capacity(sql.len() + params.len() * 8);
+    let mut last = 0;
+    let mut prev: Option<(usize, Option<String>)> = None;
+
+    for ph in placeholders {
+        let column = param_column(sql, ph.start, prev.as_ref());
+        out.push_str(&sql[last..ph.start]);
+        match params.get(ph.index.wrapping_sub(1)) {
+            Some(_) if policy.redacts(ph.index, column.as_deref()) => out.push_str(REDACTED),
+            Some(param) => out.push_str(&param_literal(*param)),
+            None => out.push_str(&sql[ph.start..ph.end]),
+        }
+        last = ph.end;
+        prev = Some((ph.end, column));
+    }
+    out.push_str(&sql[last..]);
+    out
+}
+
+// ==================== Placeholder scanning ====================
+
+/// A `$n` placeholder found outside literals and comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Placeholder {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) index: usize,
+}
+
+pub(super) fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80
+}
+
+/// If a string literal, quoted identifier, comment or dollar-quoted string starts
+/// at `i`, return the index just past it.
+pub(super) fn skip_non_code(bytes: &[u8], i: usize) -> Option<usize> {
+    match bytes[i] {
+        b'\'' => {
+            let escapes = i > 0
+                && matches!(bytes[i - 1], b'E' | b'e')
+                && (i < 2 || !is_ident_byte(bytes[i - 2]));
+            Some(skip_string(bytes, i + 1, escapes))
+        }
+        b'"' => Some(skip_quoted_ident(bytes, i + 1)),
+        b'-' if bytes.get(i + 1) == Some(&b'-') => Some(
+            bytes[i..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(bytes.len(), |p| i + p + 1),
+        ),
+        b'/' if bytes.get(i + 1) == Some(&b'*') => Some(skip_block_comment(bytes, i + 2)),
+        b'$' if (i == 0 || !is_ident_byte(bytes[i - 1]))
+            && !bytes.get(i + 1).is_some_and(u8::is_ascii_digit) =>
+        {
+            skip_dollar_quoted(bytes, i)
+        }
+        _ => None,
+    }
+}
+
+/// Find `$n` placeholders, skipping string literals, quoted identifiers,
+/// comments and dollar-quoted strings.
+pub(crate) fn scan_placeholders(sql: &str) -> Vec<Placeholder> {
+    let bytes = sql.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if let Some(next) = skip_non_code(bytes, i) {
+            i = next;
+            continue;
+        }
+        if bytes[i] == b'$' && (i == 0 || !is_ident_byte(bytes[i - 1])) {
+            let digits = bytes[i + 1..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            if digits > 0 {
+                let end = i + 1 + digits;
+                if let Ok(index) = sql[i + 1..end].parse() {
+                    out.push(Placeholder {
+                        start: i,
+                        end,
+                        index,
+                    });
+                }
+                i = end;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+fn skip_string(bytes: &[u8], mut i: usize, escapes: bool) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if escapes => i += 2,
+            b'\'' if bytes.get(i + 1) == Some(&b'\'') => i += 2,
+            b'\'' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_quoted_ident(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' if bytes.get(i + 1) == Some(&b'"') => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_block_comment(bytes: &[u8], mut i: usize) -> usize {
+    let mut depth = 1;
+    while i < bytes.len() {
+        if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Skip `$tag$ ... $tag$` starting at `start` (a `$`), if it opens a dollar quote.
+fn skip_dollar_quoted(bytes: &[u8], start: usize) -> Option<usize> {
+    let tag_len = bytes[start + 1..]
+        .iter()
+        .take_while(|&&b| is_ident_byte(b))
+        .count();
+    let tag_end = start + 1 + tag_len;
+    if bytes.get(tag_end) != Some(&b'$') {
+        return None;
+    }
+    let tag = &bytes[start..=tag_end];
+    let body = tag_end + 1;
+    Some(
+        bytes[body..]
+            .windows(tag.len())
+            .position(|w| w == tag)
+            .map_or(bytes.len(), |p| body + p + tag.len()),
+    )
+}
+
+// ==================== Column inference ====================
+
+const OPERATOR_KEYWORDS: &[&str] = &[
+    "LIKE", "ILIKE", "IN", "ANY", "ALL", "NOT", "IS", "DISTINCT", "FROM", "SIMILAR", "TO",
+    "BETWEEN",
+];
+
+/// Guess the column a placeholder is compared with or assigned to.
+fn param_column(sql: &str, start: usize, prev: Option<&(usize, Option<String>)>) -> Option<String> {
+    // Later items of a list or the upper bound of BETWEEN share the previous column.
+    if let Some((prev_end, Some(column))) = prev {
+        let gap = strip_cast(&sql[*prev_end..start]).trim();
+        if gap == "," || gap.eq_ignore_ascii_case("AND") {
+            if gap == "," && values_column(sql, start).is_some() {
+                return values_column(sql, start);
+            }
+            return Some(column.clone());
+        }
+    }
+    if let Some(column) = values_column(sql, start) {
+        return Some(column);
+    }
+
+    let mut before = sql[..start].trim_end();
+    let mut stripped = false;
+    loop {
+        let trimmed = before.trim_end_matches(|c: char| "=<>!~@&|(".contains(c));
+        let trimmed = trimmed.trim_end();
+        let upper_tail = trimmed.to_ascii_uppercase();
+        let keyword = OPERATOR_KEYWORDS.iter().find(|k| {
+            upper_tail.ends_with(*k)
+                && trimmed[..trimmed.len() - k.len()]
+                    .bytes()
+                    .last()
+                    .is_none_or(|b| !is_ident_byte(b))
+        });
+        let next = match keyword {
+            Some(k) => trimmed[..trimmed.len() - k.len()].trim_end(),
+            None => trimmed,
+        };
+        if next.len() == before.len() {
+            break;
+        }
+        stripped = true;
+        before = next;
+    }
+    if !stripped {
+        return None;
+    }
+
+    let ident_start = before
+        .bytes()
+        .rposition(|b| !(is_ident_byte(b) || b == b'.' || b == b'"' || b == b'$'))
+        .map_or(0, |p| p + 1);
+    let ident = &before[ident_start..];
+    if ident.is_empty() || ident.as_bytes()[0].is_ascii_digit() {
+        return None;
+    }
+    Some(normalize_column(ident))
+}
+
+/// Drop a trailing `::type` cast from the text between two placeholders.
+fn strip_cast(gap: &str) -> &str {
+    match gap.find("::") {
+        Some(0) => {
+            let rest = &gap[2..];
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '[' || c == ']'))
+                .unwrap_or(rest.len());
+            &rest[end..]
+        }
+        _ => gap,
+    }
+}
+
+/// Column of a placeholder inside `INSERT INTO t (cols) VALUES (...)`, by position.
+fn values_column(sql: &str, start: usize) -> Option<String> {
+    let bytes = sql.as_bytes();
+    let mut depth = 0usize;
+    let mut commas = 0usize;
+    let mut open = None;
+    for i in (0..start).rev() {
+        match bytes[i] {
+            b')' => depth += 1,
+            b'(' if depth == 0 => {
+                open = Some(i);
+                break;
+            }
+            b'(' => depth -= 1,
+            b',' if depth == 0 => commas += 1,
+            _ => {}
+        }
+    }
+    let open = open?;
+
+    let upper = sql[..open].to_ascii_uppercase();
+    let values_at = upper.rfind("VALUES")?;
+    let between = upper[values_at + "VALUES".len()..].trim();
+    if !(between.is_empty() || between.ends_with("),")) {
+        return None;
+    }
+
+    let insert_at = upper[..values_at].rfind("INSERT INTO")?;
+    let list_open = insert_at + upper[insert_at..values_at].find('(')?;
+    let list_close = list_open + upper[list_open..values_at].find(')')?;
+    sql[list_open + 1..list_close]
+        .split(',')
+        .nth(commas)
+        .map(|c| normalize_column(c.trim()))
+}
+
+// ==================== Literal rendering ====================
+
+/// Quote a string as a SQL literal (`standard_conforming_strings = on`).
+fn quote_literal(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for ch in s.chars() {
+        if ch == '\'' {
+            out.push('\'');
+        }
+        out.push(ch);
+    }
+    out.push('\'');
+    out
+}
+
+/// A decoded value that can be written back as a SQL literal.
+trait PgLiteral {
+    /// Text form used inside array and range literals.
+    fn pg_text(&self) -> String;
+    /// Standalone SQL literal.
+    fn sql_literal(&self) -> String;
+}
+
+macro_rules! number_literal {
+    ($($t:ty),*) => {$(
+        impl PgLiteral for $t {
+            fn pg_text(&self) -> String {
+                self.to_string()
+            }
+            fn sql_literal(&self) -> String {
+                self.to_string()
+            }
+        }
+    )*};
+}
+
+number_literal!(i16, i32, i64);
+
+macro_rules! float_literal {
+    ($($t:ty => $cast:literal),*) => {$(
+        impl PgLiteral for $t {
+            fn pg_text(&self) -> String {
+                if self.is_nan() {
+                    "NaN".to_string()
+                } else if self.is_infinite() {
+                    if *self > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+                } else {
+                    self.to_string()
+                }
+            }
+            fn sql_literal(&self) -> String {
+                if self.is_finite() {
+                    self.to_string()
+                } else {
+                    format!("{}::{}", quote_literal(&self.pg_text()), $cast)
+                }
+            }
+        }
+    )*};
+}
+
+float_literal!(f32 => "float4", f64 => "float8");
+
+impl PgLiteral for bool {
+    fn pg_text(&self) -> String {
+        self.to_string()
+    }
+    fn sql_literal(&self) -> String {
+        if *self { "TRUE" } else { "FALSE" }.to_string()
+    }
+}
+
+impl PgLiteral for String {
+    fn pg_text(&self) -> String {
+        self.clone()
+    }
+    fn sql_literal(&self) -> String {
+        quote_literal(self)
+    }
+}
+
+macro_rules! cast_literal {
+    ($($t:ty => $cast:literal, |$v:ident| $text:expr);* $(;)?) => {$(
+        impl PgLiteral for $t {
+            fn pg_text(&self) -> String {
+                let $v = self;
+                $text
+            }
+            fn sql_literal(&self) -> String {
+                format!("{}::{}", quote_literal(&self.pg_text()), $cast)
+            }
+        }
+    )*};
+}
+
+cast_literal! {
+    uuid::Uuid => "uuid", |v| v.to_string();
+    DateTime<Utc> => "timestamptz", |v| v.format("%Y-%m-%d %H:%M:%S%.f+00").to_string();
+    NaiveDateTime => "timestamp", |v| v.format("%Y-%m-%d %H:%M:%S%.f").to_string();
+    NaiveDate => "date", |v| v.format("%Y-%m-%d").to_string();
+    NaiveTime => "time", |v| v.format("%H:%M:%S%.f").to_string();
+    serde_json::Value => "jsonb", |v| v.to_string();
+    Vec<u8> => "bytea", |v| {
+        let mut s = String::with_capacity(2 + v.len() * 2);
+        s.push_str("\\x");
+        for b in v {
+            s.push_str(&format!("{b:02x}"));
+        }
+        s
+    };
+}
+
+fn array_literal<T: PgLiteral>(values: Vec<Option<T>>, element_type: &str) -> String {
+    if values.is_empty() {
+        return format!("'{{}}'::{element_type}[]");
+    }
+    let items: Vec<String> = values
+        .iter()
+        .map(|v| {
+            v.as_ref()
+                .map_or_else(|| "NULL".to_string(), T::sql_literal)
+        })
+        .collect();
+    format!("ARRAY[{}]::{element_type}[]", items.join(", "))
+}
+
+fn range_literal<T: PgLiteral>(range: Range<T>, range_type: &str) -> String {
+    fn bound_text<T: PgLiteral>(value: &T) -> String {
+        let text = value.pg_text();
+        if text.contains(|c: char| " ,()[]\"\\".contains(c)) {
+            format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+        } else {
+            text
+        }
+    }
+
+    if range.is_empty() {
+        return format!("'empty'::{range_type}");
+    }
+    let mut text = String::new();
+    match &range.lower {
+        Some(Bound::Inclusive(v)) => text.push_str(&format!("[{}", bound_text(v))),
+        Some(Bound::Exclusive(v)) => text.push_str(&format!("({}", bound_text(v))),
+        None => text.push('('),
+    }
+    text.push(',');
+    match &range.upper {
+        Some(Bound::Inclusive(v)) => text.push_str(&format!("{}]", bound_text(v))),
+        Some(Bound::Exclusive(v)) => text.push_str(&format!("{})", bound_text(v))),
+        None => text.push(')'),
+    }
+    format!("{}::{range_type}", quote_literal(&text))
+}
+
+/// Types tried, in order, when recovering a parameter value.
+const PROBE_TYPES: &[Type] = &[
+    Type::BOOL,
+    Type::INT2,
+    Type::INT4,
+    Type::INT8,
+    Type::FLOAT4,
+    Type::FLOAT8,
+    Type::TEXT,
+    Type::UUID,
+    Type::TIMESTAMPTZ,
+    Type::TIMESTAMP,
+    Type::DATE,
+    Type::TIME,
+    Type::JSONB,
+    Type::JSON,
+    Type::BYTEA,
+    Type::BOOL_ARRAY,
+    Type::INT2_ARRAY,
+    Type::INT4_ARRAY,
+    Type::INT8_ARRAY,
+    Type::FLOAT4_ARRAY,
+    Type::FLOAT8_ARRAY,
+    Type::TEXT_ARRAY,
+    Type::UUID_ARRAY,
+    Type::TIMESTAMPTZ_ARRAY,
+    Type::TIMESTAMP_ARRAY,
+    Type::DATE_ARRAY,
+    Type::JSONB_ARRAY,
+    Type::INT4_RANGE,
+    Type::INT8_RANGE,
+    Type::TSTZ_RANGE,
+    Type::TS_RANGE,
+    Type::DATE_RANGE,
+];
+
+fn decode<'a, T: FromSql<'a>>(ty: &Type, raw: &'a [u8]) -> Option<T> {
+    T::from_sql(ty, raw).ok()
+}
+
+fn decode_literal(ty: &Type, raw: &[u8]) -> Option<String> {
+    let element = match ty.kind() {
+        Kind::Array(element) => Some(element.name()),
+        _ => None,
+    };
+    macro_rules! array {
+        ($t:ty) => {{
+            let element = element?;
+            decode::<Vec<Option<$t>>>(ty, raw).map(|v| array_literal(v, element))
+        }};
+    }
+    macro_rules! range {
+        ($t:ty) => {
+            decode::<Range<$t>>(ty, raw).map(|r| range_literal(r, ty.name()))
+        };
+    }
+
+    match *ty {
+        Type::BOOL => decode::<bool>(ty, raw).map(|v| v.sql_literal()),
+        Type::INT2 => decode::<i16>(ty, raw).map(|v| v.sql_literal()),
+        Type::INT4 => decode::<i32>(ty, raw).map(|v| v.sql_literal()),
+        Type::INT8 => decode::<i64>(ty, raw).map(|v| v.sql_literal()),
+        Type::FLOAT4 => decode::<f32>(ty, raw).map(|v| v.sql_literal()),
+        Type::FLOAT8 => decode::<f64>(ty, raw).map(|v| v.sql_literal()),
+        Type::TEXT => decode::<String>(ty, raw).map(|v| v.sql_literal()),
+        Type::UUID => decode::<uuid::Uuid>(ty, raw).map(|v| v.sql_literal()),
+        Type::TIMESTAMPTZ => decode::<DateTime<Utc>>(ty, raw).map(|v| v.sql_literal()),
+        Type::TIMESTAMP => decode::<NaiveDateTime>(ty, raw).map(|v| v.sql_literal()),
+        Type::DATE => decode::<NaiveDate>(ty, raw).map(|v| v.sql_literal()),
+        Type::TIME => decode::<NaiveTime>(ty, raw).map(|v| v.sql_literal()),
+        Type::JSONB => decode::<serde_json::Value>(ty, raw).map(|v| v.sql_literal()),
+        Type::JSON => decode::<serde_json::Value>(ty, raw)
+            .map(|v| format!("{}::json", quote_literal(&v.to_string()))),
+        Type::BYTEA => decode::<Vec<u8>>(ty, raw).map(|v| v.sql_literal()),
+        Type::BOOL_ARRAY => array!(bool),
+        Type::INT2_ARRAY => array!(i16),
+        Type::INT4_ARRAY => array!(i32),
+        Type::INT8_ARRAY => array!(i64),
+        Type::FLOAT4_ARRAY => array!(f32),
+        Type::FLOAT8_ARRAY => array!(f64),
+        Type::TEXT_ARRAY => array!(String),
+        Type::UUID_ARRAY => array!(uuid::Uuid),
+        Type::TIMESTAMPTZ_ARRAY => array!(DateTime<Utc>),
+        Type::TIMESTAMP_ARRAY => array!(NaiveDateTime),
+        Type::DATE_ARRAY => array!(NaiveDate),
+        Type::JSONB_ARRAY => array!(serde_json::Value),
+        Type::INT4_RANGE => range!(i32),
+        Type::INT8_RANGE => range!(i64),
+        Type::TSTZ_RANGE => range!(DateTime<Utc>),
+        Type::TS_RANGE => range!(NaiveDateTime),
+        Type::DATE_RANGE => range!(NaiveDate),
+        _ => None,
+    }
+}
+
+/// Render one parameter as a SQL literal.
+pub(crate) fn param_literal(param: &(dyn ToSql + Sync)) -> String {
+    let mut buf = BytesMut::new();
+    for ty in PROBE_TYPES {
+        buf.clear();
+        match param.to_sql_checked(ty, &mut buf) {
+            Ok(IsNull::Yes) => return "NULL".to_string(),
+            Ok(IsNull::No) => {
+                if let Some(literal) = decode_literal(ty, &buf) {
+                    return literal;
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    quote_literal(&format!("{param:?}"))
+}
+
+/// Recover one parameter as a JSON value (used to serialize conditions).
+///
+/// Scalars, json and arrays of scalars are supported; `None` for anything else.
+pub(crate) fn param_json(param: &(dyn ToSql + Sync)) -> Option<serde_json::Value> {
+    let mut buf = BytesMut::new();
+    for ty in PROBE_TYPES {
+        buf.clear();
+        match param.to_sql_checked(ty, &mut buf) {
+            Ok(IsNull::Yes) => return Some(serde_json::Value::Null),
+            Ok(IsNull::No) => {
+                if let Some(value) = decode_json(ty, &buf) {
+                    return Some(value);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    None
+}
+
+fn decode_json(ty: &Type, raw: &[u8]) -> Option<serde_json::Value> {
+    macro_rules! json {
+        ($t:ty) => {
+            decode::<$t>(ty, raw).and_then(|v| serde_json::to_value(v).ok())
+        };
+    }
+
+    match *ty {
+        Type::BOOL => json!(bool),
+        Type::INT2 => json!(i16),
+        Type::INT4 => json!(i32),
+        Type::INT8 => json!(i64),
+        Type::FLOAT4 => json!(f32),
+        Type::FLOAT8 => json!(f64),
+        Type::TEXT => json!(String),
+        Type::UUID => json!(uuid::Uuid),
+        Type::TIMESTAMPTZ => json!(DateTime<Utc>),
+        Type::TIMESTAMP => json!(NaiveDateTime),
+        Type::DATE => json!(NaiveDate),
+        Type::TIME => json!(NaiveTime),
+        Type::JSONB | Type::JSON => decode::<serde_json::Value>(ty, raw),
+        Type::BOOL_ARRAY => json!(Vec<Option<bool>>),
+        Type::INT2_ARRAY => json!(Vec<Option<i16>>),
+        Type::INT4_ARRAY => json!(Vec<Option<i32>>),
+        Type::INT8_ARRAY => json!(Vec<Option<i64>>),
+        Type::FLOAT4_ARRAY => json!(Vec<Option<f32>>),
+        Type::FLOAT8_ARRAY => json!(Vec<Option<f64>>),
+        Type::TEXT_ARRAY => json!(Vec<Option<String>>),
+        Type::UUID_ARRAY => json!(Vec<Option<uuid::Uuid>>),
+        Type::TIMESTAMPTZ_ARRAY => json!(Vec<Option<DateTime<Utc>>>),
+        Type::TIMESTAMP_ARRAY => json!(Vec<Option<NaiveDateTime>>),
+        Type::DATE_ARRAY => json!(Vec<Option<NaiveDate>>),
+        Type::JSONB_ARRAY => json!(Vec<Option<serde_json::Value>>),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(sql: &str, params: &[&(dyn ToSql + Sync)]) -> String {
+        render_debug_sql(sql, params, &RedactionPolicy::new())
+    }
+
+    #[test]
+    fn scans_placeholders_outside_literals_and_comments() {
+        let sql = "SELECT '$1', \"a$2\", $$ $3 $$, $tag$ $4 $tag$ -- $5\n FROM t /* $6 /* $7 */ */ WHERE a = $8 AND b$9 = $10";
+        let found: Vec<usize> = scan_placeholders(sql).iter().map(|p| p.index).collect();
+        assert_eq!(found, vec![8, 10]);
+    }
+
+    #[test]
+    fn renders_scalar_literals() {
+        let id = uuid::Uuid::nil();
+        let at = DateTime::parse_from_rfc3339("2024-05-01T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let none: Option<i32> = None;
+        let sql = render(
+            "SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9",
+            &[
+                &"it's",
+                &42_i64,
+                &1.5_f64,
+                &true,
+                &id,
+                &at,
+                &serde_json::json!({"a": 1}),
+                &none,
+                &vec![0xde_u8, 0xad],
+            ],
+        );
+        assert_eq!(
+            sql,
+            "SELECT 'it''s', 42, 1.5, TRUE, '00000000-0000-0000-0000-000000000000'::uuid, \
+             '2024-05-01 12:30:00+00'::timestamptz, '{\"a\":1}'::jsonb, NULL, '\\xdead'::bytea"
+        );
+    }
+
+    #[test]
+    fn renders_arrays_and_ranges() {
+        let sql = render(
+            "SELECT $1, $2, $3, $4",
+            &[
+                &vec!["a".to_string(), "b c".to_string()],
+                &Vec::<i64>::new(),
+                &Range::lower_inc(1_i32, 10),
+                &Range::<NaiveDate>::new(
+                    Some(Bound::Inclusive(
+                        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    )),
+                    None,
+                ),
+            ],
+        );
+        assert_eq!(
+            sql,
+            "SELECT ARRAY['a', 'b c']::text[], '{}'::int8[], '[1,10)'::int4range, '[2024-01-01,)'::daterange"
+        );
+    }
+
+    #[test]
+    fn redacts_by_column_and_index() {
+        let policy = RedactionPolicy::new().column("users.password").param(4);
+        let sql = render_debug_sql(
+            "UPDATE users SET \"Password\" = $1 WHERE email ILIKE $2 AND id IN ($3, $4)",
+            &[&"hunter2", &"%@x.com", &1_i64, &2_i64],
+            &policy,
+        );
+        assert_eq!(
+            sql,
+            "UPDATE users SET \"Password\" = '<redacted>' WHERE email ILIKE '%@x.com' AND id IN (1, '<redacted>')"
+        );
+
+        let policy = RedactionPolicy::new().columns(["token"]);
+        let sql = render_debug_sql(
+            "INSERT INTO sessions (user_id, token) VALUES ($1, $2), ($3, $4) LIMIT $5",
+            &[&1_i64, &"abc", &2_i64, &"def", &9_i64],
+            &policy,
+        );
+        assert_eq!(
+            sql,
+            "INSERT INTO sessions (user_id, token) VALUES (1, '<redacted>'), (2, '<redacted>') LIMIT 9"
+        );
+    }
+}
@@ -22,6 +22,8 @@
 #[macro_use]
 mod exec_macros;
 mod builder;
+mod debug;
+mod named;
 mod parts;
 mod query;
 mod stream;
@@ -30,6 +32,8 @@ mod stream;
 mod tests;
 
 pub use builder::Sql;
+pub use debug::RedactionPolicy;
+pub(crate) use debug::{param_json, render_debug_sql};
 pub use query::Query;
 pub use stream::FromRowStream;
 
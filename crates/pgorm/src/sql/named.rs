@@ -0,0 +1,143 @@
+//! Named placeholders (`:name` / `@name`) for [`Query`](crate::Query) and
+//! [`Sql`](crate::Sql).
+//!
+//! Named placeholders are rewritten to positional `$n` parameters right before
+//! execution. Each distinct name gets a single parameter number, so a value bound
+//! once can be referenced several times. String literals, quoted identifiers,
+//! comments, dollar-quoted bodies and `::` casts are left untouched.
+
+use super::debug::{is_ident_byte, skip_non_code};
+use crate::error::{OrmError, OrmResult};
+
+/// A named placeholder found in SQL text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NamedPlaceholder<'a> {
+    start: usize,
+    end: usize,
+    name: &'a str,
+}
+
+fn scan_named(sql: &str) -> Vec<NamedPlaceholder<'_>> {
+    let bytes = sql.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if let Some(next) = skip_non_code(bytes, i) {
+            i = next;
+            continue;
+        }
+        let starts_name = matches!(bytes[i], b':' | b'@')
+            && bytes
+                .get(i + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_')
+            && (i == 0 || !(is_ident_byte(bytes[i - 1]) || b":@<".contains(&bytes[i - 1])));
+        if starts_name {
+            let len = bytes[i + 1..]
+                .iter()
+                .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+                .count();
+            let end = i + 1 + len;
+            out.push(NamedPlaceholder {
+                start: i,
+                end,
+                name: &sql[i + 1..end],
+            });
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Rewrite named placeholders to `$n`, numbering them from `first_index`.
+///
+/// Returns `None` when the SQL has no named placeholders and nothing was bound
+/// by name. Otherwise returns the rewritten SQL and, for each new parameter in
+/// order, the index into `names` of the value to bind.
+pub(super) fn rewrite_named(
+    sql: &str,
+    first_index: usize,
+    names: &[&str],
+) -> OrmResult<Option<(String, Vec<usize>)>> {
+    let placeholders = scan_named(sql);
+    if placeholders.is_empty() && names.is_empty() {
+        return Ok(None);
+    }
+
+    let mut order: Vec<usize> = Vec::new();
+    let mut out = String::with_capacity(sql.len());
+    let mut last = 0;
+    for ph in &placeholders {
+        let Some(bound) = names.iter().position(|n| *n == ph.name) else {
+            return Err(OrmError::Validation(format!(
+                "named parameter `{}` is used in SQL but not bound",
+                ph.name
+            )));
+        };
+        let number = match order.iter().position(|&i| i == bound) {
+            Some(pos) => first_index + pos,
+            None => {
+                order.push(bound);
+                first_index + order.len() - 1
+            }
+        };
+        out.push_str(&sql[last..ph.start]);
+        out.push('$');
+        out.push_str(&number.to_string());
+        last = ph.end;
+    }
+    out.push_str(&sql[last..]);
+
+    if let Some(unused) = (0..names.len()).find(|i| !order.contains(i)) {
+        return Err(OrmError::Validation(format!(
+            "named parameter `{}` is bound but not used in SQL",
+            names[unused]
+        )));
+    }
+    Ok(Some((out, order)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_and_reuses_names() {
+        let (sql, order) = rewrite_named(
+            "SELECT * FROM t WHERE a = :id OR b = @name OR c = :id",
+            1,
+            &["name", "id"],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(sql, "SELECT * FROM t WHERE a = $1 OR b = $2 OR c = $1");
+        assert_eq!(order, vec![1, 0]);
+    }
+
+    #[test]
+    fn skips_casts_literals_and_comments() {
+        let (sql, _) = rewrite_named(
+            "SELECT ':a', \":b\", $$ :c $$, x::text, arr[1:2], arr[lo:hi], e @> '{}', e <@ v \
+             -- :d\n /* @e */ FROM t WHERE id = :id::bigint",
+            3,
+            &["id"],
+        )
+        .unwrap()
+        .unwrap();
+        assert!(sql.ends_with("WHERE id = $3::bigint"));
+        assert!(sql.contains("':a', \":b\", $$ :c $$, x::text, arr[1:2], arr[lo:hi]"));
+
+        assert!(rewrite_named("SELECT 1::int", 1, &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn unbound_and_unused_names_are_errors() {
+        let err = rewrite_named("SELECT :a, :b", 1, &["a"]).unwrap_err();
+        assert!(matches!(err, OrmError::Validation(ref m) if m.contains("`b`")));
+
+        let err = rewrite_named("SELECT :a", 1, &["a", "c"]).unwrap_err();
+        assert!(matches!(err, OrmError::Validation(ref m) if m.contains("`c`")));
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) enum SqlPart {
     Raw(String),
     Param,
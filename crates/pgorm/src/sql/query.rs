@@ -1,13 +1,17 @@
+use crate::error::OrmResult;
+use std::borrow::Cow;
 use std::sync::Arc;
 use tokio_postgres::types::ToSql;
 
 /// A SQL string with pre-numbered placeholders (`$1, $2, ...`) plus bound parameters.
 ///
 /// Use this when you already have a complete SQL string and just want to bind values.
+/// Named placeholders (`:name` / `@name`) are also supported, see [`Query::bind_named`].
 #[must_use]
 pub struct Query {
     sql: String,
     params: Vec<Arc<dyn ToSql + Sync + Send>>,
+    named: Vec<(String, Arc<dyn ToSql + Sync + Send>)>,
     tag: Option<String>,
 }
 
@@ -17,6 +21,7 @@ impl Query {
         Self {
             sql: sql.into(),
             params: Vec::new(),
+            named: Vec::new(),
             tag: None,
         }
     }
@@ -48,12 +53,51 @@ impl Query {
         self
     }
 
+    /// Bind a value to a named placeholder (`:name` or `@name`).
+    ///
+    /// A name may appear several times in the SQL; it is sent as a single
+    /// parameter. Before execution, named placeholders are rewritten to `$n`,
+    /// numbered after the values bound with [`Query::bind`]. Binding the same
+    /// name again replaces the value.
+    ///
+    /// Named placeholders are only looked for once a value has been bound by name;
+    /// without `bind_named`, the SQL is sent as written (so `arr[:hi]` or the `@`
+    /// operator keep working). Once bound, execution fails with
+    /// `OrmError::Validation` if the SQL uses a name that was not bound, or a bound
+    /// name is not used.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let users: Vec<User> = pgorm::query(
+    ///     "SELECT * FROM users WHERE org_id = :org AND (owner_id = :me OR reviewer_id = :me)",
+    /// )
+    /// .bind_named("org", org_id)
+    /// .bind_named("me", user_id)
+    /// .fetch_all_as(&pg)
+    /// .await?;
+    /// ```
+    pub fn bind_named<T>(mut self, name: impl Into<String>, value: T) -> Self
+    where
+        T: ToSql + Sync + Send + 'static,
+    {
+        let name = name.into();
+        let value: Arc<dyn ToSql + Sync + Send> = Arc::new(value);
+        match self.named.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = value,
+            None => self.named.push((name, value)),
+        }
+        self
+    }
+
     /// Access the SQL string.
     pub fn sql(&self) -> &str {
         &self.sql
     }
 
     /// Parameter refs compatible with `tokio-postgres`.
+    ///
+    /// Only includes values bound with [`Query::bind`]; named values are added
+    /// when the SQL is resolved for execution.
     pub fn params_ref(&self) -> Vec<&(dyn ToSql + Sync)> {
         self.params
             .iter()
@@ -61,10 +105,44 @@ impl Query {
             .collect()
     }
 
+    /// Render the SQL with bound values inlined as literals, for debugging.
+    ///
+    /// See [`Sql::to_debug_sql`](crate::Sql::to_debug_sql).
+    pub fn to_debug_sql(&self) -> String {
+        self.to_debug_sql_with(&super::RedactionPolicy::default())
+    }
+
+    /// Like [`Query::to_debug_sql`], hiding values selected by `policy`.
+    pub fn to_debug_sql_with(&self, policy: &super::RedactionPolicy) -> String {
+        match self.resolve() {
+            Ok((sql, params)) => super::debug::render_debug_sql(&sql, &params, policy),
+            Err(_) => super::debug::render_debug_sql(&self.sql, &self.params_ref(), policy),
+        }
+    }
+
+    /// The SQL and parameters to execute, with named placeholders rewritten to `$n`.
+    pub(super) fn resolve(&self) -> OrmResult<(Cow<'_, str>, Vec<&(dyn ToSql + Sync)>)> {
+        let mut params = self.params_ref();
+        if self.named.is_empty() {
+            return Ok((Cow::Borrowed(self.sql.as_str()), params));
+        }
+        let names: Vec<&str> = self.named.iter().map(|(n, _)| n.as_str()).collect();
+        match super::named::rewrite_named(&self.sql, params.len() + 1, &names)? {
+            None => Ok((Cow::Borrowed(self.sql.as_str()), params)),
+            Some((sql, order)) => {
+                params.extend(
+                    order
+                        .into_iter()
+                        .map(|i| self.named[i].1.as_ref() as &(dyn ToSql + Sync)),
+                );
+                Ok((Cow::Owned(sql), params))
+            }
+        }
+    }
+
     impl_query_exec! {
         prepare(self) {
-            let sql = &self.sql;
-            let params = self.params_ref();
+            let (sql, params) = self.resolve()?;
             let tag = self.tag.as_deref();
             (sql, params, tag)
         }
@@ -308,3 +308,133 @@ async fn fetch_scalar_one_strict_multi_rows_is_too_many_rows() {
         }
     ));
 }
+
+#[test]
+fn set_operations_renumber_params() {
+    let q = sql("SELECT id FROM users WHERE status = ")
+        .bind("active")
+        .union_all(sql("SELECT id FROM admins WHERE level > ").bind(2_i32))
+        .except(sql("SELECT id FROM banned WHERE until > ").bind(0_i64));
+
+    assert_eq!(
+        q.to_sql(),
+        "((SELECT id FROM users WHERE status = $1) UNION ALL (SELECT id FROM admins WHERE level > $2)) \
+         EXCEPT (SELECT id FROM banned WHERE until > $3)"
+    );
+    assert_eq!(q.params_ref().len(), 3);
+
+    let q = sql("SELECT 1").intersect_all(sql("SELECT 2"));
+    assert_eq!(q.to_sql(), "(SELECT 1) INTERSECT ALL (SELECT 2)");
+}
+
+#[test]
+fn set_operation_with_trailing_order_and_pagination() {
+    let mut q = sql("SELECT id, name FROM users WHERE id > ")
+        .bind(10_i64)
+        .union(sql("SELECT id, name FROM admins"));
+    q.order_by(&crate::OrderBy::new().desc("id").unwrap())
+        .paginate(&crate::Pagination::new().limit(20).offset(40));
+
+    assert_eq!(
+        q.to_sql(),
+        "(SELECT id, name FROM users WHERE id > $1) UNION (SELECT id, name FROM admins) \
+         ORDER BY id DESC LIMIT $2 OFFSET $3"
+    );
+    assert_eq!(q.params_ref().len(), 3);
+}
+
+#[test]
+fn debug_sql_inlines_params() {
+    let mut q = sql("SELECT * FROM users WHERE email = ");
+    q.push_bind("a@b.com")
+        .push(" AND api_key = ")
+        .push_bind("k-123")
+        .limit(10);
+
+    assert_eq!(
+        q.to_debug_sql(),
+        "SELECT * FROM users WHERE email = 'a@b.com' AND api_key = 'k-123' LIMIT 10"
+    );
+    assert_eq!(
+        q.to_debug_sql_with(&crate::RedactionPolicy::new().column("api_key")),
+        "SELECT * FROM users WHERE email = 'a@b.com' AND api_key = '<redacted>' LIMIT 10"
+    );
+
+    let q = query("SELECT $1::text || '$2', $2").bind("x").bind(7_i32);
+    assert_eq!(q.to_debug_sql(), "SELECT 'x'::text || '$2', 7");
+}
+
+#[test]
+fn query_named_params_are_rewritten() {
+    let q =
+        query("SELECT * FROM tasks WHERE (owner_id = :me OR reviewer_id = :me) AND status = $1")
+            .bind("open")
+            .bind_named("me", 7_i64);
+    assert_eq!(
+        q.to_debug_sql(),
+        "SELECT * FROM tasks WHERE (owner_id = 7 OR reviewer_id = 7) AND status = 'open'"
+    );
+    let (sql, params) = q.resolve().unwrap();
+    assert_eq!(
+        sql,
+        "SELECT * FROM tasks WHERE (owner_id = $2 OR reviewer_id = $2) AND status = $1"
+    );
+    assert_eq!(params.len(), 2);
+
+    let q = query("SELECT :a::int")
+        .bind_named("a", 1_i32)
+        .bind_named("a", 2_i32);
+    assert_eq!(q.to_debug_sql(), "SELECT 2::int");
+
+    assert!(matches!(
+        query("SELECT :a, :missing")
+            .bind_named("a", 1_i32)
+            .resolve(),
+        Err(crate::OrmError::Validation(_))
+    ));
+    assert!(matches!(
+        query("SELECT 1").bind_named("extra", 1_i32).resolve(),
+        Err(crate::OrmError::Validation(_))
+    ));
+}
+
+#[test]
+fn sql_named_params_follow_positional_ones() {
+    let mut inner = sql("SELECT id FROM tasks WHERE owner_id = :me");
+    inner.bind_named("me", 7_i64);
+    let mut q = sql("SELECT * FROM tasks WHERE status = ");
+    q.push_bind("open")
+        .push(" AND (reviewer_id = :me OR id IN (")
+        .push_sql(inner)
+        .push("))");
+    assert_eq!(
+        q.to_sql(),
+        "SELECT * FROM tasks WHERE status = $1 AND (reviewer_id = $2 OR id IN \
+         (SELECT id FROM tasks WHERE owner_id = $2))"
+    );
+    assert_eq!(q.params_ref().len(), 2);
+    assert_eq!(
+        q.to_debug_sql(),
+        "SELECT * FROM tasks WHERE status = 'open' AND (reviewer_id = 7 OR id IN \
+         (SELECT id FROM tasks WHERE owner_id = 7))"
+    );
+
+    let mut q = sql("SELECT :a, :missing");
+    q.bind_named("a", 1_i32);
+    assert!(matches!(q.resolve(), Err(crate::OrmError::Validation(_))));
+    assert_eq!(q.to_sql(), "SELECT :a, :missing");
+
+    // Without named binds the text is left alone.
+    let mut q = sql("SELECT arr[:hi] FROM t WHERE id = ");
+    q.push_bind(1_i64);
+    assert_eq!(q.to_sql(), "SELECT arr[:hi] FROM t WHERE id = $1");
+}
+
+#[test]
+fn query_without_named_binds_is_sent_unchanged() {
+    let q = query("SELECT arr[:hi], @delta, :x FROM t WHERE id = $1").bind(1_i64);
+    let (sql, params) = q.resolve().unwrap();
+    assert_eq!(sql, "SELECT arr[:hi], @delta, :x FROM t WHERE id = $1");
+    assert!(matches!(sql, std::borrow::Cow::Borrowed(_)));
+    assert_eq!(params.len(), 1);
+}
@@ -561,6 +561,25 @@ impl crate::GenericClient for Savepoint<'_> {
     }
 }
 
+// ─── TransactionClient ──────────────────────────────────────────────────────
+
+/// Marker trait for clients that are known to run inside a transaction.
+///
+/// Row locks (`SELECT ... FOR UPDATE`) are released at the end of the
+/// transaction, so in autocommit mode they are released immediately. APIs such
+/// as the generated `select_by_pk_for_update` take `&impl TransactionClient`
+/// to rule that mistake out at compile time.
+pub trait TransactionClient: crate::GenericClient {}
+
+impl TransactionClient for tokio_postgres::Transaction<'_> {}
+
+#[cfg(feature = "pool")]
+impl TransactionClient for deadpool_postgres::Transaction<'_> {}
+
+impl TransactionClient for Savepoint<'_> {}
+
+impl<C: TransactionClient> TransactionClient for &C {}
+
 // ─── TransactionExt ─────────────────────────────────────────────────────────
 
 /// Extension trait adding savepoint support to transactions.
@@ -0,0 +1,336 @@
+//! Adjacency-list trees (`parent_id` columns) queried with recursive CTEs.
+//!
+//! `#[derive(Model)]` with `#[orm(tree(parent = "parent_id"))]` implements [`TreeModel`]
+//! and generates `roots`, `ancestors`, `path_to_root`, `descendants` and
+//! `subtree_as_nested` on the model, which call the functions of this module.
+//!
+//! Traversals are built with [`Sql::with_recursive`] and carry the depth
+//! ([`DEPTH_ALIAS`]) and the id path from the starting node ([`PATH_ALIAS`]).
+//! A row whose id is already on the path is not visited again, so cycles in the
+//! data end the walk instead of looping forever.
+//!
+//! # Example
+//! ```ignore
+//! #[derive(Debug, FromRow, Model)]
+//! #[orm(table = "categories")]
+//! #[orm(tree(parent = "parent_id"))]
+//! struct Category {
+//!     #[orm(id)]
+//!     id: i64,
+//!     parent_id: Option<i64>,
+//!     name: String,
+//! }
+//!
+//! let crumbs = Category::path_to_root(&client, 42).await?; // 42, its parent, ..., root
+//! let tree = Category::subtree_as_nested(&client, 1, Some(3)).await?;
+//! ```
+
+use crate::ModelPk;
+use crate::check::TableMeta;
+use crate::client::GenericClient;
+use crate::error::OrmResult;
+use crate::row::{FromRow, RowExt};
+use crate::sql::Sql;
+use serde::Serialize;
+use tokio_postgres::Row;
+use tokio_postgres::types::{FromSqlOwned, ToSql};
+
+/// Column alias carrying the depth (distance from the starting node).
+pub const DEPTH_ALIAS: &str = "__pgorm_depth";
+
+/// Column alias carrying the id path from the starting node (inclusive).
+pub const PATH_ALIAS: &str = "__pgorm_path";
+
+/// Name of the recursive CTE.
+const TREE_CTE: &str = "__pgorm_tree";
+
+/// Alias of the table joined in the recursive term.
+const NODE_ALIAS: &str = "__pgorm_node";
+
+/// A model stored as an adjacency list (implemented by `#[orm(tree(parent = "..."))]`).
+pub trait TreeModel: TableMeta + FromRow + ModelPk {
+    /// The primary key column.
+    const ID_COLUMN: &'static str;
+    /// The column referencing the parent row (`NULL` for roots).
+    const PARENT_COLUMN: &'static str;
+}
+
+/// A row returned by a traversal, with its depth and id path.
+#[derive(Debug, Clone)]
+pub struct TreeNode<T: ModelPk> {
+    pub node: T,
+    /// Distance from the starting node (`0` for the starting node itself).
+    pub depth: i32,
+    /// Ids from the starting node to this node, both inclusive.
+    pub path: Vec<T::Id>,
+}
+
+impl<T> FromRow for TreeNode<T>
+where
+    T: TreeModel,
+    T::Id: FromSqlOwned,
+{
+    fn from_row(row: &Row) -> OrmResult<Self> {
+        Ok(Self {
+            node: T::from_row(row)?,
+            depth: row.try_get_column(DEPTH_ALIAS)?,
+            path: row.try_get_column(PATH_ALIAS)?,
+        })
+    }
+}
+
+/// A node with its children, as built by [`nest`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Nested<T> {
+    #[serde(flatten)]
+    pub node: T,
+    pub depth: i32,
+    pub children: Vec<Nested<T>>,
+}
+
+/// Assemble depth-first ordered nodes (as returned by [`descendants`]) into trees.
+///
+/// Nodes must be in pre-order (each node directly after its parent or a sibling's
+/// subtree); nodes at the smallest depth become the returned roots.
+pub fn nest<T: ModelPk>(nodes: impl IntoIterator<Item = TreeNode<T>>) -> Vec<Nested<T>> {
+    fn close<T>(stack: &mut Vec<Nested<T>>, roots: &mut Vec<Nested<T>>) {
+        if let Some(done) = stack.pop() {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<Nested<T>> = Vec::new();
+    for node in nodes {
+        while stack.last().is_some_and(|top| top.depth >= node.depth) {
+            close(&mut stack, &mut roots);
+        }
+        stack.push(Nested {
+            node: node.node,
+            depth: node.depth,
+            children: Vec::new(),
+        });
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+/// Which way a traversal walks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// From a node to its children.
+    Down,
+    /// From a node to its parent.
+    Up,
+}
+
+/// `WITH RECURSIVE` walk starting at `id` (depth 0), up to `max_depth` steps away.
+fn traversal_sql<T>(id: T::Id, direction: Direction, max_depth: Option<i32>) -> OrmResult<Sql>
+where
+    T: TreeModel,
+    T::Id: ToSql,
+{
+    let columns = T::columns().join(", ");
+    let node_columns = T::columns()
+        .iter()
+        .map(|c| format!("{NODE_ALIAS}.{c}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let (id_col, parent_col, table) = (T::ID_COLUMN, T::PARENT_COLUMN, T::table_name());
+
+    let mut base = Sql::new(format!(
+        "SELECT {columns}, 0 AS {DEPTH_ALIAS}, ARRAY[{id_col}] AS {PATH_ALIAS} FROM {table} WHERE {id_col} = "
+    ));
+    base.push_bind(id);
+
+    let join = match direction {
+        Direction::Down => format!("{NODE_ALIAS}.{parent_col} = {TREE_CTE}.{id_col}"),
+        Direction::Up => format!("{NODE_ALIAS}.{id_col} = {TREE_CTE}.{parent_col}"),
+    };
+    let mut recursive = Sql::new(format!(
+        "SELECT {node_columns}, {TREE_CTE}.{DEPTH_ALIAS} + 1, {TREE_CTE}.{PATH_ALIAS} || {NODE_ALIAS}.{id_col} \
+         FROM {table} {NODE_ALIAS} JOIN {TREE_CTE} ON {join} \
+         WHERE {NODE_ALIAS}.{id_col} <> ALL({TREE_CTE}.{PATH_ALIAS})"
+    ));
+    if let Some(max_depth) = max_depth {
+        recursive.push(&format!(" AND {TREE_CTE}.{DEPTH_ALIAS} < "));
+        recursive.push_bind(max_depth);
+    }
+
+    let order = match direction {
+        Direction::Down => PATH_ALIAS,
+        Direction::Up => DEPTH_ALIAS,
+    };
+    Ok(Sql::empty()
+        .with_recursive(TREE_CTE, base, recursive)?
+        .select(Sql::new(format!(
+            "SELECT * FROM {TREE_CTE} ORDER BY {order}"
+        ))))
+}
+
+/// Rows whose parent column is `NULL`, ordered by id.
+pub async fn roots<T: TreeModel>(conn: &impl GenericClient) -> OrmResult<Vec<T>> {
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {} IS NULL ORDER BY {}",
+        T::columns().join(", "),
+        T::table_name(),
+        T::PARENT_COLUMN,
+        T::ID_COLUMN
+    );
+    let rows = conn.query(&sql, &[]).await?;
+    rows.iter().map(T::from_row).collect()
+}
+
+/// The ancestors of `id`, nearest first (the parent has depth 1).
+pub async fn ancestors<T>(conn: &impl GenericClient, id: T::Id) -> OrmResult<Vec<TreeNode<T>>>
+where
+    T: TreeModel,
+    T::Id: ToSql + FromSqlOwned,
+{
+    let mut nodes: Vec<TreeNode<T>> = traversal_sql::<T>(id, Direction::Up, None)?
+        .fetch_all_as(conn)
+        .await?;
+    nodes.retain(|n| n.depth > 0);
+    Ok(nodes)
+}
+
+/// `id` followed by its ancestors up to the root (empty if `id` does not exist).
+pub async fn path_to_root<T>(conn: &impl GenericClient, id: T::Id) -> OrmResult<Vec<T>>
+where
+    T: TreeModel,
+    T::Id: ToSql + FromSqlOwned,
+{
+    let nodes: Vec<TreeNode<T>> = traversal_sql::<T>(id, Direction::Up, None)?
+        .fetch_all_as(conn)
+        .await?;
+    Ok(nodes.into_iter().map(|n| n.node).collect())
+}
+
+/// The descendants of `id` in depth-first order, at most `max_depth` levels below it.
+pub async fn descendants<T>(
+    conn: &impl GenericClient,
+    id: T::Id,
+    max_depth: Option<i32>,
+) -> OrmResult<Vec<TreeNode<T>>>
+where
+    T: TreeModel,
+    T::Id: ToSql + FromSqlOwned,
+{
+    let mut nodes: Vec<TreeNode<T>> = traversal_sql::<T>(id, Direction::Down, max_depth)?
+        .fetch_all_as(conn)
+        .await?;
+    nodes.retain(|n| n.depth > 0);
+    Ok(nodes)
+}
+
+/// `id` and its descendants (at most `max_depth` levels below it) as a nested tree.
+///
+/// Returns `None` if `id` does not exist.
+pub async fn subtree_as_nested<T>(
+    conn: &impl GenericClient,
+    id: T::Id,
+    max_depth: Option<i32>,
+) -> OrmResult<Option<Nested<T>>>
+where
+    T: TreeModel,
+    T::Id: ToSql + FromSqlOwned,
+{
+    let nodes: Vec<TreeNode<T>> = traversal_sql::<T>(id, Direction::Down, max_depth)?
+        .fetch_all_as(conn)
+        .await?;
+    Ok(nest(nodes).into_iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Category {
+        id: i64,
+    }
+
+    impl TableMeta for Category {
+        fn table_name() -> &'static str {
+            "categories"
+        }
+        fn columns() -> &'static [&'static str] {
+            &["id", "parent_id", "name"]
+        }
+    }
+
+    impl FromRow for Category {
+        fn from_row(row: &Row) -> OrmResult<Self> {
+            Ok(Self {
+                id: row.try_get_column("id")?,
+            })
+        }
+    }
+
+    impl ModelPk for Category {
+        type Id = i64;
+        fn pk(&self) -> &i64 {
+            &self.id
+        }
+    }
+
+    impl TreeModel for Category {
+        const ID_COLUMN: &'static str = "id";
+        const PARENT_COLUMN: &'static str = "parent_id";
+    }
+
+    #[test]
+    fn descendants_walk_children_with_cycle_guard() {
+        let sql = traversal_sql::<Category>(1, Direction::Down, Some(2)).unwrap();
+        assert_eq!(
+            sql.to_sql(),
+            "WITH RECURSIVE __pgorm_tree AS (\
+             SELECT id, parent_id, name, 0 AS __pgorm_depth, ARRAY[id] AS __pgorm_path \
+             FROM categories WHERE id = $1 \
+             UNION ALL \
+             SELECT __pgorm_node.id, __pgorm_node.parent_id, __pgorm_node.name, \
+             __pgorm_tree.__pgorm_depth + 1, __pgorm_tree.__pgorm_path || __pgorm_node.id \
+             FROM categories __pgorm_node JOIN __pgorm_tree ON __pgorm_node.parent_id = __pgorm_tree.id \
+             WHERE __pgorm_node.id <> ALL(__pgorm_tree.__pgorm_path) AND __pgorm_tree.__pgorm_depth < $2) \
+             SELECT * FROM __pgorm_tree ORDER BY __pgorm_path"
+        );
+        assert_eq!(sql.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn ancestors_walk_parents() {
+        let sql = traversal_sql::<Category>(7, Direction::Up, None)
+            .unwrap()
+            .to_sql();
+        assert!(sql.contains("JOIN __pgorm_tree ON __pgorm_node.id = __pgorm_tree.parent_id"));
+        assert!(sql.ends_with("ORDER BY __pgorm_depth"));
+        assert!(!sql.contains("$2"));
+    }
+
+    #[test]
+    fn nest_builds_trees_from_preorder() {
+        let node = |id: i64, path: &[i64]| TreeNode {
+            node: Category { id },
+            depth: path.len() as i32 - 1,
+            path: path.to_vec(),
+        };
+        let trees = nest([
+            node(1, &[1]),
+            node(2, &[1, 2]),
+            node(4, &[1, 2, 4]),
+            node(3, &[1, 3]),
+        ]);
+        assert_eq!(trees.len(), 1);
+        let root = &trees[0];
+        assert_eq!(root.node.id, 1);
+        let children: Vec<i64> = root.children.iter().map(|c| c.node.id).collect();
+        assert_eq!(children, [2, 3]);
+        assert_eq!(root.children[0].children[0].node.id, 4);
+        assert_eq!(root.children[0].children[0].depth, 2);
+        assert!(root.children[1].children.is_empty());
+    }
+}
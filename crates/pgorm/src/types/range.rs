@@ -151,6 +151,9 @@ where
     ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
         let element_type =
             range_element_type(ty).ok_or_else(|| format!("unsupported range type: {ty}"))?;
+        if !T::accepts(&element_type) {
+            return Err(format!("range bound type does not match {ty}").into());
+        }
 
         if self.empty {
             out.extend_from_slice(&[RANGE_EMPTY]);
@@ -0,0 +1,736 @@
+//! Window functions and analytic query helpers.
+//!
+//! This module provides:
+//! - [`WindowSpec`]: `PARTITION BY` / `ORDER BY` / frame definition (reuses [`OrderBy`])
+//! - [`WindowExpr`]: a window function call with its `OVER (...)` clause
+//! - [`NamedWindows`]: the `WINDOW name AS (...)` clause
+//! - [`TopNPerGroup`]: "top N rows per group" built on `ROW_NUMBER()`
+//!
+//! # Example
+//! ```ignore
+//! use pgorm::{OrderBy, WindowExpr, WindowFrame, WindowSpec, FrameBound};
+//!
+//! let running_total = WindowExpr::sum("amount")?
+//!     .over(
+//!         WindowSpec::new()
+//!             .partition_by("account_id")?
+//!             .order_by(OrderBy::new().asc("created_at")?)
+//!             .frame(WindowFrame::rows(FrameBound::UnboundedPreceding, FrameBound::CurrentRow)),
+//!     )
+//!     .alias("running_total")?;
+//!
+//! let mut q = pgorm::sql("SELECT id, amount, ");
+//! running_total.append_to_sql(&mut q);
+//! q.push(" FROM payments");
+//! ```
+
+use crate::builder::OrderBy;
+use crate::error::{OrmError, OrmResult};
+use crate::ident::{Ident, IntoIdent};
+use crate::sql::Sql;
+
+/// Column alias used by [`TopNPerGroup`] for the row number.
+pub const ROW_NUMBER_ALIAS: &str = "__pgorm_rn";
+
+/// Default alias of the subquery wrapped by [`TopNPerGroup`].
+const RANKED_ALIAS: &str = "__pgorm_ranked";
+
+// ==================== Frames ====================
+
+/// Frame units (`ROWS`, `RANGE`, `GROUPS`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameUnits {
+    Rows,
+    Range,
+    Groups,
+}
+
+impl FrameUnits {
+    fn to_sql(self) -> &'static str {
+        match self {
+            FrameUnits::Rows => "ROWS",
+            FrameUnits::Range => "RANGE",
+            FrameUnits::Groups => "GROUPS",
+        }
+    }
+}
+
+/// A frame boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(u64),
+    CurrentRow,
+    Following(u64),
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    fn write_sql(self, out: &mut String) {
+        match self {
+            FrameBound::UnboundedPreceding => out.push_str("UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(n) => out.push_str(&format!("{n} PRECEDING")),
+            FrameBound::CurrentRow => out.push_str("CURRENT ROW"),
+            FrameBound::Following(n) => out.push_str(&format!("{n} FOLLOWING")),
+            FrameBound::UnboundedFollowing => out.push_str("UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+/// A window frame clause, e.g. `ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowFrame {
+    pub units: FrameUnits,
+    pub start: FrameBound,
+    pub end: Option<FrameBound>,
+}
+
+impl WindowFrame {
+    /// `ROWS BETWEEN start AND end`.
+    pub fn rows(start: FrameBound, end: FrameBound) -> Self {
+        Self::between(FrameUnits::Rows, start, end)
+    }
+
+    /// `RANGE BETWEEN start AND end`.
+    pub fn range(start: FrameBound, end: FrameBound) -> Self {
+        Self::between(FrameUnits::Range, start, end)
+    }
+
+    /// `GROUPS BETWEEN start AND end`.
+    pub fn groups(start: FrameBound, end: FrameBound) -> Self {
+        Self::between(FrameUnits::Groups, start, end)
+    }
+
+    /// `<units> BETWEEN start AND end`.
+    pub fn between(units: FrameUnits, start: FrameBound, end: FrameBound) -> Self {
+        Self {
+            units,
+            start,
+            end: Some(end),
+        }
+    }
+
+    /// `<units> start` (frame ends at the current row).
+    pub fn starting(units: FrameUnits, start: FrameBound) -> Self {
+        Self {
+            units,
+            start,
+            end: None,
+        }
+    }
+
+    fn write_sql(&self, out: &mut String) {
+        out.push_str(self.units.to_sql());
+        out.push(' ');
+        match self.end {
+            Some(end) => {
+                out.push_str("BETWEEN ");
+                self.start.write_sql(out);
+                out.push_str(" AND ");
+                end.write_sql(out);
+            }
+            None => self.start.write_sql(out),
+        }
+    }
+}
+
+// ==================== WindowSpec ====================
+
+/// A window definition: `([existing] PARTITION BY ... ORDER BY ... [frame])`.
+#[derive(Debug, Clone, Default)]
+pub struct WindowSpec {
+    existing: Option<Ident>,
+    partition_by: Vec<Ident>,
+    order_by: OrderBy,
+    frame: Option<WindowFrame>,
+}
+
+impl WindowSpec {
+    /// Create an empty window definition (`OVER ()`).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build on top of a named window (`OVER (w ORDER BY ...)`).
+    pub fn based_on(mut self, name: impl IntoIdent) -> OrmResult<Self> {
+        self.existing = Some(name.into_ident()?);
+        Ok(self)
+    }
+
+    /// Add a `PARTITION BY` column (validated identifier).
+    pub fn partition_by(mut self, column: impl IntoIdent) -> OrmResult<Self> {
+        self.partition_by.push(column.into_ident()?);
+        Ok(self)
+    }
+
+    /// Replace the `ORDER BY` of the window.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    /// Set the frame clause.
+    pub fn frame(mut self, frame: WindowFrame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    /// Append the parenthesized window definition to a SQL builder.
+    ///
+    /// Values bound by the `ORDER BY` (e.g. [`OrderBy::rank`]) are added to `sql`.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        let mut head = String::from("(");
+        let mut sep = "";
+        if let Some(existing) = &self.existing {
+            existing.write_sql(&mut head);
+            sep = " ";
+        }
+        if !self.partition_by.is_empty() {
+            head.push_str(sep);
+            head.push_str("PARTITION BY ");
+            for (i, col) in self.partition_by.iter().enumerate() {
+                if i > 0 {
+                    head.push_str(", ");
+                }
+                col.write_sql(&mut head);
+            }
+            sep = " ";
+        }
+        sql.push(&head);
+        if !self.order_by.is_empty() {
+            sql.push(sep);
+            self.order_by.append_clause_to_sql(sql);
+            sep = " ";
+        }
+        let mut tail = String::new();
+        if let Some(frame) = &self.frame {
+            tail.push_str(sep);
+            frame.write_sql(&mut tail);
+        }
+        tail.push(')');
+        sql.push(&tail);
+    }
+
+    /// Render the parenthesized window definition.
+    ///
+    /// Bound values are dropped; use [`append_to_sql`](Self::append_to_sql) when the
+    /// `ORDER BY` ranks by full-text relevance.
+    pub fn to_sql(&self) -> String {
+        let mut sql = Sql::empty();
+        self.append_to_sql(&mut sql);
+        sql.to_sql()
+    }
+}
+
+// ==================== WindowExpr ====================
+
+#[derive(Debug, Clone)]
+enum Over {
+    Spec(WindowSpec),
+    Named(Ident),
+}
+
+/// A window function call: `func(...) OVER (...) [AS alias]`.
+///
+/// Column arguments are validated identifiers; numeric arguments are rendered
+/// as integer literals. Only an `ORDER BY` over [`OrderBy::rank`] binds values,
+/// so prefer [`append_to_sql`](Self::append_to_sql) over [`to_sql`](Self::to_sql).
+#[derive(Debug, Clone)]
+pub struct WindowExpr {
+    func: String,
+    over: Over,
+    alias: Option<Ident>,
+}
+
+impl WindowExpr {
+    fn call(func: String) -> Self {
+        Self {
+            func,
+            over: Over::Spec(WindowSpec::new()),
+            alias: None,
+        }
+    }
+
+    fn call_column(name: &str, column: impl IntoIdent) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        Ok(Self::call(format!("{name}({})", column.to_sql())))
+    }
+
+    fn call_column_n(name: &str, column: impl IntoIdent, n: i64) -> OrmResult<Self> {
+        let column = column.into_ident()?;
+        Ok(Self::call(format!("{name}({}, {n})", column.to_sql())))
+    }
+
+    /// Raw function call (escape hatch), e.g. `"SUM(amount) FILTER (WHERE paid)"`.
+    ///
+    /// **Warning**: This bypasses SQL injection protection. Only use with
+    /// trusted, hardcoded SQL strings.
+    pub fn raw(func: impl Into<String>) -> Self {
+        Self::call(func.into())
+    }
+
+    /// `ROW_NUMBER()`
+    pub fn row_number() -> Self {
+        Self::call("ROW_NUMBER()".to_string())
+    }
+
+    /// `RANK()`
+    pub fn rank() -> Self {
+        Self::call("RANK()".to_string())
+    }
+
+    /// `DENSE_RANK()`
+    pub fn dense_rank() -> Self {
+        Self::call("DENSE_RANK()".to_string())
+    }
+
+    /// `PERCENT_RANK()`
+    pub fn percent_rank() -> Self {
+        Self::call("PERCENT_RANK()".to_string())
+    }
+
+    /// `CUME_DIST()`
+    pub fn cume_dist() -> Self {
+        Self::call("CUME_DIST()".to_string())
+    }
+
+    /// `NTILE(buckets)`
+    pub fn ntile(buckets: i64) -> OrmResult<Self> {
+        if buckets < 1 {
+            return Err(OrmError::validation(format!(
+                "ntile buckets must be >= 1, got {buckets}"
+            )));
+        }
+        Ok(Self::call(format!("NTILE({buckets})")))
+    }
+
+    /// `LAG(column)`
+    pub fn lag(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("LAG", column)
+    }
+
+    /// `LAG(column, offset)`
+    pub fn lag_by(column: impl IntoIdent, offset: i64) -> OrmResult<Self> {
+        Self::call_column_n("LAG", column, offset)
+    }
+
+    /// `LEAD(column)`
+    pub fn lead(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("LEAD", column)
+    }
+
+    /// `LEAD(column, offset)`
+    pub fn lead_by(column: impl IntoIdent, offset: i64) -> OrmResult<Self> {
+        Self::call_column_n("LEAD", column, offset)
+    }
+
+    /// `FIRST_VALUE(column)`
+    pub fn first_value(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("FIRST_VALUE", column)
+    }
+
+    /// `LAST_VALUE(column)`
+    pub fn last_value(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("LAST_VALUE", column)
+    }
+
+    /// `NTH_VALUE(column, n)`
+    pub fn nth_value(column: impl IntoIdent, n: i64) -> OrmResult<Self> {
+        Self::call_column_n("NTH_VALUE", column, n)
+    }
+
+    /// `SUM(column)`
+    pub fn sum(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("SUM", column)
+    }
+
+    /// `AVG(column)`
+    pub fn avg(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("AVG", column)
+    }
+
+    /// `MIN(column)`
+    pub fn min(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("MIN", column)
+    }
+
+    /// `MAX(column)`
+    pub fn max(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("MAX", column)
+    }
+
+    /// `COUNT(column)`
+    pub fn count(column: impl IntoIdent) -> OrmResult<Self> {
+        Self::call_column("COUNT", column)
+    }
+
+    /// `COUNT(*)`
+    pub fn count_all() -> Self {
+        Self::call("COUNT(*)".to_string())
+    }
+
+    /// Set the window definition (`OVER (...)`).
+    pub fn over(mut self, spec: WindowSpec) -> Self {
+        self.over = Over::Spec(spec);
+        self
+    }
+
+    /// Reference a named window (`OVER name`), see [`NamedWindows`].
+    pub fn over_named(mut self, name: impl IntoIdent) -> OrmResult<Self> {
+        self.over = Over::Named(name.into_ident()?);
+        Ok(self)
+    }
+
+    /// Set the output column alias (`AS alias`).
+    pub fn alias(mut self, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.alias = Some(alias.into_ident()?);
+        Ok(self)
+    }
+
+    /// Append this expression to a SQL builder (e.g. inside a SELECT list).
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        sql.push(&self.func);
+        sql.push(" OVER ");
+        match &self.over {
+            Over::Spec(spec) => spec.append_to_sql(sql),
+            Over::Named(name) => {
+                sql.push_ident_ref(name);
+            }
+        }
+        if let Some(alias) = &self.alias {
+            sql.push(" AS ");
+            sql.push_ident_ref(alias);
+        }
+    }
+
+    /// Render this expression as a SQL string.
+    ///
+    /// Bound values are dropped, see [`WindowSpec::to_sql`].
+    pub fn to_sql(&self) -> String {
+        let mut sql = Sql::empty();
+        self.append_to_sql(&mut sql);
+        sql.to_sql()
+    }
+}
+
+// ==================== NamedWindows ====================
+
+/// The `WINDOW name AS (...)` clause.
+///
+/// # Example
+/// ```ignore
+/// let windows = NamedWindows::new()
+///     .define("w", WindowSpec::new().partition_by("team_id")?)?;
+/// let rank = WindowExpr::rank().over_named("w")?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NamedWindows {
+    defs: Vec<(Ident, WindowSpec)>,
+}
+
+impl NamedWindows {
+    /// Create an empty WINDOW clause.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a named window.
+    pub fn define(mut self, name: impl IntoIdent, spec: WindowSpec) -> OrmResult<Self> {
+        self.defs.push((name.into_ident()?, spec));
+        Ok(self)
+    }
+
+    /// Check if no window is defined.
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// Append the WINDOW clause to a SQL builder.
+    ///
+    /// Does nothing if no window is defined.
+    pub fn append_to_sql(&self, sql: &mut Sql) {
+        if self.defs.is_empty() {
+            return;
+        }
+        sql.push(" WINDOW ");
+        for (i, (name, spec)) in self.defs.iter().enumerate() {
+            if i > 0 {
+                sql.push(", ");
+            }
+            sql.push_ident_ref(name);
+            sql.push(" AS ");
+            spec.append_to_sql(sql);
+        }
+    }
+}
+
+// ==================== TopNPerGroup ====================
+
+/// Keep the first `n` rows of each group (by `ROW_NUMBER()`).
+///
+/// Add [`TopNPerGroup::row_number`] to the SELECT list of the inner query,
+/// then [`TopNPerGroup::wrap`] it:
+///
+/// ```ignore
+/// let top = TopNPerGroup::new("category_id", OrderBy::new().desc("price")?, 3)?;
+///
+/// let mut inner = pgorm::sql("SELECT id, name, category_id, price, ");
+/// top.row_number().append_to_sql(&mut inner);
+/// inner.push(" FROM products WHERE in_stock");
+///
+/// let products: Vec<Product> = top
+///     .wrap(inner)
+///     .fetch_all_as(&client)
+///     .await?;
+/// ```
+///
+/// The wrapped rows contain an extra [`ROW_NUMBER_ALIAS`] column (1-based rank).
+#[derive(Debug, Clone)]
+pub struct TopNPerGroup {
+    spec: WindowSpec,
+    n: i64,
+    alias: Ident,
+}
+
+impl TopNPerGroup {
+    /// Create a top-N helper partitioned by `partition_by` and ranked by `order_by`.
+    ///
+    /// Returns an error if `n < 1`.
+    pub fn new(partition_by: impl IntoIdent, order_by: OrderBy, n: i64) -> OrmResult<Self> {
+        if n < 1 {
+            return Err(OrmError::validation(format!(
+                "top_n_per_group n must be >= 1, got {n}"
+            )));
+        }
+        Ok(Self {
+            spec: WindowSpec::new()
+                .partition_by(partition_by)?
+                .order_by(order_by),
+            n,
+            alias: Ident::parse(RANKED_ALIAS)?,
+        })
+    }
+
+    /// Set the alias of the wrapped subquery (default: `__pgorm_ranked`).
+    ///
+    /// Using the inner table name keeps qualified ORDER BY columns working.
+    pub fn alias(mut self, alias: impl IntoIdent) -> OrmResult<Self> {
+        self.alias = alias.into_ident()?;
+        Ok(self)
+    }
+
+    /// Add another `PARTITION BY` column.
+    pub fn partition_by(mut self, column: impl IntoIdent) -> OrmResult<Self> {
+        self.spec = self.spec.partition_by(column)?;
+        Ok(self)
+    }
+
+    /// The `ROW_NUMBER() OVER (...) AS __pgorm_rn` projection.
+    pub fn row_number(&self) -> WindowExpr {
+        WindowExpr {
+            func: "ROW_NUMBER()".to_string(),
+            over: Over::Spec(self.spec.clone()),
+            alias: Some(Ident::parse(ROW_NUMBER_ALIAS).expect("valid alias")),
+        }
+    }
+
+    /// Wrap an inner query (which must project [`Self::row_number`]):
+    /// `SELECT * FROM (<inner>) AS alias WHERE __pgorm_rn <= n`.
+    ///
+    /// ORDER BY / LIMIT appended to the result apply to the filtered rows.
+    pub fn wrap(&self, inner: Sql) -> Sql {
+        let mut q = Sql::new("SELECT * FROM (");
+        q.push_sql(inner);
+        q.push(") AS ");
+        q.push_ident_ref(&self.alias);
+        q.push(" WHERE ");
+        q.push(ROW_NUMBER_ALIAS);
+        q.push(" <= ");
+        q.push_bind(self.n);
+        q
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_number_over_partition_and_order() {
+        let expr = WindowExpr::row_number()
+            .over(
+                WindowSpec::new()
+                    .partition_by("category_id")
+                    .unwrap()
+                    .order_by(OrderBy::new().desc("price").unwrap()),
+            )
+            .alias("rn")
+            .unwrap();
+        assert_eq!(
+            expr.to_sql(),
+            "ROW_NUMBER() OVER (PARTITION BY category_id ORDER BY price DESC) AS rn"
+        );
+    }
+
+    #[test]
+    fn empty_over() {
+        assert_eq!(WindowExpr::count_all().to_sql(), "COUNT(*) OVER ()");
+    }
+
+    #[test]
+    fn running_total_with_frame() {
+        let expr = WindowExpr::sum("amount").unwrap().over(
+            WindowSpec::new()
+                .partition_by("account_id")
+                .unwrap()
+                .order_by(OrderBy::new().asc("created_at").unwrap())
+                .frame(WindowFrame::rows(
+                    FrameBound::UnboundedPreceding,
+                    FrameBound::CurrentRow,
+                )),
+        );
+        assert_eq!(
+            expr.to_sql(),
+            "SUM(amount) OVER (PARTITION BY account_id ORDER BY created_at ASC \
+             ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)"
+        );
+    }
+
+    #[test]
+    fn frame_without_end() {
+        let spec = WindowSpec::new().frame(WindowFrame::starting(
+            FrameUnits::Range,
+            FrameBound::Preceding(3),
+        ));
+        assert_eq!(spec.to_sql(), "(RANGE 3 PRECEDING)");
+    }
+
+    #[test]
+    fn lag_with_offset() {
+        let expr = WindowExpr::lag_by("price", 2)
+            .unwrap()
+            .over(WindowSpec::new().order_by(OrderBy::new().asc("day").unwrap()));
+        assert_eq!(expr.to_sql(), "LAG(price, 2) OVER (ORDER BY day ASC)");
+    }
+
+    #[test]
+    fn named_windows() {
+        let windows = NamedWindows::new()
+            .define("w", WindowSpec::new().partition_by("team_id").unwrap())
+            .unwrap();
+        let rank = WindowExpr::rank().over_named("w").unwrap();
+        let extended = WindowExpr::first_value("score").unwrap().over(
+            WindowSpec::new()
+                .based_on("w")
+                .unwrap()
+                .order_by(OrderBy::new().desc("score").unwrap()),
+        );
+
+        let mut q = Sql::new("SELECT ");
+        rank.append_to_sql(&mut q);
+        q.push(", ");
+        extended.append_to_sql(&mut q);
+        q.push(" FROM players");
+        windows.append_to_sql(&mut q);
+        assert_eq!(
+            q.to_sql(),
+            "SELECT RANK() OVER w, FIRST_VALUE(score) OVER (w ORDER BY score DESC) \
+             FROM players WINDOW w AS (PARTITION BY team_id)"
+        );
+    }
+
+    #[test]
+    fn invalid_identifiers_are_rejected() {
+        assert!(WindowExpr::sum("amount; DROP TABLE x").is_err());
+        assert!(WindowSpec::new().partition_by("a b").is_err());
+        assert!(WindowExpr::ntile(0).is_err());
+    }
+
+    #[test]
+    fn top_n_per_group_wraps_inner_query() {
+        let top =
+            TopNPerGroup::new("category_id", OrderBy::new().desc("price").unwrap(), 3).unwrap();
+        let mut inner = Sql::new("SELECT id, category_id, price, ");
+        top.row_number().append_to_sql(&mut inner);
+        inner.push(" FROM products WHERE in_stock = ");
+        inner.push_bind(true);
+
+        let q = top.wrap(inner);
+        assert_eq!(
+            q.to_sql(),
+            "SELECT * FROM (SELECT id, category_id, price, ROW_NUMBER() OVER \
+             (PARTITION BY category_id ORDER BY price DESC) AS __pgorm_rn \
+             FROM products WHERE in_stock = $1) AS __pgorm_ranked WHERE __pgorm_rn <= $2"
+        );
+        assert_eq!(q.params_ref().len(), 2);
+    }
+
+    #[test]
+    fn top_n_per_group_custom_alias() {
+        let top = TopNPerGroup::new("team_id", OrderBy::new().desc("score").unwrap(), 1)
+            .unwrap()
+            .partition_by("season")
+            .unwrap()
+            .alias("players")
+            .unwrap();
+        let mut inner = Sql::new("SELECT *, ");
+        top.row_number().append_to_sql(&mut inner);
+        inner.push(" FROM players");
+        assert_eq!(
+            top.wrap(inner).to_sql(),
+            "SELECT * FROM (SELECT *, ROW_NUMBER() OVER \
+             (PARTITION BY team_id, season ORDER BY score DESC) AS __pgorm_rn \
+             FROM players) AS players WHERE __pgorm_rn <= $1"
+        );
+    }
+
+    #[test]
+    fn top_n_per_group_keeps_rank_binds() {
+        use crate::search::{TsQuery, TsRank, TsVector};
+
+        let rank = TsRank::new(
+            TsVector::stored("search_vector").unwrap(),
+            TsQuery::websearch("rust orm"),
+        );
+        let top = TopNPerGroup::new(
+            "category_id",
+            OrderBy::new().rank(rank, crate::SortDir::Desc),
+            3,
+        )
+        .unwrap();
+        let mut inner = Sql::new("SELECT id, category_id, ");
+        top.row_number().append_to_sql(&mut inner);
+        inner.push(" FROM products WHERE in_stock = ");
+        inner.push_bind(true);
+
+        let q = top.wrap(inner);
+        assert_eq!(
+            q.to_sql(),
+            "SELECT * FROM (SELECT id, category_id, ROW_NUMBER() OVER \
+             (PARTITION BY category_id ORDER BY ts_rank(search_vector, websearch_to_tsquery($1)) DESC) \
+             AS __pgorm_rn FROM products WHERE in_stock = $2) AS __pgorm_ranked WHERE __pgorm_rn <= $3"
+        );
+        assert_eq!(q.params_ref().len(), 3);
+
+        let windows = NamedWindows::new()
+            .define(
+                "w",
+                WindowSpec::new().order_by(OrderBy::new().rank(
+                    TsRank::new(
+                        TsVector::stored("search_vector").unwrap(),
+                        TsQuery::plain("x"),
+                    ),
+                    crate::SortDir::Desc,
+                )),
+            )
+            .unwrap();
+        let mut q = Sql::new("SELECT id FROM docs");
+        windows.append_to_sql(&mut q);
+        assert_eq!(q.params_ref().len(), 1);
+    }
+
+    #[test]
+    fn top_n_requires_positive_n() {
+        assert!(TopNPerGroup::new("g", OrderBy::new(), 0).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+//! `checked_sql!` expansion against a fixture schema cache.
+
+#![cfg(all(feature = "derive", feature = "check"))]
+
+use pgorm::checked_sql;
+
+#[test]
+fn checked_sql_builds_query_with_bound_args() {
+    let q = checked_sql!(
+        schema = "tests/fixtures/schema.json",
+        "SELECT u.id, u.email, p.title FROM users u JOIN posts p ON p.user_id = u.id \
+         WHERE u.status = $1 AND p.id > $2",
+        "active",
+        10_i64,
+    );
+    assert!(
+        q.sql()
+            .starts_with("SELECT u.id, u.email, p.title FROM users u")
+    );
+    assert_eq!(q.params_ref().len(), 2);
+    assert_eq!(
+        q.to_debug_sql(),
+        "SELECT u.id, u.email, p.title FROM users u JOIN posts p ON p.user_id = u.id \
+         WHERE u.status = 'active' AND p.id > 10"
+    );
+}
+
+#[test]
+fn checked_sql_without_params() {
+    let q = checked_sql!(
+        schema = "tests/fixtures/schema.json",
+        "SELECT count(*) FROM posts"
+    );
+    assert_eq!(q.sql(), "SELECT count(*) FROM posts");
+    assert!(q.params_ref().is_empty());
+}
@@ -0,0 +1,71 @@
+//! Shared fixtures for integration tests, also compiled into the crate's
+//! unit tests as `crate::test_client`.
+
+#![allow(dead_code)]
+
+use pgorm::{GenericClient, OrmError, OrmResult};
+use std::sync::Mutex;
+use tokio_postgres::Row;
+use tokio_postgres::types::ToSql;
+
+/// A `GenericClient` that records every statement (SQL and parameter count)
+/// without touching a database.
+///
+/// Queries return no rows, `query_one` fails with `NotFound`, and `execute`
+/// reports a fixed number of affected rows (`0` unless set with
+/// [`RecordingClient::with_affected`]).
+#[derive(Default)]
+pub struct RecordingClient {
+    statements: Mutex<Vec<(String, usize)>>,
+    affected: u64,
+}
+
+impl RecordingClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report `affected` rows from every `execute` call.
+    pub fn with_affected(affected: u64) -> Self {
+        Self {
+            affected,
+            ..Self::default()
+        }
+    }
+
+    fn record(&self, sql: &str, params: &[&(dyn ToSql + Sync)]) {
+        self.statements
+            .lock()
+            .unwrap()
+            .push((sql.to_string(), params.len()));
+    }
+
+    /// All recorded statements, oldest first.
+    pub fn statements(&self) -> Vec<(String, usize)> {
+        self.statements.lock().unwrap().clone()
+    }
+
+    /// The most recent statement.
+    pub fn last(&self) -> (String, usize) {
+        self.statements.lock().unwrap().last().cloned().unwrap()
+    }
+}
+
+impl GenericClient for RecordingClient {
+    async fn query(&self, sql: &str, params: &[&(dyn ToSql + Sync)]) -> OrmResult<Vec<Row>> {
+        self.record(sql, params);
+        Ok(vec![])
+    }
+    async fn query_one(&self, sql: &str, params: &[&(dyn ToSql + Sync)]) -> OrmResult<Row> {
+        self.record(sql, params);
+        Err(OrmError::not_found("no rows"))
+    }
+    async fn query_opt(&self, sql: &str, params: &[&(dyn ToSql + Sync)]) -> OrmResult<Option<Row>> {
+        self.record(sql, params);
+        Ok(None)
+    }
+    async fn execute(&self, sql: &str, params: &[&(dyn ToSql + Sync)]) -> OrmResult<u64> {
+        self.record(sql, params);
+        Ok(self.affected)
+    }
+}
@@ -0,0 +1,144 @@
+//! Tests for `#[orm(cursor = "...")]` and `KeysetN` cursor pages.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![cfg(feature = "cursor")]
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{
+    CursorCodec, CursorKey, FromRow, KeysetN, Model, NullsOrder, OrmError, OrmResult, SortDir, sql,
+};
+
+#[derive(Debug, Clone, PartialEq, FromRow, Model)]
+#[orm(table = "posts")]
+#[orm(cursor = "published_at DESC NULLS LAST, id DESC")]
+struct Post {
+    #[orm(id)]
+    id: i64,
+    title: String,
+    #[orm(column = "published")]
+    published_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "users")]
+#[orm(cursor = "name, id")]
+struct User {
+    #[orm(id)]
+    id: i64,
+    name: String,
+}
+
+fn post(id: i64, published_at: Option<i64>) -> Post {
+    Post {
+        id,
+        title: format!("post {id}"),
+        published_at,
+    }
+}
+
+#[test]
+fn derive_declares_cursor_columns() {
+    let columns = Post::CURSOR_COLUMNS;
+    assert_eq!(columns.len(), 2);
+    assert_eq!(columns[0].name, "published");
+    assert_eq!(columns[0].dir, SortDir::Desc);
+    assert_eq!(columns[0].nulls, Some(NullsOrder::Last));
+    assert_eq!(columns[1].name, "id");
+    assert_eq!(columns[1].nulls, None);
+}
+
+#[tokio::test]
+async fn first_page_fetches_limit_plus_one() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let codec = CursorCodec::new();
+    let page = KeysetN::from_cursor::<Post>(&codec, None)?
+        .limit(20)
+        .fetch_page::<Post>(&conn, sql("SELECT * FROM posts"), &codec)
+        .await?;
+
+    assert!(page.items.is_empty());
+    assert!(!page.has_more);
+    assert_eq!(
+        conn.last(),
+        (
+            "SELECT * FROM posts ORDER BY published DESC NULLS LAST, id DESC LIMIT $1".to_string(),
+            1
+        )
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn cursor_tokens_round_trip() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let codec = CursorCodec::signed(b"page-secret");
+
+    let first = KeysetN::from_cursor::<Post>(&codec, None)?.limit(2);
+    let page = first.into_page(
+        vec![post(9, Some(90)), post(8, None), post(7, None)],
+        &codec,
+    )?;
+    assert_eq!(page.items, vec![post(9, Some(90)), post(8, None)]);
+    assert!(page.has_more);
+    assert!(page.prev_cursor.is_none());
+
+    let next = page.next_cursor.unwrap();
+    KeysetN::from_cursor::<Post>(&codec, Some(&next))?
+        .limit(2)
+        .fetch_page::<Post>(&conn, sql("SELECT * FROM posts"), &codec)
+        .await?;
+    let (text, params) = conn.last();
+    assert_eq!(
+        text,
+        "SELECT * FROM posts WHERE ((published < $1 OR (published IS NULL AND published IS DISTINCT FROM $2)) \
+         OR (published IS NOT DISTINCT FROM $3 AND id < $4)) \
+         ORDER BY published DESC NULLS LAST, id DESC LIMIT $5"
+    );
+    assert_eq!(params, 5);
+
+    // A `before` cursor is fetched in reverse order.
+    let page = KeysetN::from_cursor::<Post>(&codec, Some(&next))?
+        .limit(2)
+        .into_page(vec![post(6, None), post(5, None)], &codec)?;
+    let prev = page.prev_cursor.unwrap();
+    KeysetN::from_cursor::<Post>(&codec, Some(&prev))?
+        .limit(2)
+        .fetch_page::<Post>(&conn, sql("SELECT * FROM posts"), &codec)
+        .await?;
+    assert!(
+        conn.last()
+            .0
+            .ends_with("ORDER BY published ASC NULLS FIRST, id ASC LIMIT $4")
+    );
+    Ok(())
+}
+
+#[test]
+fn from_cursor_rejects_tokens_for_other_columns() -> OrmResult<()> {
+    let codec = CursorCodec::new();
+    let page = KeysetN::from_cursor::<User>(&codec, None)?
+        .limit(1)
+        .into_page(
+            vec![
+                User {
+                    id: 1,
+                    name: "a".into(),
+                },
+                User {
+                    id: 2,
+                    name: "b".into(),
+                },
+            ],
+            &codec,
+        )?;
+    let token = page.next_cursor.unwrap();
+
+    assert!(KeysetN::from_cursor::<User>(&codec, Some(&token)).is_ok());
+    let err = KeysetN::from_cursor::<Post>(&codec, Some(&token)).unwrap_err();
+    assert!(matches!(err, OrmError::Validation(_)));
+    Ok(())
+}
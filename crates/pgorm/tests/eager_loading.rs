@@ -72,6 +72,12 @@ async fn _api_compiles(
         q.push(" ORDER BY id DESC");
     })
     .await?;
+    let _latest_posts =
+        User::load_posts_map_limited(conn, &users, 3, pgorm::OrderBy::new().desc("posts.id")?)
+            .await?;
+    let _post_counts = User::load_posts_count_map(conn, &users).await?;
+    let _post_sums = User::load_posts_sum_map::<i64>(conn, &users, "views").await?;
+    let _has_posts = User::load_posts_exists_map(conn, &users).await?;
     let _users_loaded = User::load_posts(conn, users.clone()).await?;
 
     // has_one
@@ -101,6 +107,11 @@ async fn _api_compiles(
         q.push(" ORDER BY id DESC");
     })
     .await?;
+    let _first_tags =
+        Post::load_tags_map_limited(conn, &posts, 2, pgorm::OrderBy::new().asc("tags.name")?)
+            .await?;
+    let _tag_counts = Post::load_tags_count_map(conn, &posts).await?;
+    let _has_tags = Post::load_tags_exists_map(conn, &posts).await?;
     let _posts_loaded = Post::load_tags(conn, posts).await?;
 
     // belongs_to (optional fk) + strict variant
@@ -0,0 +1,86 @@
+//! Tests for `#[orm(search(...))]` on `#[derive(Model)]`.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{FromRow, Model, OrmResult, Sql};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "posts")]
+#[orm(search(fields = "title, body", weights = "A, B", config = "english"))]
+struct Post {
+    #[orm(id)]
+    id: i64,
+    title: String,
+    #[orm(column = "content")]
+    body: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "notes")]
+#[orm(search(fields = "text"))]
+struct Note {
+    #[orm(id)]
+    id: i64,
+    text: String,
+}
+
+const POST_VECTOR: &str = "(setweight(to_tsvector('english'::regconfig, coalesce(title, '')), 'A') \
+     || setweight(to_tsvector('english'::regconfig, coalesce(content, '')), 'B'))";
+
+#[test]
+fn search_filters_with_websearch_query() -> OrmResult<()> {
+    let mut sql = Sql::empty();
+    Post::query()
+        .search("rust orm")?
+        .into_where_expr()
+        .append_to_sql(&mut sql);
+    assert_eq!(
+        sql.to_sql(),
+        format!("{POST_VECTOR} @@ websearch_to_tsquery('english'::regconfig, $1)")
+    );
+    assert_eq!(sql.params_ref().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn search_without_weights_or_config() -> OrmResult<()> {
+    let mut sql = Sql::empty();
+    Note::query()
+        .search("todo")?
+        .into_where_expr()
+        .append_to_sql(&mut sql);
+    assert_eq!(
+        sql.to_sql(),
+        "to_tsvector(coalesce(text, '')) @@ websearch_to_tsquery($1)"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn search_ranked_orders_by_rank() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let _ = Post::query()
+        .order_by_desc("id")?
+        .search_ranked("rust")?
+        .limit(10)
+        .find(&conn)
+        .await?;
+
+    let (sql, params) = conn.last();
+    assert_eq!(
+        sql,
+        format!(
+            "SELECT {} FROM posts WHERE {POST_VECTOR} @@ \
+             websearch_to_tsquery('english'::regconfig, $1) ORDER BY ts_rank({POST_VECTOR}, \
+             websearch_to_tsquery('english'::regconfig, $2)) DESC, id DESC LIMIT $3",
+            Post::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 3);
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! Tests for `has_many_through` / `has_one_through` relations.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{FromRow, Model, OrmResult, Sql, WhereExpr};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "countries")]
+#[orm(has_many_through(
+    Post,
+    through = User,
+    foreign_key = "country_id",
+    through_key = "user_id",
+    as = "posts"
+))]
+#[orm(has_many_through(
+    Comment,
+    through = [User, Post],
+    foreign_key = "country_id",
+    through_key = ["user_id", "post_id"],
+    as = "comments"
+))]
+#[orm(has_one_through(
+    Post,
+    through = User,
+    foreign_key = "country_id",
+    through_key = "user_id",
+    as = "latest_post"
+))]
+struct Country {
+    #[orm(id)]
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "users")]
+struct User {
+    #[orm(id)]
+    id: i64,
+    country_id: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "posts")]
+struct Post {
+    #[orm(id)]
+    id: i64,
+    user_id: i64,
+    title: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "comments")]
+struct Comment {
+    #[orm(id)]
+    id: i64,
+    post_id: i64,
+}
+
+fn country(id: i64) -> Country {
+    Country {
+        id,
+        name: format!("country {id}"),
+    }
+}
+
+fn render(expr: WhereExpr) -> Sql {
+    let mut sql = Sql::empty();
+    expr.append_to_sql(&mut sql);
+    sql
+}
+
+#[tokio::test]
+async fn has_many_through_joins_the_intermediate_model() {
+    let conn = RecordingClient::new();
+
+    let loaded = Country::load_posts(&conn, vec![country(1), country(2)])
+        .await
+        .unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert!(loaded.iter().all(|c| c.rel.is_empty()));
+
+    let posts = country(1).select_posts(&conn).await.unwrap();
+    assert!(posts.is_empty());
+
+    assert_eq!(
+        conn.statements()[0],
+        (
+            "SELECT __pgorm_through_0.country_id AS __pgorm_parent_id, \
+             posts.id, posts.user_id, posts.title FROM posts \
+             INNER JOIN users AS __pgorm_through_0 ON __pgorm_through_0.id = posts.user_id \
+             WHERE __pgorm_through_0.country_id = ANY($1)"
+                .to_string(),
+            1
+        )
+    );
+    assert_eq!(conn.statements()[1].0, conn.statements()[0].0);
+}
+
+#[tokio::test]
+async fn nested_through_chains_every_intermediate_model() {
+    let conn = RecordingClient::new();
+
+    let _: pgorm::HasManyMap<i64, Comment> =
+        Country::load_comments_map_with(&conn, &[country(1)], |q| {
+            q.push(" ORDER BY comments.id");
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "SELECT __pgorm_through_0.country_id AS __pgorm_parent_id, \
+             comments.id, comments.post_id FROM comments \
+             INNER JOIN posts AS __pgorm_through_1 ON __pgorm_through_1.id = comments.post_id \
+             INNER JOIN users AS __pgorm_through_0 ON __pgorm_through_0.id = __pgorm_through_1.user_id \
+             WHERE __pgorm_through_0.country_id = ANY($1) ORDER BY comments.id"
+                .to_string(),
+            1
+        )]
+    );
+}
+
+#[tokio::test]
+async fn has_one_through_returns_an_option_per_parent() {
+    let conn = RecordingClient::new();
+
+    let loaded = Country::load_latest_post_with(&conn, vec![country(1)], |q| {
+        q.push(" ORDER BY posts.id DESC");
+    })
+    .await
+    .unwrap();
+    assert!(loaded[0].rel.is_none());
+    assert!(
+        country(1)
+            .select_latest_post(&conn)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Empty input runs no query.
+    assert!(
+        Country::load_latest_post(&conn, vec![])
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(conn.statements().len(), 2);
+    assert!(
+        conn.statements()[0]
+            .0
+            .ends_with("ANY($1) ORDER BY posts.id DESC")
+    );
+}
+
+#[test]
+fn where_has_through_keeps_intermediate_tables_in_a_subquery() -> OrmResult<()> {
+    let expr = Country::query()
+        .where_has_posts_with(|q| q.eq(PostQuery::title, "hello"))?
+        .into_where_expr();
+    let sql = render(expr);
+    assert_eq!(
+        sql.to_sql(),
+        "EXISTS (SELECT 1 FROM posts WHERE (posts.user_id IN (SELECT __pgorm_through_0.id \
+         FROM users AS __pgorm_through_0 WHERE __pgorm_through_0.country_id = countries.id) \
+         AND title = $1))"
+    );
+    assert_eq!(sql.params_ref().len(), 1);
+
+    let expr = Country::query()
+        .where_doesnt_have_comments()
+        .into_where_expr();
+    assert_eq!(
+        render(expr).to_sql(),
+        "(NOT EXISTS (SELECT 1 FROM comments WHERE comments.post_id IN \
+         (SELECT __pgorm_through_1.id FROM posts AS __pgorm_through_1 \
+         INNER JOIN users AS __pgorm_through_0 ON __pgorm_through_0.id = __pgorm_through_1.user_id \
+         WHERE __pgorm_through_0.country_id = countries.id)))"
+    );
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! Tests for `#[orm(jsonb_merge)]` on `UpdateModel` fields.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::UpdateModel;
+use tokio_postgres::types::Json;
+
+#[derive(UpdateModel)]
+#[orm(table = "users", id_column = "id")]
+struct UserSettingsPatch {
+    name: Option<String>,
+    #[orm(jsonb_merge)]
+    settings: Option<serde_json::Value>,
+    #[orm(jsonb_merge, column = "meta")]
+    metadata: Option<Option<Json<serde_json::Value>>>,
+}
+
+#[tokio::test]
+async fn jsonb_merge_field_merges_instead_of_replacing() {
+    let conn = RecordingClient::with_affected(1);
+    UserSettingsPatch {
+        name: Some("alice".into()),
+        settings: Some(serde_json::json!({ "theme": "dark" })),
+        metadata: None,
+    }
+    .update_by_id(&conn, 1_i64)
+    .await
+    .unwrap();
+
+    let (sql, params) = conn.last();
+    assert_eq!(
+        sql,
+        "UPDATE users SET name = $1, \
+         settings = COALESCE(settings, '{}'::jsonb) || $2::jsonb WHERE users.id = $3"
+    );
+    assert_eq!(params, 3);
+}
+
+#[tokio::test]
+async fn jsonb_merge_nested_option_can_clear_column() {
+    let conn = RecordingClient::with_affected(1);
+    UserSettingsPatch {
+        name: None,
+        settings: None,
+        metadata: Some(None),
+    }
+    .update_by_id(&conn, 1_i64)
+    .await
+    .unwrap();
+    assert_eq!(
+        conn.last().0,
+        "UPDATE users SET meta = NULL WHERE users.id = $1"
+    );
+
+    UserSettingsPatch {
+        name: None,
+        settings: None,
+        metadata: Some(Some(Json(serde_json::json!({ "beta": true })))),
+    }
+    .update_by_id(&conn, 1_i64)
+    .await
+    .unwrap();
+    assert_eq!(
+        conn.last().0,
+        "UPDATE users SET meta = COALESCE(meta, '{}'::jsonb) || $1::jsonb WHERE users.id = $2"
+    );
+}
@@ -0,0 +1,173 @@
+//! Tests for many_to_many pivot loading and join-table writes.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{FromRow, Model, OrmError, SetExpr};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "posts")]
+#[orm(many_to_many(
+    Tag,
+    through = "post_tags",
+    self_key = "post_id",
+    other_key = "tag_id",
+    pivot = "PostTag",
+    as = "tags"
+))]
+struct Post {
+    #[orm(id)]
+    id: i64,
+    title: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "tags")]
+struct Tag {
+    #[orm(id)]
+    id: i64,
+    name: String,
+}
+
+/// Extra columns of the `post_tags` join table; `id` shares its name with `tags.id`.
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "post_tags")]
+struct PostTag {
+    id: i64,
+    position: i32,
+    role: String,
+}
+
+fn post(id: i64) -> Post {
+    Post {
+        id,
+        title: format!("post {id}"),
+    }
+}
+
+#[tokio::test]
+async fn pivot_loader_selects_join_table_columns() {
+    let conn = RecordingClient::with_affected(1);
+
+    let loaded = Post::load_tags_pivot(&conn, vec![post(1), post(2)])
+        .await
+        .unwrap();
+    assert!(loaded.iter().all(|p| p.rel.is_empty()));
+
+    let _: pgorm::HasManyMap<i64, (Tag, PostTag)> =
+        Post::load_tags_pivot_map_with(&conn, &[post(1)], |q| {
+            q.push(" ORDER BY jt.position");
+        })
+        .await
+        .unwrap();
+
+    let statements = conn.statements();
+    assert_eq!(
+        statements[0],
+        (
+            "SELECT jt.post_id AS __pgorm_parent_id, tags.id, tags.name, \
+             jt.id AS __pgorm_pivot_id, jt.position AS __pgorm_pivot_position, \
+             jt.role AS __pgorm_pivot_role \
+             FROM tags JOIN post_tags jt ON jt.tag_id = tags.id WHERE jt.post_id = ANY($1)"
+                .to_string(),
+            1
+        )
+    );
+    assert!(statements[1].0.ends_with("ANY($1) ORDER BY jt.position"));
+}
+
+#[tokio::test]
+async fn attach_and_detach_write_the_join_table() {
+    let conn = RecordingClient::with_affected(1);
+    let post = post(1);
+
+    post.attach_tags(
+        &conn,
+        7,
+        [
+            SetExpr::set("position", 1).unwrap(),
+            SetExpr::set("role", "primary").unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+    post.attach_tags(&conn, 8, []).await.unwrap();
+    post.detach_tags(&conn, vec![3, 4]).await.unwrap();
+    assert_eq!(post.detach_tags(&conn, vec![]).await.unwrap(), 0);
+
+    assert_eq!(
+        conn.statements(),
+        vec![
+            (
+                "INSERT INTO post_tags (post_id, tag_id, position, role) VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (post_id, tag_id) DO UPDATE SET position = EXCLUDED.position, \
+                 role = EXCLUDED.role"
+                    .to_string(),
+                4
+            ),
+            (
+                "INSERT INTO post_tags (post_id, tag_id) VALUES ($1, $2) \
+                 ON CONFLICT (post_id, tag_id) DO NOTHING"
+                    .to_string(),
+                2
+            ),
+            (
+                "DELETE FROM post_tags WHERE post_id = $1 AND tag_id = ANY($2)".to_string(),
+                2
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn sync_diffs_in_one_statement() {
+    let conn = RecordingClient::with_affected(1);
+
+    // The recording client has no row to return; only the SQL is checked.
+    let _ = post(1)
+        .sync_tags(
+            &conn,
+            vec![
+                (7, vec![SetExpr::set("position", 1).unwrap()]),
+                (9, vec![SetExpr::set("position", 2).unwrap()]),
+                (11, vec![]),
+            ],
+        )
+        .await;
+
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "WITH __pgorm_detached AS (DELETE FROM post_tags WHERE post_id = $1 \
+             AND NOT (tag_id = ANY($2)) RETURNING 1), \
+             __pgorm_upsert_0 AS (INSERT INTO post_tags (post_id, tag_id, position) \
+             VALUES ($3, $4, $5), ($6, $7, $8) ON CONFLICT (post_id, tag_id) \
+             DO UPDATE SET position = EXCLUDED.position RETURNING (xmax = 0) AS inserted), \
+             __pgorm_upsert_1 AS (INSERT INTO post_tags (post_id, tag_id) VALUES ($9, $10) \
+             ON CONFLICT (post_id, tag_id) DO NOTHING RETURNING (xmax = 0) AS inserted) \
+             SELECT (SELECT COUNT(*) FROM __pgorm_detached) AS detached, \
+             COUNT(*) FILTER (WHERE inserted) AS attached, \
+             COUNT(*) FILTER (WHERE NOT inserted) AS updated \
+             FROM (SELECT inserted FROM __pgorm_upsert_0 UNION ALL \
+             SELECT inserted FROM __pgorm_upsert_1) AS __pgorm_upserted"
+                .to_string(),
+            10
+        )]
+    );
+}
+
+#[tokio::test]
+async fn sync_rejects_repeated_ids() {
+    let conn = RecordingClient::with_affected(1);
+
+    let err = post(1)
+        .sync_tags(&conn, vec![(7, vec![]), (7, vec![])])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OrmError::Validation(_)), "{err:?}");
+    assert!(conn.statements().is_empty());
+}
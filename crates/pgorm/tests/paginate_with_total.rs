@@ -0,0 +1,77 @@
+//! Tests for `paginate_with_total` on `Sql` and generated Model queries.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{CountMode, FromRow, Model, OrmError, OrmResult, sql};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "users")]
+struct User {
+    #[orm(id)]
+    id: i64,
+    name: String,
+    status: String,
+}
+
+#[tokio::test]
+async fn model_query_paginates_in_one_statement() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let page = User::query()
+        .eq(UserQuery::COL_STATUS, "active")?
+        .order_by_desc(UserQuery::COL_ID)?
+        .limit(5)
+        .paginate_with_total(&conn, 2, 20, CountMode::Exact)
+        .await?;
+
+    assert!(page.items.is_empty());
+    assert_eq!(
+        (page.total, page.page, page.per_page, page.total_pages),
+        (0, 2, 20, 0)
+    );
+
+    let (text, params) = conn.last();
+    assert_eq!(
+        text,
+        format!(
+            "SELECT __pgorm_page.*, __pgorm_count.__pgorm_total, __pgorm_count.__pgorm_exact \
+             FROM (SELECT COUNT(*) AS __pgorm_total, true AS __pgorm_exact \
+             FROM (SELECT {select} FROM users WHERE status = $1 ORDER BY id DESC) AS __pgorm_c) \
+             AS __pgorm_count \
+             LEFT JOIN (SELECT *, true AS __pgorm_hit \
+             FROM (SELECT {select} FROM users WHERE status = $2 ORDER BY id DESC \
+             LIMIT $3 OFFSET $4) AS __pgorm_rows) AS __pgorm_page ON true",
+            select = User::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn sql_paginate_with_capped_count() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let mut q = sql("SELECT * FROM events WHERE kind = ");
+    q.push_bind("click").push(" ORDER BY id");
+    q.paginate_with_total::<User>(&conn, 1, 50, CountMode::Capped(10_000))
+        .await?;
+
+    let (text, params) = conn.last();
+    assert!(text.contains(
+        "SELECT LEAST(COUNT(*), $1) AS __pgorm_total, COUNT(*) <= $2 AS __pgorm_exact \
+         FROM (SELECT 1 FROM (SELECT * FROM events WHERE kind = $3 ORDER BY id) AS __pgorm_rows \
+         LIMIT $4) AS __pgorm_c"
+    ));
+    assert_eq!(params, 7);
+
+    let err = sql("SELECT 1")
+        .paginate_with_total::<User>(&conn, 0, 50, CountMode::Exact)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OrmError::Validation(_)));
+    Ok(())
+}
@@ -0,0 +1,351 @@
+//! Tests for nested preloading (`Model::query().preload(...)`).
+//!
+//! A recording client captures the generated SQL (no real database operations).
+//! It returns no rows, so only levels with in-memory parents issue queries.
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::preload::Strategy;
+use pgorm::{FromRow, Loaded, Model, OrmError};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, FromRow, Model, Deserialize)]
+#[orm(table = "users")]
+#[orm(has_many(Post, foreign_key = "user_id", as = "posts"))]
+#[orm(has_one(Profile, foreign_key = "user_id", as = "profile"))]
+struct User {
+    #[orm(id)]
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model, Deserialize)]
+#[orm(table = "profiles")]
+struct Profile {
+    #[orm(id)]
+    id: i64,
+    user_id: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Model, Deserialize)]
+#[orm(table = "posts")]
+#[orm(has_many(Comment, foreign_key = "post_id", as = "comments"))]
+#[orm(many_to_many(
+    Tag,
+    through = "post_tags",
+    self_key = "post_id",
+    other_key = "tag_id",
+    as = "tags"
+))]
+struct Post {
+    #[orm(id)]
+    id: i64,
+    user_id: i64,
+    title: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "comments")]
+#[orm(belongs_to(User, foreign_key = "author_id", as = "author"))]
+struct Comment {
+    #[orm(id)]
+    id: i64,
+    post_id: i64,
+    author_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "tags")]
+struct Tag {
+    #[orm(id)]
+    id: i64,
+    name: String,
+}
+
+fn user(id: i64) -> User {
+    User {
+        id,
+        name: format!("user {id}"),
+    }
+}
+
+#[tokio::test]
+async fn preload_runs_base_query_then_levels() {
+    let conn = RecordingClient::new();
+
+    type PostWithComments = Loaded<Post, Vec<Loaded<Comment, Option<User>>>>;
+
+    let users: Vec<Loaded<User, Vec<PostWithComments>>> = User::query()
+        .eq("name", "alice")
+        .unwrap()
+        .preload(User::posts().then(Post::comments().then(Comment::author())))
+        .find(&conn)
+        .await
+        .unwrap();
+
+    assert!(users.is_empty());
+    // No users, so no level below runs.
+    assert_eq!(
+        conn.statements(),
+        vec![("SELECT id, name FROM users WHERE name = $1".to_string(), 1)]
+    );
+}
+
+#[tokio::test]
+async fn level_filter_and_order_are_appended() {
+    let conn = RecordingClient::new();
+
+    let loaded = pgorm::preload::attach(
+        &conn,
+        vec![user(1), user(2)],
+        User::posts()
+            .filter(|q| {
+                q.push("posts.title <> ");
+                q.push_bind("draft");
+            })
+            .filter(|q| {
+                q.push("posts.id > 0");
+            })
+            .order_by(|q| {
+                q.push("posts.id DESC");
+            })
+            .then(Post::tags()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[0].id, 1);
+    assert!(loaded.iter().all(|u| u.rel.is_empty()));
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "SELECT id, user_id, title FROM posts \
+             WHERE posts.user_id = ANY($1) AND (posts.title <> $2) AND (posts.id > 0) \
+             ORDER BY posts.id DESC"
+                .to_string(),
+            2
+        )]
+    );
+}
+
+#[tokio::test]
+async fn belongs_to_skips_null_foreign_keys() {
+    let conn = RecordingClient::new();
+    let comment = |id, author_id| Comment {
+        id,
+        post_id: 1,
+        author_id,
+    };
+
+    let loaded = pgorm::preload::attach(
+        &conn,
+        vec![comment(1, None), comment(2, Some(7))],
+        Comment::author(),
+    )
+    .await
+    .unwrap();
+    assert!(loaded.iter().all(|c| c.rel.is_none()));
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "SELECT id, name FROM users WHERE users.id = ANY($1)".to_string(),
+            1
+        )]
+    );
+
+    let conn = RecordingClient::new();
+    let loaded = pgorm::preload::attach(&conn, vec![comment(1, None)], Comment::author())
+        .await
+        .unwrap();
+    assert!(loaded[0].rel.is_none());
+    assert!(conn.statements().is_empty());
+}
+
+#[tokio::test]
+async fn has_one_and_find_one_opt() {
+    let conn = RecordingClient::new();
+
+    let found = User::query()
+        .preload(User::profile())
+        .find_one_opt(&conn)
+        .await
+        .unwrap();
+    assert!(found.is_none());
+
+    let loaded = pgorm::preload::attach(&conn, vec![user(1)], User::profile())
+        .await
+        .unwrap();
+    assert!(loaded[0].rel.is_none());
+
+    let statements = conn.statements();
+    assert_eq!(statements.len(), 2);
+    assert!(
+        statements[1]
+            .0
+            .ends_with("WHERE profiles.user_id = ANY($1)")
+    );
+}
+
+#[test]
+fn preload_future_is_send() {
+    fn assert_send<T: Send>(_: T) {}
+
+    let conn = RecordingClient::new();
+    assert_send(
+        User::query()
+            .preload(User::posts().then(Post::tags()).filter(|q| {
+                q.push("true");
+            }))
+            .find(&conn),
+    );
+}
+
+#[tokio::test]
+async fn json_agg_embeds_children_in_parent_query() {
+    let conn = RecordingClient::new();
+
+    let users = User::query()
+        .eq("name", "alice")
+        .unwrap()
+        .preload(
+            User::posts()
+                .strategy(Strategy::JsonAgg)
+                .filter(|q| {
+                    q.push("posts.title <> ");
+                    q.push_bind("draft");
+                })
+                .order_by(|q| {
+                    q.push("posts.id DESC");
+                }),
+        )
+        .find(&conn)
+        .await
+        .unwrap();
+
+    assert!(users.is_empty());
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "SELECT id, name, (SELECT COALESCE(json_agg(\
+             jsonb_build_object('id', posts.id, 'user_id', posts.user_id, 'title', posts.title) \
+             ORDER BY posts.id DESC), '[]'::json) FROM posts \
+             WHERE posts.user_id = users.id AND (posts.title <> $1)) AS __pgorm_json \
+             FROM users WHERE name = $2"
+                .to_string(),
+            2
+        )]
+    );
+}
+
+#[tokio::test]
+async fn json_agg_has_one_and_belongs_to_use_limit_one() {
+    let conn = RecordingClient::new();
+    User::query()
+        .preload(User::profile().strategy(Strategy::JsonAgg))
+        .find_one_opt(&conn)
+        .await
+        .unwrap();
+    Comment::query()
+        .preload(Comment::author().strategy(Strategy::JsonAgg))
+        .find(&conn)
+        .await
+        .unwrap();
+
+    let statements = conn.statements();
+    assert_eq!(
+        statements[0].0,
+        "SELECT id, name, (SELECT jsonb_build_object('id', profiles.id, 'user_id', profiles.user_id) \
+         FROM profiles WHERE profiles.user_id = users.id LIMIT 1) AS __pgorm_json \
+         FROM users LIMIT $1"
+    );
+    assert_eq!(
+        statements[1].0,
+        "SELECT id, post_id, author_id, (SELECT jsonb_build_object('id', users.id, 'name', users.name) \
+         FROM users WHERE users.id = comments.author_id LIMIT 1) AS __pgorm_json \
+         FROM comments"
+    );
+}
+
+#[tokio::test]
+async fn json_agg_nested_level_falls_back_to_batched() {
+    let conn = RecordingClient::new();
+
+    let loaded = pgorm::preload::attach(
+        &conn,
+        vec![user(1)],
+        User::posts().strategy(Strategy::JsonAgg),
+    )
+    .await
+    .unwrap();
+    assert!(loaded[0].rel.is_empty());
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "SELECT id, user_id, title FROM posts WHERE posts.user_id = ANY($1)".to_string(),
+            1
+        )]
+    );
+}
+
+#[tokio::test]
+async fn json_agg_rejects_self_referential_relations() {
+    #[derive(Debug, Clone, FromRow, Model, Deserialize)]
+    #[orm(table = "nodes")]
+    #[orm(has_many(Node, foreign_key = "parent_id", as = "children"))]
+    struct Node {
+        #[orm(id)]
+        id: i64,
+        parent_id: Option<i64>,
+    }
+
+    let conn = RecordingClient::new();
+    let err = Node::query()
+        .preload(Node::children().strategy(Strategy::JsonAgg))
+        .find(&conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OrmError::Validation(_)), "{err:?}");
+    assert!(conn.statements().is_empty());
+}
+
+#[tokio::test]
+async fn with_count_groups_by_parent() {
+    let conn = RecordingClient::new();
+
+    let loaded = pgorm::preload::attach(
+        &conn,
+        vec![user(1), user(2)],
+        User::posts()
+            .filter(|q| {
+                q.push("posts.title <> ");
+                q.push_bind("draft");
+            })
+            .with_count(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(loaded.iter().map(|u| u.rel).collect::<Vec<_>>(), vec![0, 0]);
+
+    pgorm::preload::attach(
+        &conn,
+        vec![user(1)],
+        User::posts().then(Post::tags().with_count()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        conn.statements()[0],
+        (
+            "SELECT posts.user_id AS __pgorm_parent_id, COUNT(*) AS __pgorm_aggregate \
+             FROM posts WHERE posts.user_id = ANY($1) AND (posts.title <> $2) \
+             GROUP BY posts.user_id"
+                .to_string(),
+            2
+        )
+    );
+}
@@ -159,3 +159,54 @@ fn query_params_apply_and_into_query() {
     let _q1 = params.clone().into_query().unwrap();
     let _q2 = params.apply(AuditLog::query()).unwrap();
 }
+
+#[derive(QueryParams)]
+#[orm(
+    model = "AuditLog",
+    query_string(sort(AuditLogQuery::COL_CREATED_AT, AuditLogQuery::COL_STATUS_CODE))
+)]
+struct AuditLogFilters<'a> {
+    #[orm(eq(AuditLogQuery::COL_USER_ID))]
+    user_id: Option<uuid::Uuid>,
+
+    #[orm(
+        gte(AuditLogQuery::COL_STATUS_CODE),
+        lte(AuditLogQuery::COL_STATUS_CODE)
+    )]
+    status_code: Option<i16>,
+
+    #[orm(in_list(AuditLogQuery::COL_STATUS_CODE))]
+    status_in: Option<Vec<i16>>,
+
+    #[orm(ilike(AuditLogQuery::COL_OPERATION_TYPE))]
+    op: Option<&'a str>,
+
+    #[orm(is_null(AuditLogQuery::COL_IP_ADDRESS))]
+    no_ip: Option<bool>,
+
+    #[orm(eq(AuditLogQuery::COL_IP_ADDRESS), map(parse_ip))]
+    ip_address: Option<&'a str>,
+}
+
+#[test]
+fn query_params_allow_list_parses_query_strings() {
+    let allow = AuditLogFilters::allow_list().unwrap();
+    let parsed = allow
+        .parse("sort=-created_at&status_code=gte.200&status_code=in.(200,204)&operation_type=ilike.log*&ip_address=is.null")
+        .unwrap();
+
+    let mut sql = pgorm::sql("SELECT * FROM audit_logs WHERE ");
+    parsed.filter.append_to_sql(&mut sql);
+    parsed.order_by.append_to_sql(&mut sql);
+    assert_eq!(
+        sql.to_sql(),
+        "SELECT * FROM audit_logs WHERE (status_code >= $1 AND status_code IN ($2, $3) \
+         AND operation_type ILIKE $4 AND ip_address IS NULL) ORDER BY created_at DESC"
+    );
+
+    // `map(...)` filters and undeclared operators are not exposed.
+    let errors = allow
+        .parse("ip_address=eq.127.0.0.1&status_code=gt.1&sort=ip_address")
+        .unwrap_err();
+    assert_eq!(errors.len(), 3);
+}
@@ -0,0 +1,168 @@
+//! Tests for relation-aware filters (`where_has_*` / `where_doesnt_have_*`)
+//! generated by `#[derive(Model)]`.
+//!
+//! These tests only render SQL (no real database operations).
+
+#![allow(dead_code)]
+
+use pgorm::{FromRow, Model, OrmResult, Sql, WhereExpr};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "users")]
+#[orm(has_many(Order, foreign_key = "user_id", as = "orders"))]
+#[orm(has_one(Profile, foreign_key = "user_id", as = "profile"))]
+struct User {
+    #[orm(id)]
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "profiles")]
+struct Profile {
+    #[orm(id)]
+    id: i64,
+    user_id: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "orders")]
+#[orm(belongs_to(User, foreign_key = "user_id", as = "user"))]
+#[orm(many_to_many(
+    Coupon,
+    through = "order_coupons",
+    self_key = "order_id",
+    other_key = "coupon_id",
+    as = "coupons"
+))]
+struct Order {
+    #[orm(id)]
+    id: i64,
+    user_id: i64,
+    status: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "coupons")]
+struct Coupon {
+    #[orm(id)]
+    id: i64,
+    code: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "categories")]
+#[orm(has_many(Category, foreign_key = "parent_id", as = "children"))]
+struct Category {
+    #[orm(id)]
+    id: i64,
+    parent_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "sections")]
+#[orm(join(table = "sites", on = "sections.site_id = sites.id", type = "inner"))]
+#[orm(has_many(Section, foreign_key = "parent_id", as = "children"))]
+struct Section {
+    #[orm(id)]
+    id: i64,
+    parent_id: Option<i64>,
+    name: String,
+    #[orm(table = "sites", column = "host")]
+    site_host: String,
+}
+
+fn render(expr: WhereExpr) -> Sql {
+    let mut sql = Sql::empty();
+    expr.append_to_sql(&mut sql);
+    sql
+}
+
+#[test]
+fn where_has_has_many() {
+    let sql = render(User::query().where_has_orders().into_where_expr());
+    assert_eq!(
+        sql.to_sql(),
+        "EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id)"
+    );
+}
+
+#[test]
+fn where_has_with_nested_filter() -> OrmResult<()> {
+    let expr = User::query()
+        .eq(UserQuery::name, "alice")?
+        .where_has_orders_with(|q| q.eq(OrderQuery::status, "unpaid"))?
+        .into_where_expr();
+    let sql = render(expr);
+    assert_eq!(
+        sql.to_sql(),
+        "(name = $1 AND EXISTS (SELECT 1 FROM orders \
+         WHERE (orders.user_id = users.id AND status = $2)))"
+    );
+    assert_eq!(sql.params_ref().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn where_doesnt_have_has_one() {
+    let sql = render(User::query().where_doesnt_have_profile().into_where_expr());
+    assert_eq!(
+        sql.to_sql(),
+        "(NOT EXISTS (SELECT 1 FROM profiles WHERE profiles.user_id = users.id))"
+    );
+}
+
+#[test]
+fn where_has_belongs_to() -> OrmResult<()> {
+    let expr = Order::query()
+        .where_has_user_with(|q| q.eq(UserQuery::name, "alice"))?
+        .into_where_expr();
+    assert_eq!(
+        render(expr).to_sql(),
+        "EXISTS (SELECT 1 FROM users WHERE (users.id = orders.user_id AND name = $1))"
+    );
+    Ok(())
+}
+
+#[test]
+fn where_doesnt_have_many_to_many() -> OrmResult<()> {
+    let expr = Order::query()
+        .where_doesnt_have_coupons_with(|q| q.eq(CouponQuery::code, "WELCOME"))?
+        .into_where_expr();
+    assert_eq!(
+        render(expr).to_sql(),
+        "(NOT EXISTS (SELECT 1 FROM order_coupons __pgorm_jt \
+         WHERE (__pgorm_jt.order_id = orders.id AND EXISTS (SELECT 1 FROM coupons \
+         WHERE (coupons.id = __pgorm_jt.coupon_id AND code = $1)))))"
+    );
+    Ok(())
+}
+
+#[test]
+fn where_has_self_referential() {
+    let sql = render(Category::query().where_has_children().into_where_expr());
+    assert_eq!(
+        sql.to_sql(),
+        "EXISTS (SELECT 1 FROM (SELECT categories.id AS __pgorm_key) AS __pgorm_outer \
+         WHERE EXISTS (SELECT 1 FROM categories \
+         WHERE categories.parent_id = __pgorm_outer.__pgorm_key))"
+    );
+}
+
+#[test]
+fn where_has_self_referential_with_joins() -> OrmResult<()> {
+    // Joined models qualify nested filters with the table name; both the filter and
+    // the JOIN clause must bind to the related row, not the outer one.
+    let expr = Section::query()
+        .where_has_children_with(|q| q.eq(SectionQuery::name, "docs"))?
+        .into_where_expr();
+    let sql = render(expr);
+    assert_eq!(
+        sql.to_sql(),
+        "EXISTS (SELECT 1 FROM (SELECT sections.id AS __pgorm_key) AS __pgorm_outer \
+         WHERE EXISTS (SELECT 1 FROM sections INNER JOIN sites ON sections.site_id = sites.id \
+         WHERE (sections.parent_id = __pgorm_outer.__pgorm_key AND sections.name = $1)))"
+    );
+    assert_eq!(sql.params_ref().len(), 1);
+    Ok(())
+}
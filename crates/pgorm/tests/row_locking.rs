@@ -0,0 +1,146 @@
+//! Tests for row-locking clauses on generated Model queries and `Sql`.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{FromRow, Model, OrmResult, RowLock, TransactionClient, sql};
+
+// Stands in for a transaction in these tests.
+impl TransactionClient for RecordingClient {}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "jobs")]
+struct Job {
+    #[orm(id)]
+    id: i64,
+    status: String,
+}
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "public.orders")]
+#[orm(join(
+    table = "customers",
+    on = "orders.customer_id = customers.id",
+    type = "inner"
+))]
+struct OrderWithCustomer {
+    #[orm(id)]
+    id: i64,
+    #[orm(table = "customers", column = "name")]
+    customer_name: String,
+}
+
+#[tokio::test]
+async fn model_query_skip_locked() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    Job::query()
+        .eq(JobQuery::COL_STATUS, "queued")?
+        .order_by_asc(JobQuery::COL_ID)?
+        .limit(10)
+        .for_update()
+        .skip_locked()
+        .find(&conn)
+        .await?;
+
+    let (text, params) = conn.last();
+    assert_eq!(
+        text,
+        format!(
+            "SELECT {} FROM jobs WHERE status = $1 ORDER BY id ASC LIMIT $2 FOR UPDATE SKIP LOCKED",
+            Job::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_query_lock_modifiers() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let _ = Job::query()
+        .nowait()
+        .for_share()
+        .find_one_opt(&conn)
+        .await?;
+    let (text, _) = conn.last();
+    assert!(text.ends_with("LIMIT $1 FOR SHARE NOWAIT"), "{text}");
+
+    Job::query()
+        .of(["jobs"])?
+        .for_key_share()
+        .find(&conn)
+        .await?;
+    let (text, _) = conn.last();
+    assert!(text.ends_with(" FOR KEY SHARE OF jobs"), "{text}");
+
+    assert!(Job::query().of(["jobs; DROP"]).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn joined_model_query_locks_own_table() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    OrderWithCustomer::query().for_update().find(&conn).await?;
+    let (text, _) = conn.last();
+    assert!(text.ends_with(" FOR UPDATE OF orders"), "{text}");
+
+    let _ = OrderWithCustomer::query()
+        .for_share()
+        .skip_locked()
+        .find_one_opt(&conn)
+        .await?;
+    let (text, _) = conn.last();
+    assert!(
+        text.ends_with("LIMIT $1 FOR SHARE OF orders SKIP LOCKED"),
+        "{text}"
+    );
+
+    // An explicit `of` replaces the default.
+    OrderWithCustomer::query()
+        .of(["customers"])?
+        .find(&conn)
+        .await?;
+    let (text, _) = conn.last();
+    assert!(text.ends_with(" FOR UPDATE OF customers"), "{text}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_by_pk_for_update_locks_row() {
+    let conn = RecordingClient::new();
+    let err = Job::select_by_pk_for_update(&conn, 7).await.unwrap_err();
+    assert!(err.is_not_found());
+    let (text, params) = conn.last();
+    assert_eq!(
+        text,
+        format!(
+            "SELECT {} FROM jobs WHERE jobs.id = $1 FOR UPDATE",
+            Job::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 1);
+
+    let _ = OrderWithCustomer::select_by_pk_for_update(&conn, 1).await;
+    let (text, _) = conn.last();
+    assert!(
+        text.ends_with("WHERE public.orders.id = $1 FOR UPDATE OF orders"),
+        "{text}"
+    );
+}
+
+#[test]
+fn sql_row_lock_helper() {
+    let mut q = sql("SELECT * FROM jobs WHERE status = ");
+    q.push_bind("queued")
+        .push(" ORDER BY id")
+        .limit(1)
+        .row_lock(&RowLock::for_no_key_update().skip_locked());
+    assert_eq!(
+        q.to_sql(),
+        "SELECT * FROM jobs WHERE status = $1 ORDER BY id LIMIT $2 FOR NO KEY UPDATE SKIP LOCKED"
+    );
+}
@@ -0,0 +1,77 @@
+//! Tests for `#[orm(tree(parent = "..."))]` on `#[derive(Model)]`.
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{FromRow, Model, OrmResult, TreeModel};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "categories")]
+#[orm(tree(parent = "parent"))]
+struct Category {
+    #[orm(id)]
+    id: i64,
+    #[orm(column = "parent_id")]
+    parent: Option<i64>,
+    name: String,
+}
+
+#[test]
+fn tree_model_uses_parent_column() {
+    assert_eq!(Category::ID_COLUMN, "id");
+    assert_eq!(Category::PARENT_COLUMN, "parent_id");
+}
+
+#[tokio::test]
+async fn roots_select_rows_without_parent() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let roots = Category::roots(&conn).await?;
+    assert!(roots.is_empty());
+    assert_eq!(
+        conn.last(),
+        (
+            "SELECT id, parent_id, name FROM categories WHERE parent_id IS NULL ORDER BY id"
+                .to_string(),
+            0
+        )
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn descendants_use_recursive_cte_with_depth_limit() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let _ = Category::descendants(&conn, 1, Some(2)).await?;
+
+    let (sql, params) = conn.last();
+    assert!(sql.starts_with(
+        "WITH RECURSIVE __pgorm_tree AS (SELECT id, parent_id, name, 0 AS __pgorm_depth, \
+         ARRAY[id] AS __pgorm_path FROM categories WHERE id = $1 UNION ALL"
+    ));
+    assert!(sql.contains("ON __pgorm_node.parent_id = __pgorm_tree.id"));
+    assert!(sql.contains("__pgorm_node.id <> ALL(__pgorm_tree.__pgorm_path)"));
+    assert!(sql.contains("__pgorm_tree.__pgorm_depth < $2"));
+    assert_eq!(params, 2);
+
+    assert!(Category::subtree_as_nested(&conn, 1, None).await?.is_none());
+    assert_eq!(conn.last().1, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn ancestors_walk_up_to_the_root() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let _ = Category::ancestors(&conn, 5).await?;
+    let (sql, params) = conn.last();
+    assert!(sql.contains("ON __pgorm_node.id = __pgorm_tree.parent_id"));
+    assert!(sql.ends_with("SELECT * FROM __pgorm_tree ORDER BY __pgorm_depth"));
+    assert_eq!(params, 1);
+
+    assert!(Category::path_to_root(&conn, 5).await?.is_empty());
+    assert_eq!(conn.last().0, sql);
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Tests for `UpdateModel::update_many_by_id` (per-row patches via `UPDATE ... FROM UNNEST`).
+//!
+//! A recording client captures the generated SQL (no real database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{FromRow, Model, OrmError, UpdateModel};
+use tokio_postgres::types::{IsNull, ToSql, Type, to_sql_checked};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "products")]
+struct Product {
+    #[orm(id)]
+    id: i64,
+    name: String,
+    price: i64,
+    note: Option<String>,
+}
+
+#[derive(UpdateModel)]
+#[orm(table = "products", model = "Product", returning = "Product")]
+struct ProductPatch {
+    name: Option<String>,
+    price: i64,
+    note: Option<Option<String>>,
+    #[orm(jsonb_merge)]
+    attrs: Option<serde_json::Value>,
+    #[orm(default)]
+    synced_at: Option<String>,
+}
+
+fn patch(name: Option<&str>, price: i64, note: Option<Option<&str>>) -> ProductPatch {
+    ProductPatch {
+        name: name.map(Into::into),
+        price,
+        note: note.map(|n| n.map(Into::into)),
+        attrs: None,
+        synced_at: None,
+    }
+}
+
+#[tokio::test]
+async fn update_many_by_id_flags_unset_fields() {
+    let conn = RecordingClient::with_affected(2);
+    let affected = ProductPatch::update_many_by_id(
+        &conn,
+        vec![
+            (1_i64, patch(Some("a"), 10, None)),
+            (2_i64, patch(None, 20, Some(None))),
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(affected, 2);
+
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "UPDATE products SET \
+             name = CASE WHEN __pgorm_u.__pgorm_s0 THEN __pgorm_u.__pgorm_v0 ELSE products.name END, \
+             price = __pgorm_u.__pgorm_v1, \
+             note = CASE WHEN __pgorm_u.__pgorm_s2 THEN __pgorm_u.__pgorm_v2 ELSE products.note END, \
+             attrs = CASE WHEN __pgorm_u.__pgorm_s3 \
+             THEN COALESCE(products.attrs, '{}'::jsonb) || __pgorm_u.__pgorm_v3 ELSE products.attrs END, \
+             synced_at = DEFAULT \
+             FROM UNNEST($1::bigint[], $2::text[], $3::boolean[], $4::bigint[], $5::text[], \
+             $6::boolean[], $7::jsonb[], $8::boolean[]) \
+             AS __pgorm_u(__pgorm_id, __pgorm_v0, __pgorm_s0, __pgorm_v1, __pgorm_v2, __pgorm_s2, \
+             __pgorm_v3, __pgorm_s3) \
+             WHERE products.id = __pgorm_u.__pgorm_id"
+                .to_string(),
+            8
+        )]
+    );
+
+    // Empty input runs no query.
+    assert_eq!(
+        ProductPatch::update_many_by_id::<i64>(&conn, vec![])
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(conn.statements().len(), 1);
+}
+
+#[tokio::test]
+async fn update_many_by_id_returning_wraps_in_cte() {
+    let conn = RecordingClient::with_affected(2);
+    let rows =
+        ProductPatch::update_many_by_id_returning(&conn, vec![(1_i64, patch(None, 5, None))])
+            .await
+            .unwrap();
+    assert!(rows.is_empty());
+
+    let (sql, params) = conn.statements().remove(0);
+    assert!(sql.starts_with("WITH products AS (UPDATE products SET "));
+    assert!(sql.ends_with(
+        "WHERE products.id = __pgorm_u.__pgorm_id RETURNING products.*) \
+         SELECT id, name, price, note FROM products "
+    ));
+    assert_eq!(params, 8);
+}
+
+#[derive(UpdateModel)]
+#[orm(table = "items", id_column = "id")]
+struct VersionedItemPatch {
+    name: Option<String>,
+    #[orm(version)]
+    version: i32,
+}
+
+#[tokio::test]
+async fn update_many_by_id_matches_each_row_version() {
+    let conn = RecordingClient::with_affected(2);
+    // The recording client fails the target-count precheck, so only the SQL is checked.
+    let err = VersionedItemPatch::update_many_by_id(
+        &conn,
+        vec![(
+            7_i64,
+            VersionedItemPatch {
+                name: Some("x".into()),
+                version: 3,
+            },
+        )],
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, OrmError::NotFound(_)));
+    assert_eq!(
+        conn.statements(),
+        vec![(
+            "SELECT COUNT(*) FROM items WHERE items.id = ANY($1)".to_string(),
+            1
+        )]
+    );
+}
+
+/// A `ToSql` type without a `PgType` impl: single-row updates keep working.
+#[derive(Debug)]
+struct Rating(i32);
+
+impl ToSql for Rating {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut tokio_postgres::types::private::BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        self.0.to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+#[derive(UpdateModel)]
+#[orm(table = "reviews", id_column = "id")]
+struct ReviewPatch {
+    rating: Option<Rating>,
+}
+
+#[tokio::test]
+async fn patches_without_pg_type_still_update_by_id() {
+    let conn = RecordingClient::with_affected(2);
+    ReviewPatch {
+        rating: Some(Rating(5)),
+    }
+    .update_by_id(&conn, 1_i64)
+    .await
+    .unwrap();
+    assert_eq!(
+        conn.statements()[0].0,
+        "UPDATE reviews SET rating = $1 WHERE reviews.id = $2"
+    );
+}
@@ -0,0 +1,184 @@
+//! Tests for window function helpers and their Model query integration.
+//!
+//! A recording client captures the SQL generated by Model queries (no real
+//! database operations).
+
+#![allow(dead_code)]
+
+mod common;
+
+use common::RecordingClient;
+use pgorm::{
+    FrameBound, FromRow, Model, OrderBy, OrmError, OrmResult, TopNPerGroup, WindowExpr,
+    WindowFrame, WindowSpec,
+};
+
+#[derive(Debug, Clone, FromRow, Model)]
+#[orm(table = "products")]
+struct Product {
+    #[orm(id)]
+    id: i64,
+    category_id: i64,
+    price_cents: i64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct RankedProduct {
+    id: i64,
+    category_id: i64,
+    price_cents: i64,
+    price_rank: i64,
+}
+
+#[tokio::test]
+async fn model_top_n_per_group() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    // Top 3 most expensive products per category.
+    let query = Product::query()
+        .gt(ProductQuery::price_cents, 0_i64)?
+        .top_n_per_group(
+            ProductQuery::category_id,
+            OrderBy::new().desc(ProductQuery::price_cents)?,
+            3,
+        )?
+        .order_by_asc(ProductQuery::category_id)?;
+    let _top: Vec<Product> = query.find(&conn).await?;
+
+    let (sql, params) = conn.last();
+    assert_eq!(
+        sql,
+        format!(
+            "SELECT * FROM (SELECT {}, ROW_NUMBER() OVER \
+             (PARTITION BY category_id ORDER BY price_cents DESC) AS __pgorm_rn \
+             FROM products WHERE price_cents > $1) AS products WHERE __pgorm_rn <= $2 \
+             ORDER BY category_id ASC",
+            Product::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 2);
+
+    // COUNT wraps the ranked query so only the kept rows are counted.
+    assert!(query.count(&conn).await.is_err());
+    let (sql, params) = conn.last();
+    assert_eq!(
+        sql,
+        format!(
+            "SELECT COUNT(*) FROM (SELECT * FROM (SELECT {}, ROW_NUMBER() OVER \
+             (PARTITION BY category_id ORDER BY price_cents DESC) AS __pgorm_rn \
+             FROM products WHERE price_cents > $1) AS products WHERE __pgorm_rn <= $2) \
+             AS __pgorm_count",
+            Product::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_named_window_with_find_as() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    // Window column projected into a custom row type.
+    let _ranked: Vec<RankedProduct> = Product::query()
+        .eq(ProductQuery::category_id, 7_i64)?
+        .define_window(
+            "by_category",
+            WindowSpec::new().partition_by(ProductQuery::category_id)?,
+        )?
+        .window(
+            WindowExpr::rank()
+                .over(
+                    WindowSpec::new()
+                        .based_on("by_category")?
+                        .order_by(OrderBy::new().desc(ProductQuery::price_cents)?),
+                )
+                .alias("price_rank")?,
+        )
+        .find_as(&conn)
+        .await?;
+
+    let (sql, params) = conn.last();
+    assert_eq!(
+        sql,
+        format!(
+            "SELECT {}, RANK() OVER (by_category ORDER BY price_cents DESC) AS price_rank \
+             FROM products WHERE category_id = $1 \
+             WINDOW by_category AS (PARTITION BY category_id)",
+            Product::SELECT_LIST
+        )
+    );
+    assert_eq!(params, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_window_rejects_row_lock() -> OrmResult<()> {
+    let conn = RecordingClient::new();
+    let ranked = Product::query()
+        .window(
+            WindowExpr::row_number()
+                .over(WindowSpec::new().order_by(OrderBy::new().asc(ProductQuery::id)?))
+                .alias("rn")?,
+        )
+        .for_update();
+    let err = ranked.find_as::<RankedProduct>(&conn).await.unwrap_err();
+    assert!(matches!(err, OrmError::Validation(_)));
+
+    let top = Product::query()
+        .top_n_per_group(
+            ProductQuery::category_id,
+            OrderBy::new().desc(ProductQuery::price_cents)?,
+            3,
+        )?
+        .for_share();
+    assert!(matches!(
+        top.find_one(&conn).await,
+        Err(OrmError::Validation(_))
+    ));
+
+    assert!(conn.statements().is_empty());
+    Ok(())
+}
+
+#[test]
+fn window_expr_in_sql_projection() -> OrmResult<()> {
+    let running_total = WindowExpr::sum("price_cents")?
+        .over(
+            WindowSpec::new()
+                .partition_by("category_id")?
+                .order_by(OrderBy::new().asc("id")?)
+                .frame(WindowFrame::rows(
+                    FrameBound::UnboundedPreceding,
+                    FrameBound::CurrentRow,
+                )),
+        )
+        .alias("running_total")?;
+
+    let mut q = pgorm::sql("SELECT id, ");
+    running_total.append_to_sql(&mut q);
+    q.push(" FROM products");
+
+    assert_eq!(
+        q.to_sql(),
+        "SELECT id, SUM(price_cents) OVER (PARTITION BY category_id ORDER BY id ASC \
+         ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS running_total FROM products"
+    );
+    Ok(())
+}
+
+#[test]
+fn top_n_per_group_on_sql() -> OrmResult<()> {
+    let top = TopNPerGroup::new("category_id", OrderBy::new().desc("price_cents")?, 2)?;
+    let mut inner = pgorm::sql("SELECT id, category_id, price_cents, ");
+    top.row_number().append_to_sql(&mut inner);
+    inner.push(" FROM products");
+
+    let mut q = top.wrap(inner);
+    q.push(" ORDER BY category_id");
+    assert_eq!(
+        q.to_sql(),
+        "SELECT * FROM (SELECT id, category_id, price_cents, ROW_NUMBER() OVER \
+         (PARTITION BY category_id ORDER BY price_cents DESC) AS __pgorm_rn FROM products) \
+         AS __pgorm_ranked WHERE __pgorm_rn <= $1 ORDER BY category_id"
+    );
+    Ok(())
+}